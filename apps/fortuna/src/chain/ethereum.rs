@@ -43,7 +43,7 @@ pub type SignablePythContractInner<T> = PythRandom<MiddlewaresWrapper<T>>;
 pub type SignablePythContract = SignablePythContractInner<Http>;
 pub type InstrumentedSignablePythContract = SignablePythContractInner<TracedClient>;
 
-pub type PythContractCall = ContractCall<MiddlewaresWrapper<TracedClient>, ()>;
+pub type PythContractCall<T = TracedClient> = ContractCall<MiddlewaresWrapper<T>, ()>;
 
 pub type PythContract = PythRandom<Provider<Http>>;
 pub type InstrumentedPythContract = PythRandom<Provider<TracedClient>>;
@@ -344,7 +344,7 @@ impl<T: JsonRpcClient + 'static> EntropyReader for PythRandom<Provider<T>> {
         sequence_number: u64,
         user_random_number: [u8; 32],
         provider_revelation: [u8; 32],
-    ) -> Result<U256> {
+    ) -> Result<Option<U256>> {
         let result = self
             .reveal_with_callback(
                 provider,
@@ -356,6 +356,10 @@ impl<T: JsonRpcClient + 'static> EntropyReader for PythRandom<Provider<T>> {
             .estimate_gas()
             .await;
 
-        result.map_err(|e| e.into())
+        match result {
+            Ok(gas) => Ok(Some(gas)),
+            Err(e) if e.is_revert() => Ok(None),
+            Err(e) => Err(e.into()),
+        }
     }
 }
@@ -54,6 +54,8 @@ pub trait EntropyReader: Send + Sync {
     ) -> Result<Vec<RequestedWithCallbackEvent>>;
 
     /// Estimate the gas required to reveal a random number with a callback.
+    /// Returns `None` if the reveal reverts, e.g., because the consumer's callback reverts
+    /// or the request has already been fulfilled.
     async fn estimate_reveal_with_callback_gas(
         &self,
         sender: Address,
@@ -61,7 +63,7 @@ pub trait EntropyReader: Send + Sync {
         sequence_number: u64,
         user_random_number: [u8; 32],
         provider_revelation: [u8; 32],
-    ) -> Result<U256>;
+    ) -> Result<Option<U256>>;
 }
 
 /// An in-flight request stored in the contract.
@@ -177,8 +179,8 @@ pub mod mock {
             _sequence_number: u64,
             _user_random_number: [u8; 32],
             _provider_revelation: [u8; 32],
-        ) -> Result<U256> {
-            Ok(U256::from(5))
+        ) -> Result<Option<U256>> {
+            Ok(Some(U256::from(5)))
         }
     }
 }
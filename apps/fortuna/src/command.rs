@@ -1,3 +1,4 @@
+mod admin;
 mod generate;
 mod get_request;
 mod inspect;
@@ -8,7 +9,7 @@ mod setup_provider;
 mod withdraw_fees;
 
 pub use {
-    generate::generate, get_request::get_request, inspect::inspect,
+    admin::admin, generate::generate, get_request::get_request, inspect::inspect,
     register_provider::register_provider, request_randomness::request_randomness, run::run,
     setup_provider::setup_provider, withdraw_fees::withdraw_fees,
 };
@@ -0,0 +1,469 @@
+use {
+    crate::{
+        api::{get_register_uri, ChainId},
+        chain::ethereum::{ProviderInfo, PythContract},
+        command::{register_provider, register_provider::CommitmentMetadata, withdraw_fees},
+        config::{
+            AdminCommand, AdminOptions, Config, EthereumConfig, InspectHashChainOptions,
+            TopUpAction, ValidateConfigOptions,
+        },
+        keeper::{estimate_tx_cost, fee_with_profit},
+        state::PebbleHashChain,
+    },
+    anyhow::{anyhow, Result},
+    ethers::{
+        abi::Bytes as AbiBytes,
+        middleware::Middleware,
+        signers::{LocalWallet, Signer},
+        types::{Address, Bytes},
+    },
+    std::sync::Arc,
+};
+
+/// Run an administrative operation for the provider.
+pub async fn admin(opts: &AdminOptions) -> Result<()> {
+    match &opts.command {
+        AdminCommand::RegisterProvider(opts) => register_provider(opts).await,
+        AdminCommand::WithdrawFees(opts) => withdraw_fees(opts).await,
+        AdminCommand::InspectHashChain(opts) => inspect_hash_chain(opts).await,
+        AdminCommand::ValidateConfig(opts) => validate_config(opts).await,
+    }
+}
+
+/// Print the provider's current on-chain commitment and check that the hash chain generated from
+/// the configured secret matches it.
+pub async fn inspect_hash_chain(opts: &InspectHashChainOptions) -> Result<()> {
+    let config = Config::load(&opts.config.config)?;
+    let chain_config = config.get_chain_config(&opts.chain_id)?;
+    let contract = PythContract::from_config(&chain_config)?;
+    let provider_address = config.provider.address;
+    let provider_info = contract.get_provider_info(provider_address).call().await?;
+    let metadata = bincode::deserialize::<CommitmentMetadata>(&provider_info.commitment_metadata)
+        .map_err(|e| anyhow!("Failed to deserialize commitment metadata: {}", e))?;
+
+    println!("Provider: {:?}", provider_address);
+    println!("Current commitment:");
+    println!(
+        "  original sequence number: {}",
+        provider_info.original_commitment_sequence_number
+    );
+    println!("  chain length: {}", metadata.chain_length);
+    println!("  seed: 0x{}", hex::encode(metadata.seed));
+    println!(
+        "  commitment: 0x{}",
+        hex::encode(provider_info.original_commitment)
+    );
+    println!(
+        "Sequence number: {} (end: {}, remaining: {})",
+        provider_info.sequence_number,
+        provider_info.end_sequence_number,
+        provider_info
+            .end_sequence_number
+            .saturating_sub(provider_info.sequence_number)
+    );
+    println!(
+        "Current commitment sequence number: {} (max num hashes: {})",
+        provider_info.current_commitment_sequence_number, provider_info.max_num_hashes
+    );
+
+    let secret = config
+        .provider
+        .secret
+        .load()?
+        .ok_or(anyhow!("Please specify a provider secret in the config"))?;
+    let hash_chain = PebbleHashChain::from_config(
+        &secret,
+        &opts.chain_id,
+        &provider_address,
+        &chain_config.contract_addr,
+        &metadata.seed,
+        metadata.chain_length,
+        config.provider.chain_sample_interval,
+    )?;
+    println!(
+        "Generated hash chain root matches on-chain commitment: {}",
+        hash_chain.reveal_ith(0)? == provider_info.original_commitment
+    );
+
+    let historical_commitments = chain_config.commitments.unwrap_or_default();
+    println!(
+        "Configured historical commitments: {}",
+        historical_commitments.len()
+    );
+    for commitment in historical_commitments {
+        println!(
+            "  original sequence number: {} chain length: {} seed: 0x{}",
+            commitment.original_commitment_sequence_number,
+            commitment.chain_length,
+            hex::encode(commitment.seed)
+        );
+    }
+
+    Ok(())
+}
+
+/// Check the configuration of the selected chains against the deployed Entropy contracts and
+/// report every mismatch. Returns an error if any chain is misconfigured.
+pub async fn validate_config(opts: &ValidateConfigOptions) -> Result<()> {
+    let config = Config::load(&opts.config.config)?;
+    let chain_ids = match &opts.chain_id {
+        Some(chain_id) => vec![chain_id.clone()],
+        None => {
+            let mut chain_ids: Vec<ChainId> = config.chains.keys().cloned().collect();
+            chain_ids.sort();
+            chain_ids
+        }
+    };
+
+    let mut all_ok = true;
+    for chain_id in chain_ids {
+        let chain_config = config.get_chain_config(&chain_id)?;
+        println!("Validating chain: {}", chain_id);
+        match validate_chain_config(&config, &chain_id, &chain_config).await {
+            Ok(problems) if problems.is_empty() => println!("  ok"),
+            Ok(problems) => {
+                all_ok = false;
+                for problem in problems {
+                    println!("  - {}", problem);
+                }
+            }
+            Err(e) => {
+                all_ok = false;
+                println!("  - Failed to validate chain: {:?}", e);
+            }
+        }
+    }
+
+    match all_ok {
+        true => Ok(()),
+        false => Err(anyhow!(
+            "The configuration does not match the deployed contracts"
+        )),
+    }
+}
+
+/// Returns the list of problems found with the configuration of a single chain.
+async fn validate_chain_config(
+    config: &Config,
+    chain_id: &ChainId,
+    chain_config: &EthereumConfig,
+) -> Result<Vec<String>> {
+    let contract = Arc::new(PythContract::from_config(chain_config)?);
+
+    let code = contract
+        .client()
+        .get_code(chain_config.contract_addr, None)
+        .await?;
+    if code.is_empty() {
+        return Ok(vec![format!(
+            "No contract is deployed at {:?}",
+            chain_config.contract_addr
+        )]);
+    }
+
+    let provider_info = contract
+        .get_provider_info(config.provider.address)
+        .call()
+        .await?;
+    let max_callback_cost = estimate_tx_cost(
+        contract.clone(),
+        chain_config.legacy_tx,
+        chain_config.gas_limit.into(),
+    )
+    .await?;
+    let mut problems = check_provider_info(
+        config,
+        chain_id,
+        chain_config,
+        &provider_info,
+        max_callback_cost,
+    )?;
+
+    if let Some(keeper_private_key) = config.keeper.private_key.load()? {
+        let keeper_address = keeper_private_key.parse::<LocalWallet>()?.address();
+        let keeper_balance = contract.client().get_balance(keeper_address, None).await?;
+        if keeper_balance < chain_config.min_keeper_balance.into() {
+            problems.push(format!(
+                "Keeper balance {} is below the configured minimum {}",
+                keeper_balance, chain_config.min_keeper_balance
+            ));
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Returns the list of problems found by comparing the on-chain registration of the provider with
+/// the configuration. `max_callback_cost` is the cost of a callback with the configured gas limit at
+/// the current gas price.
+fn check_provider_info(
+    config: &Config,
+    chain_id: &ChainId,
+    chain_config: &EthereumConfig,
+    provider_info: &ProviderInfo,
+    max_callback_cost: u128,
+) -> Result<Vec<String>> {
+    let mut problems = vec![];
+    let provider_config = &config.provider;
+    let provider_address = provider_config.address;
+    if let Some(private_key) = provider_config.private_key.load()? {
+        let key_address = private_key.parse::<LocalWallet>()?.address();
+        if key_address != provider_address {
+            problems.push(format!(
+                "Provider private key is for {:?} but the provider address is {:?}",
+                key_address, provider_address
+            ));
+        }
+    }
+
+    if provider_info.end_sequence_number == 0 {
+        problems.push(format!("Provider {:?} is not registered", provider_address));
+        return Ok(problems);
+    }
+    if provider_info.end_sequence_number <= provider_info.sequence_number {
+        problems.push("Provider has no random numbers left to request".to_string());
+    }
+
+    // The keeper adjusts the on-chain fee whenever it leaves this range, so the configured fee
+    // is only a lower bound.
+    let min_fee = fee_with_profit(
+        max_callback_cost,
+        chain_config.min_profit_pct,
+        chain_config.fee,
+    );
+    let max_fee = fee_with_profit(
+        max_callback_cost,
+        chain_config.max_profit_pct,
+        chain_config.fee,
+    );
+    if provider_info.fee_in_wei < min_fee || provider_info.fee_in_wei > max_fee {
+        problems.push(format!(
+            "On-chain fee {} is outside of the fee range [{}, {}] at the current gas price",
+            provider_info.fee_in_wei, min_fee, max_fee
+        ));
+    }
+
+    let uri = get_register_uri(&provider_config.uri, chain_id)?;
+    let uri_as_bytes: Bytes = AbiBytes::from(uri.as_str()).into();
+    if provider_info.uri != uri_as_bytes {
+        problems.push(format!(
+            "On-chain uri {:?} does not match configured uri {}",
+            String::from_utf8_lossy(&provider_info.uri),
+            uri
+        ));
+    }
+
+    let fee_manager = provider_config.fee_manager.unwrap_or(Address::zero());
+    if provider_info.fee_manager != fee_manager {
+        problems.push(format!(
+            "On-chain fee manager {:?} does not match configured fee manager {:?}",
+            provider_info.fee_manager, fee_manager
+        ));
+    }
+
+    let max_num_hashes = chain_config.max_num_hashes.unwrap_or(0);
+    if provider_info.max_num_hashes != max_num_hashes {
+        problems.push(format!(
+            "On-chain max num hashes {} does not match configured max num hashes {}",
+            provider_info.max_num_hashes, max_num_hashes
+        ));
+    }
+
+    match provider_config.secret.load()? {
+        Some(secret) => {
+            let metadata =
+                bincode::deserialize::<CommitmentMetadata>(&provider_info.commitment_metadata)
+                    .map_err(|e| anyhow!("Failed to deserialize commitment metadata: {}", e))?;
+            let hash_chain = PebbleHashChain::from_config(
+                &secret,
+                chain_id,
+                &provider_address,
+                &chain_config.contract_addr,
+                &metadata.seed,
+                metadata.chain_length,
+                provider_config.chain_sample_interval,
+            )?;
+            if hash_chain.reveal_ith(0)? != provider_info.original_commitment {
+                problems.push(
+                    "The root of the generated hash chain does not match the on-chain commitment"
+                        .to_string(),
+                );
+            }
+        }
+        None => problems.push("No provider secret is configured".to_string()),
+    }
+
+    let last_prior_commitment = chain_config
+        .commitments
+        .iter()
+        .flatten()
+        .map(|c| c.original_commitment_sequence_number)
+        .max();
+    if let Some(last_prior_commitment) = last_prior_commitment {
+        if last_prior_commitment >= provider_info.original_commitment_sequence_number {
+            problems.push(format!(
+                "Configured historical commitment at sequence number {} is not older than the on-chain commitment at {}",
+                last_prior_commitment, provider_info.original_commitment_sequence_number
+            ));
+        }
+    }
+
+    if let Some(keeper_private_key) = config.keeper.private_key.load()? {
+        let keeper_address = keeper_private_key.parse::<LocalWallet>()?.address();
+        if chain_config
+            .top_up_actions
+            .contains(&TopUpAction::WithdrawFees)
+            && provider_info.fee_manager != keeper_address
+        {
+            problems.push(format!(
+                "The withdraw_fees top-up action requires the keeper {:?} to be the fee manager",
+                keeper_address
+            ));
+        }
+    }
+
+    Ok(problems)
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, ethers::types::H160};
+
+    const PROVIDER_SECRET: &str =
+        "4242424242424242424242424242424242424242424242424242424242424242";
+
+    fn config() -> Config {
+        // The private keys are the first two default anvil accounts
+        serde_yaml::from_str(
+            "
+chains:
+  test:
+    geth_rpc_addr: http://localhost:8545
+    contract_addr: 0x0000000000000000000000000000000000000001
+    reveal_delay_blocks: 0
+    gas_limit: 500000
+    min_profit_pct: 0
+    target_profit_pct: 20
+    max_profit_pct: 100
+    fee: 500
+provider:
+  uri: http://localhost:8080/
+  address: 0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266
+  private_key:
+    value: ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80
+  secret:
+    value: 4242424242424242424242424242424242424242424242424242424242424242
+  chain_length: 100
+  fee_manager: 0x70997970C51812dc3A010C7d01b50e0d17dc79C8
+keeper:
+  private_key:
+    value: 59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d
+",
+        )
+        .unwrap()
+    }
+
+    /// The registration of the provider that matches `config`, with a fee of `fee_in_wei`.
+    fn provider_info(config: &Config, fee_in_wei: u128) -> ProviderInfo {
+        let chain_config = config.get_chain_config(&"test".to_string()).unwrap();
+        let metadata = CommitmentMetadata {
+            seed: [1; 32],
+            chain_length: 100,
+        };
+        let hash_chain = PebbleHashChain::from_config(
+            PROVIDER_SECRET,
+            &"test".to_string(),
+            &config.provider.address,
+            &chain_config.contract_addr,
+            &metadata.seed,
+            metadata.chain_length,
+            config.provider.chain_sample_interval,
+        )
+        .unwrap();
+        let uri = get_register_uri(&config.provider.uri, "test").unwrap();
+        ProviderInfo {
+            fee_in_wei,
+            accrued_fees_in_wei: 0,
+            original_commitment: hash_chain.reveal_ith(0).unwrap(),
+            original_commitment_sequence_number: 0,
+            commitment_metadata: bincode::serialize(&metadata).unwrap().into(),
+            uri: AbiBytes::from(uri.as_str()).into(),
+            end_sequence_number: 100,
+            sequence_number: 10,
+            current_commitment: [0; 32],
+            current_commitment_sequence_number: 0,
+            fee_manager: config.provider.fee_manager.unwrap(),
+            max_num_hashes: 0,
+        }
+    }
+
+    fn check(config: &Config, provider_info: &ProviderInfo) -> Vec<String> {
+        let chain_config = config.get_chain_config(&"test".to_string()).unwrap();
+        // The fee range is [1000, 2000] for the configured profit percentages
+        check_provider_info(
+            config,
+            &"test".to_string(),
+            &chain_config,
+            provider_info,
+            1000,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_check_provider_info() {
+        let config = config();
+        assert!(check(&config, &provider_info(&config, 1500)).is_empty());
+
+        let mut info = provider_info(&config, 1000);
+        info.uri = AbiBytes::from("http://localhost:8081/v1/chains/test").into();
+        info.original_commitment = [0; 32];
+        info.max_num_hashes = 10;
+        assert_eq!(
+            check(&config, &info),
+            [
+                r#"On-chain uri "http://localhost:8081/v1/chains/test" does not match configured uri http://localhost:8080/v1/chains/test"#,
+                "On-chain max num hashes 10 does not match configured max num hashes 0",
+                "The root of the generated hash chain does not match the on-chain commitment",
+            ]
+        );
+
+        let mut info = provider_info(&config, 1000);
+        info.end_sequence_number = 0;
+        assert_eq!(
+            check(&config, &info),
+            ["Provider 0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266 is not registered"]
+        );
+    }
+
+    #[test]
+    fn test_check_provider_fee() {
+        let config = config();
+        // The on-chain fee doesn't need to match the configured fee since the keeper adjusts it
+        assert!(check(&config, &provider_info(&config, 1000)).is_empty());
+        assert!(check(&config, &provider_info(&config, 2000)).is_empty());
+
+        assert_eq!(
+            check(&config, &provider_info(&config, 500)),
+            ["On-chain fee 500 is outside of the fee range [1000, 2000] at the current gas price"]
+        );
+        assert_eq!(
+            check(&config, &provider_info(&config, 2001)),
+            ["On-chain fee 2001 is outside of the fee range [1000, 2000] at the current gas price"]
+        );
+    }
+
+    #[test]
+    fn test_check_withdraw_fees_fee_manager() {
+        let config = config();
+        let mut info = provider_info(&config, 1500);
+        info.fee_manager = H160::repeat_byte(2);
+        assert_eq!(
+            check(&config, &info),
+            [
+                "On-chain fee manager 0x0202020202020202020202020202020202020202 does not match configured fee manager 0x70997970c51812dc3a010c7d01b50e0d17dc79c8",
+                "The withdraw_fees top-up action requires the keeper 0x70997970c51812dc3a010c7d01b50e0d17dc79c8 to be the fee manager",
+            ]
+        );
+    }
+}
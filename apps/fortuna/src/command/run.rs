@@ -99,6 +99,7 @@ pub async fn run_keeper(
     chains: HashMap<String, api::BlockchainState>,
     config: Config,
     private_key: String,
+    treasury_private_key: Option<String>,
     metrics_registry: Arc<RwLock<Registry>>,
     rpc_metrics: Arc<RpcMetrics>,
 ) -> Result<()> {
@@ -113,6 +114,7 @@ pub async fn run_keeper(
         let private_key = private_key.clone();
         handles.push(spawn(keeper::run_keeper_threads(
             private_key,
+            treasury_private_key.clone(),
             chain_eth_config,
             chain_config.clone(),
             keeper_metrics.clone(),
@@ -182,10 +184,15 @@ pub async fn run(opts: &RunOptions) -> Result<()> {
     });
 
     if let Some(keeper_private_key) = config.keeper.private_key.load()? {
+        let treasury_private_key = match &config.keeper.treasury_private_key {
+            Some(secret) => secret.load()?,
+            None => None,
+        };
         spawn(run_keeper(
             chains.clone(),
             config.clone(),
             keeper_private_key,
+            treasury_private_key,
             metrics_registry.clone(),
             rpc_metrics.clone(),
         ));
@@ -9,11 +9,18 @@ use {
     std::{collections::HashMap, fs},
 };
 pub use {
-    generate::GenerateOptions, get_request::GetRequestOptions, inspect::InspectOptions,
-    register_provider::RegisterProviderOptions, request_randomness::RequestRandomnessOptions,
-    run::RunOptions, setup_provider::SetupProviderOptions, withdraw_fees::WithdrawFeesOptions,
+    admin::{AdminCommand, AdminOptions, InspectHashChainOptions, ValidateConfigOptions},
+    generate::GenerateOptions,
+    get_request::GetRequestOptions,
+    inspect::InspectOptions,
+    register_provider::RegisterProviderOptions,
+    request_randomness::RequestRandomnessOptions,
+    run::RunOptions,
+    setup_provider::SetupProviderOptions,
+    withdraw_fees::WithdrawFeesOptions,
 };
 
+mod admin;
 mod generate;
 mod get_request;
 mod inspect;
@@ -57,6 +64,10 @@ pub enum Options {
 
     /// Withdraw any of the provider's accumulated fees from the contract.
     WithdrawFees(WithdrawFeesOptions),
+
+    /// Administrative operations for the provider, such as registration, fee withdrawal,
+    /// hash chain inspection and config validation.
+    Admin(AdminOptions),
 }
 
 #[derive(Args, Clone, Debug)]
@@ -83,17 +94,32 @@ impl Config {
         // TODO: the default serde deserialization doesn't enforce unique keys
         let yaml_content = fs::read_to_string(path)?;
         let config: Config = serde_yaml::from_str(&yaml_content)?;
+        config.validate()?;
+
+        Ok(config)
+    }
 
-        // Run correctness checks for the config and fail if there are any issues.
-        for (chain_id, config) in config.chains.iter() {
-            if !(config.min_profit_pct <= config.target_profit_pct
-                && config.target_profit_pct <= config.max_profit_pct)
+    /// Run correctness checks for the config and fail if there are any issues.
+    pub fn validate(&self) -> Result<()> {
+        for (chain_id, chain_config) in self.chains.iter() {
+            if !(chain_config.min_profit_pct <= chain_config.target_profit_pct
+                && chain_config.target_profit_pct <= chain_config.max_profit_pct)
             {
                 return Err(anyhow!("chain id {:?} configuration is invalid. Config must satisfy min_profit_pct <= target_profit_pct <= max_profit_pct.", chain_id));
             }
+
+            let uses_treasury = chain_config
+                .top_up_actions
+                .contains(&TopUpAction::TreasuryTransfer);
+            if uses_treasury && self.keeper.treasury_private_key.is_none() {
+                return Err(anyhow!("chain id {:?} configuration is invalid. The treasury_transfer top-up action requires keeper.treasury_private_key to be set.", chain_id));
+            }
+            if uses_treasury && chain_config.treasury_top_up_amount == 0 {
+                return Err(anyhow!("chain id {:?} configuration is invalid. The treasury_transfer top-up action requires a non-zero treasury_top_up_amount.", chain_id));
+            }
         }
 
-        Ok(config)
+        Ok(())
     }
 
     pub fn get_chain_config(&self, chain_id: &ChainId) -> Result<EthereumConfig> {
@@ -151,11 +177,26 @@ pub struct EthereumConfig {
     pub max_profit_pct: u64,
 
     /// Minimum wallet balance for the keeper. If the balance falls below this level, the keeper will
-    /// withdraw fees from the contract to top up. This functionality requires the keeper to be the fee
-    /// manager for the provider.
+    /// run the configured `top_up_actions`. By default, it withdraws fees from the contract to top up,
+    /// which requires the keeper to be the fee manager for the provider.
     #[serde(default)]
     pub min_keeper_balance: u128,
 
+    /// Actions to take when the keeper wallet balance falls below `min_keeper_balance`. The actions are
+    /// attempted in order until the balance is restored. Defaults to withdrawing the provider's accrued fees.
+    #[serde(default = "default_top_up_actions")]
+    pub top_up_actions: Vec<TopUpAction>,
+
+    /// The amount (in wei) to transfer from the treasury wallet per `treasury_transfer` top-up action.
+    #[serde(default)]
+    pub treasury_top_up_amount: u128,
+
+    /// The maximum number of times to retry a reveal whose consumer callback reverts during gas
+    /// estimation before abandoning the request. Reverts are retried because they can be caused by
+    /// a lagging RPC node. Reveal transactions that revert after they are mined are not retried.
+    #[serde(default = "default_max_callback_revert_retries")]
+    pub max_callback_revert_retries: u32,
+
     /// How much the provider charges for a request on this chain.
     #[serde(default)]
     pub fee: u128,
@@ -168,6 +209,27 @@ pub struct EthereumConfig {
     pub max_num_hashes: Option<u32>,
 }
 
+fn default_max_callback_revert_retries() -> u32 {
+    5
+}
+
+/// An action the keeper can take to restore its wallet balance when it drops below the configured minimum.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TopUpAction {
+    /// Withdraw the provider's accrued fees to the keeper wallet. This requires the keeper to be
+    /// the fee manager for the provider.
+    WithdrawFees,
+    /// Transfer `treasury_top_up_amount` wei from the treasury wallet configured in the keeper config.
+    TreasuryTransfer,
+    /// Do not move any funds, only log an error and increment the low balance alert metric.
+    Alert,
+}
+
+fn default_top_up_actions() -> Vec<TopUpAction> {
+    vec![TopUpAction::WithdrawFees]
+}
+
 /// A commitment that the provider used to generate random numbers at some point in the past.
 /// These historical commitments need to be stored in the configuration to support transition points where
 /// the commitment changes. In theory, this information is stored on the blockchain, but unfortunately it
@@ -224,6 +286,11 @@ pub struct KeeperConfig {
     /// This key *does not need to be a registered provider*. In particular, production deployments
     /// should ensure this is a different key in order to reduce the severity of security breaches.
     pub private_key: SecretString,
+
+    /// The private key of a treasury wallet that funds the keeper wallet. This key is only used by
+    /// chains that configure a `treasury_transfer` top-up action.
+    #[serde(default)]
+    pub treasury_private_key: Option<SecretString>,
 }
 
 // A secret is a string that can be provided either as a literal in the config,
@@ -250,3 +317,100 @@ impl SecretString {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_top_up_actions() {
+        let actions: Vec<TopUpAction> = serde_yaml::from_str(
+            "
+- withdraw_fees
+- treasury_transfer
+- alert
+",
+        )
+        .unwrap();
+
+        assert_eq!(
+            actions,
+            vec![
+                TopUpAction::WithdrawFees,
+                TopUpAction::TreasuryTransfer,
+                TopUpAction::Alert,
+            ]
+        );
+        assert_eq!(default_top_up_actions(), vec![TopUpAction::WithdrawFees]);
+    }
+
+    #[test]
+    fn test_sample_config() {
+        // The sample leaves the addresses for the operator to fill in
+        let yaml_content = include_str!("../config.sample.yaml")
+            .replace("0xADDRESS", "0x0000000000000000000000000000000000000001");
+        let config: Config = serde_yaml::from_str(&yaml_content).unwrap();
+        config.validate().unwrap();
+    }
+
+    fn config_with_top_up(top_up: &str, treasury_private_key: bool) -> Config {
+        let treasury = if treasury_private_key {
+            "  treasury_private_key:\n    value: 0xabcd\n"
+        } else {
+            ""
+        };
+        serde_yaml::from_str(&format!(
+            "
+chains:
+  test:
+    geth_rpc_addr: http://localhost:8545
+    contract_addr: 0x0000000000000000000000000000000000000001
+    reveal_delay_blocks: 0
+    gas_limit: 500000
+    min_profit_pct: 0
+    target_profit_pct: 20
+    max_profit_pct: 100
+{top_up}
+provider:
+  uri: http://localhost:8080/
+  address: 0x0000000000000000000000000000000000000002
+  private_key:
+    value: 0xabcd
+  secret:
+    value: abcd
+  chain_length: 100
+keeper:
+  private_key:
+    value: 0xabcd
+{treasury}"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate_treasury_transfer() {
+        let treasury_transfer = "    top_up_actions: [treasury_transfer]";
+        let amount = "    treasury_top_up_amount: 100";
+
+        assert!(config_with_top_up("", false).validate().is_ok());
+        assert!(
+            config_with_top_up(&format!("{treasury_transfer}\n{amount}"), true)
+                .validate()
+                .is_ok()
+        );
+        // The treasury key is required
+        assert!(
+            config_with_top_up(&format!("{treasury_transfer}\n{amount}"), false)
+                .validate()
+                .is_err()
+        );
+        // A missing amount would send zero-value transfers
+        assert!(config_with_top_up(treasury_transfer, true)
+            .validate()
+            .is_err());
+        // The amount is only required for the treasury_transfer action
+        assert!(config_with_top_up("    top_up_actions: [alert]", false)
+            .validate()
+            .is_ok());
+    }
+}
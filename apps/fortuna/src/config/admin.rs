@@ -0,0 +1,55 @@
+use {
+    crate::{
+        api::ChainId,
+        config::{ConfigOptions, RegisterProviderOptions, WithdrawFeesOptions},
+    },
+    clap::{Args, Subcommand},
+};
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "Admin Options")]
+#[group(id = "Admin")]
+pub struct AdminOptions {
+    #[command(subcommand)]
+    pub command: AdminCommand,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum AdminCommand {
+    /// Register the provider on a chain with a newly generated hash chain.
+    RegisterProvider(RegisterProviderOptions),
+
+    /// Withdraw any of the provider's accumulated fees from the contract.
+    WithdrawFees(WithdrawFeesOptions),
+
+    /// Inspect the provider's hash chain on a chain and check it against the on-chain commitment.
+    InspectHashChain(InspectHashChainOptions),
+
+    /// Validate the configuration of each chain against the deployed Entropy contracts.
+    ValidateConfig(ValidateConfigOptions),
+}
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "Inspect Hash Chain Options")]
+#[group(id = "InspectHashChain")]
+pub struct InspectHashChainOptions {
+    #[command(flatten)]
+    pub config: ConfigOptions,
+
+    /// Inspect the hash chain on this chain
+    #[arg(long = "chain-id")]
+    #[arg(env = "FORTUNA_CHAIN_ID")]
+    pub chain_id: ChainId,
+}
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "Validate Config Options")]
+#[group(id = "ValidateConfig")]
+pub struct ValidateConfigOptions {
+    #[command(flatten)]
+    pub config: ConfigOptions,
+
+    /// Validate the configuration of this chain, or all chains if not specified.
+    #[arg(long = "chain-id")]
+    pub chain_id: Option<ChainId>,
+}
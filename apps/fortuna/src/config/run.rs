@@ -11,4 +11,10 @@ pub struct RunOptions {
     #[arg(default_value = super::DEFAULT_RPC_ADDR)]
     #[arg(env = "RPC_ADDR")]
     pub addr: SocketAddr,
+
+    /// Endpoint of an OpenTelemetry collector to export traces to via OTLP/gRPC.
+    /// Traces are not exported if this is not set.
+    #[arg(long = "otlp-endpoint")]
+    #[arg(env = "OTEL_EXPORTER_OTLP_ENDPOINT")]
+    pub otlp_endpoint: Option<String>,
 }
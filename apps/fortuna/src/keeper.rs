@@ -5,33 +5,38 @@ use {
             eth_gas_oracle::eip1559_default_estimator,
             ethereum::{
                 InstrumentedPythContract, InstrumentedSignablePythContract, PythContractCall,
+                PythRandom, SignablePythContractInner,
             },
             reader::{BlockNumber, RequestedWithCallbackEvent},
             traced_client::{RpcMetrics, TracedClient},
         },
-        config::EthereumConfig,
+        config::{EthereumConfig, TopUpAction},
     },
     anyhow::{anyhow, Result},
     backoff::ExponentialBackoff,
     ethers::{
-        providers::{Middleware, Provider, Ws},
+        providers::{JsonRpcClient, Middleware, Provider, Ws},
         signers::Signer,
-        types::{Address, U256},
+        types::{Address, TransactionRequest, U256},
     },
     futures::StreamExt,
     prometheus_client::{
-        encoding::EncodeLabelSet,
-        metrics::{counter::Counter, family::Family, gauge::Gauge},
+        encoding::{EncodeLabelSet, EncodeLabelValue},
+        metrics::{counter::Counter, family::Family, gauge::Gauge, histogram::Histogram},
         registry::Registry,
     },
     std::{
         collections::HashSet,
-        sync::{atomic::AtomicU64, Arc},
+        sync::{
+            atomic::{AtomicU32, AtomicU64, Ordering},
+            Arc,
+        },
     },
+    thiserror::Error,
     tokio::{
         spawn,
         sync::{mpsc, RwLock},
-        time::{self, timeout, Duration},
+        time::{self, timeout, Duration, Instant},
     },
     tracing::{self, Instrument},
 };
@@ -46,8 +51,8 @@ const BLOCK_BATCH_SIZE: u64 = 100;
 const POLL_INTERVAL: Duration = Duration::from_secs(2);
 /// Track metrics in this interval
 const TRACK_INTERVAL: Duration = Duration::from_secs(10);
-/// Check whether we need to conduct a withdrawal at this interval.
-const WITHDRAW_INTERVAL: Duration = Duration::from_secs(300);
+/// Check whether we need to top up the keeper wallet at this interval.
+const TOP_UP_INTERVAL: Duration = Duration::from_secs(300);
 /// Check whether we need to adjust the fee at this interval.
 const ADJUST_FEE_INTERVAL: Duration = Duration::from_secs(30);
 /// Check whether we need to manually update the commitments to reduce numHashes for future
@@ -63,7 +68,39 @@ pub struct AccountLabel {
     pub address: String,
 }
 
-#[derive(Default)]
+/// The stage of processing a request at which the reveal failed.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, EncodeLabelValue)]
+pub enum RevealErrorClass {
+    /// The provider revelation could not be computed from the hash chain.
+    Revelation,
+    /// The gas estimation call for the reveal failed.
+    GasEstimate,
+    /// The gas estimate for the reveal exceeded the configured gas limit.
+    GasLimitExceeded,
+    /// The reveal transaction could not be filled or submitted.
+    Submission,
+    /// The reveal transaction was submitted but its confirmation could not be observed.
+    Confirmation,
+    /// The reveal reverted even though the request is still pending, which means that the
+    /// consumer's callback reverted.
+    CallbackRevert,
+}
+
+/// A failed attempt to fulfill a request, tagged with the stage at which it failed.
+#[derive(Debug, Error)]
+#[error("{class:?} error: {error:?}")]
+pub struct RevealFailure {
+    pub class: RevealErrorClass,
+    pub error: anyhow::Error,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct RevealErrorLabel {
+    pub chain_id: String,
+    pub address: String,
+    pub class: RevealErrorClass,
+}
+
 pub struct KeeperMetrics {
     pub current_sequence_number: Family<AccountLabel, Gauge>,
     pub end_sequence_number: Family<AccountLabel, Gauge>,
@@ -71,10 +108,65 @@ pub struct KeeperMetrics {
     pub collected_fee: Family<AccountLabel, Gauge<f64, AtomicU64>>,
     pub current_fee: Family<AccountLabel, Gauge<f64, AtomicU64>>,
     pub total_gas_spent: Family<AccountLabel, Gauge<f64, AtomicU64>>,
+    pub total_gas_fee_spent: Family<AccountLabel, Gauge<f64, AtomicU64>>,
     pub requests: Family<AccountLabel, Counter>,
     pub requests_processed: Family<AccountLabel, Counter>,
     pub requests_reprocessed: Family<AccountLabel, Counter>,
     pub reveals: Family<AccountLabel, Counter>,
+    pub reveal_errors: Family<RevealErrorLabel, Counter>,
+    pub requests_abandoned: Family<RevealErrorLabel, Counter>,
+    pub reveal_gas_used: Family<AccountLabel, Histogram>,
+    pub reveal_latency: Family<AccountLabel, Histogram>,
+    pub last_processed_block: Family<AccountLabel, Gauge>,
+    pub low_balance_alerts: Family<AccountLabel, Counter>,
+    pub treasury_top_ups: Family<AccountLabel, Counter>,
+}
+
+impl Default for KeeperMetrics {
+    fn default() -> Self {
+        Self {
+            current_sequence_number: Family::default(),
+            end_sequence_number: Family::default(),
+            balance: Family::default(),
+            collected_fee: Family::default(),
+            current_fee: Family::default(),
+            total_gas_spent: Family::default(),
+            total_gas_fee_spent: Family::default(),
+            requests: Family::default(),
+            requests_processed: Family::default(),
+            requests_reprocessed: Family::default(),
+            reveals: Family::default(),
+            reveal_errors: Family::default(),
+            requests_abandoned: Family::default(),
+            reveal_gas_used: Family::new_with_constructor(|| {
+                Histogram::new(
+                    [
+                        50_000.0,
+                        100_000.0,
+                        200_000.0,
+                        300_000.0,
+                        500_000.0,
+                        750_000.0,
+                        1_000_000.0,
+                        2_000_000.0,
+                        5_000_000.0,
+                    ]
+                    .into_iter(),
+                )
+            }),
+            reveal_latency: Family::new_with_constructor(|| {
+                Histogram::new(
+                    [
+                        1.0, 2.0, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0, 180.0, 240.0, 300.0,
+                    ]
+                    .into_iter(),
+                )
+            }),
+            last_processed_block: Family::default(),
+            low_balance_alerts: Family::default(),
+            treasury_top_ups: Family::default(),
+        }
+    }
 }
 
 impl KeeperMetrics {
@@ -136,12 +228,60 @@ impl KeeperMetrics {
             keeper_metrics.total_gas_spent.clone(),
         );
 
+        writable_registry.register(
+            "total_gas_fee_spent",
+            "Total amount of native tokens spent on gas fees revealing requests",
+            keeper_metrics.total_gas_fee_spent.clone(),
+        );
+
+        writable_registry.register(
+            "reveal_errors",
+            "Number of failed reveal attempts by error class",
+            keeper_metrics.reveal_errors.clone(),
+        );
+
+        writable_registry.register(
+            "requests_abandoned",
+            "Number of requests the keeper gave up on, by the error class of the last failed attempt",
+            keeper_metrics.requests_abandoned.clone(),
+        );
+
+        writable_registry.register(
+            "reveal_gas_used",
+            "Gas used by reveal transactions, including the consumer callback",
+            keeper_metrics.reveal_gas_used.clone(),
+        );
+
+        writable_registry.register(
+            "reveal_latency",
+            "Time in seconds from starting to process a request until its reveal is confirmed",
+            keeper_metrics.reveal_latency.clone(),
+        );
+
+        writable_registry.register(
+            "last_processed_block",
+            "The last block whose request events have been fetched by the keeper",
+            keeper_metrics.last_processed_block.clone(),
+        );
+
         writable_registry.register(
             "requests_reprocessed",
             "Number of requests reprocessed",
             keeper_metrics.requests_reprocessed.clone(),
         );
 
+        writable_registry.register(
+            "low_balance_alerts",
+            "Number of alerts raised because the keeper balance was below the configured minimum",
+            keeper_metrics.low_balance_alerts.clone(),
+        );
+
+        writable_registry.register(
+            "treasury_top_ups",
+            "Number of transfers from the treasury wallet to the keeper wallet",
+            keeper_metrics.treasury_top_ups.clone(),
+        );
+
         keeper_metrics
     }
 }
@@ -190,6 +330,7 @@ async fn get_latest_safe_block(chain_state: &BlockchainState) -> BlockNumber {
 #[tracing::instrument(name = "keeper", skip_all, fields(chain_id = chain_state.id))]
 pub async fn run_keeper_threads(
     private_key: String,
+    treasury_private_key: Option<String>,
     chain_eth_config: EthereumConfig,
     chain_state: BlockchainState,
     metrics: Arc<KeeperMetrics>,
@@ -211,6 +352,24 @@ pub async fn run_keeper_threads(
     );
     let keeper_address = contract.wallet().address();
 
+    // The treasury wallet is only needed if this chain is configured to top up from it.
+    let uses_treasury = chain_eth_config
+        .top_up_actions
+        .contains(&TopUpAction::TreasuryTransfer);
+    let treasury_contract = match treasury_private_key {
+        Some(treasury_private_key) if uses_treasury => Some(Arc::new(
+            InstrumentedSignablePythContract::from_config(
+                &chain_eth_config,
+                &treasury_private_key,
+                chain_state.id.clone(),
+                rpc_metrics.clone(),
+            )
+            .await
+            .expect("Chain config should be valid"),
+        )),
+        _ => None,
+    };
+
     let fulfilled_requests_cache = Arc::new(RwLock::new(HashSet::<u64>::new()));
 
     // Spawn a thread to handle the events from last BACKLOG_RANGE blocks.
@@ -223,6 +382,7 @@ pub async fn run_keeper_threads(
             },
             contract.clone(),
             gas_limit,
+            chain_eth_config.max_callback_revert_retries,
             chain_state.clone(),
             metrics.clone(),
             fulfilled_requests_cache.clone(),
@@ -248,19 +408,25 @@ pub async fn run_keeper_threads(
             rx,
             Arc::clone(&contract),
             gas_limit,
+            chain_eth_config.max_callback_revert_retries,
             metrics.clone(),
             fulfilled_requests_cache.clone(),
         )
         .in_current_span(),
     );
 
-    // Spawn a thread that watches the keeper wallet balance and submits withdrawal transactions as needed to top-up the balance.
+    // Spawn a thread that watches the keeper wallet balance and runs the configured top-up actions as needed.
     spawn(
-        withdraw_fees_wrapper(
+        top_up_keeper_balance_wrapper(
             contract.clone(),
+            treasury_contract,
+            chain_state.id.clone(),
             chain_state.provider_address,
-            WITHDRAW_INTERVAL,
+            TOP_UP_INTERVAL,
             U256::from(chain_eth_config.min_keeper_balance),
+            chain_eth_config.top_up_actions.clone(),
+            U256::from(chain_eth_config.treasury_top_up_amount),
+            metrics.clone(),
         )
         .in_current_span(),
     );
@@ -333,6 +499,8 @@ pub async fn run_keeper_threads(
 }
 
 /// Process an event with backoff. It will retry the reveal on failure for 5 minutes.
+/// Reverting consumer callbacks are retried at most `max_callback_revert_retries` times, since
+/// they are unlikely to succeed later and would otherwise keep consuming RPC calls.
 #[tracing::instrument(name = "process_event_with_backoff", skip_all, fields(
     sequence_number = event.sequence_number
 ))]
@@ -341,6 +509,7 @@ pub async fn process_event_with_backoff(
     chain_state: BlockchainState,
     contract: Arc<InstrumentedSignablePythContract>,
     gas_limit: U256,
+    max_callback_revert_retries: u32,
     metrics: Arc<KeeperMetrics>,
 ) {
     metrics
@@ -351,6 +520,8 @@ pub async fn process_event_with_backoff(
         })
         .inc();
     tracing::info!("Started processing event");
+    let start_time = Instant::now();
+    let callback_reverts = AtomicU32::new(0);
     let backoff = ExponentialBackoff {
         max_elapsed_time: Some(Duration::from_secs(300)), // retry for 5 minutes
         ..Default::default()
@@ -358,7 +529,11 @@ pub async fn process_event_with_backoff(
     match backoff::future::retry_notify(
         backoff,
         || async {
-            process_event(&event, &chain_state, &contract, gas_limit, metrics.clone()).await
+            limit_callback_revert_retries(
+                process_event(&event, &chain_state, &contract, gas_limit, metrics.clone()).await,
+                &callback_reverts,
+                max_callback_revert_retries,
+            )
         },
         |e, dur| {
             tracing::error!("Error happened at {:?}: {}", dur, e);
@@ -368,9 +543,28 @@ pub async fn process_event_with_backoff(
     {
         Ok(()) => {
             tracing::info!("Processed event",);
+            metrics
+                .reveal_latency
+                .get_or_create(&AccountLabel {
+                    chain_id: chain_state.id.clone(),
+                    address: chain_state.provider_address.to_string(),
+                })
+                .observe(start_time.elapsed().as_secs_f64());
         }
         Err(e) => {
-            tracing::error!("Failed to process event: {:?}", e);
+            tracing::error!(
+                error_class = ?e.class,
+                "Abandoning request. Failed to process event: {:?}",
+                e
+            );
+            metrics
+                .requests_abandoned
+                .get_or_create(&RevealErrorLabel {
+                    chain_id: chain_state.id.clone(),
+                    address: chain_state.provider_address.to_string(),
+                    class: e.class,
+                })
+                .inc();
         }
     }
     metrics
@@ -382,27 +576,67 @@ pub async fn process_event_with_backoff(
         .inc();
 }
 
+/// Makes a transient callback revert permanent once it happened more than
+/// `max_callback_revert_retries` times for the same request. Other errors are returned unchanged.
+fn limit_callback_revert_retries(
+    result: Result<(), backoff::Error<RevealFailure>>,
+    callback_reverts: &AtomicU32,
+    max_callback_revert_retries: u32,
+) -> Result<(), backoff::Error<RevealFailure>> {
+    match result {
+        Err(backoff::Error::Transient { err, .. })
+            if err.class == RevealErrorClass::CallbackRevert =>
+        {
+            let reverts = callback_reverts.fetch_add(1, Ordering::Relaxed) + 1;
+            if reverts > max_callback_revert_retries {
+                Err(backoff::Error::permanent(err))
+            } else {
+                Err(backoff::Error::transient(err))
+            }
+        }
+        result => result,
+    }
+}
+
 const TX_CONFIRMATION_TIMEOUT_SECS: u64 = 30;
 
 /// Process a callback on a chain. It estimates the gas for the reveal with callback and
 /// submits the transaction if the gas estimate is below the gas limit.
 /// It will return a permanent or transient error depending on the error type and whether
-/// retry is possible or not.
+/// retry is possible or not. Failures of the consumer callback are reported with the
+/// `CallbackRevert` class to distinguish them from infrastructure failures.
 pub async fn process_event(
     event: &RequestedWithCallbackEvent,
     chain_config: &BlockchainState,
     contract: &InstrumentedSignablePythContract,
     gas_limit: U256,
     metrics: Arc<KeeperMetrics>,
-) -> Result<(), backoff::Error<anyhow::Error>> {
+) -> Result<(), backoff::Error<RevealFailure>> {
     // ignore requests that are not for the configured provider
     if chain_config.provider_address != event.provider_address {
         return Ok(());
     }
+    let fail = |class: RevealErrorClass, error: anyhow::Error| {
+        metrics
+            .reveal_errors
+            .get_or_create(&RevealErrorLabel {
+                chain_id: chain_config.id.clone(),
+                address: chain_config.provider_address.to_string(),
+                class,
+            })
+            .inc();
+        RevealFailure { class, error }
+    };
+
     let provider_revelation = chain_config
         .state
         .reveal(event.sequence_number)
-        .map_err(|e| backoff::Error::permanent(anyhow!("Error revealing: {:?}", e)))?;
+        .map_err(|e| {
+            backoff::Error::permanent(fail(
+                RevealErrorClass::Revelation,
+                anyhow!("Error revealing: {:?}", e),
+            ))
+        })?;
 
     let gas_estimate_res = chain_config
         .contract
@@ -417,17 +651,49 @@ pub async fn process_event(
         .await;
 
     let gas_estimate = gas_estimate_res.map_err(|e| {
-        // we consider the error transient even if it is a contract revert since
-        // it can be because of routing to a lagging RPC node. Retrying such errors will
-        // incur a few additional RPC calls, but it is fine.
-        backoff::Error::transient(anyhow!("Error estimating gas for reveal: {:?}", e))
+        backoff::Error::transient(fail(
+            RevealErrorClass::GasEstimate,
+            anyhow!("Error estimating gas for reveal: {:?}", e),
+        ))
     })?;
 
+    let gas_estimate = match gas_estimate {
+        Some(gas_estimate) => gas_estimate,
+        None => {
+            // The reveal also reverts if the request has already been fulfilled, in which case
+            // there is nothing left to do.
+            let request = chain_config
+                .contract
+                .get_request(event.provider_address, event.sequence_number)
+                .await
+                .map_err(|e| {
+                    backoff::Error::transient(fail(
+                        RevealErrorClass::GasEstimate,
+                        anyhow!("Error fetching request after reveal revert: {:?}", e),
+                    ))
+                })?;
+            if request.is_none() {
+                tracing::info!("Request has already been fulfilled");
+                return Ok(());
+            }
+
+            // We consider the revert transient since it can be because of routing to a lagging
+            // RPC node. The caller bounds the number of retries for callback reverts.
+            return Err(backoff::Error::transient(fail(
+                RevealErrorClass::CallbackRevert,
+                anyhow!("Reveal with callback reverted while the request is still pending"),
+            )));
+        }
+    };
+
     if gas_estimate > gas_limit {
-        return Err(backoff::Error::permanent(anyhow!(
-            "Gas estimate for reveal with callback is higher than the gas limit {} > {}",
-            gas_estimate,
-            gas_limit
+        return Err(backoff::Error::permanent(fail(
+            RevealErrorClass::GasLimitExceeded,
+            anyhow!(
+                "Gas estimate for reveal with callback is higher than the gas limit {} > {}",
+                gas_estimate,
+                gas_limit
+            ),
         )));
     }
 
@@ -450,16 +716,22 @@ pub async fn process_event(
         .fill_transaction(&mut transaction, None)
         .await
         .map_err(|e| {
-            backoff::Error::transient(anyhow!("Error filling the reveal transaction: {:?}", e))
+            backoff::Error::transient(fail(
+                RevealErrorClass::Submission,
+                anyhow!("Error filling the reveal transaction: {:?}", e),
+            ))
         })?;
     let pending_tx = client
         .send_transaction(transaction.clone(), None)
         .await
         .map_err(|e| {
-            backoff::Error::transient(anyhow!(
-                "Error submitting the reveal transaction. Tx:{:?}, Error:{:?}",
-                transaction,
-                e
+            backoff::Error::transient(fail(
+                RevealErrorClass::Submission,
+                anyhow!(
+                    "Error submitting the reveal transaction. Tx:{:?}, Error:{:?}",
+                    transaction,
+                    e
+                ),
             ))
         })?;
 
@@ -478,27 +750,33 @@ pub async fn process_event(
         // in this case ethers internal polling will not reduce the number of retries
         // and keep retrying indefinitely. So we set a manual timeout here and reset the nonce.
         reset_nonce();
-        backoff::Error::transient(anyhow!(
-            "Tx stuck in mempool. Resetting nonce. Tx:{:?}",
-            transaction
+        backoff::Error::transient(fail(
+            RevealErrorClass::Confirmation,
+            anyhow!("Tx stuck in mempool. Resetting nonce. Tx:{:?}", transaction),
         ))
     })?;
 
     let receipt = pending_receipt
         .map_err(|e| {
-            backoff::Error::transient(anyhow!(
-                "Error waiting for transaction receipt. Tx:{:?} Error:{:?}",
-                transaction,
-                e
+            backoff::Error::transient(fail(
+                RevealErrorClass::Confirmation,
+                anyhow!(
+                    "Error waiting for transaction receipt. Tx:{:?} Error:{:?}",
+                    transaction,
+                    e
+                ),
             ))
         })?
         .ok_or_else(|| {
             // RPC may not return an error on tx submission if the nonce is too high.
             // But we will never get a receipt. So we reset the nonce manager to get the correct nonce.
             reset_nonce();
-            backoff::Error::transient(anyhow!(
-                "Can't verify the reveal, probably dropped from mempool. Resetting nonce. Tx:{:?}",
-                transaction
+            backoff::Error::transient(fail(
+                RevealErrorClass::Confirmation,
+                anyhow!(
+                    "Can't verify the reveal, probably dropped from mempool. Resetting nonce. Tx:{:?}",
+                    transaction
+                ),
             ))
         })?;
 
@@ -510,34 +788,77 @@ pub async fn process_event(
         receipt
     );
 
+    let label = AccountLabel {
+        chain_id: chain_config.id.clone(),
+        address: chain_config.provider_address.to_string(),
+    };
     if let Some(gas_used) = receipt.gas_used {
-        let gas_used = gas_used.as_u128() as f64 / 1e18;
-        metrics
-            .total_gas_spent
-            .get_or_create(&AccountLabel {
-                chain_id: chain_config.id.clone(),
-                address: client
-                    .inner()
-                    .inner()
-                    .inner()
-                    .signer()
-                    .address()
-                    .to_string(),
-            })
-            .inc_by(gas_used);
+        let keeper_label = AccountLabel {
+            chain_id: chain_config.id.clone(),
+            address: contract.wallet().address().to_string(),
+        };
+        track_gas_spent(
+            &metrics,
+            &label,
+            &keeper_label,
+            gas_used,
+            receipt.effective_gas_price,
+        );
     }
 
-    metrics
-        .reveals
-        .get_or_create(&AccountLabel {
-            chain_id: chain_config.id.clone(),
-            address: chain_config.provider_address.to_string(),
-        })
-        .inc();
+    // The reveal transaction can still revert if the consumer's callback behaves differently
+    // than it did during gas estimation. Unlike a revert during gas estimation, this one was
+    // mined and paid for, so we don't retry it.
+    if receipt.status == Some(0.into()) {
+        return Err(backoff::Error::permanent(fail(
+            RevealErrorClass::CallbackRevert,
+            anyhow!(
+                "Reveal transaction reverted. Tx hash: {:?}",
+                receipt.transaction_hash
+            ),
+        )));
+    }
+
+    metrics.reveals.get_or_create(&label).inc();
 
     Ok(())
 }
 
+/// Converts `value` to a metric value, saturating at `u128::MAX` instead of panicking.
+fn u256_to_f64(value: U256) -> f64 {
+    u128::try_from(value).unwrap_or(u128::MAX) as f64
+}
+
+/// Records the gas used by a reveal transaction, and its fee if the gas price is known.
+/// `total_gas_spent` is labelled with the keeper wallet that paid for the transaction, and
+/// the other metrics with the provider.
+fn track_gas_spent(
+    metrics: &KeeperMetrics,
+    provider_label: &AccountLabel,
+    keeper_label: &AccountLabel,
+    gas_used: U256,
+    gas_price: Option<U256>,
+) {
+    metrics
+        .reveal_gas_used
+        .get_or_create(provider_label)
+        .observe(u256_to_f64(gas_used));
+
+    if let Some(gas_price) = gas_price {
+        // The fee is in wei, so we divide by 1e18 to convert it to eth.
+        let gas_fee = u256_to_f64(gas_used.saturating_mul(gas_price)) / 1e18;
+        metrics
+            .total_gas_fee_spent
+            .get_or_create(provider_label)
+            .inc_by(gas_fee);
+    }
+
+    metrics
+        .total_gas_spent
+        .get_or_create(keeper_label)
+        .inc_by(u256_to_f64(gas_used) / 1e18);
+}
+
 /// Process a range of blocks in batches. It calls the `process_single_block_batch` method for each batch.
 #[tracing::instrument(skip_all, fields(
     range_from_block = block_range.from, range_to_block = block_range.to
@@ -546,6 +867,7 @@ pub async fn process_block_range(
     block_range: BlockRange,
     contract: Arc<InstrumentedSignablePythContract>,
     gas_limit: U256,
+    max_callback_revert_retries: u32,
     chain_state: api::BlockchainState,
     metrics: Arc<KeeperMetrics>,
     fulfilled_requests_cache: Arc<RwLock<HashSet<u64>>>,
@@ -569,6 +891,7 @@ pub async fn process_block_range(
             },
             contract.clone(),
             gas_limit,
+            max_callback_revert_retries,
             chain_state.clone(),
             metrics.clone(),
             fulfilled_requests_cache.clone(),
@@ -591,6 +914,7 @@ pub async fn process_single_block_batch(
     block_range: BlockRange,
     contract: Arc<InstrumentedSignablePythContract>,
     gas_limit: U256,
+    max_callback_revert_retries: u32,
     chain_state: api::BlockchainState,
     metrics: Arc<KeeperMetrics>,
     fulfilled_requests_cache: Arc<RwLock<HashSet<u64>>>,
@@ -617,6 +941,7 @@ pub async fn process_single_block_batch(
                                 chain_state.clone(),
                                 contract.clone(),
                                 gas_limit,
+                                max_callback_revert_retries,
                                 metrics.clone(),
                             )
                             .in_current_span(),
@@ -624,6 +949,14 @@ pub async fn process_single_block_batch(
                     }
                 }
                 tracing::info!(num_of_events = &events.len(), "Processed",);
+                metrics
+                    .last_processed_block
+                    .get_or_create(&AccountLabel {
+                        chain_id: chain_state.id.clone(),
+                        address: chain_state.provider_address.to_string(),
+                    })
+                    // Block numbers fit comfortably in an i64, which is what prometheus supports for gauges.
+                    .set(block_range.to as i64);
                 break;
             }
             Err(e) => {
@@ -763,6 +1096,7 @@ pub async fn process_new_blocks(
     mut rx: mpsc::Receiver<BlockRange>,
     contract: Arc<InstrumentedSignablePythContract>,
     gas_limit: U256,
+    max_callback_revert_retries: u32,
     metrics: Arc<KeeperMetrics>,
     fulfilled_requests_cache: Arc<RwLock<HashSet<u64>>>,
 ) {
@@ -773,6 +1107,7 @@ pub async fn process_new_blocks(
                 block_range,
                 Arc::clone(&contract),
                 gas_limit,
+                max_callback_revert_retries,
                 chain_state.clone(),
                 metrics.clone(),
                 fulfilled_requests_cache.clone(),
@@ -789,6 +1124,7 @@ pub async fn process_backlog(
     backlog_range: BlockRange,
     contract: Arc<InstrumentedSignablePythContract>,
     gas_limit: U256,
+    max_callback_revert_retries: u32,
     chain_state: BlockchainState,
     metrics: Arc<KeeperMetrics>,
     fulfilled_requests_cache: Arc<RwLock<HashSet<u64>>>,
@@ -798,6 +1134,7 @@ pub async fn process_backlog(
         backlog_range,
         contract,
         gas_limit,
+        max_callback_revert_retries,
         chain_state,
         metrics,
         fulfilled_requests_cache,
@@ -898,27 +1235,158 @@ pub async fn track_provider(
         .set(end_sequence_number as i64);
 }
 
-#[tracing::instrument(name = "withdraw_fees", skip_all, fields())]
-pub async fn withdraw_fees_wrapper(
+#[tracing::instrument(name = "top_up_keeper_balance", skip_all)]
+#[allow(clippy::too_many_arguments)]
+pub async fn top_up_keeper_balance_wrapper(
     contract: Arc<InstrumentedSignablePythContract>,
+    treasury_contract: Option<Arc<InstrumentedSignablePythContract>>,
+    chain_id: ChainId,
     provider_address: Address,
     poll_interval: Duration,
     min_balance: U256,
+    top_up_actions: Vec<TopUpAction>,
+    treasury_top_up_amount: U256,
+    metrics: Arc<KeeperMetrics>,
 ) {
     loop {
-        if let Err(e) = withdraw_fees_if_necessary(contract.clone(), provider_address, min_balance)
-            .in_current_span()
-            .await
+        if let Err(e) = top_up_keeper_balance_if_necessary(
+            contract.clone(),
+            treasury_contract.clone(),
+            &chain_id,
+            provider_address,
+            min_balance,
+            &top_up_actions,
+            treasury_top_up_amount,
+            metrics.clone(),
+        )
+        .in_current_span()
+        .await
         {
-            tracing::error!("Withdrawing fees. error: {:?}", e);
+            tracing::error!("Topping up keeper balance. error: {:?}", e);
         }
         time::sleep(poll_interval).await;
     }
 }
 
+/// Runs the configured top-up actions in order until the keeper wallet balance is at least `min_balance`.
+/// A failing action is logged and does not prevent the subsequent actions from running.
+#[allow(clippy::too_many_arguments)]
+pub async fn top_up_keeper_balance_if_necessary<T: JsonRpcClient + Clone + 'static>(
+    contract: Arc<SignablePythContractInner<T>>,
+    treasury_contract: Option<Arc<SignablePythContractInner<T>>>,
+    chain_id: &ChainId,
+    provider_address: Address,
+    min_balance: U256,
+    top_up_actions: &[TopUpAction],
+    treasury_top_up_amount: U256,
+    metrics: Arc<KeeperMetrics>,
+) -> Result<()> {
+    let keeper_address = contract.wallet().address();
+    let label = AccountLabel {
+        chain_id: chain_id.clone(),
+        address: keeper_address.to_string(),
+    };
+
+    for action in top_up_actions {
+        let keeper_balance = contract
+            .provider()
+            .get_balance(keeper_address, None)
+            .await
+            .map_err(|e| anyhow!("Error while getting balance. error: {:?}", e))?;
+        if keeper_balance >= min_balance {
+            return Ok(());
+        }
+
+        let result = match action {
+            TopUpAction::WithdrawFees => {
+                withdraw_fees_if_necessary(contract.clone(), provider_address, min_balance).await
+            }
+            TopUpAction::TreasuryTransfer => match &treasury_contract {
+                Some(treasury_contract) => {
+                    let result = transfer_from_treasury(
+                        treasury_contract.clone(),
+                        keeper_address,
+                        treasury_top_up_amount,
+                    )
+                    .await;
+                    if result.is_ok() {
+                        metrics.treasury_top_ups.get_or_create(&label).inc();
+                    }
+                    result
+                }
+                None => Err(anyhow!("No treasury private key is configured")),
+            },
+            TopUpAction::Alert => {
+                tracing::error!(
+                    "Keeper balance {:?} is below the configured minimum {:?}",
+                    keeper_balance,
+                    min_balance
+                );
+                metrics.low_balance_alerts.get_or_create(&label).inc();
+                Ok(())
+            }
+        };
+
+        if let Err(e) = result {
+            tracing::error!("Top-up action {:?} failed. error: {:?}", action, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Transfers `amount` wei from the treasury wallet to the keeper wallet.
+pub async fn transfer_from_treasury<T: JsonRpcClient + Clone + 'static>(
+    treasury_contract: Arc<SignablePythContractInner<T>>,
+    keeper_address: Address,
+    amount: U256,
+) -> Result<()> {
+    let treasury_address = treasury_contract.wallet().address();
+    let treasury_balance = treasury_contract
+        .provider()
+        .get_balance(treasury_address, None)
+        .await
+        .map_err(|e| anyhow!("Error while getting treasury balance. error: {:?}", e))?;
+
+    if treasury_balance < amount {
+        return Err(anyhow!(
+            "Treasury balance {:?} is too low to transfer {:?} to the keeper",
+            treasury_balance,
+            amount
+        ));
+    }
+
+    tracing::info!(
+        "Transferring {:?} from treasury {:?} to keeper {:?}...",
+        amount,
+        treasury_address,
+        keeper_address
+    );
+    let client = treasury_contract.client();
+    let transaction = TransactionRequest::new().to(keeper_address).value(amount);
+    let pending_tx = client
+        .send_transaction(transaction, None)
+        .await
+        .map_err(|e| anyhow!("Error submitting treasury transfer {:?}", e))?;
+
+    let tx_result = pending_tx
+        .await
+        .map_err(|e| anyhow!("Error waiting for treasury transfer receipt: {:?}", e))?
+        .ok_or_else(|| {
+            anyhow!("Can't verify the treasury transfer, probably dropped from mempool")
+        })?;
+
+    tracing::info!(
+        transaction_hash = &tx_result.transaction_hash.to_string(),
+        "Confirmed treasury transfer. Receipt: {:?}",
+        tx_result,
+    );
+    Ok(())
+}
+
 /// Withdraws accumulated fees in the contract as needed to maintain the balance of the keeper wallet.
-pub async fn withdraw_fees_if_necessary(
-    contract: Arc<InstrumentedSignablePythContract>,
+pub async fn withdraw_fees_if_necessary<T: JsonRpcClient + Clone + 'static>(
+    contract: Arc<SignablePythContractInner<T>>,
     provider_address: Address,
     min_balance: U256,
 ) -> Result<()> {
@@ -953,7 +1421,9 @@ pub async fn withdraw_fees_if_necessary(
     Ok(())
 }
 
-pub async fn send_and_confirm(contract_call: PythContractCall) -> Result<()> {
+pub async fn send_and_confirm<T: JsonRpcClient + 'static>(
+    contract_call: PythContractCall<T>,
+) -> Result<()> {
     let call_name = contract_call.function.name.as_str();
     let pending_tx = contract_call
         .send()
@@ -1112,18 +1582,9 @@ pub async fn adjust_fee_if_necessary(
     let max_callback_cost: u128 = estimate_tx_cost(contract.clone(), legacy_tx, gas_limit.into())
         .await
         .map_err(|e| anyhow!("Could not estimate transaction cost. error {:?}", e))?;
-    let target_fee_min = std::cmp::max(
-        (max_callback_cost * (100 + u128::from(min_profit_pct))) / 100,
-        min_fee_wei,
-    );
-    let target_fee = std::cmp::max(
-        (max_callback_cost * (100 + u128::from(target_profit_pct))) / 100,
-        min_fee_wei,
-    );
-    let target_fee_max = std::cmp::max(
-        (max_callback_cost * (100 + u128::from(max_profit_pct))) / 100,
-        min_fee_wei,
-    );
+    let target_fee_min = fee_with_profit(max_callback_cost, min_profit_pct, min_fee_wei);
+    let target_fee = fee_with_profit(max_callback_cost, target_profit_pct, min_fee_wei);
+    let target_fee_max = fee_with_profit(max_callback_cost, max_profit_pct, min_fee_wei);
 
     // Calculate current P&L to determine if we can reduce fees.
     let current_keeper_balance = contract
@@ -1192,9 +1653,18 @@ pub async fn adjust_fee_if_necessary(
     Ok(())
 }
 
+/// The fee that makes a profit of `profit_pct` percent over `max_callback_cost`, with min_fee_wei
+/// as a lower bound.
+pub fn fee_with_profit(max_callback_cost: u128, profit_pct: u64, min_fee_wei: u128) -> u128 {
+    std::cmp::max(
+        (max_callback_cost * (100 + u128::from(profit_pct))) / 100,
+        min_fee_wei,
+    )
+}
+
 /// Estimate the cost (in wei) of a transaction consuming gas_used gas.
-pub async fn estimate_tx_cost(
-    contract: Arc<InstrumentedSignablePythContract>,
+pub async fn estimate_tx_cost<M: Middleware + 'static>(
+    contract: Arc<PythRandom<M>>,
     use_legacy_tx: bool,
     gas_used: u128,
 ) -> Result<u128> {
@@ -1219,3 +1689,281 @@ pub async fn estimate_tx_cost(
 
     Ok(gas_price * gas_used)
 }
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        ethers::{
+            providers::MockProvider,
+            types::{U256, U64},
+        },
+        prometheus_client::encoding::text::encode,
+    };
+
+    const KEEPER_PRIVATE_KEY: &str =
+        "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const TREASURY_PRIVATE_KEY: &str =
+        "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    /// Creates a contract whose RPC calls are answered with `responses`, in order.
+    async fn mock_contract(
+        private_key: &str,
+        responses: &[U256],
+    ) -> Arc<SignablePythContractInner<MockProvider>> {
+        let chain_config: EthereumConfig = serde_yaml::from_str(
+            "
+geth_rpc_addr: http://localhost:8545
+contract_addr: 0x0000000000000000000000000000000000000001
+reveal_delay_blocks: 0
+gas_limit: 500000
+min_profit_pct: 0
+target_profit_pct: 20
+max_profit_pct: 100
+",
+        )
+        .unwrap();
+        let (provider, mock) = Provider::mocked();
+        // The mock answers the last pushed response first
+        for response in responses.iter().rev() {
+            mock.push::<U256, _>(response).unwrap();
+        }
+        mock.push(U64::from(1)).unwrap();
+        Arc::new(
+            SignablePythContractInner::from_config_and_provider(
+                &chain_config,
+                private_key,
+                provider,
+            )
+            .await
+            .unwrap(),
+        )
+    }
+
+    fn label(contract: &SignablePythContractInner<MockProvider>) -> AccountLabel {
+        AccountLabel {
+            chain_id: "test".to_string(),
+            address: contract.wallet().address().to_string(),
+        }
+    }
+
+    async fn top_up(
+        contract: Arc<SignablePythContractInner<MockProvider>>,
+        treasury_contract: Option<Arc<SignablePythContractInner<MockProvider>>>,
+        top_up_actions: &[TopUpAction],
+        metrics: Arc<KeeperMetrics>,
+    ) {
+        top_up_keeper_balance_if_necessary(
+            contract,
+            treasury_contract,
+            &"test".to_string(),
+            Address::zero(),
+            U256::from(1000),
+            top_up_actions,
+            U256::from(500),
+            metrics,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_track_gas_spent() {
+        let metrics = KeeperMetrics::default();
+        let label = AccountLabel {
+            chain_id: "test".to_string(),
+            address: Address::zero().to_string(),
+        };
+        let keeper_label = AccountLabel {
+            chain_id: "test".to_string(),
+            address: Address::repeat_byte(1).to_string(),
+        };
+
+        track_gas_spent(&metrics, &label, &keeper_label, U256::from(100_000), None);
+        track_gas_spent(
+            &metrics,
+            &label,
+            &keeper_label,
+            U256::from(100_000),
+            Some(U256::from(10_000_000_000u64)),
+        );
+        assert_eq!(
+            metrics.total_gas_spent.get_or_create(&keeper_label).get(),
+            2e-13
+        );
+        assert_eq!(metrics.total_gas_spent.get_or_create(&label).get(), 0.0);
+        assert_eq!(
+            metrics.total_gas_fee_spent.get_or_create(&label).get(),
+            1e-3
+        );
+
+        // A fee that doesn't fit in a u128 saturates instead of panicking
+        track_gas_spent(&metrics, &label, &keeper_label, U256::MAX, Some(U256::MAX));
+        assert_eq!(
+            metrics.total_gas_fee_spent.get_or_create(&label).get(),
+            1e-3 + u128::MAX as f64 / 1e18
+        );
+    }
+
+    fn failure(class: RevealErrorClass) -> RevealFailure {
+        RevealFailure {
+            class,
+            error: anyhow!("test"),
+        }
+    }
+
+    fn is_transient(result: &Result<(), backoff::Error<RevealFailure>>) -> bool {
+        matches!(result, Err(backoff::Error::Transient { .. }))
+    }
+
+    #[test]
+    fn test_limit_callback_revert_retries() {
+        let callback_reverts = AtomicU32::new(0);
+        let callback_revert = || {
+            Err(backoff::Error::transient(failure(
+                RevealErrorClass::CallbackRevert,
+            )))
+        };
+
+        for _ in 0..2 {
+            let result = limit_callback_revert_retries(callback_revert(), &callback_reverts, 2);
+            assert!(is_transient(&result));
+        }
+        // Other errors don't count towards the limit
+        let result = limit_callback_revert_retries(
+            Err(backoff::Error::transient(failure(
+                RevealErrorClass::Submission,
+            ))),
+            &callback_reverts,
+            2,
+        );
+        assert!(is_transient(&result));
+        // The request is abandoned after the configured number of retries
+        let result = limit_callback_revert_retries(callback_revert(), &callback_reverts, 2);
+        assert!(matches!(
+            result,
+            Err(backoff::Error::Permanent(RevealFailure {
+                class: RevealErrorClass::CallbackRevert,
+                ..
+            }))
+        ));
+
+        // Permanent errors and successes are returned unchanged
+        let callback_reverts = AtomicU32::new(0);
+        let result = limit_callback_revert_retries(
+            Err(backoff::Error::permanent(failure(
+                RevealErrorClass::CallbackRevert,
+            ))),
+            &callback_reverts,
+            2,
+        );
+        assert!(matches!(result, Err(backoff::Error::Permanent(_))));
+        assert!(limit_callback_revert_retries(Ok(()), &callback_reverts, 2).is_ok());
+        assert_eq!(callback_reverts.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_no_retries_for_callback_reverts() {
+        let result = limit_callback_revert_retries(
+            Err(backoff::Error::transient(failure(
+                RevealErrorClass::CallbackRevert,
+            ))),
+            &AtomicU32::new(0),
+            0,
+        );
+        assert!(matches!(result, Err(backoff::Error::Permanent(_))));
+    }
+
+    #[tokio::test]
+    async fn test_reveal_metrics_are_registered() {
+        let registry = Arc::new(RwLock::new(Registry::default()));
+        let metrics = KeeperMetrics::new(registry.clone()).await;
+        let label = AccountLabel {
+            chain_id: "test".to_string(),
+            address: Address::zero().to_string(),
+        };
+        metrics
+            .reveal_errors
+            .get_or_create(&RevealErrorLabel {
+                chain_id: label.chain_id.clone(),
+                address: label.address.clone(),
+                class: RevealErrorClass::GasEstimate,
+            })
+            .inc();
+        metrics.reveal_latency.get_or_create(&label).observe(3.0);
+        track_gas_spent(&metrics, &label, &label, U256::from(100_000), None);
+
+        let mut buffer = String::new();
+        encode(&mut buffer, &*registry.read().await).unwrap();
+        let address = Address::zero().to_string();
+        assert!(buffer.contains(&format!(
+            r#"reveal_errors_total{{chain_id="test",address="{address}",class="GasEstimate"}} 1"#
+        )));
+        assert!(buffer.contains(&format!(
+            r#"reveal_latency_bucket{{le="5.0",chain_id="test",address="{address}"}} 1"#
+        )));
+        assert!(buffer.contains(&format!(
+            r#"reveal_gas_used_sum{{chain_id="test",address="{address}"}} 100000.0"#
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_top_up_skipped_above_min_balance() {
+        let contract = mock_contract(KEEPER_PRIVATE_KEY, &[U256::from(1000)]).await;
+        let metrics = Arc::new(KeeperMetrics::default());
+
+        top_up(
+            contract.clone(),
+            None,
+            &[TopUpAction::Alert, TopUpAction::Alert],
+            metrics.clone(),
+        )
+        .await;
+
+        assert_eq!(
+            metrics
+                .low_balance_alerts
+                .get_or_create(&label(&contract))
+                .get(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_top_up_continues_after_failed_action() {
+        let contract = mock_contract(KEEPER_PRIVATE_KEY, &[U256::from(999), U256::from(999)]).await;
+        let metrics = Arc::new(KeeperMetrics::default());
+
+        // The treasury transfer fails without a treasury wallet, which still raises the alert
+        top_up(
+            contract.clone(),
+            None,
+            &[TopUpAction::TreasuryTransfer, TopUpAction::Alert],
+            metrics.clone(),
+        )
+        .await;
+
+        let label = label(&contract);
+        assert_eq!(metrics.treasury_top_ups.get_or_create(&label).get(), 0);
+        assert_eq!(metrics.low_balance_alerts.get_or_create(&label).get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_top_up_treasury_balance_too_low() {
+        let contract = mock_contract(KEEPER_PRIVATE_KEY, &[U256::from(999), U256::from(999)]).await;
+        let treasury_contract = mock_contract(TREASURY_PRIVATE_KEY, &[U256::from(499)]).await;
+        let metrics = Arc::new(KeeperMetrics::default());
+
+        top_up(
+            contract.clone(),
+            Some(treasury_contract),
+            &[TopUpAction::TreasuryTransfer, TopUpAction::Alert],
+            metrics.clone(),
+        )
+        .await;
+
+        let label = label(&contract);
+        assert_eq!(metrics.treasury_top_ups.get_or_create(&label).get(), 0);
+        assert_eq!(metrics.low_balance_alerts.get_or_create(&label).get(), 1);
+    }
+}
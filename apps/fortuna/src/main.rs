@@ -1,6 +1,9 @@
 #![allow(clippy::just_underscores_and_digits)]
 
-use {anyhow::Result, clap::Parser, std::io::IsTerminal};
+use {
+    anyhow::Result, clap::Parser, opentelemetry_otlp::WithExportConfig, std::io::IsTerminal,
+    tracing_subscriber::layer::SubscriberExt,
+};
 
 pub mod api;
 pub mod chain;
@@ -18,6 +21,35 @@ pub mod state;
 #[tokio::main]
 #[tracing::instrument]
 async fn main() -> Result<()> {
+    let options = config::Options::parse();
+
+    // Only the long-running service exports traces; one-off commands just log to stderr.
+    let otlp_endpoint = match &options {
+        config::Options::Run(opts) => opts.otlp_endpoint.clone(),
+        _ => None,
+    };
+    let otlp_layer = match otlp_endpoint {
+        Some(endpoint) => Some(
+            tracing_opentelemetry::layer().with_tracer(
+                opentelemetry_otlp::new_pipeline()
+                    .tracing()
+                    .with_exporter(
+                        opentelemetry_otlp::new_exporter()
+                            .tonic()
+                            .with_endpoint(endpoint),
+                    )
+                    .with_trace_config(opentelemetry_sdk::trace::config().with_resource(
+                        opentelemetry_sdk::Resource::new(vec![opentelemetry::KeyValue::new(
+                            "service.name",
+                            "fortuna",
+                        )]),
+                    ))
+                    .install_batch(opentelemetry_sdk::runtime::Tokio)?,
+            ),
+        ),
+        None => None,
+    };
+
     // Initialize a Tracing Subscriber
     tracing::subscriber::set_global_default(
         tracing_subscriber::fmt()
@@ -27,10 +59,11 @@ async fn main() -> Result<()> {
             .with_thread_ids(true)
             .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
             .with_ansi(std::io::stderr().is_terminal())
-            .finish(),
+            .finish()
+            .with(otlp_layer),
     )?;
 
-    match config::Options::parse() {
+    let result = match options {
         config::Options::GetRequest(opts) => command::get_request(&opts).await,
         config::Options::Generate(opts) => command::generate(&opts).await,
         config::Options::Run(opts) => command::run(&opts).await,
@@ -39,5 +72,11 @@ async fn main() -> Result<()> {
         config::Options::RequestRandomness(opts) => command::request_randomness(&opts).await,
         config::Options::Inspect(opts) => command::inspect(&opts).await,
         config::Options::WithdrawFees(opts) => command::withdraw_fees(&opts).await,
-    }
+        config::Options::Admin(opts) => command::admin(&opts).await,
+    };
+
+    // Flush any traces that have not been exported yet.
+    opentelemetry::global::shutdown_tracer_provider();
+
+    result
 }
@@ -0,0 +1,199 @@
+use {
+    crate::state::State,
+    axum::{
+        http::StatusCode,
+        response::{IntoResponse, Response},
+        routing::get,
+        Router,
+    },
+    prometheus_client::registry::Registry,
+    std::sync::Arc,
+    tokio::sync::RwLock,
+    tower_http::cors::CorsLayer,
+};
+pub use {live::*, metrics::*, proposals::*, ready::*, targets::*};
+
+mod live;
+mod metrics;
+mod proposals;
+mod ready;
+mod targets;
+pub mod types;
+
+#[derive(Clone)]
+pub struct ApiState {
+    pub state: Arc<State>,
+    pub metrics_registry: Arc<RwLock<Registry>>,
+}
+
+pub enum RestError {
+    ProposalNotFound { sequence: u64 },
+}
+
+impl IntoResponse for RestError {
+    fn into_response(self) -> Response {
+        match self {
+            RestError::ProposalNotFound { sequence } => (
+                StatusCode::NOT_FOUND,
+                format!("Proposal not found: {}", sequence),
+            )
+                .into_response(),
+        }
+    }
+}
+
+pub fn routes(state: ApiState) -> Router {
+    Router::new()
+        .route("/v1/targets", get(targets))
+        .route("/v1/proposals", get(proposals))
+        .route("/v1/proposals/:sequence", get(proposal))
+        .route("/metrics", get(metrics))
+        .route("/live", get(live))
+        .route("/ready", get(ready))
+        .with_state(state)
+        // Permissive CORS layer to allow all origins
+        .layer(CorsLayer::permissive())
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::{
+            governance::{GovernanceHeader, GovernanceModule, Proposal},
+            metrics::Metrics,
+            state::unix_timestamp,
+            targets::{TargetConfig, TargetContract, Targets},
+        },
+        axum_test::TestServer,
+        serde_json::json,
+    };
+
+    fn target(name: &str, chain_id: u16) -> TargetConfig {
+        TargetConfig {
+            name: name.to_string(),
+            chain_id,
+            contract: TargetContract::Evm {
+                rpc_url: "http://localhost:8545".parse().unwrap(),
+                address: "0x01".to_string(),
+            },
+        }
+    }
+
+    fn proposal(sequence: u64, timestamp: u64, target_chain_id: u16) -> Proposal {
+        Proposal {
+            sequence,
+            timestamp,
+            header: Some(GovernanceHeader {
+                module: GovernanceModule::Target,
+                action: 3,
+                target_chain_id,
+            }),
+        }
+    }
+
+    async fn server() -> TestServer {
+        let ethereum = target("ethereum", 2);
+        let bsc = target("bsc", 4);
+        let mut registry = Registry::default();
+        let metrics = Metrics::new(&mut registry);
+        let state = Arc::new(State::new(
+            Targets::new([ethereum.clone(), bsc.clone()]).unwrap(),
+            5,
+            metrics,
+        ));
+        state.set_last_executed_sequence(&ethereum, 4, 100).await;
+        state.set_last_executed_sequence(&bsc, 4, 100).await;
+        state.add_proposal(proposal(5, 1_000, 0)).await;
+        state.add_proposal(proposal(6, 2_000, 4)).await;
+        state.set_last_executed_sequence(&ethereum, 5, 1_030).await;
+        state.set_error(&bsc, "timeout".to_string(), 1_040).await;
+        TestServer::new(
+            routes(ApiState {
+                state,
+                metrics_registry: Arc::new(RwLock::new(registry)),
+            })
+            .into_make_service(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_proposals() {
+        let server = server().await;
+
+        server.get("/v1/proposals/5").await.assert_json(&json!({
+            "sequence": 5,
+            "timestamp": 1000,
+            "module": "target",
+            "action": 3,
+            "targetChainId": 0,
+            "executions": {
+                "bsc": {"status": "pending", "lagSecs": null},
+                "ethereum": {"status": "executed", "executedAt": 1030, "lagSecs": 30},
+            },
+        }));
+
+        let response = server
+            .get("/v1/proposals")
+            .add_query_params([("limit", 1)])
+            .await
+            .json::<serde_json::Value>();
+        assert_eq!(response.as_array().unwrap().len(), 1);
+        assert_eq!(response[0]["sequence"], 6);
+
+        let response = server.get("/v1/proposals/7").expect_failure().await;
+        response.assert_status(StatusCode::NOT_FOUND);
+        response.assert_text("Proposal not found: 7");
+    }
+
+    #[tokio::test]
+    async fn test_targets() {
+        let response = server()
+            .await
+            .get("/v1/targets")
+            .await
+            .json::<serde_json::Value>();
+        assert_eq!(
+            response[0],
+            json!({
+                "name": "ethereum",
+                "chainId": 2,
+                "type": "evm",
+                "lastExecutedSequence": 5,
+                "lastCheckedAt": 1030,
+                "error": null,
+                "pendingSequences": [],
+                "pendingLagSecs": null,
+                "lastExecutionLagSecs": 30,
+            })
+        );
+        assert_eq!(response[1]["name"], "bsc");
+        assert_eq!(response[1]["error"], "timeout");
+        assert_eq!(response[1]["pendingSequences"], json!([5, 6]));
+        assert!(response[1]["pendingLagSecs"].as_u64().unwrap() >= unix_timestamp() - 1_000);
+    }
+
+    #[tokio::test]
+    async fn test_metrics() {
+        let metrics = server().await.get("/metrics").await.text();
+        assert!(metrics.contains("governance_latest_sequence 6"));
+        assert!(metrics
+            .contains(r#"governance_last_executed_sequence{target="ethereum",chain_id="2"} 5"#));
+        assert!(metrics.contains(r#"governance_pending_proposals{target="bsc",chain_id="4"} 2"#));
+        assert!(metrics
+            .contains(r#"governance_execution_lag_seconds{target="ethereum",chain_id="2"} 30"#));
+        assert!(metrics.contains(r#"governance_check_failing{target="bsc",chain_id="4"} 1"#));
+    }
+
+    #[tokio::test]
+    async fn test_ready() {
+        // The test server never synced with the guardian API
+        server()
+            .await
+            .get("/ready")
+            .expect_failure()
+            .await
+            .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -0,0 +1,42 @@
+use {
+    crate::api::{types::ProposalSummary, ApiState, RestError},
+    axum::{
+        extract::{Path, Query, State},
+        Json,
+    },
+    serde::Deserialize,
+};
+
+const DEFAULT_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct ProposalsQueryParams {
+    /// Maximum number of proposals to return. Default is 100.
+    limit: Option<usize>,
+}
+
+/// Get the latest governance proposals with their execution on every target they apply to,
+/// newest first.
+pub async fn proposals(
+    State(state): State<ApiState>,
+    Query(params): Query<ProposalsQueryParams>,
+) -> Json<Vec<ProposalSummary>> {
+    let proposals = state
+        .state
+        .latest_proposals(params.limit.unwrap_or(DEFAULT_LIMIT))
+        .await;
+    Json(proposals.into_iter().map(ProposalSummary::from).collect())
+}
+
+/// Get a governance proposal by the sequence number of its VAA.
+pub async fn proposal(
+    State(state): State<ApiState>,
+    Path(sequence): Path<u64>,
+) -> Result<Json<ProposalSummary>, RestError> {
+    let proposal = state
+        .state
+        .proposal(sequence)
+        .await
+        .ok_or(RestError::ProposalNotFound { sequence })?;
+    Ok(Json(proposal.into()))
+}
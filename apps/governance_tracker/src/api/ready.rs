@@ -0,0 +1,24 @@
+use {
+    crate::{api::ApiState, state::unix_timestamp},
+    axum::{
+        extract::State,
+        http::StatusCode,
+        response::{IntoResponse, Response},
+    },
+};
+
+/// The service is ready while it fetched all governance VAAs at most this many seconds ago.
+const MAX_SYNC_AGE_SECS: u64 = 300;
+
+pub async fn ready(State(state): State<ApiState>) -> Response {
+    match state.state.last_synced_at().await {
+        Some(synced_at) if unix_timestamp().saturating_sub(synced_at) <= MAX_SYNC_AGE_SECS => {
+            (StatusCode::OK, "OK").into_response()
+        }
+        _ => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Not synced with the guardian API",
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,27 @@
+use {
+    crate::{
+        api::{types::TargetSummary, ApiState},
+        state::unix_timestamp,
+    },
+    axum::{extract::State, Json},
+};
+
+/// Get the execution status of the governance proposals on every target, in the order of the
+/// targets file.
+pub async fn targets(State(state): State<ApiState>) -> Json<Vec<TargetSummary>> {
+    let state = &*state.state;
+    let now = unix_timestamp();
+    let mut targets = Vec::new();
+    for target in state.targets.iter() {
+        let pending = state.pending_proposals(&target.name).await;
+        let last_execution = state.last_execution(&target.name).await;
+        targets.push(TargetSummary::new(
+            target,
+            state.status(&target.name).await,
+            &pending,
+            last_execution.as_ref(),
+            now,
+        ));
+    }
+    Json(targets)
+}
@@ -0,0 +1,107 @@
+use {
+    crate::{
+        governance::GovernanceModule,
+        state::{Execution, ProposalRecord, TargetStatus},
+        targets::{TargetConfig, TargetType},
+    },
+    serde::Serialize,
+    std::collections::BTreeMap,
+};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionSummary {
+    #[serde(flatten)]
+    pub execution: Execution,
+    /// Seconds between the VAA and its execution, if the tracker saw the execution.
+    pub lag_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalSummary {
+    pub sequence: u64,
+    /// Timestamp of the VAA, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Module of the governance instruction, or `None` if the payload is not a governance
+    /// instruction of a known module.
+    pub module: Option<GovernanceModule>,
+    pub action: Option<u8>,
+    /// Wormhole chain id of the target chain, 0 for all chains.
+    pub target_chain_id: Option<u16>,
+    /// Execution by name of the targets that the proposal applies to.
+    pub executions: BTreeMap<String, ExecutionSummary>,
+}
+
+impl From<ProposalRecord> for ProposalSummary {
+    fn from(record: ProposalRecord) -> Self {
+        let executions = record
+            .executions
+            .iter()
+            .map(|(target, execution)| {
+                let summary = ExecutionSummary {
+                    execution: *execution,
+                    lag_secs: record.execution_lag(target),
+                };
+                (target.clone(), summary)
+            })
+            .collect();
+        let header = record.proposal.header;
+        Self {
+            sequence: record.proposal.sequence,
+            timestamp: record.proposal.timestamp,
+            module: header.map(|header| header.module),
+            action: header.map(|header| header.action),
+            target_chain_id: header.map(|header| header.target_chain_id),
+            executions,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetSummary {
+    pub name: String,
+    pub chain_id: u16,
+    #[serde(rename = "type")]
+    pub target_type: TargetType,
+    pub last_executed_sequence: Option<u64>,
+    /// When the target was last queried successfully, in seconds since the Unix epoch.
+    pub last_checked_at: Option<u64>,
+    /// Error of the last query of the target, if it failed.
+    pub error: Option<String>,
+    /// Sequence numbers of the proposals that the target hasn't executed yet, oldest first.
+    pub pending_sequences: Vec<u64>,
+    /// Age of the oldest proposal that the target hasn't executed yet, in seconds.
+    pub pending_lag_secs: Option<u64>,
+    /// Lag of the latest proposal that the target executed while the tracker was running.
+    pub last_execution_lag_secs: Option<u64>,
+}
+
+impl TargetSummary {
+    pub fn new(
+        target: &TargetConfig,
+        status: TargetStatus,
+        pending: &[ProposalRecord],
+        last_execution: Option<&ProposalRecord>,
+        now: u64,
+    ) -> Self {
+        Self {
+            name: target.name.clone(),
+            chain_id: target.chain_id,
+            target_type: target.contract.target_type(),
+            last_executed_sequence: status.last_executed_sequence,
+            last_checked_at: status.last_checked_at,
+            error: status.error,
+            pending_sequences: pending
+                .iter()
+                .map(|record| record.proposal.sequence)
+                .collect(),
+            pending_lag_secs: pending
+                .first()
+                .map(|record| now.saturating_sub(record.proposal.timestamp)),
+            last_execution_lag_secs: last_execution
+                .and_then(|record| record.execution_lag(&target.name)),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use {
+    clap::{crate_authors, crate_description, crate_name, crate_version, Args, Parser},
+    std::{net::SocketAddr, path::PathBuf},
+    url::Url,
+};
+
+const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:34300";
+
+/// Emitter of the mainnet governance proposals, the vault of the multisig on Solana.
+const DEFAULT_EMITTER_ADDRESS: &str =
+    "5635979a221c34931e32620b9293a463065555ea71fe97cd6237ade875b12e9e";
+
+#[derive(Parser, Clone, Debug)]
+#[command(name = crate_name!())]
+#[command(author = crate_authors!())]
+#[command(about = crate_description!())]
+#[command(version = crate_version!())]
+pub struct Options {
+    #[command(flatten)]
+    pub tracker: TrackerOptions,
+
+    #[command(flatten)]
+    pub api: ApiOptions,
+}
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "Tracker Options")]
+#[group(id = "Tracker")]
+pub struct TrackerOptions {
+    /// Base URL of a Wormhole guardian REST API that serves `/v1/signed_vaa`.
+    #[arg(long = "wormhole-api-url")]
+    #[arg(env = "WORMHOLE_API_URL")]
+    pub wormhole_api_url: Url,
+
+    /// Wormhole chain id of the governance emitter.
+    #[arg(long = "emitter-chain")]
+    #[arg(env = "EMITTER_CHAIN")]
+    #[arg(default_value = "1")]
+    pub emitter_chain: u16,
+
+    /// Hex encoded Wormhole address of the governance emitter.
+    #[arg(long = "emitter-address")]
+    #[arg(env = "EMITTER_ADDRESS")]
+    #[arg(default_value = DEFAULT_EMITTER_ADDRESS)]
+    #[arg(value_parser = parse_address)]
+    pub emitter_address: [u8; 32],
+
+    /// Sequence number of the first governance VAA to track. Earlier proposals are ignored.
+    #[arg(long = "start-sequence")]
+    #[arg(env = "START_SEQUENCE")]
+    pub start_sequence: u64,
+
+    /// Path to a JSON file that lists the contracts that execute the proposals.
+    #[arg(long = "targets-file")]
+    #[arg(env = "TARGETS_FILE")]
+    pub targets_file: PathBuf,
+
+    /// How often to poll for new VAAs and for the state of the targets, in seconds.
+    #[arg(long = "poll-interval")]
+    #[arg(env = "POLL_INTERVAL")]
+    #[arg(default_value = "30")]
+    pub poll_interval_secs: u64,
+}
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "API Options")]
+#[group(id = "Api")]
+pub struct ApiOptions {
+    /// Address and port the HTTP server will bind to.
+    #[arg(long = "listen-addr")]
+    #[arg(env = "LISTEN_ADDR")]
+    #[arg(default_value = DEFAULT_LISTEN_ADDR)]
+    pub listen_addr: SocketAddr,
+}
+
+fn parse_address(value: &str) -> Result<[u8; 32], String> {
+    let mut address = [0u8; 32];
+    hex::decode_to_slice(value.trim_start_matches("0x"), &mut address)
+        .map_err(|err| format!("invalid emitter address: {err}"))?;
+    Ok(address)
+}
@@ -0,0 +1,246 @@
+//! Queries of the sequence number of the last governance VAA that each target executed.
+
+use {
+    crate::{
+        pubkey::Pubkey,
+        rpc::{self, endpoint, json_rpc},
+        state::{unix_timestamp, State},
+        targets::{TargetConfig, TargetContract},
+    },
+    anyhow::{bail, ensure, Context, Result},
+    base64::{engine::general_purpose::STANDARD as base64_standard_engine, Engine as _},
+    serde::Deserialize,
+    serde_json::json,
+    sha3::{Digest, Keccak256},
+    std::{sync::Arc, time::Duration},
+    url::Url,
+};
+
+/// Storage key of the config of the Pyth CosmWasm contract, `config_v1` with the length
+/// prefix of `cosmwasm_storage` singletons.
+const COSMWASM_CONFIG_KEY: &[u8] = b"\x00\x09config_v1";
+
+/// Seed of the `ClaimRecord` PDA of an emitter in the remote executor.
+const CLAIM_RECORD_SEED: &[u8] = b"CLAIM_RECORD";
+
+/// Length of the discriminator of Anchor accounts.
+const ANCHOR_DISCRIMINATOR_LEN: usize = 8;
+
+/// Decodes the return value of `lastExecutedGovernanceSequence()`, an ABI encoded `uint64`.
+fn decode_evm_sequence(result: &str) -> Result<u64> {
+    let data = hex::decode(result.trim_start_matches("0x"))?;
+    ensure!(data.len() == 32, "invalid return data: {}", result);
+    let (high, low) = data.split_at(24);
+    ensure!(high.iter().all(|byte| *byte == 0), "sequence overflows u64");
+    Ok(u64::from_be_bytes(low.try_into()?))
+}
+
+async fn evm_sequence(client: &reqwest::Client, rpc_url: &Url, address: &str) -> Result<u64> {
+    let selector = &Keccak256::digest(b"lastExecutedGovernanceSequence()")[..4];
+    let result: String = json_rpc(
+        client,
+        rpc_url,
+        "eth_call",
+        json!([{"to": address, "data": format!("0x{}", hex::encode(selector))}, "latest"]),
+    )
+    .await?;
+    decode_evm_sequence(&result)
+}
+
+#[derive(Deserialize)]
+struct CosmwasmConfig {
+    governance_sequence_number: u64,
+}
+
+#[derive(Deserialize)]
+struct RawContractState {
+    data: Option<String>,
+}
+
+/// Decodes the stored config of the Pyth contract, in the response of the raw contract state
+/// query of the LCD API.
+fn decode_cosmwasm_config(state: RawContractState) -> Result<u64> {
+    let Some(data) = state.data else {
+        bail!("contract has no config");
+    };
+    let config: CosmwasmConfig = serde_json::from_slice(&base64_standard_engine.decode(data)?)?;
+    Ok(config.governance_sequence_number)
+}
+
+async fn cosmwasm_sequence(client: &reqwest::Client, lcd_url: &Url, address: &str) -> Result<u64> {
+    let key = base64_standard_engine.encode(COSMWASM_CONFIG_KEY);
+    let url = endpoint(
+        lcd_url,
+        ["cosmwasm", "wasm", "v1", "contract", address, "raw", &key],
+    )?;
+    let state = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    decode_cosmwasm_config(state)
+}
+
+#[derive(Deserialize)]
+struct AccountInfo {
+    value: Option<Account>,
+}
+
+#[derive(Deserialize)]
+struct Account {
+    /// Base64 encoded data and the name of the encoding.
+    data: (String, String),
+}
+
+/// Decodes the `ClaimRecord` account of the remote executor: the Anchor discriminator followed
+/// by the little endian sequence number. The account doesn't exist until the first proposal is
+/// executed.
+fn decode_claim_record(info: AccountInfo) -> Result<u64> {
+    let Some(account) = info.value else {
+        return Ok(0);
+    };
+    let data = base64_standard_engine.decode(account.data.0)?;
+    let sequence = data
+        .get(ANCHOR_DISCRIMINATOR_LEN..ANCHOR_DISCRIMINATOR_LEN + 8)
+        .context("claim record is too short")?;
+    Ok(u64::from_le_bytes(sequence.try_into()?))
+}
+
+/// The `ClaimRecord` account of `emitter_address` in the remote executor `program_id`.
+fn claim_record(program_id: &Pubkey, emitter_address: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[CLAIM_RECORD_SEED, emitter_address], program_id).0
+}
+
+async fn claim_record_sequence(
+    client: &reqwest::Client,
+    rpc_url: &Url,
+    claim_record: &Pubkey,
+) -> Result<u64> {
+    let info = json_rpc(
+        client,
+        rpc_url,
+        "getAccountInfo",
+        json!([claim_record.to_string(), {"encoding": "base64", "commitment": "confirmed"}]),
+    )
+    .await?;
+    decode_claim_record(info)
+}
+
+/// Sequence number of the last VAA of the governance emitter `emitter_address` that `contract`
+/// executed.
+pub async fn last_executed_sequence(
+    client: &reqwest::Client,
+    contract: &TargetContract,
+    emitter_address: &[u8; 32],
+) -> Result<u64> {
+    match contract {
+        TargetContract::Evm { rpc_url, address } => evm_sequence(client, rpc_url, address).await,
+        TargetContract::Cosmwasm { lcd_url, address } => {
+            cosmwasm_sequence(client, lcd_url, address).await
+        }
+        TargetContract::RemoteExecutor {
+            rpc_url,
+            program_id,
+        } => {
+            claim_record_sequence(client, rpc_url, &claim_record(program_id, emitter_address)).await
+        }
+    }
+}
+
+/// Polls the last executed sequence number of `target` and records it in `state`.
+#[tracing::instrument(name = "check_target", skip_all, fields(target = target.name))]
+pub async fn run(
+    target: TargetConfig,
+    emitter_address: [u8; 32],
+    poll_interval: Duration,
+    state: Arc<State>,
+) -> Result<()> {
+    let client = rpc::client()?;
+    loop {
+        match last_executed_sequence(&client, &target.contract, &emitter_address).await {
+            Ok(sequence) => {
+                tracing::debug!("Last executed sequence: {}", sequence);
+                state
+                    .set_last_executed_sequence(&target, sequence, unix_timestamp())
+                    .await;
+            }
+            Err(err) => {
+                tracing::warn!("Failed to query the target: {:#}", err);
+                state
+                    .set_error(&target, format!("{:#}", err), unix_timestamp())
+                    .await;
+            }
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, crate::targets::REMOTE_EXECUTOR_PROGRAM_ID};
+
+    #[test]
+    fn test_decode_evm_sequence() {
+        assert_eq!(
+            decode_evm_sequence(&format!("0x{}2a", "0".repeat(62))).unwrap(),
+            42
+        );
+        assert!(decode_evm_sequence(&format!("0x1{}", "0".repeat(63))).is_err());
+        assert!(decode_evm_sequence("0x").is_err());
+    }
+
+    #[test]
+    fn test_decode_cosmwasm_config() {
+        let config = json!({
+            "governance_source_index": 1,
+            "governance_sequence_number": 17,
+            "chain_id": 20,
+        });
+        let data = base64_standard_engine.encode(config.to_string());
+        assert_eq!(
+            decode_cosmwasm_config(RawContractState { data: Some(data) }).unwrap(),
+            17
+        );
+        assert!(decode_cosmwasm_config(RawContractState { data: None }).is_err());
+    }
+
+    #[test]
+    fn test_claim_record() {
+        let emitter_address =
+            hex::decode("5635979a221c34931e32620b9293a463065555ea71fe97cd6237ade875b12e9e")
+                .unwrap();
+        assert_eq!(
+            claim_record(
+                &REMOTE_EXECUTOR_PROGRAM_ID,
+                &emitter_address.try_into().unwrap()
+            )
+            .to_string(),
+            "AJhGD5Sw4K712uzfEGpMyLazizb6aSt544dS7JPjhz3T"
+        );
+    }
+
+    #[test]
+    fn test_decode_claim_record() {
+        let mut data = vec![0xff; ANCHOR_DISCRIMINATOR_LEN];
+        data.extend_from_slice(&300u64.to_le_bytes());
+        let info = AccountInfo {
+            value: Some(Account {
+                data: (base64_standard_engine.encode(&data), "base64".to_string()),
+            }),
+        };
+        assert_eq!(decode_claim_record(info).unwrap(), 300);
+        assert_eq!(decode_claim_record(AccountInfo { value: None }).unwrap(), 0);
+
+        let info = AccountInfo {
+            value: Some(Account {
+                data: (
+                    base64_standard_engine.encode(&data[..12]),
+                    "base64".to_string(),
+                ),
+            }),
+        };
+        assert!(decode_claim_record(info).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+//! Decoding of governance VAAs.
+//!
+//! The payload of a governance VAA starts with a header that says which contracts must
+//! execute it:
+//! - the magic number `PTGM`,
+//! - the module, 0 for the remote executor and 1 for the Pyth contracts,
+//! - the action within the module,
+//! - the big endian Wormhole chain id of the target chain, or 0 for all chains.
+
+use {
+    crate::targets::TargetConfig,
+    anyhow::{bail, Result},
+    serde::Serialize,
+    serde_wormhole::RawMessage,
+    wormhole_sdk::{Address, Chain, Vaa},
+};
+
+pub const MAGIC: &[u8; 4] = b"PTGM";
+
+/// Target chain id of proposals for every chain.
+pub const ALL_CHAINS: u16 = 0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GovernanceModule {
+    /// The remote executor on Pythnet and other SVM chains.
+    Executor,
+    /// The Pyth contracts on the target chains.
+    Target,
+}
+
+impl GovernanceModule {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(GovernanceModule::Executor),
+            1 => Some(GovernanceModule::Target),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GovernanceHeader {
+    pub module: GovernanceModule,
+    pub action: u8,
+    pub target_chain_id: u16,
+}
+
+impl GovernanceHeader {
+    /// Decodes the header at the start of `payload`. Returns `None` if the payload is not a
+    /// governance instruction of a known module.
+    pub fn parse(payload: &[u8]) -> Option<Self> {
+        match payload {
+            [m0, m1, m2, m3, module, action, chain0, chain1, ..]
+                if [*m0, *m1, *m2, *m3] == *MAGIC =>
+            {
+                Some(Self {
+                    module: GovernanceModule::from_u8(*module)?,
+                    action: *action,
+                    target_chain_id: u16::from_be_bytes([*chain0, *chain1]),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether `target` must execute the instruction.
+    pub fn applies_to(&self, target: &TargetConfig) -> bool {
+        self.module == target.contract.module()
+            && (self.target_chain_id == ALL_CHAINS || self.target_chain_id == target.chain_id)
+    }
+}
+
+/// A governance VAA of the tracked emitter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Proposal {
+    pub sequence: u64,
+    /// Timestamp of the VAA, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Header of the payload, or `None` if it's not an instruction for a known module.
+    pub header: Option<GovernanceHeader>,
+}
+
+impl Proposal {
+    /// Decodes a VAA and checks that it was sent by the governance emitter. The signatures
+    /// are not verified: the tracker only reports on the VAA, the targets verify it.
+    pub fn from_vaa(vaa: &[u8], emitter_chain: u16, emitter_address: &[u8; 32]) -> Result<Self> {
+        let vaa: Vaa<&RawMessage> = serde_wormhole::from_slice(vaa)?;
+        if vaa.emitter_chain != Chain::from(emitter_chain)
+            || vaa.emitter_address != Address(*emitter_address)
+        {
+            bail!(
+                "VAA is from emitter {}/{}, not the governance emitter",
+                vaa.emitter_chain,
+                vaa.emitter_address
+            );
+        }
+        Ok(Self {
+            sequence: vaa.sequence,
+            timestamp: vaa.timestamp.into(),
+            header: GovernanceHeader::parse(vaa.payload),
+        })
+    }
+
+    pub fn applies_to(&self, target: &TargetConfig) -> bool {
+        self.header
+            .as_ref()
+            .is_some_and(|header| header.applies_to(target))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::targets::TargetContract,
+        wormhole_sdk::vaa::{Body, Header},
+    };
+
+    fn evm_target(chain_id: u16) -> TargetConfig {
+        TargetConfig {
+            name: "evm".to_string(),
+            chain_id,
+            contract: TargetContract::Evm {
+                rpc_url: "http://localhost:8545".parse().unwrap(),
+                address: "0x01".to_string(),
+            },
+        }
+    }
+
+    /// Serializes a VAA of `emitter_address` on Solana, without signatures.
+    fn vaa(emitter_address: [u8; 32], sequence: u64, payload: &[u8]) -> Vec<u8> {
+        let header = Header {
+            version: 1,
+            ..Default::default()
+        };
+        let body = Body {
+            timestamp: 1_700_000_000,
+            nonce: 0,
+            emitter_chain: Chain::Solana,
+            emitter_address: Address(emitter_address),
+            sequence,
+            consistency_level: 32,
+            payload: <&RawMessage>::from(payload),
+        };
+        serde_wormhole::to_vec(&(header, body)).unwrap()
+    }
+
+    #[test]
+    fn test_governance_header() {
+        // SetFee on chain 2 (Ethereum)
+        let header = GovernanceHeader::parse(b"PTGM\x01\x03\x00\x02fee").unwrap();
+        assert_eq!(
+            header,
+            GovernanceHeader {
+                module: GovernanceModule::Target,
+                action: 3,
+                target_chain_id: 2,
+            }
+        );
+        assert!(header.applies_to(&evm_target(2)));
+        assert!(!header.applies_to(&evm_target(4)));
+
+        let header = GovernanceHeader::parse(b"PTGM\x01\x01\x00\x00").unwrap();
+        assert!(header.applies_to(&evm_target(4)));
+
+        // Remote executor instructions don't apply to the Pyth contracts
+        let header = GovernanceHeader::parse(b"PTGM\x00\x00\x00\x1a").unwrap();
+        assert_eq!(header.module, GovernanceModule::Executor);
+        assert!(!header.applies_to(&evm_target(26)));
+
+        assert_eq!(GovernanceHeader::parse(b"PTGM\x02\x00\x00\x00"), None);
+        assert_eq!(GovernanceHeader::parse(b"PTGX\x01\x00\x00\x00"), None);
+        assert_eq!(GovernanceHeader::parse(b"PTGM\x01\x00\x00"), None);
+    }
+
+    #[test]
+    fn test_proposal_from_vaa() {
+        let emitter = [7; 32];
+        let proposal =
+            Proposal::from_vaa(&vaa(emitter, 42, b"PTGM\x01\x03\x00\x02"), 1, &emitter).unwrap();
+        assert_eq!(
+            proposal,
+            Proposal {
+                sequence: 42,
+                timestamp: 1_700_000_000,
+                header: Some(GovernanceHeader {
+                    module: GovernanceModule::Target,
+                    action: 3,
+                    target_chain_id: 2,
+                }),
+            }
+        );
+        assert!(proposal.applies_to(&evm_target(2)));
+
+        let proposal = Proposal::from_vaa(&vaa(emitter, 43, b"hello"), 1, &emitter).unwrap();
+        assert_eq!(proposal.header, None);
+        assert!(!proposal.applies_to(&evm_target(2)));
+
+        assert!(Proposal::from_vaa(&vaa([8; 32], 44, b""), 1, &emitter).is_err());
+        assert!(Proposal::from_vaa(&vaa(emitter, 44, b""), 26, &emitter).is_err());
+        assert!(Proposal::from_vaa(b"\x01\x00", 1, &emitter).is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use {
+    crate::{api::ApiState, config::Options, metrics::Metrics, state::State, targets::Targets},
+    anyhow::Result,
+    clap::Parser,
+    futures::future::select_all,
+    prometheus_client::registry::Registry,
+    std::{io::IsTerminal, sync::Arc, time::Duration},
+    tokio::sync::RwLock,
+};
+
+pub mod api;
+pub mod config;
+pub mod executions;
+pub mod governance;
+pub mod metrics;
+pub mod pubkey;
+pub mod rpc;
+pub mod state;
+pub mod targets;
+pub mod watcher;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize a Tracing Subscriber
+    tracing::subscriber::set_global_default(
+        tracing_subscriber::fmt()
+            .compact()
+            .with_file(false)
+            .with_line_number(true)
+            .with_thread_ids(true)
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .with_ansi(std::io::stderr().is_terminal())
+            .finish(),
+    )?;
+
+    let options = Options::parse();
+    let targets = Targets::load(&options.tracker.targets_file)?;
+    let mut registry = Registry::default();
+    let metrics = Metrics::new(&mut registry);
+    let state = Arc::new(State::new(targets, options.tracker.start_sequence, metrics));
+
+    let poll_interval = Duration::from_secs(options.tracker.poll_interval_secs);
+    let mut tasks = vec![tokio::spawn(watcher::run(
+        options.tracker.clone(),
+        state.clone(),
+    ))];
+    for target in state.targets.iter() {
+        tasks.push(tokio::spawn(executions::run(
+            target.clone(),
+            options.tracker.emitter_address,
+            poll_interval,
+            state.clone(),
+        )));
+    }
+
+    tracing::info!("Starting server on: {:?}", &options.api.listen_addr);
+    let server = axum::Server::try_bind(&options.api.listen_addr)?.serve(
+        api::routes(ApiState {
+            state,
+            metrics_registry: Arc::new(RwLock::new(registry)),
+        })
+        .into_make_service(),
+    );
+    tokio::select! {
+        result = server => result?,
+        (result, _, _) = select_all(tasks) => result??,
+    }
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use {
+    crate::targets::TargetConfig,
+    prometheus_client::{
+        encoding::EncodeLabelSet,
+        metrics::{family::Family, gauge::Gauge},
+        registry::Registry,
+    },
+};
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct TargetLabel {
+    pub target: String,
+    pub chain_id: u16,
+}
+
+impl TargetLabel {
+    pub fn new(target: &TargetConfig) -> Self {
+        Self {
+            target: target.name.clone(),
+            chain_id: target.chain_id,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Metrics {
+    pub latest_sequence: Gauge,
+    pub last_executed_sequence: Family<TargetLabel, Gauge>,
+    pub pending_proposals: Family<TargetLabel, Gauge>,
+    pub pending_lag_seconds: Family<TargetLabel, Gauge>,
+    pub execution_lag_seconds: Family<TargetLabel, Gauge>,
+    pub check_failing: Family<TargetLabel, Gauge>,
+}
+
+impl Metrics {
+    pub fn new(registry: &mut Registry) -> Self {
+        let metrics = Self::default();
+        registry.register(
+            "governance_latest_sequence",
+            "Sequence number of the latest VAA of the governance emitter",
+            metrics.latest_sequence.clone(),
+        );
+        registry.register(
+            "governance_last_executed_sequence",
+            "Sequence number of the last governance VAA executed by the target",
+            metrics.last_executed_sequence.clone(),
+        );
+        registry.register(
+            "governance_pending_proposals",
+            "Number of proposals for the target that it hasn't executed yet",
+            metrics.pending_proposals.clone(),
+        );
+        registry.register(
+            "governance_pending_lag_seconds",
+            "Age of the oldest proposal that the target hasn't executed yet, or 0",
+            metrics.pending_lag_seconds.clone(),
+        );
+        registry.register(
+            "governance_execution_lag_seconds",
+            "Time between the VAA and the execution of the last proposal executed by the target",
+            metrics.execution_lag_seconds.clone(),
+        );
+        registry.register(
+            "governance_check_failing",
+            "1 if the last query of the target failed, 0 otherwise",
+            metrics.check_failing.clone(),
+        );
+        metrics
+    }
+}
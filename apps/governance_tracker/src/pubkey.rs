@@ -0,0 +1,106 @@
+//! Solana account addresses and the derivation of program derived addresses (PDAs), for the
+//! remote executor targets.
+//!
+//! This follows `solana_program::pubkey`, which isn't a dependency as its dependency tree no
+//! longer builds with the toolchain of the tracker.
+
+use {
+    anyhow::{bail, Result},
+    curve25519_dalek::edwards::CompressedEdwardsY,
+    sha2::{Digest, Sha256},
+    std::{fmt, str::FromStr},
+};
+
+/// Marker appended to the seeds of a PDA before hashing them.
+const PDA_MARKER: &[u8] = b"ProgramDerivedAddress";
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Pubkey([u8; 32]);
+
+impl Pubkey {
+    /// Parses a base58 encoded address in a const context. Panics if it isn't valid.
+    pub const fn from_str_const(value: &str) -> Self {
+        Self(bs58::decode(value.as_bytes()).into_array_const_unwrap())
+    }
+
+    /// The address of the seeds and the program, which must not be on the ed25519 curve so that
+    /// no private key signs for it.
+    fn create_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Option<Pubkey> {
+        let mut hasher = Sha256::new();
+        for seed in seeds {
+            hasher.update(seed);
+        }
+        hasher.update(program_id.0);
+        hasher.update(PDA_MARKER);
+        let hash: [u8; 32] = hasher.finalize().into();
+        match CompressedEdwardsY(hash).decompress() {
+            Some(_) => None,
+            None => Some(Pubkey(hash)),
+        }
+    }
+
+    /// The PDA of the seeds and the program with the highest bump seed that is off the curve,
+    /// as found by the programs with `Pubkey::find_program_address`.
+    pub fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
+        for bump in (0..=u8::MAX).rev() {
+            let mut seeds_with_bump = seeds.to_vec();
+            let bump_seed = [bump];
+            seeds_with_bump.push(&bump_seed);
+            if let Some(address) = Self::create_program_address(&seeds_with_bump, program_id) {
+                return (address, bump);
+            }
+        }
+        // The chance of no bump seed giving an address off the curve is 2^-256.
+        panic!("no program derived address found");
+    }
+}
+
+impl FromStr for Pubkey {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let bytes = bs58::decode(value).into_vec()?;
+        let Ok(bytes) = bytes.try_into() else {
+            bail!("invalid length of address: {}", value);
+        };
+        Ok(Pubkey(bytes))
+    }
+}
+
+impl fmt::Display for Pubkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", bs58::encode(self.0).into_string())
+    }
+}
+
+impl fmt::Debug for Pubkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_pubkey() {
+        let address = "exe6S3AxPVNmy46L4Nj6HrnnAVQUhwyYzMSNcnRn3qq";
+        assert_eq!(
+            address.parse::<Pubkey>().unwrap(),
+            Pubkey::from_str_const(address)
+        );
+        assert_eq!(
+            address.parse::<Pubkey>().unwrap().to_string(),
+            address.to_string()
+        );
+        assert!("exe6S3AxPVNmy46L4Nj6HrnnAVQUhwyYzMSNcnRn3q"
+            .parse::<Pubkey>()
+            .is_err());
+        assert!("0OIl".parse::<Pubkey>().is_err());
+        assert_eq!(
+            Pubkey::default().to_string(),
+            "11111111111111111111111111111111"
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! HTTP and JSON-RPC helpers shared by the watcher and the target checks.
+
+use {
+    anyhow::{anyhow, bail, Result},
+    serde::{de::DeserializeOwned, Deserialize},
+    serde_json::{json, Value},
+    std::time::Duration,
+    url::Url,
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub fn client() -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()?)
+}
+
+/// Appends `segments` to the path of `base`, escaping them as needed.
+pub fn endpoint<'a>(base: &Url, segments: impl IntoIterator<Item = &'a str>) -> Result<Url> {
+    let mut url = base.clone();
+    url.path_segments_mut()
+        .map_err(|_| anyhow!("invalid base URL: {}", base))?
+        .pop_if_empty()
+        .extend(segments);
+    Ok(url)
+}
+
+#[derive(Deserialize)]
+struct JsonRpcResponse<T> {
+    result: Option<T>,
+    error: Option<Value>,
+}
+
+/// Calls `method` on the JSON-RPC server at `url`.
+pub async fn json_rpc<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    url: &Url,
+    method: &str,
+    params: Value,
+) -> Result<T> {
+    let response: JsonRpcResponse<T> = client
+        .post(url.clone())
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params}))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    match response {
+        JsonRpcResponse {
+            error: Some(error), ..
+        } => bail!("{} failed: {}", method, error),
+        JsonRpcResponse {
+            result: Some(result),
+            ..
+        } => Ok(result),
+        _ => bail!("{} returned no result", method),
+    }
+}
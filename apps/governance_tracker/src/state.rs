@@ -0,0 +1,419 @@
+//! Correlation of the governance proposals with their execution on the targets.
+//!
+//! Every target stores the sequence number of the last governance VAA it executed, and
+//! rejects VAAs with a smaller sequence number afterwards. A proposal therefore counts as
+//! executed on a target once the tracker sees the sequence number of the proposal on the
+//! target, and as superseded once the target moves past it to a later proposal. The execution
+//! time is when the tracker first saw the new sequence number, so it is at most one poll
+//! interval late.
+
+use {
+    crate::{
+        governance::Proposal,
+        metrics::{Metrics, TargetLabel},
+        targets::{TargetConfig, Targets},
+    },
+    serde::Serialize,
+    std::{
+        cmp::Ordering,
+        collections::{BTreeMap, HashMap},
+        time::{SystemTime, UNIX_EPOCH},
+    },
+    tokio::sync::RwLock,
+};
+
+/// Current time in seconds since the Unix epoch.
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(
+    tag = "status",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum Execution {
+    Pending,
+    /// `executed_at` is when the tracker saw the execution, or `None` if the target had
+    /// already executed the proposal when the tracker first checked it.
+    Executed {
+        executed_at: Option<u64>,
+    },
+    /// The target executed the later proposal `superseded_by` without the tracker seeing this
+    /// one executed, so the target can't execute it anymore. A proposal that was executed
+    /// during the same poll interval as a later one also shows up as superseded.
+    Superseded {
+        superseded_by: u64,
+        superseded_at: Option<u64>,
+    },
+}
+
+impl Execution {
+    /// Execution of `proposal` on a target whose last executed sequence number is `sequence`,
+    /// seen at `now`.
+    fn after(proposal: u64, sequence: u64, now: Option<u64>) -> Self {
+        match proposal.cmp(&sequence) {
+            Ordering::Less => Execution::Superseded {
+                superseded_by: sequence,
+                superseded_at: now,
+            },
+            Ordering::Equal => Execution::Executed { executed_at: now },
+            Ordering::Greater => Execution::Pending,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProposalRecord {
+    pub proposal: Proposal,
+    /// Execution of the proposal by name of the targets it applies to.
+    pub executions: BTreeMap<String, Execution>,
+}
+
+impl ProposalRecord {
+    /// Seconds between the VAA and its execution on `target`, if it's known.
+    pub fn execution_lag(&self, target: &str) -> Option<u64> {
+        match self.executions.get(target)? {
+            Execution::Executed {
+                executed_at: Some(executed_at),
+            } => Some(executed_at.saturating_sub(self.proposal.timestamp)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TargetStatus {
+    pub last_executed_sequence: Option<u64>,
+    /// When the target was last queried successfully.
+    pub last_checked_at: Option<u64>,
+    /// Error of the last query, if it failed.
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+struct Inner {
+    next_sequence: u64,
+    last_synced_at: Option<u64>,
+    proposals: BTreeMap<u64, ProposalRecord>,
+    statuses: HashMap<String, TargetStatus>,
+}
+
+pub struct State {
+    pub targets: Targets,
+    inner: RwLock<Inner>,
+    metrics: Metrics,
+}
+
+impl State {
+    pub fn new(targets: Targets, start_sequence: u64, metrics: Metrics) -> Self {
+        Self {
+            targets,
+            inner: RwLock::new(Inner {
+                next_sequence: start_sequence,
+                ..Default::default()
+            }),
+            metrics,
+        }
+    }
+
+    /// Sequence number of the next VAA of the governance emitter.
+    pub async fn next_sequence(&self) -> u64 {
+        self.inner.read().await.next_sequence
+    }
+
+    /// When the tracker last fetched all VAAs of the governance emitter.
+    pub async fn last_synced_at(&self) -> Option<u64> {
+        self.inner.read().await.last_synced_at
+    }
+
+    pub async fn set_synced(&self, now: u64) {
+        self.inner.write().await.last_synced_at = Some(now);
+    }
+
+    /// Adds the VAA with the next sequence number.
+    pub async fn add_proposal(&self, proposal: Proposal) {
+        let mut inner = self.inner.write().await;
+        let executions = self
+            .targets
+            .iter()
+            .filter(|target| proposal.applies_to(target))
+            .map(|target| {
+                let last_executed_sequence = inner
+                    .statuses
+                    .get(&target.name)
+                    .and_then(|status| status.last_executed_sequence);
+                let execution = match last_executed_sequence {
+                    Some(sequence) => Execution::after(proposal.sequence, sequence, None),
+                    None => Execution::Pending,
+                };
+                (target.name.clone(), execution)
+            })
+            .collect();
+        inner.next_sequence = proposal.sequence + 1;
+        self.metrics.latest_sequence.set(proposal.sequence as i64);
+        inner.proposals.insert(
+            proposal.sequence,
+            ProposalRecord {
+                proposal,
+                executions,
+            },
+        );
+    }
+
+    /// Records the sequence number of the last proposal that `target` executed, and marks that
+    /// proposal as executed and the earlier pending ones as superseded.
+    pub async fn set_last_executed_sequence(&self, target: &TargetConfig, sequence: u64, now: u64) {
+        let mut inner = self.inner.write().await;
+        let status = inner.statuses.entry(target.name.clone()).or_default();
+        let first_check = status.last_executed_sequence.is_none();
+        status.last_executed_sequence = Some(sequence);
+        status.last_checked_at = Some(now);
+        status.error = None;
+
+        for record in inner
+            .proposals
+            .range_mut(..=sequence)
+            .map(|(_, record)| record)
+        {
+            if let Some(execution @ Execution::Pending) = record.executions.get_mut(&target.name) {
+                *execution = Execution::after(
+                    record.proposal.sequence,
+                    sequence,
+                    (!first_check).then_some(now),
+                );
+            }
+        }
+        self.update_metrics(&inner, target, now);
+    }
+
+    pub async fn set_error(&self, target: &TargetConfig, error: String, now: u64) {
+        let mut inner = self.inner.write().await;
+        inner.statuses.entry(target.name.clone()).or_default().error = Some(error);
+        self.update_metrics(&inner, target, now);
+    }
+
+    pub async fn status(&self, target: &str) -> TargetStatus {
+        self.inner
+            .read()
+            .await
+            .statuses
+            .get(target)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub async fn proposal(&self, sequence: u64) -> Option<ProposalRecord> {
+        self.inner.read().await.proposals.get(&sequence).cloned()
+    }
+
+    /// The latest `limit` proposals, newest first.
+    pub async fn latest_proposals(&self, limit: usize) -> Vec<ProposalRecord> {
+        self.inner
+            .read()
+            .await
+            .proposals
+            .values()
+            .rev()
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// The proposals that `target` hasn't executed yet, oldest first.
+    pub async fn pending_proposals(&self, target: &str) -> Vec<ProposalRecord> {
+        pending(&*self.inner.read().await, target)
+            .cloned()
+            .collect()
+    }
+
+    /// The latest proposal that `target` executed while the tracker was running.
+    pub async fn last_execution(&self, target: &str) -> Option<ProposalRecord> {
+        last_execution(&*self.inner.read().await, target).cloned()
+    }
+
+    fn update_metrics(&self, inner: &Inner, target: &TargetConfig, now: u64) {
+        let label = TargetLabel::new(target);
+        let status = inner
+            .statuses
+            .get(&target.name)
+            .cloned()
+            .unwrap_or_default();
+        if let Some(sequence) = status.last_executed_sequence {
+            self.metrics
+                .last_executed_sequence
+                .get_or_create(&label)
+                .set(sequence as i64);
+        }
+        self.metrics
+            .check_failing
+            .get_or_create(&label)
+            .set(status.error.is_some().into());
+
+        let mut pending = pending(inner, &target.name).peekable();
+        let pending_lag = pending
+            .peek()
+            .map_or(0, |record| now.saturating_sub(record.proposal.timestamp));
+        self.metrics
+            .pending_proposals
+            .get_or_create(&label)
+            .set(pending.count() as i64);
+        self.metrics
+            .pending_lag_seconds
+            .get_or_create(&label)
+            .set(pending_lag as i64);
+        if let Some(lag) = last_execution(inner, &target.name)
+            .and_then(|record| record.execution_lag(&target.name))
+        {
+            self.metrics
+                .execution_lag_seconds
+                .get_or_create(&label)
+                .set(lag as i64);
+        }
+    }
+}
+
+fn pending<'a>(inner: &'a Inner, target: &'a str) -> impl Iterator<Item = &'a ProposalRecord> {
+    inner
+        .proposals
+        .values()
+        .filter(move |record| record.executions.get(target) == Some(&Execution::Pending))
+}
+
+fn last_execution<'a>(inner: &'a Inner, target: &str) -> Option<&'a ProposalRecord> {
+    inner
+        .proposals
+        .values()
+        .rev()
+        .find(|record| record.execution_lag(target).is_some())
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::{
+            governance::{GovernanceHeader, GovernanceModule},
+            targets::TargetContract,
+        },
+        prometheus_client::registry::Registry,
+    };
+
+    fn target(name: &str, chain_id: u16) -> TargetConfig {
+        TargetConfig {
+            name: name.to_string(),
+            chain_id,
+            contract: TargetContract::Evm {
+                rpc_url: "http://localhost:8545".parse().unwrap(),
+                address: "0x01".to_string(),
+            },
+        }
+    }
+
+    fn proposal(sequence: u64, timestamp: u64, target_chain_id: u16) -> Proposal {
+        Proposal {
+            sequence,
+            timestamp,
+            header: Some(GovernanceHeader {
+                module: GovernanceModule::Target,
+                action: 3,
+                target_chain_id,
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_executions() {
+        let ethereum = target("ethereum", 2);
+        let bsc = target("bsc", 4);
+        let targets = Targets::new([ethereum.clone(), bsc.clone()]).unwrap();
+        let state = State::new(targets, 10, Metrics::new(&mut Registry::default()));
+
+        state.add_proposal(proposal(10, 100, 0)).await;
+        // Ethereum had executed the first proposal before the tracker checked it
+        state.set_last_executed_sequence(&ethereum, 10, 110).await;
+        state.set_last_executed_sequence(&bsc, 9, 110).await;
+        state.add_proposal(proposal(11, 200, 2)).await;
+        state.add_proposal(proposal(12, 300, 0)).await;
+        assert_eq!(state.next_sequence().await, 13);
+
+        let record = state.proposal(11).await.unwrap();
+        assert_eq!(
+            record.executions,
+            [("ethereum".to_string(), Execution::Pending)].into()
+        );
+        assert_eq!(
+            state.proposal(10).await.unwrap().executions["ethereum"],
+            Execution::Executed { executed_at: None }
+        );
+        assert_eq!(
+            state
+                .pending_proposals("bsc")
+                .await
+                .iter()
+                .map(|record| record.proposal.sequence)
+                .collect::<Vec<_>>(),
+            [10, 12]
+        );
+
+        // Executing a proposal supersedes the earlier pending ones
+        state.set_last_executed_sequence(&ethereum, 12, 400).await;
+        assert!(state.pending_proposals("ethereum").await.is_empty());
+        let record = state.proposal(11).await.unwrap();
+        assert_eq!(
+            record.executions["ethereum"],
+            Execution::Superseded {
+                superseded_by: 12,
+                superseded_at: Some(400)
+            }
+        );
+        assert_eq!(record.execution_lag("ethereum"), None);
+        let record = state.proposal(12).await.unwrap();
+        assert_eq!(
+            record.executions["ethereum"],
+            Execution::Executed {
+                executed_at: Some(400)
+            }
+        );
+        assert_eq!(record.execution_lag("ethereum"), Some(100));
+        assert_eq!(
+            state
+                .last_execution("ethereum")
+                .await
+                .map(|record| record.proposal.sequence),
+            Some(12)
+        );
+
+        state.set_error(&bsc, "timeout".to_string(), 500).await;
+        let status = state.status("bsc").await;
+        assert_eq!(status.last_executed_sequence, Some(9));
+        assert_eq!(status.error.as_deref(), Some("timeout"));
+        state.set_last_executed_sequence(&bsc, 10, 600).await;
+        assert_eq!(state.status("bsc").await.error, None);
+        assert_eq!(state.pending_proposals("bsc").await.len(), 1);
+
+        // A new proposal for a target that is already past it is superseded right away
+        state.set_last_executed_sequence(&bsc, 14, 700).await;
+        state.add_proposal(proposal(13, 650, 4)).await;
+        assert_eq!(
+            state.proposal(13).await.unwrap().executions["bsc"],
+            Execution::Superseded {
+                superseded_by: 14,
+                superseded_at: None
+            }
+        );
+        assert_eq!(
+            state
+                .latest_proposals(2)
+                .await
+                .iter()
+                .map(|record| record.proposal.sequence)
+                .collect::<Vec<_>>(),
+            [13, 12]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! The contracts that execute governance proposals.
+//!
+//! The targets file lists every contract to track, with the Wormhole id of its chain and how
+//! to query the sequence number of the last proposal it executed:
+//!
+//! ```json
+//! [
+//!   { "name": "ethereum", "chainId": 2, "type": "evm", "rpcUrl": "https://eth.example.com", "address": "0x4305FB66699C3B2702D4d05CF36551390A4c69C6" },
+//!   { "name": "osmosis", "chainId": 20, "type": "cosmwasm", "lcdUrl": "https://lcd.osmosis.example.com", "address": "osmo13ge29x4e2s63a8ytz2px8gurtyznmue4a69n5275692v3qn3ks8q7cwck7" },
+//!   { "name": "pythnet", "chainId": 26, "type": "remoteExecutor", "rpcUrl": "https://pythnet.example.com" }
+//! ]
+//! ```
+//!
+//! Remote executor targets take an optional `programId`, the remote executor program on their
+//! chain, which defaults to the one on Pythnet.
+
+use {
+    crate::{governance::GovernanceModule, pubkey::Pubkey},
+    anyhow::{bail, Context, Result},
+    serde::{Deserialize, Deserializer, Serialize},
+    std::{collections::HashSet, fs, path::Path, str::FromStr},
+    url::Url,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetConfig {
+    /// Name of the target in the API and the metrics, e.g. `ethereum`.
+    pub name: String,
+    /// Wormhole chain id of the chain of the target.
+    pub chain_id: u16,
+    #[serde(flatten)]
+    pub contract: TargetContract,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum TargetContract {
+    /// A Pyth contract on an EVM chain, queried over JSON-RPC.
+    Evm { rpc_url: Url, address: String },
+    /// A Pyth contract on a CosmWasm chain, queried over the LCD API of the chain.
+    Cosmwasm { lcd_url: Url, address: String },
+    /// The remote executor on Pythnet or another SVM chain, queried over JSON-RPC.
+    RemoteExecutor {
+        rpc_url: Url,
+        #[serde(
+            default = "default_remote_executor",
+            deserialize_with = "deserialize_pubkey"
+        )]
+        program_id: Pubkey,
+    },
+}
+
+/// The remote executor program on Pythnet.
+pub const REMOTE_EXECUTOR_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("exe6S3AxPVNmy46L4Nj6HrnnAVQUhwyYzMSNcnRn3qq");
+
+fn default_remote_executor() -> Pubkey {
+    REMOTE_EXECUTOR_PROGRAM_ID
+}
+
+fn deserialize_pubkey<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Pubkey::from_str(&value).map_err(serde::de::Error::custom)
+}
+
+/// Type of a target, as shown in the API.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TargetType {
+    Evm,
+    Cosmwasm,
+    RemoteExecutor,
+}
+
+impl TargetContract {
+    pub fn target_type(&self) -> TargetType {
+        match self {
+            TargetContract::Evm { .. } => TargetType::Evm,
+            TargetContract::Cosmwasm { .. } => TargetType::Cosmwasm,
+            TargetContract::RemoteExecutor { .. } => TargetType::RemoteExecutor,
+        }
+    }
+
+    /// The governance module of the proposals that the contract executes.
+    pub fn module(&self) -> GovernanceModule {
+        match self {
+            TargetContract::Evm { .. } | TargetContract::Cosmwasm { .. } => {
+                GovernanceModule::Target
+            }
+            TargetContract::RemoteExecutor { .. } => GovernanceModule::Executor,
+        }
+    }
+}
+
+/// The tracked targets, in the order of the targets file.
+#[derive(Debug, Default)]
+pub struct Targets {
+    targets: Vec<TargetConfig>,
+}
+
+impl Targets {
+    pub fn new(targets: impl IntoIterator<Item = TargetConfig>) -> Result<Self> {
+        let targets: Vec<_> = targets.into_iter().collect();
+        let mut names = HashSet::new();
+        for target in &targets {
+            if !names.insert(&target.name) {
+                bail!("duplicate target name: {}", target.name);
+            }
+        }
+        if targets.is_empty() {
+            bail!("no targets configured");
+        }
+        Ok(Self { targets })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let targets: Vec<TargetConfig> = serde_json::from_str(&data)
+            .with_context(|| format!("invalid targets file {}", path.display()))?;
+        Self::new(targets)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TargetConfig> {
+        self.targets.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("targets.json");
+        fs::write(
+            &path,
+            r#"[
+                {"name": "ethereum", "chainId": 2, "type": "evm", "rpcUrl": "http://localhost:8545", "address": "0x01"},
+                {"name": "osmosis", "chainId": 20, "type": "cosmwasm", "lcdUrl": "http://localhost:1317", "address": "osmo1"},
+                {"name": "pythnet", "chainId": 26, "type": "remoteExecutor", "rpcUrl": "http://localhost:8899"},
+                {"name": "eclipse", "chainId": 40003, "type": "remoteExecutor", "rpcUrl": "http://localhost:8900", "programId": "11111111111111111111111111111111"}
+            ]"#,
+        )
+        .unwrap();
+        let targets = Targets::load(&path).unwrap();
+        let targets: Vec<_> = targets.iter().collect();
+        assert_eq!(targets.len(), 4);
+        assert_eq!(
+            targets[0],
+            &TargetConfig {
+                name: "ethereum".to_string(),
+                chain_id: 2,
+                contract: TargetContract::Evm {
+                    rpc_url: "http://localhost:8545".parse().unwrap(),
+                    address: "0x01".to_string(),
+                },
+            }
+        );
+        assert_eq!(targets[1].contract.target_type(), TargetType::Cosmwasm);
+        assert_eq!(targets[2].contract.module(), GovernanceModule::Executor);
+        assert_eq!(
+            targets[2].contract,
+            TargetContract::RemoteExecutor {
+                rpc_url: "http://localhost:8899".parse().unwrap(),
+                program_id: REMOTE_EXECUTOR_PROGRAM_ID,
+            }
+        );
+        assert_eq!(
+            targets[3].contract,
+            TargetContract::RemoteExecutor {
+                rpc_url: "http://localhost:8900".parse().unwrap(),
+                program_id: Pubkey::default(),
+            }
+        );
+
+        fs::write(
+            &path,
+            r#"[
+                {"name": "ethereum", "chainId": 2, "type": "evm", "rpcUrl": "http://localhost:8545", "address": "0x01"},
+                {"name": "ethereum", "chainId": 2, "type": "evm", "rpcUrl": "http://localhost:8546", "address": "0x01"}
+            ]"#,
+        )
+        .unwrap();
+        assert!(Targets::load(&path).is_err());
+        fs::write(&path, "[]").unwrap();
+        assert!(Targets::load(&path).is_err());
+    }
+}
@@ -0,0 +1,79 @@
+//! Polling of the governance VAAs from the Wormhole guardian API.
+
+use {
+    crate::{
+        config::TrackerOptions,
+        governance::Proposal,
+        rpc::{self, endpoint},
+        state::{unix_timestamp, State},
+    },
+    anyhow::{ensure, Result},
+    base64::{engine::general_purpose::STANDARD as base64_standard_engine, Engine as _},
+    reqwest::StatusCode,
+    serde::Deserialize,
+    std::{sync::Arc, time::Duration},
+};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedVaaResponse {
+    vaa_bytes: String,
+}
+
+/// Fetches the VAA of the governance emitter with `sequence`, or `None` if it doesn't exist yet.
+async fn fetch_vaa(
+    client: &reqwest::Client,
+    options: &TrackerOptions,
+    sequence: u64,
+) -> Result<Option<Vec<u8>>> {
+    let url = endpoint(
+        &options.wormhole_api_url,
+        [
+            "v1",
+            "signed_vaa",
+            &options.emitter_chain.to_string(),
+            &hex::encode(options.emitter_address),
+            &sequence.to_string(),
+        ],
+    )?;
+    let response = client.get(url).send().await?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let response: SignedVaaResponse = response.error_for_status()?.json().await?;
+    Ok(Some(base64_standard_engine.decode(response.vaa_bytes)?))
+}
+
+/// Adds the new VAAs of the governance emitter to `state` until there are no more.
+async fn sync(client: &reqwest::Client, options: &TrackerOptions, state: &State) -> Result<()> {
+    loop {
+        let sequence = state.next_sequence().await;
+        let Some(vaa) = fetch_vaa(client, options, sequence).await? else {
+            return Ok(());
+        };
+        let proposal = Proposal::from_vaa(&vaa, options.emitter_chain, &options.emitter_address)?;
+        ensure!(
+            proposal.sequence == sequence,
+            "requested VAA {} but got {}",
+            sequence,
+            proposal.sequence
+        );
+        tracing::info!(
+            "New governance VAA {}: {:?}",
+            proposal.sequence,
+            proposal.header
+        );
+        state.add_proposal(proposal).await;
+    }
+}
+
+pub async fn run(options: TrackerOptions, state: Arc<State>) -> Result<()> {
+    let client = rpc::client()?;
+    loop {
+        match sync(&client, &options, &state).await {
+            Ok(()) => state.set_synced(unix_timestamp()).await,
+            Err(err) => tracing::warn!("Failed to fetch the governance VAAs: {:#}", err),
+        }
+        tokio::time::sleep(Duration::from_secs(options.poll_interval_secs)).await;
+    }
+}
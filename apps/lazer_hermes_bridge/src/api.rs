@@ -0,0 +1,239 @@
+use {
+    crate::{
+        feeds::{FeedConfig, PriceId},
+        state::State,
+    },
+    axum::{
+        http::StatusCode,
+        response::{IntoResponse, Response},
+        routing::get,
+        Extension, Router,
+    },
+    serde_qs::axum::QsQueryConfig,
+    std::sync::Arc,
+    tower_http::cors::CorsLayer,
+};
+pub use {latest_price_updates::*, live::*, ready::*, sse::*};
+
+mod latest_price_updates;
+mod live;
+mod ready;
+mod sse;
+pub mod types;
+
+#[derive(Clone)]
+pub struct ApiState {
+    pub state: Arc<State>,
+}
+
+pub enum RestError {
+    UpdateDataNotFound,
+    PriceIdsNotFound { missing_ids: Vec<PriceId> },
+}
+
+impl IntoResponse for RestError {
+    fn into_response(self) -> Response {
+        match self {
+            RestError::UpdateDataNotFound => {
+                (StatusCode::NOT_FOUND, "Update data not found").into_response()
+            }
+            RestError::PriceIdsNotFound { missing_ids } => {
+                let missing_ids = missing_ids
+                    .into_iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("Price ids not found: {}", missing_ids),
+                )
+                    .into_response()
+            }
+        }
+    }
+}
+
+/// Looks up the configured feeds of `price_ids`. Unknown ids are an error, unless
+/// `remove_invalid` is true, in which case they are left out.
+pub fn validate_price_ids(
+    state: &State,
+    price_ids: &[PriceId],
+    remove_invalid: bool,
+) -> Result<Vec<FeedConfig>, RestError> {
+    let (valid, invalid): (Vec<_>, Vec<_>) = price_ids
+        .iter()
+        .map(|id| state.feeds.get(id).copied().ok_or(*id))
+        .partition(Result::is_ok);
+    if invalid.is_empty() || remove_invalid {
+        Ok(valid.into_iter().filter_map(Result::ok).collect())
+    } else {
+        Err(RestError::PriceIdsNotFound {
+            missing_ids: invalid.into_iter().filter_map(Result::err).collect(),
+        })
+    }
+}
+
+pub fn routes(state: ApiState) -> Router {
+    Router::new()
+        .route("/v2/updates/price/latest", get(latest_price_updates))
+        .route("/v2/updates/price/stream", get(price_stream_sse_handler))
+        .route("/live", get(live))
+        .route("/ready", get(ready))
+        .with_state(state)
+        // Permissive CORS layer to allow all origins
+        .layer(CorsLayer::permissive())
+        // Non-strict mode permits escaped [] in URL parameters, as in Hermes.
+        .layer(Extension(QsQueryConfig::new(5, false)))
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::{feeds::Feeds, state::LazerUpdate},
+        axum_test::TestServer,
+        pyth_lazer_client::{
+            protocol::router::{Channel, Price, PriceFeedId, TimestampUs},
+            sdk::update::FeedUpdate,
+        },
+        serde_json::json,
+    };
+
+    const ID_1: &str = "e62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43";
+    const ID_2: &str = "ff61491a931112ddf1bd8147cd1b641375f79f5825126d665480874634fd0ace";
+    const ID_UNKNOWN: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+
+    fn feed_update(feed_id: u32, price: i64, spread: Option<i64>) -> (PriceFeedId, FeedUpdate) {
+        let feed_id = PriceFeedId(feed_id);
+        let update = FeedUpdate {
+            feed_id,
+            price: Some(Price::new(price).unwrap()),
+            best_bid_price: spread.map(|spread| Price::new(price - spread).unwrap()),
+            best_ask_price: spread.map(|spread| Price::new(price + spread).unwrap()),
+        };
+        (feed_id, update)
+    }
+
+    async fn server() -> TestServer {
+        let feeds = Feeds::new([
+            FeedConfig {
+                id: ID_1.parse().unwrap(),
+                lazer_id: PriceFeedId(1),
+                exponent: -8,
+            },
+            FeedConfig {
+                id: ID_2.parse().unwrap(),
+                lazer_id: PriceFeedId(2),
+                exponent: -5,
+            },
+        ])
+        .unwrap();
+        let state = Arc::new(State::new(feeds, Channel::RealTime));
+        state
+            .publish(LazerUpdate {
+                timestamp_us: TimestampUs(1_000_000),
+                prev_timestamp_us: None,
+                feeds: [feed_update(1, 10, None)].into(),
+                evm: None,
+                solana: None,
+            })
+            .await;
+        state
+            .publish(LazerUpdate {
+                timestamp_us: TimestampUs(2_500_000),
+                prev_timestamp_us: None,
+                feeds: [feed_update(1, 100, Some(4)), feed_update(2, 200, None)].into(),
+                evm: Some(vec![1, 2]),
+                solana: Some(vec![3, 4]),
+            })
+            .await;
+        TestServer::new(routes(ApiState { state }).into_make_service()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_latest_price_updates() {
+        let server = server().await;
+
+        server
+            .get("/v2/updates/price/latest")
+            .add_query_params([("ids[]", ID_1), ("ids[]", ID_2)])
+            .await
+            .assert_json(&json!({
+                "binary": {"encoding": "hex", "data": ["0102"]},
+                "parsed": [
+                    {
+                        "id": ID_1,
+                        "price": {"price": "100", "conf": "4", "expo": -8, "publish_time": 2},
+                        "ema_price": {"price": "100", "conf": "4", "expo": -8, "publish_time": 2},
+                        "metadata": {"slot": null, "proof_available_time": null, "prev_publish_time": 1},
+                    },
+                    {
+                        "id": ID_2,
+                        "price": {"price": "200", "conf": "0", "expo": -5, "publish_time": 2},
+                        "ema_price": {"price": "200", "conf": "0", "expo": -5, "publish_time": 2},
+                        "metadata": {"slot": null, "proof_available_time": null, "prev_publish_time": 1},
+                    },
+                ],
+                "source": {
+                    "provider": "lazer",
+                    "channel": "real_time",
+                    "verification": "lazer-signature",
+                    "messageFormat": "evm",
+                    "timestampUs": "2500000",
+                },
+            }));
+
+        let response = server
+            .get("/v2/updates/price/latest")
+            .add_query_params([
+                ("ids[]", format!("0x{ID_2}").as_str()),
+                ("encoding", "base64"),
+                ("parsed", "false"),
+                ("message_format", "solana"),
+            ])
+            .await
+            .json::<serde_json::Value>();
+        assert_eq!(
+            response["binary"],
+            json!({"encoding": "base64", "data": ["AwQ="]})
+        );
+        assert!(response.get("parsed").is_none());
+        assert_eq!(response["source"]["messageFormat"], "solana");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_price_ids() {
+        let server = server().await;
+
+        let response = server
+            .get("/v2/updates/price/latest")
+            .add_query_params([("ids[]", ID_1), ("ids[]", ID_UNKNOWN)])
+            .expect_failure()
+            .await;
+        response.assert_status(StatusCode::NOT_FOUND);
+        response.assert_text(format!("Price ids not found: {ID_UNKNOWN}"));
+
+        let response = server
+            .get("/v2/updates/price/latest")
+            .add_query_params([
+                ("ids[]", ID_1),
+                ("ids[]", ID_UNKNOWN),
+                ("ignore_invalid_price_ids", "true"),
+            ])
+            .await
+            .json::<serde_json::Value>();
+        assert_eq!(response["parsed"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_ready() {
+        // The updates of the test server are from 1970
+        server()
+            .await
+            .get("/ready")
+            .expect_failure()
+            .await
+            .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -0,0 +1,62 @@
+use {
+    crate::{
+        api::{
+            types::{EncodingType, MessageFormat, PriceUpdate},
+            validate_price_ids, ApiState, RestError,
+        },
+        feeds::PriceId,
+    },
+    axum::{extract::State, Json},
+    serde::Deserialize,
+    serde_qs::axum::QsQuery,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct LatestPriceUpdatesQueryParams {
+    /// Get the most recent price update for this set of price feed ids, e.g.
+    /// `?ids[]=a12...&ids[]=b4c...`.
+    ids: Vec<PriceId>,
+
+    /// Encoding of the binary data. Default is `hex`.
+    #[serde(default)]
+    encoding: EncodingType,
+
+    /// If true, include the parsed price update in the `parsed` field of each returned feed. Default is `true`.
+    #[serde(default = "default_true")]
+    parsed: bool,
+
+    /// If true, invalid price IDs in the `ids` parameter are ignored. Default is `false`.
+    #[serde(default)]
+    ignore_invalid_price_ids: bool,
+
+    /// Which signed Lazer message to return as the binary data, `evm` or `solana`. Default
+    /// is `evm`. Not a Hermes parameter.
+    #[serde(default)]
+    message_format: MessageFormat,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Get the latest price updates by price feed id.
+///
+/// Same as the endpoint of Hermes, but the prices come from the latest verified Lazer update.
+pub async fn latest_price_updates(
+    State(state): State<ApiState>,
+    QsQuery(params): QsQuery<LatestPriceUpdatesQueryParams>,
+) -> Result<Json<PriceUpdate>, RestError> {
+    let state = &*state.state;
+    let feeds = validate_price_ids(state, &params.ids, params.ignore_invalid_price_ids)?;
+    let update = state.latest().await.ok_or(RestError::UpdateDataNotFound)?;
+    let price_update = PriceUpdate::new(
+        &update,
+        state.channel,
+        &feeds,
+        params.encoding,
+        params.message_format,
+        params.parsed,
+    )
+    .ok_or(RestError::UpdateDataNotFound)?;
+    Ok(Json(price_update))
+}
@@ -0,0 +1,23 @@
+use {
+    crate::api::ApiState,
+    axum::{
+        extract::State,
+        http::StatusCode,
+        response::{IntoResponse, Response},
+    },
+    pyth_lazer_client::protocol::router::TimestampUs,
+};
+
+/// The service is ready while its latest update is at most this old.
+const MAX_UPDATE_AGE_US: u64 = 10_000_000;
+
+pub async fn ready(State(state): State<ApiState>) -> Response {
+    match state.state.latest().await {
+        Some(update)
+            if TimestampUs::now().saturating_us_since(update.timestamp_us) <= MAX_UPDATE_AGE_US =>
+        {
+            (StatusCode::OK, "OK").into_response()
+        }
+        _ => (StatusCode::SERVICE_UNAVAILABLE, "No recent Lazer update").into_response(),
+    }
+}
@@ -0,0 +1,82 @@
+use {
+    crate::{
+        api::{
+            types::{EncodingType, MessageFormat, PriceUpdate},
+            validate_price_ids, ApiState, RestError,
+        },
+        feeds::PriceId,
+    },
+    axum::{
+        extract::State,
+        response::sse::{Event, KeepAlive, Sse},
+    },
+    futures::Stream,
+    serde::Deserialize,
+    serde_qs::axum::QsQuery,
+    std::convert::Infallible,
+    tokio_stream::{wrappers::BroadcastStream, StreamExt as _},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct StreamPriceUpdatesQueryParams {
+    /// Stream the price updates of this set of price feed ids, e.g.
+    /// `?ids[]=a12...&ids[]=b4c...`.
+    ids: Vec<PriceId>,
+
+    /// Encoding of the binary data. Default is `hex`.
+    #[serde(default)]
+    encoding: EncodingType,
+
+    /// If true, include the parsed price update in the `parsed` field of each returned feed. Default is `true`.
+    #[serde(default = "default_true")]
+    parsed: bool,
+
+    /// If true, invalid price IDs in the `ids` parameter are ignored. Default is `false`.
+    #[serde(default)]
+    ignore_invalid_price_ids: bool,
+
+    /// Which signed Lazer message to return as the binary data, `evm` or `solana`. Default
+    /// is `evm`. Not a Hermes parameter.
+    #[serde(default)]
+    message_format: MessageFormat,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// SSE route handler for streaming price updates.
+///
+/// Sends an event for every verified Lazer update that has a price for one of the feeds.
+pub async fn price_stream_sse_handler(
+    State(state): State<ApiState>,
+    QsQuery(params): QsQuery<StreamPriceUpdatesQueryParams>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, RestError> {
+    let feeds = validate_price_ids(&state.state, &params.ids, params.ignore_invalid_price_ids)?;
+    let channel = state.state.channel;
+    let stream =
+        BroadcastStream::new(state.state.subscribe()).filter_map(move |message| match message {
+            Ok(update) => PriceUpdate::new(
+                &update,
+                channel,
+                &feeds,
+                params.encoding,
+                params.message_format,
+                params.parsed,
+            )
+            .map(|update| {
+                Ok(Event::default()
+                    .json_data(update)
+                    .unwrap_or_else(error_event))
+            }),
+            Err(e) => Some(Ok(error_event(e))),
+        });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+fn error_event<E: std::fmt::Debug>(e: E) -> Event {
+    Event::default()
+        .event("error")
+        .data(format!("Error receiving update: {:?}", e))
+}
@@ -0,0 +1,178 @@
+//! Response types of the Hermes price update API.
+//!
+//! The fields match Hermes, so existing clients can parse the responses. Responses also
+//! contain a `source` object, which Hermes doesn't have, to label the data as coming from
+//! Lazer.
+
+use {
+    crate::{
+        feeds::{FeedConfig, PriceId},
+        state::LazerUpdate,
+    },
+    base64::{engine::general_purpose::STANDARD as base64_standard_engine, Engine as _},
+    pyth_lazer_client::protocol::router::{Channel, Price},
+    serde::{Deserialize, Serialize, Serializer},
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum EncodingType {
+    #[default]
+    #[serde(rename = "hex")]
+    Hex,
+    #[serde(rename = "base64")]
+    Base64,
+}
+
+impl EncodingType {
+    pub fn encode_str(&self, data: &[u8]) -> String {
+        match self {
+            EncodingType::Base64 => base64_standard_engine.encode(data),
+            EncodingType::Hex => hex::encode(data),
+        }
+    }
+}
+
+/// Signed Lazer message to return as the binary data of updates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MessageFormat {
+    #[default]
+    Evm,
+    Solana,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BinaryUpdate {
+    pub encoding: EncodingType,
+    pub data: Vec<String>,
+}
+
+fn as_string<T: ToString, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&value.to_string())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RpcPrice {
+    #[serde(serialize_with = "as_string")]
+    pub price: i64,
+    /// Half of the spread between the best bid and ask price, or 0 if they are not known.
+    #[serde(serialize_with = "as_string")]
+    pub conf: u64,
+    pub expo: i32,
+    /// Timestamp of the Lazer update, in seconds since the Unix epoch.
+    pub publish_time: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RpcPriceFeedMetadataV2 {
+    pub slot: Option<u64>,
+    pub proof_available_time: Option<i64>,
+    pub prev_publish_time: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParsedPriceUpdate {
+    pub id: PriceId,
+    pub price: RpcPrice,
+    /// Lazer has no EMA price, so this is the same as `price`.
+    pub ema_price: RpcPrice,
+    pub metadata: RpcPriceFeedMetadataV2,
+}
+
+/// Where the data of an update comes from, and how it can be verified.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSource {
+    /// Always `"lazer"`.
+    pub provider: &'static str,
+    pub channel: Channel,
+    /// Always `"lazer-signature"`: the bridge checked the signature of the update against
+    /// its trusted Lazer signers. The binary data is a signed Lazer message, which must be
+    /// verified by the Lazer contracts. The Pyth receiver contracts will reject it.
+    pub verification: &'static str,
+    pub message_format: MessageFormat,
+    /// Timestamp of the Lazer update, in microseconds since the Unix epoch.
+    #[serde(serialize_with = "as_string")]
+    pub timestamp_us: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PriceUpdate {
+    pub binary: BinaryUpdate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parsed: Option<Vec<ParsedPriceUpdate>>,
+    pub source: UpdateSource,
+}
+
+fn to_seconds(timestamp_us: u64) -> i64 {
+    (timestamp_us / 1_000_000) as i64
+}
+
+fn parsed_price_update(update: &LazerUpdate, feed: &FeedConfig) -> Option<ParsedPriceUpdate> {
+    let data = update.feeds.get(&feed.lazer_id)?;
+    let price = data.price?.0.get();
+    let conf = match (data.best_bid_price, data.best_ask_price) {
+        (Some(Price(bid)), Some(Price(ask))) => ask.get().abs_diff(bid.get()) / 2,
+        _ => 0,
+    };
+    let price = RpcPrice {
+        price,
+        conf,
+        expo: feed.exponent,
+        publish_time: to_seconds(update.timestamp_us.0),
+    };
+    Some(ParsedPriceUpdate {
+        id: feed.id,
+        ema_price: price.clone(),
+        price,
+        metadata: RpcPriceFeedMetadataV2 {
+            slot: None,
+            proof_available_time: None,
+            prev_publish_time: update
+                .prev_timestamp_us
+                .map(|timestamp_us| to_seconds(timestamp_us.0)),
+        },
+    })
+}
+
+impl PriceUpdate {
+    /// Builds the response for `feeds` from a Lazer update. Feeds without a price in the
+    /// update are left out. Returns `None` if none of the feeds has a price.
+    pub fn new(
+        update: &LazerUpdate,
+        channel: Channel,
+        feeds: &[FeedConfig],
+        encoding: EncodingType,
+        message_format: MessageFormat,
+        parsed: bool,
+    ) -> Option<Self> {
+        let parsed_updates: Vec<_> = feeds
+            .iter()
+            .filter_map(|feed| parsed_price_update(update, feed))
+            .collect();
+        if parsed_updates.is_empty() {
+            return None;
+        }
+        let message = match message_format {
+            MessageFormat::Evm => &update.evm,
+            MessageFormat::Solana => &update.solana,
+        };
+        Some(Self {
+            binary: BinaryUpdate {
+                encoding,
+                data: message
+                    .iter()
+                    .map(|data| encoding.encode_str(data))
+                    .collect(),
+            },
+            parsed: parsed.then_some(parsed_updates),
+            source: UpdateSource {
+                provider: "lazer",
+                channel,
+                verification: "lazer-signature",
+                message_format,
+                timestamp_us: update.timestamp_us.0,
+            },
+        })
+    }
+}
@@ -0,0 +1,83 @@
+use {
+    clap::{crate_authors, crate_description, crate_name, crate_version, Args, Parser},
+    pyth_lazer_client::protocol::router::Channel,
+    serde::{
+        de::value::{BorrowedStrDeserializer, Error},
+        Deserialize,
+    },
+    std::{net::SocketAddr, path::PathBuf},
+    url::Url,
+};
+
+const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:34200";
+
+#[derive(Parser, Clone, Debug)]
+#[command(name = crate_name!())]
+#[command(author = crate_authors!())]
+#[command(about = crate_description!())]
+#[command(version = crate_version!())]
+pub struct Options {
+    #[command(flatten)]
+    pub lazer: LazerOptions,
+
+    #[command(flatten)]
+    pub api: ApiOptions,
+}
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "Lazer Options")]
+#[group(id = "Lazer")]
+pub struct LazerOptions {
+    /// WebSocket URLs of the stream endpoints of the Lazer routers, separated by commas.
+    /// The bridge fails over to the next endpoint if a connection becomes unhealthy.
+    #[arg(long = "lazer-urls", value_delimiter = ',', required = true)]
+    #[arg(env = "LAZER_URLS")]
+    pub urls: Vec<Url>,
+
+    /// Access token for the Lazer routers.
+    #[arg(long = "lazer-access-token")]
+    #[arg(env = "LAZER_ACCESS_TOKEN")]
+    pub access_token: String,
+
+    /// Channel to subscribe to, e.g. `real_time` or `fixed_rate@200ms`.
+    #[arg(long = "channel")]
+    #[arg(env = "CHANNEL")]
+    #[arg(default_value = "fixed_rate@200ms")]
+    #[arg(value_parser = parse_channel)]
+    pub channel: Channel,
+
+    /// Hex encoded ed25519 public keys of the signers that updates must be signed by,
+    /// separated by commas. Updates with other signatures are dropped.
+    #[arg(long = "trusted-signers", value_delimiter = ',', required = true)]
+    #[arg(env = "TRUSTED_SIGNERS")]
+    #[arg(value_parser = parse_public_key)]
+    pub trusted_signers: Vec<[u8; 32]>,
+
+    /// Path to a JSON file that maps Hermes price feed ids to Lazer price feeds.
+    #[arg(long = "feeds-file")]
+    #[arg(env = "FEEDS_FILE")]
+    pub feeds_file: PathBuf,
+}
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "API Options")]
+#[group(id = "Api")]
+pub struct ApiOptions {
+    /// Address and port the HTTP server will bind to.
+    #[arg(long = "listen-addr")]
+    #[arg(env = "LISTEN_ADDR")]
+    #[arg(default_value = DEFAULT_LISTEN_ADDR)]
+    pub listen_addr: SocketAddr,
+}
+
+fn parse_channel(value: &str) -> Result<Channel, String> {
+    Channel::deserialize(BorrowedStrDeserializer::<Error>::new(value))
+        .map_err(|err| err.to_string())
+}
+
+fn parse_public_key(value: &str) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    hex::decode_to_slice(value.trim_start_matches("0x"), &mut key)
+        .map_err(|err| format!("invalid public key: {err}"))?;
+    Ok(key)
+}
@@ -0,0 +1,149 @@
+//! Mapping of Hermes price feed ids to Lazer price feeds.
+//!
+//! Lazer identifies feeds by a number and publishes prices as integers with a fixed
+//! exponent per feed, which is not part of the updates. The feeds file lists both for every
+//! Hermes price feed id that the bridge serves:
+//!
+//! ```json
+//! [{ "id": "e62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43", "lazerId": 1, "exponent": -8 }]
+//! ```
+
+use {
+    anyhow::{bail, Context, Result},
+    pyth_lazer_client::protocol::router::PriceFeedId,
+    serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer},
+    std::{collections::HashMap, fmt, fs, path::Path, str::FromStr},
+};
+
+/// A Hermes price feed id, hex encoded with an optional `0x` prefix.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PriceId(pub [u8; 32]);
+
+impl FromStr for PriceId {
+    type Err = hex::FromHexError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut id = [0u8; 32];
+        hex::decode_to_slice(value.trim_start_matches("0x"), &mut id)?;
+        Ok(Self(id))
+    }
+}
+
+impl fmt::Display for PriceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl fmt::Debug for PriceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PriceId({self})")
+    }
+}
+
+impl Serialize for PriceId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PriceId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(D::Error::custom)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedConfig {
+    pub id: PriceId,
+    pub lazer_id: PriceFeedId,
+    /// Exponent of the prices of the Lazer feed.
+    pub exponent: i32,
+}
+
+/// The price feeds served by the bridge.
+#[derive(Debug, Default)]
+pub struct Feeds {
+    by_id: HashMap<PriceId, FeedConfig>,
+}
+
+impl Feeds {
+    pub fn new(feeds: impl IntoIterator<Item = FeedConfig>) -> Result<Self> {
+        let mut by_id = HashMap::new();
+        for feed in feeds {
+            if by_id.insert(feed.id, feed).is_some() {
+                bail!("duplicate price feed id: {}", feed.id);
+            }
+        }
+        if by_id.is_empty() {
+            bail!("no price feeds configured");
+        }
+        Ok(Self { by_id })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let feeds: Vec<FeedConfig> = serde_json::from_str(&data)
+            .with_context(|| format!("invalid feeds file {}", path.display()))?;
+        Self::new(feeds)
+    }
+
+    pub fn get(&self, id: &PriceId) -> Option<&FeedConfig> {
+        self.by_id.get(id)
+    }
+
+    /// IDs of the Lazer feeds to subscribe to, without duplicates.
+    pub fn lazer_ids(&self) -> Vec<PriceFeedId> {
+        let mut ids: Vec<_> = self.by_id.values().map(|feed| feed.lazer_id).collect();
+        ids.sort_by_key(|id| id.0);
+        ids.dedup();
+        ids
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ID: &str = "e62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43";
+
+    #[test]
+    fn test_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("feeds.json");
+        fs::write(
+            &path,
+            format!(
+                r#"[
+                    {{"id": "0x{ID}", "lazerId": 1, "exponent": -8}},
+                    {{"id": "{}", "lazerId": 1, "exponent": -8}}
+                ]"#,
+                "11".repeat(32)
+            ),
+        )
+        .unwrap();
+        let feeds = Feeds::load(&path).unwrap();
+        let id: PriceId = ID.parse().unwrap();
+        assert_eq!(
+            feeds.get(&id),
+            Some(&FeedConfig {
+                id,
+                lazer_id: PriceFeedId(1),
+                exponent: -8,
+            })
+        );
+        assert_eq!(feeds.lazer_ids(), [PriceFeedId(1)]);
+        assert_eq!(serde_json::to_string(&id).unwrap(), format!("\"{ID}\""));
+
+        fs::write(
+            &path,
+            format!(r#"[{{"id": "{ID}", "lazerId": 1, "exponent": -8}}, {{"id": "{ID}", "lazerId": 2, "exponent": -8}}]"#),
+        )
+        .unwrap();
+        assert!(Feeds::load(&path).is_err());
+        assert!("0x1234".parse::<PriceId>().is_err());
+    }
+}
@@ -0,0 +1,111 @@
+//! Subscription to the Lazer routers.
+
+use {
+    crate::{
+        config::LazerOptions,
+        state::{LazerUpdate, State},
+    },
+    anyhow::Result,
+    pyth_lazer_client::{
+        failover::{FailoverClient, FailoverConfig, FailoverEvent},
+        protocol::{
+            router::{
+                Chain, DeliveryFormat, JsonBinaryEncoding, JsonUpdate, PriceFeedProperty,
+                SubscriptionParams, SubscriptionParamsRepr,
+            },
+            subscription::{Response, SubscribeRequest, SubscriptionId},
+        },
+        sdk::verifier::{decode_json_binary, Signer, Verifier},
+        LazerMessage,
+    },
+    std::sync::Arc,
+};
+
+/// Verifies `update` and converts it to the update that the API serves. Returns
+/// `None` if the update is dropped.
+fn verify(verifier: &Verifier, state: &State, update: &JsonUpdate) -> Result<Option<LazerUpdate>> {
+    let verified = match verifier.verify_json_update(update) {
+        Ok(verified) => verified,
+        Err(err) => {
+            tracing::warn!("Dropping update: {}", err);
+            return Ok(None);
+        }
+    };
+    if verified.update.channel != state.channel {
+        tracing::warn!("Dropping update of channel {:?}", verified.update.channel);
+        return Ok(None);
+    }
+    Ok(Some(LazerUpdate {
+        timestamp_us: verified.update.timestamp_us,
+        prev_timestamp_us: None,
+        feeds: verified
+            .update
+            .feeds
+            .into_iter()
+            .map(|feed| (feed.feed_id, feed))
+            .collect(),
+        evm: update.evm.as_ref().map(decode_json_binary).transpose()?,
+        solana: update.solana.as_ref().map(decode_json_binary).transpose()?,
+    }))
+}
+
+/// Publishes the verified updates of the configured feeds to `state`.
+pub async fn run(options: LazerOptions, state: Arc<State>) -> Result<()> {
+    let verifier = Verifier::new(options.trusted_signers.iter().copied().map(Signer::Solana));
+    let mut client = FailoverClient::new(FailoverConfig::new(
+        options.urls.clone(),
+        options.access_token.clone(),
+    ))?;
+    let params = SubscriptionParams::new(SubscriptionParamsRepr {
+        price_feed_ids: state.feeds.lazer_ids(),
+        properties: vec![
+            PriceFeedProperty::Price,
+            PriceFeedProperty::BestBidPrice,
+            PriceFeedProperty::BestAskPrice,
+        ],
+        chains: vec![Chain::Evm, Chain::Solana],
+        delivery_format: DeliveryFormat::Json,
+        json_binary_encoding: JsonBinaryEncoding::Base64,
+        parsed: false,
+        channel: options.channel,
+        compact_format_versions: Vec::new(),
+    })
+    .map_err(anyhow::Error::msg)?;
+    client
+        .subscribe(SubscribeRequest {
+            subscription_id: SubscriptionId(1),
+            params,
+        })
+        .await?;
+
+    loop {
+        match client.next_event().await {
+            FailoverEvent::Message(LazerMessage::Response(Response::StreamUpdated(update))) => {
+                match verify(&verifier, &state, &update.payload) {
+                    Ok(Some(update)) => state.publish(update).await,
+                    Ok(None) => {}
+                    Err(err) => tracing::warn!("Dropping update: {:#}", err),
+                }
+            }
+            FailoverEvent::Message(LazerMessage::Response(Response::SubscriptionError(err))) => {
+                tracing::error!("Lazer subscription failed: {}", err.error);
+            }
+            FailoverEvent::Message(LazerMessage::Response(Response::Error(err))) => {
+                tracing::error!("Lazer router error: {}", err.error);
+            }
+            FailoverEvent::Message(message) => tracing::debug!("Lazer message: {:?}", message),
+            FailoverEvent::Connected { endpoint } => {
+                tracing::info!("Connected to {}", endpoint);
+            }
+            FailoverEvent::Disconnected { endpoint, reason } => {
+                tracing::warn!("Disconnected from {}: {}", endpoint, reason);
+            }
+            FailoverEvent::Gap(gap) => tracing::warn!(
+                "Missed {} updates between {} and {}",
+                gap.missed_updates,
+                gap.last_timestamp_us.0,
+                gap.timestamp_us.0
+            ),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use {
+    crate::{api::ApiState, config::Options, feeds::Feeds, state::State},
+    anyhow::Result,
+    clap::Parser,
+    std::{io::IsTerminal, sync::Arc},
+};
+
+pub mod api;
+pub mod config;
+pub mod feeds;
+pub mod ingest;
+pub mod state;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize a Tracing Subscriber
+    tracing::subscriber::set_global_default(
+        tracing_subscriber::fmt()
+            .compact()
+            .with_file(false)
+            .with_line_number(true)
+            .with_thread_ids(true)
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .with_ansi(std::io::stderr().is_terminal())
+            .finish(),
+    )?;
+
+    let options = Options::parse();
+    let feeds = Feeds::load(&options.lazer.feeds_file)?;
+    let state = Arc::new(State::new(feeds, options.lazer.channel));
+
+    let ingest = tokio::spawn(ingest::run(options.lazer.clone(), state.clone()));
+    tracing::info!("Starting server on: {:?}", &options.api.listen_addr);
+    let server = axum::Server::try_bind(&options.api.listen_addr)?
+        .serve(api::routes(ApiState { state }).into_make_service());
+    tokio::select! {
+        result = server => result?,
+        result = ingest => result??,
+    }
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use {
+    crate::feeds::Feeds,
+    pyth_lazer_client::{
+        protocol::router::{Channel, PriceFeedId, TimestampUs},
+        sdk::update::FeedUpdate,
+    },
+    std::{collections::HashMap, sync::Arc},
+    tokio::sync::{broadcast, RwLock},
+};
+
+/// Number of updates that SSE subscribers may fall behind before they miss updates.
+const UPDATES_CHANNEL_CAPACITY: usize = 1000;
+
+/// A verified Lazer update, with the signed messages it was received in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LazerUpdate {
+    pub timestamp_us: TimestampUs,
+    /// Timestamp of the update before this one, if any.
+    pub prev_timestamp_us: Option<TimestampUs>,
+    pub feeds: HashMap<PriceFeedId, FeedUpdate>,
+    /// Signed EVM message of the update, if the router sent one.
+    pub evm: Option<Vec<u8>>,
+    /// Signed Solana message of the update, if the router sent one.
+    pub solana: Option<Vec<u8>>,
+}
+
+pub struct State {
+    pub feeds: Feeds,
+    pub channel: Channel,
+    latest: RwLock<Option<Arc<LazerUpdate>>>,
+    updates_tx: broadcast::Sender<Arc<LazerUpdate>>,
+}
+
+impl State {
+    pub fn new(feeds: Feeds, channel: Channel) -> Self {
+        let (updates_tx, _) = broadcast::channel(UPDATES_CHANNEL_CAPACITY);
+        Self {
+            feeds,
+            channel,
+            latest: RwLock::new(None),
+            updates_tx,
+        }
+    }
+
+    pub async fn latest(&self) -> Option<Arc<LazerUpdate>> {
+        self.latest.read().await.clone()
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<LazerUpdate>> {
+        self.updates_tx.subscribe()
+    }
+
+    /// Stores `update` as the latest update and sends it to the subscribers. Updates that are
+    /// older than the latest update are dropped.
+    pub async fn publish(&self, mut update: LazerUpdate) {
+        let mut latest = self.latest.write().await;
+        if let Some(latest) = latest.as_ref() {
+            if update.timestamp_us <= latest.timestamp_us {
+                return;
+            }
+            update.prev_timestamp_us = Some(latest.timestamp_us);
+        }
+        let update = Arc::new(update);
+        *latest = Some(update.clone());
+        // Sending only fails if there are no subscribers.
+        let _ = self.updates_tx.send(update);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::feeds::{FeedConfig, PriceId},
+    };
+
+    fn update(timestamp_us: u64) -> LazerUpdate {
+        LazerUpdate {
+            timestamp_us: TimestampUs(timestamp_us),
+            prev_timestamp_us: None,
+            feeds: HashMap::new(),
+            evm: None,
+            solana: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish() {
+        let feeds = Feeds::new([FeedConfig {
+            id: PriceId([1; 32]),
+            lazer_id: PriceFeedId(1),
+            exponent: -8,
+        }])
+        .unwrap();
+        let state = State::new(feeds, Channel::RealTime);
+        let mut updates = state.subscribe();
+
+        state.publish(update(10)).await;
+        state.publish(update(20)).await;
+        // Updates that are not newer than the latest one are dropped
+        state.publish(update(20)).await;
+        state.publish(update(15)).await;
+
+        assert_eq!(*updates.recv().await.unwrap(), update(10));
+        let latest = updates.recv().await.unwrap();
+        assert_eq!(latest.prev_timestamp_us, Some(TimestampUs(10)));
+        assert_eq!(state.latest().await, Some(latest));
+        assert!(updates.try_recv().is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use {
+    crate::archive::Archive,
+    axum::{
+        extract::State,
+        http::{header::AUTHORIZATION, Request, StatusCode},
+        middleware::{self, Next},
+        response::{IntoResponse, Response},
+        routing::get,
+        Router,
+    },
+    std::{collections::HashSet, sync::Arc},
+};
+pub use {history::*, live::*};
+
+mod history;
+mod live;
+
+#[derive(Clone)]
+pub struct ApiState {
+    pub archive: Arc<Archive>,
+    /// Tokens that authorize history queries
+    pub api_tokens: Arc<HashSet<String>>,
+}
+
+pub enum RestError {
+    /// The caller did not pass a valid API token
+    Unauthorized,
+    /// The requested time range is empty
+    InvalidRange,
+    /// The caller requested more updates than a page can contain
+    InvalidLimit,
+    /// The archive could not be read
+    TemporarilyUnavailable,
+}
+
+impl IntoResponse for RestError {
+    fn into_response(self) -> Response {
+        match self {
+            RestError::Unauthorized => {
+                (StatusCode::UNAUTHORIZED, "A valid API token is required").into_response()
+            }
+            RestError::InvalidRange => (
+                StatusCode::BAD_REQUEST,
+                "The start of the time range must be before its end",
+            )
+                .into_response(),
+            RestError::InvalidLimit => (
+                StatusCode::BAD_REQUEST,
+                format!("The limit must be between 1 and {MAX_LIMIT}"),
+            )
+                .into_response(),
+            RestError::TemporarilyUnavailable => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "This service is temporarily unavailable",
+            )
+                .into_response(),
+        }
+    }
+}
+
+/// Rejects requests without a `Bearer` token from the API tokens.
+async fn authorize<B>(
+    State(state): State<ApiState>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match token {
+        Some(token) if state.api_tokens.contains(token) => next.run(request).await,
+        _ => RestError::Unauthorized.into_response(),
+    }
+}
+
+pub fn routes(state: ApiState) -> Router {
+    Router::new()
+        .route("/history", get(history))
+        .route_layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .route("/live", get(live))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::{
+            archive::{test_update, CHUNK_DURATION_US},
+            store::{FileStore, Store},
+        },
+        axum_test::TestServer,
+        pyth_lazer_client::protocol::router::Channel,
+        serde_json::json,
+    };
+
+    async fn server(dir: &tempfile::TempDir) -> TestServer {
+        let archive = Archive::new(
+            Store::File(FileStore::new(dir.path().to_path_buf())),
+            Channel::RealTime,
+        );
+        archive
+            .write_chunk(0, &[test_update(10, &[1, 2]), test_update(20, &[1])])
+            .await
+            .unwrap();
+        let state = ApiState {
+            archive: Arc::new(archive),
+            api_tokens: Arc::new(["token".to_string()].into()),
+        };
+        TestServer::new(routes(state).into_make_service()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_authorization() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = server(&dir).await;
+        let query = json!({"feed": 1, "from": 0, "to": 100});
+
+        server.get("/live").await.assert_status_ok();
+        server
+            .get("/history")
+            .add_query_params(&query)
+            .expect_failure()
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        server
+            .get("/history")
+            .add_query_params(&query)
+            .add_header(AUTHORIZATION, "Bearer other".parse().unwrap())
+            .expect_failure()
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        server
+            .get("/history")
+            .add_query_params(&query)
+            .add_header(AUTHORIZATION, "Bearer token".parse().unwrap())
+            .await
+            .assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn test_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = server(&dir).await;
+        let get = |query: serde_json::Value| {
+            server
+                .get("/history")
+                .add_query_params(query)
+                .add_header(AUTHORIZATION, "Bearer token".parse().unwrap())
+        };
+
+        get(json!({"feed": 2, "from": 0, "to": 100}))
+            .await
+            .assert_json(&json!({
+                "updates": [{
+                    "timestampUs": "10",
+                    "priceFeeds": [{"priceFeedId": 2, "price": "100"}],
+                    "solana": "00",
+                }],
+            }));
+        get(json!({"feed": 1, "from": 0, "to": 2 * CHUNK_DURATION_US, "limit": 1}))
+            .await
+            .assert_json(&json!({
+                "updates": [{
+                    "timestampUs": "10",
+                    "priceFeeds": [{"priceFeedId": 1, "price": "100"}],
+                    "solana": "00",
+                }],
+                "nextFrom": "20",
+            }));
+        get(json!({"feed": 1, "from": 100, "to": 100}))
+            .expect_failure()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        get(json!({"feed": 1, "from": 0, "to": 100, "limit": 0}))
+            .expect_failure()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+}
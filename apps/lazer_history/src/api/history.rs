@@ -0,0 +1,70 @@
+use {
+    crate::{
+        api::{ApiState, RestError},
+        archive::ArchivedUpdate,
+    },
+    axum::{
+        extract::{Query, State},
+        Json,
+    },
+    pyth_lazer_client::protocol::router::PriceFeedId,
+    serde::{Deserialize, Serialize},
+};
+
+pub const DEFAULT_LIMIT: usize = 1000;
+pub const MAX_LIMIT: usize = 10_000;
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    /// ID of the price feed
+    pub feed: u32,
+    /// Start of the time range (inclusive), in microseconds since the Unix epoch
+    pub from: u64,
+    /// End of the time range (exclusive), in microseconds since the Unix epoch
+    pub to: u64,
+    /// Maximum number of updates to return
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryResponse {
+    /// Updates of the feed within the time range, in order. Every update contains the signed
+    /// messages it was received in, so the prices can be verified independently of this
+    /// service.
+    pub updates: Vec<ArchivedUpdate>,
+    /// If set, the time range has more updates, which can be requested with this value as
+    /// `from`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_from: Option<String>,
+}
+
+/// Get the archived updates of a price feed within a time range.
+///
+/// Responses contain at most `limit` updates. Use `nextFrom` of the response to request the
+/// next page.
+pub async fn history(
+    State(state): State<ApiState>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<HistoryResponse>, RestError> {
+    if query.from >= query.to {
+        return Err(RestError::InvalidRange);
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+    if limit == 0 || limit > MAX_LIMIT {
+        return Err(RestError::InvalidLimit);
+    }
+
+    let page = state
+        .archive
+        .query(PriceFeedId(query.feed), query.from, query.to, limit)
+        .await
+        .map_err(|err| {
+            tracing::error!("Failed to read the archive: {:#}", err);
+            RestError::TemporarilyUnavailable
+        })?;
+    Ok(Json(HistoryResponse {
+        updates: page.updates,
+        next_from: page.next_from_us.map(|value| value.to_string()),
+    }))
+}
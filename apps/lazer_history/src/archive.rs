@@ -0,0 +1,272 @@
+//! Layout of the archive in the object storage.
+//!
+//! Updates are grouped into chunks by their timestamp. A chunk is stored as one object with
+//! a JSON encoded [`ArchivedUpdate`] per line, at a key derived from the channel and the
+//! start time of the chunk. A time range therefore maps to a known list of objects, and
+//! queries don't need to list the bucket.
+
+use {
+    crate::store::Store,
+    anyhow::{Context, Result},
+    chrono::DateTime,
+    pyth_lazer_client::protocol::router::{Channel, ParsedPayload, PriceFeedId},
+    serde::{Deserialize, Serialize},
+};
+
+/// Duration of the time range covered by one chunk.
+pub const CHUNK_DURATION_US: u64 = 60_000_000;
+
+/// A query reads at most this many chunks, so that a page over a sparse range of the
+/// archive still completes quickly.
+pub const MAX_CHUNKS_PER_PAGE: usize = 60;
+
+/// A verified update, with the signed messages it was received in.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedUpdate {
+    /// The data of the signed payload.
+    #[serde(flatten)]
+    pub parsed: ParsedPayload,
+    /// Hex encoded Solana message that the payload is signed in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solana: Option<String>,
+    /// Hex encoded EVM message that the payload is signed in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evm: Option<String>,
+}
+
+impl ArchivedUpdate {
+    pub fn timestamp_us(&self) -> u64 {
+        self.parsed.timestamp_us.0
+    }
+}
+
+/// Start of the chunk that contains `timestamp_us`.
+pub fn chunk_start(timestamp_us: u64) -> u64 {
+    timestamp_us - timestamp_us % CHUNK_DURATION_US
+}
+
+/// Updates of a time range, and the start of the next page if the range has more updates.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistoryPage {
+    pub updates: Vec<ArchivedUpdate>,
+    pub next_from_us: Option<u64>,
+}
+
+/// Archived updates of one channel.
+pub struct Archive {
+    store: Store,
+    channel: Channel,
+}
+
+impl Archive {
+    pub fn new(store: Store, channel: Channel) -> Self {
+        Self { store, channel }
+    }
+
+    pub fn channel(&self) -> Channel {
+        self.channel
+    }
+
+    /// Key of the chunk starting at `start_us`, e.g. `fixed_rate@200ms/2024-10-14/1230.jsonl`.
+    fn chunk_key(&self, start_us: u64) -> Result<String> {
+        let channel = serde_json::to_value(self.channel)?;
+        let time = i64::try_from(start_us)
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+            .context("invalid timestamp")?;
+        Ok(format!(
+            "{}/{}.jsonl",
+            channel.as_str().context("invalid channel")?,
+            time.format("%Y-%m-%d/%H%M")
+        ))
+    }
+
+    pub async fn read_chunk(&self, start_us: u64) -> Result<Vec<ArchivedUpdate>> {
+        let key = self.chunk_key(start_us)?;
+        let Some(data) = self.store.get(&key).await? else {
+            return Ok(Vec::new());
+        };
+        data.split(|&byte| byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).with_context(|| format!("invalid {key}")))
+            .collect()
+    }
+
+    /// Writes `updates` to the chunk starting at `start_us`. Updates that are already in the
+    /// chunk, e.g. from before a restart of the service, are kept.
+    pub async fn write_chunk(&self, start_us: u64, updates: &[ArchivedUpdate]) -> Result<()> {
+        let mut chunk = self.read_chunk(start_us).await?;
+        chunk.extend_from_slice(updates);
+        chunk.sort_by_key(ArchivedUpdate::timestamp_us);
+        chunk.dedup_by_key(|update| update.timestamp_us());
+
+        let mut data = Vec::new();
+        for update in &chunk {
+            serde_json::to_writer(&mut data, update)?;
+            data.push(b'\n');
+        }
+        self.store.put(&self.chunk_key(start_us)?, data).await
+    }
+
+    /// Reads up to `limit` updates of `feed_id` with timestamps in `from_us..to_us`. The
+    /// updates only contain the data of `feed_id`, but the signed messages cover all feeds
+    /// that were archived with it.
+    pub async fn query(
+        &self,
+        feed_id: PriceFeedId,
+        from_us: u64,
+        to_us: u64,
+        limit: usize,
+    ) -> Result<HistoryPage> {
+        let mut updates = Vec::new();
+        let mut start_us = chunk_start(from_us);
+        for _ in 0..MAX_CHUNKS_PER_PAGE {
+            if start_us >= to_us {
+                break;
+            }
+            for mut update in self.read_chunk(start_us).await? {
+                let timestamp_us = update.timestamp_us();
+                if timestamp_us < from_us || timestamp_us >= to_us {
+                    continue;
+                }
+                update
+                    .parsed
+                    .price_feeds
+                    .retain(|feed| feed.price_feed_id == feed_id);
+                if update.parsed.price_feeds.is_empty() {
+                    continue;
+                }
+                if updates.len() == limit {
+                    return Ok(HistoryPage {
+                        updates,
+                        next_from_us: Some(timestamp_us),
+                    });
+                }
+                updates.push(update);
+            }
+            start_us += CHUNK_DURATION_US;
+        }
+        Ok(HistoryPage {
+            updates,
+            next_from_us: Some(start_us).filter(|&start_us| start_us < to_us),
+        })
+    }
+}
+
+/// Creates an update with the price of `feed_ids` at `timestamp_us`.
+#[cfg(test)]
+pub fn test_update(timestamp_us: u64, feed_ids: &[u32]) -> ArchivedUpdate {
+    use pyth_lazer_client::protocol::router::{ParsedFeedPayload, Price, TimestampUs};
+
+    ArchivedUpdate {
+        parsed: ParsedPayload {
+            timestamp_us: TimestampUs(timestamp_us),
+            price_feeds: feed_ids
+                .iter()
+                .map(|&feed_id| ParsedFeedPayload {
+                    price_feed_id: PriceFeedId(feed_id),
+                    price: Some(Price::new(100).unwrap()),
+                    best_bid_price: None,
+                    best_ask_price: None,
+                })
+                .collect(),
+        },
+        solana: Some("00".to_string()),
+        evm: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, crate::store::FileStore, pyth_lazer_client::protocol::router::FixedRate};
+
+    fn archive(dir: &tempfile::TempDir) -> Archive {
+        Archive::new(
+            Store::File(FileStore::new(dir.path().to_path_buf())),
+            Channel::FixedRate(FixedRate::from_ms(200).unwrap()),
+        )
+    }
+
+    #[test]
+    fn test_chunk_key() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            archive(&dir).chunk_key(1_728_909_000_000_000).unwrap(),
+            "fixed_rate@200ms/2024-10-14/1230.jsonl"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_chunk_merges() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = archive(&dir);
+        archive
+            .write_chunk(0, &[test_update(1, &[1]), test_update(3, &[1])])
+            .await
+            .unwrap();
+        archive
+            .write_chunk(0, &[test_update(2, &[1]), test_update(3, &[1])])
+            .await
+            .unwrap();
+        let chunk = archive.read_chunk(0).await.unwrap();
+        assert_eq!(
+            chunk.iter().map(|u| u.timestamp_us()).collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        assert!(archive
+            .read_chunk(CHUNK_DURATION_US)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = archive(&dir);
+        archive
+            .write_chunk(0, &[test_update(10, &[1, 2]), test_update(20, &[2])])
+            .await
+            .unwrap();
+        archive
+            .write_chunk(
+                CHUNK_DURATION_US,
+                &[test_update(CHUNK_DURATION_US + 10, &[1])],
+            )
+            .await
+            .unwrap();
+
+        let page = archive
+            .query(PriceFeedId(1), 0, 2 * CHUNK_DURATION_US, 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            page,
+            HistoryPage {
+                updates: vec![
+                    test_update(10, &[1]),
+                    test_update(CHUNK_DURATION_US + 10, &[1])
+                ],
+                next_from_us: None,
+            }
+        );
+
+        // Pages end at the limit
+        let page = archive
+            .query(PriceFeedId(2), 0, 2 * CHUNK_DURATION_US, 1)
+            .await
+            .unwrap();
+        assert_eq!(page.updates, [test_update(10, &[2])]);
+        assert_eq!(page.next_from_us, Some(20));
+
+        // ... and after reading `MAX_CHUNKS_PER_PAGE` chunks
+        let to_us = 2 * MAX_CHUNKS_PER_PAGE as u64 * CHUNK_DURATION_US;
+        let page = archive.query(PriceFeedId(1), 11, to_us, 10).await.unwrap();
+        assert_eq!(page.updates, [test_update(CHUNK_DURATION_US + 10, &[1])]);
+        assert_eq!(
+            page.next_from_us,
+            Some(MAX_CHUNKS_PER_PAGE as u64 * CHUNK_DURATION_US)
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use {
+    clap::{crate_authors, crate_description, crate_name, crate_version, Args, Parser},
+    pyth_lazer_client::protocol::router::{Channel, PriceFeedId},
+    serde::{
+        de::value::{BorrowedStrDeserializer, Error},
+        Deserialize,
+    },
+    std::{net::SocketAddr, path::PathBuf},
+    url::Url,
+};
+
+const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:34100";
+
+#[derive(Parser, Clone, Debug)]
+#[command(name = crate_name!())]
+#[command(author = crate_authors!())]
+#[command(about = crate_description!())]
+#[command(version = crate_version!())]
+pub struct Options {
+    #[command(flatten)]
+    pub lazer: LazerOptions,
+
+    #[command(flatten)]
+    pub storage: StorageOptions,
+
+    #[command(flatten)]
+    pub api: ApiOptions,
+}
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "Lazer Options")]
+#[group(id = "Lazer")]
+pub struct LazerOptions {
+    /// WebSocket URL of the stream endpoint of the Lazer router.
+    #[arg(long = "lazer-url")]
+    #[arg(env = "LAZER_URL")]
+    pub url: Url,
+
+    /// Access token for the Lazer router.
+    #[arg(long = "lazer-access-token")]
+    #[arg(env = "LAZER_ACCESS_TOKEN")]
+    pub access_token: String,
+
+    /// IDs of the price feeds to archive, separated by commas.
+    #[arg(long = "feed-ids", value_delimiter = ',', required = true)]
+    #[arg(env = "FEED_IDS")]
+    #[arg(value_parser = parse_feed_id)]
+    pub feed_ids: Vec<PriceFeedId>,
+
+    /// Channel to archive, e.g. `real_time` or `fixed_rate@200ms`.
+    #[arg(long = "channel")]
+    #[arg(env = "CHANNEL")]
+    #[arg(default_value = "fixed_rate@200ms")]
+    #[arg(value_parser = parse_channel)]
+    pub channel: Channel,
+
+    /// Hex encoded ed25519 public keys of the signers that updates must be signed by,
+    /// separated by commas. Updates with other signatures are not archived.
+    #[arg(long = "trusted-signers", value_delimiter = ',', required = true)]
+    #[arg(env = "TRUSTED_SIGNERS")]
+    #[arg(value_parser = parse_public_key)]
+    pub trusted_signers: Vec<[u8; 32]>,
+}
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "Storage Options")]
+#[group(id = "Storage")]
+pub struct StorageOptions {
+    /// Where to archive updates: a local directory (`file:///var/lib/lazer-history`) or a
+    /// bucket of an S3 compatible object storage (`s3://<bucket>/<prefix>`).
+    #[arg(long = "storage-url")]
+    #[arg(env = "STORAGE_URL")]
+    pub url: Url,
+
+    /// Endpoint of the S3 compatible object storage.
+    #[arg(long = "s3-endpoint")]
+    #[arg(env = "S3_ENDPOINT")]
+    #[arg(default_value = "https://s3.amazonaws.com")]
+    pub s3_endpoint: Url,
+
+    #[arg(long = "s3-region")]
+    #[arg(env = "AWS_REGION")]
+    #[arg(default_value = "us-east-1")]
+    pub s3_region: String,
+
+    #[arg(long = "s3-access-key-id")]
+    #[arg(env = "AWS_ACCESS_KEY_ID")]
+    pub s3_access_key_id: Option<String>,
+
+    #[arg(long = "s3-secret-access-key")]
+    #[arg(env = "AWS_SECRET_ACCESS_KEY")]
+    pub s3_secret_access_key: Option<String>,
+}
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "API Options")]
+#[group(id = "Api")]
+pub struct ApiOptions {
+    /// Address and port the HTTP server will bind to.
+    #[arg(long = "listen-addr")]
+    #[arg(env = "LISTEN_ADDR")]
+    #[arg(default_value = DEFAULT_LISTEN_ADDR)]
+    pub listen_addr: SocketAddr,
+
+    /// Path to a file with the tokens that authorize history queries, one per line.
+    #[arg(long = "api-tokens-file")]
+    #[arg(env = "API_TOKENS_FILE")]
+    pub api_tokens_file: PathBuf,
+}
+
+fn parse_feed_id(value: &str) -> Result<PriceFeedId, String> {
+    value
+        .parse()
+        .map(PriceFeedId)
+        .map_err(|err| format!("invalid feed id: {err}"))
+}
+
+fn parse_channel(value: &str) -> Result<Channel, String> {
+    Channel::deserialize(BorrowedStrDeserializer::<Error>::new(value))
+        .map_err(|err| err.to_string())
+}
+
+fn parse_public_key(value: &str) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    hex::decode_to_slice(value.trim_start_matches("0x"), &mut key)
+        .map_err(|err| format!("invalid public key: {err}"))?;
+    Ok(key)
+}
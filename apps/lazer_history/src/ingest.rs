@@ -0,0 +1,193 @@
+//! Subscription to the Lazer router and archiving of the received updates.
+
+use {
+    crate::{
+        archive::{chunk_start, Archive, ArchivedUpdate, CHUNK_DURATION_US},
+        config::LazerOptions,
+    },
+    anyhow::{bail, Result},
+    pyth_lazer_client::{
+        protocol::{
+            router::{
+                Chain, DeliveryFormat, JsonBinaryEncoding, JsonUpdate, ParsedFeedPayload,
+                ParsedPayload, PriceFeedProperty, SubscriptionParams, SubscriptionParamsRepr,
+                TimestampUs,
+            },
+            subscription::{Response, SubscribeRequest, SubscriptionId},
+        },
+        sdk::verifier::{decode_json_binary, Signer, Verifier},
+        LazerConnection, LazerMessage,
+    },
+    std::{sync::Arc, time::Duration},
+};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Updates of the chunk that is currently being received.
+struct PendingChunk {
+    start_us: u64,
+    updates: Vec<ArchivedUpdate>,
+}
+
+/// Verifies updates and writes them to the archive once their chunk is complete.
+pub struct Recorder {
+    archive: Arc<Archive>,
+    verifier: Verifier,
+    chunk: Option<PendingChunk>,
+}
+
+impl Recorder {
+    pub fn new(archive: Arc<Archive>, verifier: Verifier) -> Self {
+        Self {
+            archive,
+            verifier,
+            chunk: None,
+        }
+    }
+
+    /// Adds an update to the archive. Updates without a valid signature of a trusted signer
+    /// are dropped.
+    pub async fn record(&mut self, update: &JsonUpdate) -> Result<()> {
+        let verified = match self.verifier.verify_json_update(update) {
+            Ok(verified) => verified,
+            Err(err) => {
+                tracing::warn!("Dropping update: {}", err);
+                return Ok(());
+            }
+        };
+        if verified.update.channel != self.archive.channel() {
+            tracing::warn!("Dropping update of channel {:?}", verified.update.channel);
+            return Ok(());
+        }
+
+        let archived = ArchivedUpdate {
+            parsed: ParsedPayload {
+                timestamp_us: verified.update.timestamp_us,
+                price_feeds: verified
+                    .update
+                    .feeds
+                    .iter()
+                    .map(|feed| ParsedFeedPayload {
+                        price_feed_id: feed.feed_id,
+                        price: feed.price,
+                        best_bid_price: feed.best_bid_price,
+                        best_ask_price: feed.best_ask_price,
+                    })
+                    .collect(),
+            },
+            solana: update
+                .solana
+                .as_ref()
+                .map(|data| decode_json_binary(data).map(hex::encode))
+                .transpose()?,
+            evm: update
+                .evm
+                .as_ref()
+                .map(|data| decode_json_binary(data).map(hex::encode))
+                .transpose()?,
+        };
+
+        let start_us = chunk_start(archived.timestamp_us());
+        if self
+            .chunk
+            .as_ref()
+            .is_some_and(|chunk| chunk.start_us != start_us)
+        {
+            self.flush().await?;
+        }
+        self.chunk
+            .get_or_insert_with(|| PendingChunk {
+                start_us,
+                updates: Vec::new(),
+            })
+            .updates
+            .push(archived);
+        Ok(())
+    }
+
+    /// Writes the pending chunk if its time range has passed.
+    pub async fn flush_expired(&mut self, now: TimestampUs) -> Result<()> {
+        if self
+            .chunk
+            .as_ref()
+            .is_some_and(|chunk| now.0 >= chunk.start_us + CHUNK_DURATION_US)
+        {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Writes the pending chunk. It's kept if the write fails, so it can be retried.
+    pub async fn flush(&mut self) -> Result<()> {
+        if let Some(chunk) = &self.chunk {
+            self.archive
+                .write_chunk(chunk.start_us, &chunk.updates)
+                .await?;
+            tracing::info!(
+                "Archived {} updates from {}",
+                chunk.updates.len(),
+                chunk.start_us
+            );
+            self.chunk = None;
+        }
+        Ok(())
+    }
+}
+
+/// Archives the updates of the subscription described by `options`, reconnecting to the
+/// router on errors.
+pub async fn run(options: LazerOptions, archive: Arc<Archive>) {
+    let verifier = Verifier::new(options.trusted_signers.iter().copied().map(Signer::Solana));
+    let mut recorder = Recorder::new(archive, verifier);
+    loop {
+        if let Err(err) = subscribe(&options, &mut recorder).await {
+            tracing::error!("Lazer subscription failed: {:#}", err);
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn subscribe(options: &LazerOptions, recorder: &mut Recorder) -> Result<()> {
+    let mut connection = LazerConnection::connect(&options.url, &options.access_token).await?;
+    let params = SubscriptionParams::new(SubscriptionParamsRepr {
+        price_feed_ids: options.feed_ids.clone(),
+        properties: vec![
+            PriceFeedProperty::Price,
+            PriceFeedProperty::BestBidPrice,
+            PriceFeedProperty::BestAskPrice,
+        ],
+        chains: vec![Chain::Solana, Chain::Evm],
+        delivery_format: DeliveryFormat::Json,
+        json_binary_encoding: JsonBinaryEncoding::Hex,
+        parsed: false,
+        channel: options.channel,
+        compact_format_versions: Vec::new(),
+    })
+    .map_err(anyhow::Error::msg)?;
+    connection
+        .subscribe(SubscribeRequest {
+            subscription_id: SubscriptionId(1),
+            params,
+        })
+        .await?;
+
+    let mut flush_interval = tokio::time::interval(FLUSH_INTERVAL);
+    loop {
+        tokio::select! {
+            message = connection.next_message() => match message? {
+                Some(LazerMessage::Response(Response::StreamUpdated(update))) => {
+                    recorder.record(&update.payload).await?;
+                }
+                Some(LazerMessage::Response(Response::Subscribed(_))) => {
+                    tracing::info!("Subscribed to {}", options.url);
+                }
+                Some(LazerMessage::Response(Response::Error(err))) => bail!(err.error),
+                Some(LazerMessage::Response(Response::SubscriptionError(err))) => bail!(err.error),
+                Some(message) => tracing::warn!("Unexpected message: {:?}", message),
+                None => bail!("connection closed by the router"),
+            },
+            _ = flush_interval.tick() => recorder.flush_expired(TimestampUs::now()).await?,
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use {
+    crate::{api::ApiState, archive::Archive, config::Options, store::Store},
+    anyhow::{Context, Result},
+    clap::Parser,
+    std::{fs, io::IsTerminal, sync::Arc},
+};
+
+pub mod api;
+pub mod archive;
+pub mod config;
+pub mod ingest;
+pub mod store;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize a Tracing Subscriber
+    tracing::subscriber::set_global_default(
+        tracing_subscriber::fmt()
+            .compact()
+            .with_file(false)
+            .with_line_number(true)
+            .with_thread_ids(true)
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .with_ansi(std::io::stderr().is_terminal())
+            .finish(),
+    )?;
+
+    let options = Options::parse();
+    let api_tokens = fs::read_to_string(&options.api.api_tokens_file)
+        .with_context(|| {
+            format!(
+                "failed to read API tokens from {}",
+                options.api.api_tokens_file.display()
+            )
+        })?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let store = Store::from_options(&options.storage)?;
+    let archive = Arc::new(Archive::new(store, options.lazer.channel));
+    tokio::spawn(ingest::run(options.lazer.clone(), archive.clone()));
+
+    let state = ApiState {
+        archive,
+        api_tokens: Arc::new(api_tokens),
+    };
+    tracing::info!("Starting server on: {:?}", &options.api.listen_addr);
+    axum::Server::try_bind(&options.api.listen_addr)?
+        .serve(api::routes(state).into_make_service())
+        .await?;
+    Ok(())
+}
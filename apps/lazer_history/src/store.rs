@@ -0,0 +1,70 @@
+//! Object storage backends of the archive.
+
+use {
+    crate::config::StorageOptions,
+    anyhow::{anyhow, bail, Context, Result},
+};
+pub use {
+    file::FileStore,
+    s3::{S3Config, S3Store},
+};
+
+mod file;
+mod s3;
+
+pub enum Store {
+    File(FileStore),
+    S3(S3Store),
+}
+
+impl Store {
+    pub fn from_options(options: &StorageOptions) -> Result<Self> {
+        match options.url.scheme() {
+            "file" => {
+                let root = options
+                    .url
+                    .to_file_path()
+                    .map_err(|_| anyhow!("invalid storage path: {}", options.url))?;
+                Ok(Store::File(FileStore::new(root)))
+            }
+            "s3" => {
+                let bucket = options.url.host_str().context("missing bucket name")?;
+                let mut prefix = options.url.path().trim_start_matches('/').to_string();
+                if !prefix.is_empty() && !prefix.ends_with('/') {
+                    prefix.push('/');
+                }
+                Ok(Store::S3(S3Store::new(S3Config {
+                    endpoint: options.s3_endpoint.clone(),
+                    region: options.s3_region.clone(),
+                    bucket: bucket.to_string(),
+                    prefix,
+                    access_key_id: options
+                        .s3_access_key_id
+                        .clone()
+                        .context("missing S3 access key id")?,
+                    secret_access_key: options
+                        .s3_secret_access_key
+                        .clone()
+                        .context("missing S3 secret access key")?,
+                })))
+            }
+            scheme => bail!("unsupported storage scheme: {scheme}"),
+        }
+    }
+
+    /// Writes the object at `key`, replacing any existing object.
+    pub async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        match self {
+            Store::File(store) => store.put(key, data).await,
+            Store::S3(store) => store.put(key, data).await,
+        }
+    }
+
+    /// Reads the object at `key`, or returns `None` if it does not exist.
+    pub async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match self {
+            Store::File(store) => store.get(key).await,
+            Store::S3(store) => store.get(key).await,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use {
+    anyhow::Result,
+    std::{io::ErrorKind, path::PathBuf},
+    tokio::fs,
+};
+
+/// Stores objects as files in a local directory, e.g. a mounted volume.
+pub struct FileStore {
+    root: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    pub async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        // Write to a temporary file first, so that readers never see a partial object.
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, data).await?;
+        fs::rename(&tmp_path, &path).await?;
+        Ok(())
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match fs::read(self.root.join(key)).await {
+            Ok(data) => Ok(Some(data)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
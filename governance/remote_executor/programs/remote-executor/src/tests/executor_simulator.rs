@@ -19,7 +19,7 @@ use {
     },
     solana_sdk::{
         account::Account,
-        bpf_loader_upgradeable,
+        bpf_loader, bpf_loader_upgradeable,
         instruction::{Instruction, InstructionError},
         signature::Keypair,
         signer::Signer,
@@ -106,6 +106,21 @@ impl ExecutorBench {
         }
     }
 
+    /// Deploys the program at `path` as non-upgradable, e.g. to test the instructions of
+    /// another program that the executor has authority over
+    pub fn add_bpf_program(&mut self, program_id: &Pubkey, path: &str) {
+        let bpf_data = read_file(std::env::current_dir().unwrap().join(Path::new(path)));
+
+        let program_account = Account {
+            lamports: Rent::default().minimum_balance(bpf_data.len()),
+            data: bpf_data,
+            owner: bpf_loader::ID,
+            executable: true,
+            rent_epoch: Epoch::default(),
+        };
+        self.program_test.add_account(*program_id, program_account);
+    }
+
     /// Start local validator based on the current bench
     pub async fn start(self) -> ExecutorSimulator {
         // Start validator
@@ -233,7 +248,7 @@ impl ExecutorSimulator {
 
     /// Process a transaction containing `instruction` signed by `signers`.
     /// `payer` is used to pay for and sign the transaction.
-    async fn process_ix(
+    pub async fn process_ix(
         &mut self,
         instruction: Instruction,
         signers: &Vec<&Keypair>,
@@ -1,3 +1,4 @@
 mod executor_simulator;
 mod test_adversarial;
 mod test_basic_instructions;
+mod test_message_buffer_admin;
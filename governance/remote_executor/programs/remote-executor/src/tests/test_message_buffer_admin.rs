@@ -0,0 +1,107 @@
+use {
+    super::executor_simulator::{ExecutorAttack, ExecutorBench, VaaAttack},
+    anchor_lang::{
+        prelude::{AccountMeta, Pubkey},
+        solana_program::{hash::hash, system_program},
+        AnchorSerialize,
+    },
+    solana_sdk::{
+        instruction::Instruction, native_token::LAMPORTS_PER_SOL, signature::Keypair,
+        signer::Signer,
+    },
+    std::str::FromStr,
+};
+
+const MESSAGE_BUFFER_PROGRAM_ID: &str = "7Vbmv1jt4vyuqBZcpYPpnVhrqVe5e6ZPb6JxDcffRHUM";
+
+/// Data of the message buffer instruction `name`: its Anchor discriminator followed by `args`
+fn message_buffer_ix_data(name: &str, args: impl AnchorSerialize) -> Vec<u8> {
+    let mut data = hash(format!("global:{}", name).as_bytes()).to_bytes()[..8].to_vec();
+    data.extend(args.try_to_vec().unwrap());
+    data
+}
+
+#[tokio::test]
+/// This test checks that the admin of the message buffer whitelist can be handed over to the
+/// executor key of an emitter, and that the executor can then act as the admin
+async fn test_message_buffer_admin() {
+    let mut bench = ExecutorBench::new();
+    let message_buffer_program_id = Pubkey::from_str(MESSAGE_BUFFER_PROGRAM_ID).unwrap();
+    bench.add_bpf_program(
+        &message_buffer_program_id,
+        "../../../../pythnet/message_buffer/target/deploy/message_buffer.so",
+    );
+
+    let emitter = Pubkey::new_unique();
+    let executor_key = bench.get_executor_key(&emitter);
+    let whitelist = Pubkey::find_program_address(
+        &[b"message".as_ref(), b"whitelist".as_ref()],
+        &message_buffer_program_id,
+    )
+    .0;
+    let allowed_program = Pubkey::new_unique();
+
+    let accept_whitelist_admin = Instruction {
+        program_id: message_buffer_program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(executor_key, true),
+            AccountMeta::new(whitelist, false),
+        ],
+        data: message_buffer_ix_data("accept_whitelist_admin", ()),
+    };
+    let set_allowed_programs = Instruction {
+        program_id: message_buffer_program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(executor_key, true),
+            AccountMeta::new(whitelist, false),
+        ],
+        data: message_buffer_ix_data("set_allowed_programs", vec![allowed_program]),
+    };
+    let vaa_account = bench.add_vaa_account(
+        &emitter,
+        &[accept_whitelist_admin, set_allowed_programs],
+        VaaAttack::None,
+    );
+
+    let mut sim = bench.start().await;
+
+    let admin = Keypair::new();
+    sim.airdrop(&admin.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    let initialize = Instruction {
+        program_id: message_buffer_program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(admin.pubkey(), true),
+            AccountMeta::new(admin.pubkey(), true),
+            AccountMeta::new(whitelist, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: message_buffer_ix_data("initialize", ()),
+    };
+    sim.process_ix(initialize, &vec![&admin]).await.unwrap();
+
+    let propose_whitelist_admin = Instruction {
+        program_id: message_buffer_program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(admin.pubkey(), true),
+            AccountMeta::new(whitelist, false),
+        ],
+        data: message_buffer_ix_data("propose_whitelist_admin", executor_key),
+    };
+    sim.process_ix(propose_whitelist_admin, &vec![&admin])
+        .await
+        .unwrap();
+
+    sim.execute_posted_vaa(&vaa_account, &vec![], ExecutorAttack::None)
+        .await
+        .unwrap();
+
+    // Whitelist layout: discriminator, bump, admin, allowed_programs, pending_admin
+    let data = sim.get_account(whitelist).await.unwrap().data;
+    assert_eq!(&data[9..41], executor_key.as_ref());
+    assert_eq!(&data[41..45], &1u32.to_le_bytes());
+    assert_eq!(&data[45..77], allowed_program.as_ref());
+    assert_eq!(data[77], 0);
+}
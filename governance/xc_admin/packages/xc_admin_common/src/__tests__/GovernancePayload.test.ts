@@ -27,11 +27,18 @@ import {
   RequestGovernanceDataSourceTransfer,
 } from "../governance_payload/GovernanceDataSourceTransfer";
 import { SetFee, SetFeeInToken } from "../governance_payload/SetFee";
+import {
+  CosmosSetFeeRecipient,
+  NearSetFeeRecipient,
+} from "../governance_payload/SetFeeRecipient";
+import { SetPriceFeedRetention } from "../governance_payload/SetPriceFeedRetention";
 import { SetValidPeriod } from "../governance_payload/SetValidPeriod";
 import {
   DataSource,
   SetDataSources,
 } from "../governance_payload/SetDataSources";
+import { SetIbcChannels } from "../governance_payload/SetIbcChannels";
+import { SetPriceHistoryDepth } from "../governance_payload/SetPriceHistoryDepth";
 
 test("GovernancePayload ser/de", (done) => {
   jest.setTimeout(60000);
@@ -244,6 +251,74 @@ test("GovernancePayload ser/de", (done) => {
     )
   ).toBeTruthy();
 
+  const setIbcChannels = new SetIbcChannels("osmosis", [
+    "channel-0",
+    "channel-12",
+  ]);
+  const setIbcChannelsBuffer = setIbcChannels.encode();
+  console.log(setIbcChannelsBuffer.toJSON());
+  expect(
+    setIbcChannelsBuffer.equals(
+      Buffer.from([
+        80, 84, 71, 77, 1, 8, 234, 110, 2, 9, 99, 104, 97, 110, 110, 101, 108,
+        45, 48, 10, 99, 104, 97, 110, 110, 101, 108, 45, 49, 50,
+      ])
+    )
+  ).toBeTruthy();
+
+  const setPriceHistoryDepth = new SetPriceHistoryDepth("osmosis", 1000);
+  const setPriceHistoryDepthBuffer = setPriceHistoryDepth.encode();
+  console.log(setPriceHistoryDepthBuffer.toJSON());
+  expect(
+    setPriceHistoryDepthBuffer.equals(
+      Buffer.from([80, 84, 71, 77, 1, 9, 234, 110, 3, 232])
+    )
+  ).toBeTruthy();
+
+  const cosmosSetFeeRecipient = new CosmosSetFeeRecipient(
+    "osmosis",
+    Buffer.from("0102030405060708090a0b0c0d0e0f1011121314", "hex")
+  );
+  const cosmosSetFeeRecipientBuffer = cosmosSetFeeRecipient.encode();
+  console.log(cosmosSetFeeRecipientBuffer.toJSON());
+  expect(
+    cosmosSetFeeRecipientBuffer.equals(
+      Buffer.from([
+        80, 84, 71, 77, 1, 10, 234, 110, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12,
+        13, 14, 15, 16, 17, 18, 19, 20,
+      ])
+    )
+  ).toBeTruthy();
+
+  const nearSetFeeRecipient = new NearSetFeeRecipient("near", "pyth.near");
+  const nearSetFeeRecipientBuffer = nearSetFeeRecipient.encode();
+  console.log(nearSetFeeRecipientBuffer.toJSON());
+  expect(
+    nearSetFeeRecipientBuffer.equals(
+      Buffer.from([
+        80, 84, 71, 77, 1, 10, 0, 15, 112, 121, 116, 104, 46, 110, 101, 97, 114,
+      ])
+    )
+  ).toBeTruthy();
+  expect(decodeGovernancePayload(nearSetFeeRecipientBuffer)).toEqual(
+    nearSetFeeRecipient
+  );
+  expect(() => new NearSetFeeRecipient("near", "Pyth.near").encode()).toThrow();
+  expect(
+    decodeGovernancePayload(
+      Buffer.from([80, 84, 71, 77, 1, 10, 0, 15, 46, 110, 101, 97, 114])
+    )
+  ).toBeUndefined();
+
+  const setPriceFeedRetention = new SetPriceFeedRetention("near", BigInt(86400));
+  const setPriceFeedRetentionBuffer = setPriceFeedRetention.encode();
+  console.log(setPriceFeedRetentionBuffer.toJSON());
+  expect(
+    setPriceFeedRetentionBuffer.equals(
+      Buffer.from([80, 84, 71, 77, 1, 11, 0, 15, 0, 0, 0, 0, 0, 1, 81, 128])
+    )
+  ).toBeTruthy();
+
   const setWormholeAddress = new StarknetSetWormholeAddress(
     "starknet",
     "05033f06d5c47bcce7960ea703b04a0bf64bf33f6f2eb5613496da747522d9c2"
@@ -320,6 +395,17 @@ function dataSourceArb(): Arbitrary<DataSource> {
   });
 }
 
+/** Fastcheck generator for NEAR account ids, e.g. `pyth-oracle.near`. */
+function nearAccountIdArb(): Arbitrary<string> {
+  const part = fc.stringOf(
+    fc.constantFrom(..."abcdefghijklmnopqrstuvwxyz0123456789"),
+    { minLength: 2, maxLength: 10 }
+  );
+  return fc
+    .array(part, { minLength: 1, maxLength: 4 })
+    .map((parts) => parts.join("."));
+}
+
 /**
  * Fastcheck generator for arbitrary PythGovernanceActions.
  *
@@ -359,12 +445,35 @@ function governanceActionArb(): Arbitrary<PythGovernanceAction> {
       return fc.array(dataSourceArb()).map((dataSources) => {
         return new SetDataSources(header.targetChainId, dataSources);
       });
+    } else if (header.action === "SetIbcChannels") {
+      return fc
+        .array(fc.string({ maxLength: 64 }), { maxLength: 16 })
+        .map((channels) => {
+          return new SetIbcChannels(header.targetChainId, channels);
+        });
+    } else if (header.action === "SetPriceHistoryDepth") {
+      return uintArb(16).map((depth) => {
+        return new SetPriceHistoryDepth(header.targetChainId, depth);
+      });
     } else if (header.action === "SetFee") {
       return fc
         .record({ v: fc.bigUintN(64), e: fc.bigUintN(64) })
         .map(({ v, e }) => {
           return new SetFee(header.targetChainId, v, e);
         });
+    } else if (header.action === "SetFeeRecipient") {
+      if (header.targetChainId === "near") {
+        return nearAccountIdArb().map((accountId) => {
+          return new NearSetFeeRecipient(header.targetChainId, accountId);
+        });
+      }
+      return bufferArb({ minLength: 1, maxLength: 64 }).map((address) => {
+        return new CosmosSetFeeRecipient(header.targetChainId, address);
+      });
+    } else if (header.action === "SetPriceFeedRetention") {
+      return fc.bigUintN(64).map((retentionPeriod) => {
+        return new SetPriceFeedRetention(header.targetChainId, retentionPeriod);
+      });
     } else if (header.action === "SetValidPeriod") {
       return fc.bigUintN(64).map((period) => {
         return new SetValidPeriod(header.targetChainId, period);
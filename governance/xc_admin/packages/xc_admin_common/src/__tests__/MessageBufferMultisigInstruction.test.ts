@@ -10,6 +10,7 @@ import {
 import { Connection, Keypair, PublicKey } from "@solana/web3.js";
 import {
   AnchorMultisigInstruction,
+  mapKey,
   MESSAGE_BUFFER_PROGRAM_ID,
   MultisigInstructionProgram,
   MultisigParser,
@@ -222,6 +223,24 @@ test("Message buffer multisig instruction parse: delete buffer", (done) => {
           parsedInstruction.accounts.named["messageBuffer"].isWritable
         ).toBe(instruction.keys[3].isWritable);
 
+        // The buffer writers account is required and resolved from the args
+        const [bufferWriters] = PublicKey.findProgramAddressSync(
+          [
+            allowedProgramAuth.toBuffer(),
+            Buffer.from("message"),
+            baseAccountKey.toBuffer(),
+            Buffer.from("writers"),
+          ],
+          new PublicKey(MESSAGE_BUFFER_PROGRAM_ID)
+        );
+        expect(
+          parsedInstruction.accounts.named["bufferWriters"].pubkey.equals(
+            bufferWriters
+          )
+        ).toBeTruthy();
+        expect(instruction.keys[4].pubkey.equals(bufferWriters)).toBeTruthy();
+        expect(instruction.keys[4].isWritable).toBe(true);
+
         expect(parsedInstruction.accounts.remaining.length).toBe(0);
 
         expect(
@@ -237,3 +256,71 @@ test("Message buffer multisig instruction parse: delete buffer", (done) => {
       }
     });
 });
+
+test("Message buffer multisig instruction parse: accept whitelist admin", (done) => {
+  jest.setTimeout(60000);
+
+  const cluster: PythCluster = "pythtest-crosschain";
+
+  const messageBufferProgram = new Program(
+    messageBuffer as Idl,
+    new PublicKey(MESSAGE_BUFFER_PROGRAM_ID),
+    new AnchorProvider(
+      new Connection(getPythClusterApiUrl(cluster)),
+      new Wallet(new Keypair()),
+      AnchorProvider.defaultOptions()
+    )
+  ) as unknown as Program<MessageBuffer>;
+
+  const parser = MultisigParser.fromCluster(cluster);
+
+  // The pending admin is the remote executor PDA of the governance vault
+  const pendingAdmin = mapKey(PublicKey.unique());
+
+  messageBufferProgram.methods
+    .acceptWhitelistAdmin()
+    .accounts({
+      pendingAdmin,
+    })
+    .instruction()
+    .then((instruction) => {
+      const parsedInstruction = parser.parseInstruction(instruction);
+
+      if (parsedInstruction instanceof AnchorMultisigInstruction) {
+        expect(parsedInstruction.program).toBe(
+          MultisigInstructionProgram.MessageBuffer
+        );
+        expect(parsedInstruction.name).toBe("acceptWhitelistAdmin");
+
+        expect(
+          parsedInstruction.accounts.named["pendingAdmin"].pubkey.equals(
+            pendingAdmin
+          )
+        ).toBeTruthy();
+        expect(parsedInstruction.accounts.named["pendingAdmin"].isSigner).toBe(
+          true
+        );
+        expect(
+          parsedInstruction.accounts.named["pendingAdmin"].isWritable
+        ).toBe(false);
+
+        expect(
+          parsedInstruction.accounts.named["whitelist"].pubkey.equals(
+            instruction.keys[1].pubkey
+          )
+        ).toBeTruthy();
+        expect(parsedInstruction.accounts.named["whitelist"].isSigner).toBe(
+          false
+        );
+        expect(parsedInstruction.accounts.named["whitelist"].isWritable).toBe(
+          true
+        );
+
+        expect(parsedInstruction.accounts.remaining.length).toBe(0);
+
+        done();
+      } else {
+        done("Not instance of MessageBufferMultisigInstruction");
+      }
+    });
+});
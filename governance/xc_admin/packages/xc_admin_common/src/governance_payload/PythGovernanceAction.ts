@@ -16,6 +16,10 @@ export const TargetAction = {
   RequestGovernanceDataSourceTransfer: 5,
   SetWormholeAddress: 6,
   SetFeeInToken: 7,
+  SetIbcChannels: 8,
+  SetPriceHistoryDepth: 9,
+  SetFeeRecipient: 10,
+  SetPriceFeedRetention: 11,
 } as const;
 
 export const EvmExecutorAction = {
@@ -46,6 +50,14 @@ export function toActionName(
         return "SetWormholeAddress";
       case 7:
         return "SetFeeInToken";
+      case 8:
+        return "SetIbcChannels";
+      case 9:
+        return "SetPriceHistoryDepth";
+      case 10:
+        return "SetFeeRecipient";
+      case 11:
+        return "SetPriceFeedRetention";
     }
   } else if (
     deserialized.moduleId == MODULE_EVM_EXECUTOR &&
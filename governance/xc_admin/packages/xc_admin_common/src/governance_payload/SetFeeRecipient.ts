@@ -0,0 +1,77 @@
+import {
+  PythGovernanceActionImpl,
+  PythGovernanceHeader,
+} from "./PythGovernanceAction";
+import { ChainName } from "../chains";
+
+/** Set the account the fees collected on targetChainId (a CosmWasm chain) are swept to.
+ * address is the canonical (not bech32) form of the recipient address and takes up the rest of the payload.
+ */
+export class CosmosSetFeeRecipient extends PythGovernanceActionImpl {
+  constructor(targetChainId: ChainName, readonly address: Buffer) {
+    super(targetChainId, "SetFeeRecipient");
+  }
+
+  static decode(data: Buffer): CosmosSetFeeRecipient | undefined {
+    const header = PythGovernanceHeader.decode(data);
+    if (!header || header.action !== "SetFeeRecipient") {
+      return undefined;
+    }
+
+    return new CosmosSetFeeRecipient(
+      header.targetChainId,
+      data.subarray(PythGovernanceHeader.span)
+    );
+  }
+
+  encode(): Buffer {
+    return Buffer.concat([this.header().encode(), this.address]);
+  }
+}
+
+/** Set the account the fees collected on NEAR are sent to.
+ * accountId is a NEAR account id (e.g. `treasury.near`), utf8 encoded in the rest of the payload.
+ */
+export class NearSetFeeRecipient extends PythGovernanceActionImpl {
+  constructor(targetChainId: ChainName, readonly accountId: string) {
+    super(targetChainId, "SetFeeRecipient");
+  }
+
+  static decode(data: Buffer): NearSetFeeRecipient | undefined {
+    const header = PythGovernanceHeader.decode(data);
+    if (!header || header.action !== "SetFeeRecipient") {
+      return undefined;
+    }
+
+    const accountId = data
+      .subarray(PythGovernanceHeader.span)
+      .toString("utf8");
+    if (!isNearAccountId(accountId)) {
+      return undefined;
+    }
+
+    return new NearSetFeeRecipient(header.targetChainId, accountId);
+  }
+
+  encode(): Buffer {
+    if (!isNearAccountId(this.accountId)) {
+      throw new Error(`Invalid NEAR account id: ${this.accountId}`);
+    }
+
+    return Buffer.concat([
+      this.header().encode(),
+      Buffer.from(this.accountId, "utf8"),
+    ]);
+  }
+}
+
+/** Whether accountId follows the NEAR account id rules: 2 to 64 lowercase alphanumeric
+ * characters, with single `.`, `-` or `_` separators that don't start or end it.
+ */
+export function isNearAccountId(accountId: string): boolean {
+  return (
+    accountId.length >= 2 &&
+    accountId.length <= 64 &&
+    /^(([a-z\d]+[-_])*[a-z\d]+\.)*([a-z\d]+[-_])*[a-z\d]+$/.test(accountId)
+  );
+}
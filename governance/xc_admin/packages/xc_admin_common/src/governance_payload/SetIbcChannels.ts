@@ -0,0 +1,62 @@
+import {
+  ActionName,
+  PythGovernanceAction,
+  PythGovernanceHeader,
+} from "./PythGovernanceAction";
+import { ChainName } from "../chains";
+import * as BufferLayout from "@solana/buffer-layout";
+
+/** Set the IBC channels that can subscribe to the price feeds on targetChainId (a CosmWasm chain) to the provided values. */
+export class SetIbcChannels implements PythGovernanceAction {
+  readonly actionName: ActionName;
+
+  constructor(readonly targetChainId: ChainName, readonly channels: string[]) {
+    this.actionName = "SetIbcChannels";
+  }
+
+  static decode(data: Buffer): SetIbcChannels | undefined {
+    const header = PythGovernanceHeader.decode(data);
+    if (!header || header.action !== "SetIbcChannels") {
+      return undefined;
+    }
+
+    let index = PythGovernanceHeader.span;
+    const numChannels = BufferLayout.u8().decode(data, index);
+    index += 1;
+    const channels = [];
+    for (let i = 0; i < numChannels; i++) {
+      const channelLength = BufferLayout.u8().decode(data, index);
+      index += 1;
+      channels.push(
+        data.subarray(index, index + channelLength).toString("utf8")
+      );
+      index += channelLength;
+    }
+
+    return new SetIbcChannels(header.targetChainId, channels);
+  }
+
+  encode(): Buffer {
+    if (this.channels.length > 255) {
+      throw new Error("Can not set more than 255 IBC channels");
+    }
+
+    const headerBuffer = new PythGovernanceHeader(
+      this.targetChainId,
+      "SetIbcChannels"
+    ).encode();
+
+    const numChannelsBuf = Buffer.alloc(1);
+    BufferLayout.u8().encode(this.channels.length, numChannelsBuf);
+
+    const channelBufs = this.channels.map((channel) => {
+      const channelBuf = Buffer.from(channel, "utf8");
+      if (channelBuf.length > 255) {
+        throw new Error(`IBC channel id is too long: ${channel}`);
+      }
+      return Buffer.concat([Buffer.from([channelBuf.length]), channelBuf]);
+    });
+
+    return Buffer.concat([headerBuffer, numChannelsBuf, ...channelBufs]);
+  }
+}
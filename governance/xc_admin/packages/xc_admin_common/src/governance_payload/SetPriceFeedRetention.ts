@@ -0,0 +1,34 @@
+import { PythGovernanceActionImpl } from "./PythGovernanceAction";
+import * as BufferLayout from "@solana/buffer-layout";
+import * as BufferLayoutExt from "./BufferLayoutExt";
+import { ChainName } from "../chains";
+
+/** Set how long (in seconds) price feeds that are no longer updated are kept on targetChainId (NEAR) to the provided value */
+export class SetPriceFeedRetention extends PythGovernanceActionImpl {
+  static layout: BufferLayout.Structure<Readonly<{ retentionPeriod: bigint }>> =
+    BufferLayout.struct([BufferLayoutExt.u64be("retentionPeriod")]);
+
+  constructor(targetChainId: ChainName, readonly retentionPeriod: bigint) {
+    super(targetChainId, "SetPriceFeedRetention");
+  }
+
+  static decode(data: Buffer): SetPriceFeedRetention | undefined {
+    const decoded = PythGovernanceActionImpl.decodeWithPayload(
+      data,
+      "SetPriceFeedRetention",
+      SetPriceFeedRetention.layout
+    );
+    if (!decoded) return undefined;
+
+    return new SetPriceFeedRetention(
+      decoded[0].targetChainId,
+      decoded[1].retentionPeriod
+    );
+  }
+
+  encode(): Buffer {
+    return super.encodeWithPayload(SetPriceFeedRetention.layout, {
+      retentionPeriod: this.retentionPeriod,
+    });
+  }
+}
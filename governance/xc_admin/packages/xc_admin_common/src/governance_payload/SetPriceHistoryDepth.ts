@@ -0,0 +1,30 @@
+import { PythGovernanceActionImpl } from "./PythGovernanceAction";
+import * as BufferLayout from "@solana/buffer-layout";
+import { ChainName } from "../chains";
+
+/** Set the number of past prices kept for each price feed on targetChainId (a CosmWasm chain) to the provided value */
+export class SetPriceHistoryDepth extends PythGovernanceActionImpl {
+  static layout: BufferLayout.Structure<Readonly<{ depth: number }>> =
+    BufferLayout.struct([BufferLayout.u16be("depth")]);
+
+  constructor(targetChainId: ChainName, readonly depth: number) {
+    super(targetChainId, "SetPriceHistoryDepth");
+  }
+
+  static decode(data: Buffer): SetPriceHistoryDepth | undefined {
+    const decoded = PythGovernanceActionImpl.decodeWithPayload(
+      data,
+      "SetPriceHistoryDepth",
+      SetPriceHistoryDepth.layout
+    );
+    if (!decoded) return undefined;
+
+    return new SetPriceHistoryDepth(decoded[0].targetChainId, decoded[1].depth);
+  }
+
+  encode(): Buffer {
+    return super.encodeWithPayload(SetPriceHistoryDepth.layout, {
+      depth: this.depth,
+    });
+  }
+}
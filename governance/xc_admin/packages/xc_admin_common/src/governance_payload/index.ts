@@ -13,8 +13,12 @@ import {
   RequestGovernanceDataSourceTransfer,
 } from "./GovernanceDataSourceTransfer";
 import { SetDataSources } from "./SetDataSources";
+import { SetIbcChannels } from "./SetIbcChannels";
+import { SetPriceHistoryDepth } from "./SetPriceHistoryDepth";
 import { SetValidPeriod } from "./SetValidPeriod";
 import { SetFee, SetFeeInToken } from "./SetFee";
+import { CosmosSetFeeRecipient, NearSetFeeRecipient } from "./SetFeeRecipient";
+import { SetPriceFeedRetention } from "./SetPriceFeedRetention";
 import {
   EvmSetWormholeAddress,
   StarknetSetWormholeAddress,
@@ -50,10 +54,24 @@ export function decodeGovernancePayload(
       return AuthorizeGovernanceDataSourceTransfer.decode(data);
     case "SetDataSources":
       return SetDataSources.decode(data);
+    case "SetIbcChannels":
+      return SetIbcChannels.decode(data);
+    case "SetPriceHistoryDepth":
+      return SetPriceHistoryDepth.decode(data);
     case "SetFee":
       return SetFee.decode(data);
     case "SetFeeInToken":
       return SetFeeInToken.decode(data);
+    case "SetFeeRecipient":
+      // NOTE: the recipient is encoded differently on each chain and takes up the rest of the
+      // payload, so the target chain is the only way to tell them apart.
+      if (header.targetChainId === "near") {
+        return NearSetFeeRecipient.decode(data);
+      } else {
+        return CosmosSetFeeRecipient.decode(data);
+      }
+    case "SetPriceFeedRetention":
+      return SetPriceFeedRetention.decode(data);
     case "SetValidPeriod":
       return SetValidPeriod.decode(data);
     case "RequestGovernanceDataSourceTransfer":
@@ -84,7 +102,11 @@ export * from "./UpgradeContract";
 export * from "./PythGovernanceAction";
 export * from "./GovernanceDataSourceTransfer";
 export * from "./SetDataSources";
+export * from "./SetIbcChannels";
+export * from "./SetPriceHistoryDepth";
 export * from "./SetValidPeriod";
 export * from "./SetFee";
+export * from "./SetFeeRecipient";
+export * from "./SetPriceFeedRetention";
 export * from "./SetWormholeAddress";
 export * from "./ExecuteAction";
@@ -0,0 +1,146 @@
+//! An example program that consumes Pyth Lazer price updates on Solana.
+//!
+//! `update_price` verifies a Pyth Lazer message by calling the Pyth Lazer program and stores
+//! the price of one feed in a [`PriceAccount`]. The transaction has to contain an
+//! `ed25519_program` instruction that verifies the signature of the message before
+//! `update_price` (see [`pyth_lazer_solana_contract::ed25519_instruction`]).
+
+use {
+    anchor_lang::{prelude::*, solana_program::sysvar},
+    pyth_lazer_solana_contract::{
+        cpi::accounts::VerifyMessage,
+        program::PythLazerSolanaContract,
+        protocol::{
+            payload::{PayloadData, PayloadPropertyValue},
+            router::PriceFeedId,
+        },
+        ANCHOR_DISCRIMINATOR_BYTES, ANCHOR_MESSAGE_OFFSET, STORAGE_ID,
+    },
+    std::mem::size_of,
+};
+
+declare_id!("7vETLbckPZ89mhnfK3btTREbJ3CbBw6SNkLrJ6xp2M5U");
+
+pub const PRICE_SEED: &[u8] = b"price";
+
+#[account]
+pub struct PriceAccount {
+    pub feed_id: u32,
+    /// Timestamp of the stored price, in microseconds since the Unix epoch.
+    pub timestamp_us: u64,
+    pub price: i64,
+}
+
+impl PriceAccount {
+    const SERIALIZED_LEN: usize = size_of::<u32>() + size_of::<u64>() + size_of::<i64>();
+}
+
+#[program]
+pub mod pyth_lazer_solana_consumer_example {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>, feed_id: u32) -> Result<()> {
+        ctx.accounts.price.feed_id = feed_id;
+        Ok(())
+    }
+
+    /// Verifies a Pyth Lazer message and stores the price of the feed of the price account.
+    ///
+    /// - `message_data` is the signed Pyth Lazer message in the Solana format.
+    /// - `ed25519_instruction_index` is the index of the `ed25519_program` instruction
+    ///   within the transaction. This instruction must precede the current instruction.
+    /// - `signature_index` is the index of the signature within the inputs to the `ed25519_program`.
+    pub fn update_price(
+        ctx: Context<UpdatePrice>,
+        message_data: Vec<u8>,
+        ed25519_instruction_index: u16,
+        signature_index: u8,
+    ) -> Result<()> {
+        let verified = pyth_lazer_solana_contract::verify_message_cpi(
+            ctx.accounts.pyth_lazer_program.to_account_info(),
+            VerifyMessage {
+                payer: ctx.accounts.payer.to_account_info(),
+                storage: ctx.accounts.storage.to_account_info(),
+                treasury: ctx.accounts.treasury.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                instructions_sysvar: ctx.accounts.instructions_sysvar.to_account_info(),
+            },
+            &message_data,
+            ed25519_instruction_index,
+            signature_index,
+            // `message_data` is the first argument of this instruction.
+            ANCHOR_MESSAGE_OFFSET,
+        )?;
+
+        let payload = PayloadData::deserialize_slice_le(&verified.payload).map_err(|err| {
+            msg!("invalid payload: {:?}", err);
+            ProgramError::InvalidInstructionData
+        })?;
+        let price_account = &mut ctx.accounts.price;
+        if payload.timestamp_us.0 <= price_account.timestamp_us {
+            msg!("update is not newer than the stored price");
+            return Err(ProgramError::InvalidInstructionData.into());
+        }
+        let price = payload
+            .feeds
+            .iter()
+            .find(|feed| feed.feed_id == PriceFeedId(price_account.feed_id))
+            .and_then(|feed| {
+                feed.properties.iter().find_map(|property| match property {
+                    PayloadPropertyValue::Price(price) => *price,
+                    _ => None,
+                })
+            })
+            .ok_or_else(|| {
+                msg!(
+                    "update does not contain the price of feed {}",
+                    price_account.feed_id
+                );
+                ProgramError::InvalidInstructionData
+            })?;
+
+        price_account.timestamp_us = payload.timestamp_us.0;
+        price_account.price = price.into_inner().get();
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(feed_id: u32)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR_BYTES + PriceAccount::SERIALIZED_LEN,
+        seeds = [PRICE_SEED, &feed_id.to_le_bytes()],
+        bump,
+    )]
+    pub price: Account<'info, PriceAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePrice<'info> {
+    /// Pays the update fee of the Pyth Lazer program.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [PRICE_SEED, &price.feed_id.to_le_bytes()],
+        bump,
+    )]
+    pub price: Account<'info, PriceAccount>,
+    pub pyth_lazer_program: Program<'info, PythLazerSolanaContract>,
+    /// CHECK: checked by the Pyth Lazer program.
+    #[account(address = STORAGE_ID)]
+    pub storage: AccountInfo<'info>,
+    /// CHECK: checked by the Pyth Lazer program.
+    #[account(mut)]
+    pub treasury: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: account ID is checked by the address constraint.
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
@@ -0,0 +1,248 @@
+use {
+    anchor_lang::{prelude::AccountMeta, AccountDeserialize, InstructionData, ToAccountMetas},
+    pyth_lazer_solana_consumer_example::{PriceAccount, PRICE_SEED},
+    pyth_lazer_solana_contract::{
+        ed25519_instruction,
+        protocol::{
+            message::SolanaMessage,
+            payload::{AggregatedPriceFeedData, PayloadData},
+            router::{Channel, Price, PriceFeedId, PriceFeedProperty, TimestampUs},
+        },
+        ANCHOR_MESSAGE_OFFSET,
+    },
+    solana_program_test::{BanksClient, ProgramTest},
+    solana_sdk::{
+        hash::Hash, instruction::Instruction, pubkey::Pubkey, signature::Keypair, signer::Signer,
+        system_instruction, system_program, sysvar, transaction::Transaction,
+    },
+    std::env,
+};
+
+const FEED_ID: u32 = 1;
+
+fn program_test() -> ProgramTest {
+    if env::var("SBF_OUT_DIR").is_err() {
+        env::set_var(
+            "SBF_OUT_DIR",
+            format!(
+                "{}/../../../../target/sbf-solana-solana/release",
+                env::var("CARGO_MANIFEST_DIR").unwrap()
+            ),
+        );
+    }
+    println!("if add_program fails, run `cargo build-sbf` first.");
+    let mut program_test = ProgramTest::new(
+        "pyth_lazer_solana_consumer_example",
+        pyth_lazer_solana_consumer_example::ID,
+        None,
+    );
+    program_test.add_program(
+        "pyth_lazer_solana_contract",
+        pyth_lazer_solana_contract::ID,
+        None,
+    );
+    program_test
+}
+
+fn price_account_id() -> Pubkey {
+    Pubkey::find_program_address(
+        &[PRICE_SEED, &FEED_ID.to_le_bytes()],
+        &pyth_lazer_solana_consumer_example::ID,
+    )
+    .0
+}
+
+/// Creates a Pyth Lazer message with the price of `FEED_ID`, signed by `signer`.
+fn message(signer: &Keypair, timestamp_us: u64, price: i64) -> Vec<u8> {
+    let mut payload = Vec::new();
+    PayloadData::new(
+        TimestampUs(timestamp_us),
+        Channel::RealTime.id(),
+        &[(
+            PriceFeedId(FEED_ID),
+            AggregatedPriceFeedData {
+                price: Some(Price::new(price).unwrap()),
+                best_bid_price: None,
+                best_ask_price: None,
+            },
+        )],
+        &[PriceFeedProperty::Price],
+    )
+    .serialize::<byteorder::LE>(&mut payload)
+    .unwrap();
+
+    let mut message = Vec::new();
+    SolanaMessage {
+        signature: signer.sign_message(&payload).as_ref().try_into().unwrap(),
+        public_key: signer.pubkey().to_bytes(),
+        payload,
+    }
+    .serialize(&mut message)
+    .unwrap();
+    message
+}
+
+struct Setup {
+    banks_client: BanksClient,
+    payer: Keypair,
+    recent_blockhash: Hash,
+    treasury: Pubkey,
+}
+
+impl Setup {
+    /// Sets up the Pyth Lazer program with `trusted_signer`, and the price account of `FEED_ID`.
+    async fn new(trusted_signer: Pubkey) -> Self {
+        let (banks_client, payer, recent_blockhash) = program_test().start().await;
+        let treasury =
+            Pubkey::create_with_seed(&payer.pubkey(), "treasury", &system_program::ID).unwrap();
+        let mut setup = Self {
+            banks_client,
+            payer,
+            recent_blockhash,
+            treasury,
+        };
+
+        setup
+            .process(&[
+                system_instruction::create_account_with_seed(
+                    &setup.payer.pubkey(),
+                    &treasury,
+                    &setup.payer.pubkey(),
+                    "treasury",
+                    10_000_000,
+                    0,
+                    &system_program::ID,
+                ),
+                Instruction::new_with_bytes(
+                    pyth_lazer_solana_contract::ID,
+                    &pyth_lazer_solana_contract::instruction::Initialize {
+                        top_authority: setup.payer.pubkey(),
+                        treasury,
+                    }
+                    .data(),
+                    vec![
+                        AccountMeta::new(setup.payer.pubkey(), true),
+                        AccountMeta::new(pyth_lazer_solana_contract::STORAGE_ID, false),
+                        AccountMeta::new_readonly(system_program::ID, false),
+                    ],
+                ),
+                Instruction::new_with_bytes(
+                    pyth_lazer_solana_contract::ID,
+                    &pyth_lazer_solana_contract::instruction::Update {
+                        trusted_signer,
+                        expires_at: i64::MAX,
+                    }
+                    .data(),
+                    vec![
+                        AccountMeta::new(setup.payer.pubkey(), true),
+                        AccountMeta::new(pyth_lazer_solana_contract::STORAGE_ID, false),
+                    ],
+                ),
+                Instruction::new_with_bytes(
+                    pyth_lazer_solana_consumer_example::ID,
+                    &pyth_lazer_solana_consumer_example::instruction::Initialize {
+                        feed_id: FEED_ID,
+                    }
+                    .data(),
+                    pyth_lazer_solana_consumer_example::accounts::Initialize {
+                        payer: setup.payer.pubkey(),
+                        price: price_account_id(),
+                        system_program: system_program::ID,
+                    }
+                    .to_account_metas(None),
+                ),
+            ])
+            .await
+            .unwrap();
+        setup
+    }
+
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+    ) -> Result<(), solana_program_test::BanksClientError> {
+        let mut transaction = Transaction::new_with_payer(instructions, Some(&self.payer.pubkey()));
+        transaction.sign(&[&self.payer], self.recent_blockhash);
+        self.banks_client.process_transaction(transaction).await
+    }
+
+    async fn update_price(
+        &mut self,
+        message: &[u8],
+    ) -> Result<(), solana_program_test::BanksClientError> {
+        // Instruction #0 will be ed25519 instruction;
+        // Instruction #1 will be the `update_price` instruction.
+        let instructions = [
+            ed25519_instruction(message, 1, ANCHOR_MESSAGE_OFFSET),
+            Instruction::new_with_bytes(
+                pyth_lazer_solana_consumer_example::ID,
+                &pyth_lazer_solana_consumer_example::instruction::UpdatePrice {
+                    message_data: message.to_vec(),
+                    ed25519_instruction_index: 0,
+                    signature_index: 0,
+                }
+                .data(),
+                pyth_lazer_solana_consumer_example::accounts::UpdatePrice {
+                    payer: self.payer.pubkey(),
+                    price: price_account_id(),
+                    pyth_lazer_program: pyth_lazer_solana_contract::ID,
+                    storage: pyth_lazer_solana_contract::STORAGE_ID,
+                    treasury: self.treasury,
+                    system_program: system_program::ID,
+                    instructions_sysvar: sysvar::instructions::ID,
+                }
+                .to_account_metas(None),
+            ),
+        ];
+        self.process(&instructions).await
+    }
+
+    async fn price_account(&mut self) -> PriceAccount {
+        let account = self
+            .banks_client
+            .get_account(price_account_id())
+            .await
+            .unwrap()
+            .unwrap();
+        PriceAccount::try_deserialize(&mut &account.data[..]).unwrap()
+    }
+}
+
+#[tokio::test]
+async fn test_update_price() {
+    let signer = Keypair::new();
+    let mut setup = Setup::new(signer.pubkey()).await;
+
+    setup
+        .update_price(&message(&signer, 1_000, 100_000_000))
+        .await
+        .unwrap();
+    let price_account = setup.price_account().await;
+    assert_eq!(price_account.feed_id, FEED_ID);
+    assert_eq!(price_account.timestamp_us, 1_000);
+    assert_eq!(price_account.price, 100_000_000);
+
+    setup
+        .update_price(&message(&signer, 2_000, 110_000_000))
+        .await
+        .unwrap();
+    let price_account = setup.price_account().await;
+    assert_eq!(price_account.timestamp_us, 2_000);
+    assert_eq!(price_account.price, 110_000_000);
+
+    // Older updates are rejected.
+    assert!(setup
+        .update_price(&message(&signer, 1_500, 120_000_000))
+        .await
+        .is_err());
+    assert_eq!(setup.price_account().await.price, 110_000_000);
+}
+
+#[tokio::test]
+async fn test_reject_untrusted_signer() {
+    let mut setup = Setup::new(Keypair::new().pubkey()).await;
+    assert!(setup
+        .update_price(&message(&Keypair::new(), 1_000, 100_000_000))
+        .await
+        .is_err());
+}
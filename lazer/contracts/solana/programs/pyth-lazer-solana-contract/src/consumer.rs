@@ -0,0 +1,79 @@
+//! Helpers for programs that verify Pyth Lazer messages by calling this program.
+
+use {
+    crate::signature::{ed25519_program_args, Ed25519SignatureOffsets},
+    anchor_lang::solana_program::{ed25519_program, instruction::Instruction},
+};
+#[cfg(feature = "cpi")]
+use {
+    crate::VerifiedMessage,
+    anchor_lang::{prelude::*, solana_program::sysvar},
+};
+
+/// Offset of the message within the instruction data if the message is the first argument
+/// of an Anchor instruction: 8 bytes for Anchor header, 4 bytes for Vec length.
+pub const ANCHOR_MESSAGE_OFFSET: u16 = 12;
+
+/// Creates the `ed25519_program` instruction that verifies the signature of a Pyth Lazer message.
+///
+/// - `message` is the Pyth Lazer message being sent.
+/// - `instruction_index` is the index of the instruction that contains the message
+///   within the transaction.
+/// - `message_offset` is the offset of the message within the data of that instruction.
+pub fn ed25519_instruction(
+    message: &[u8],
+    instruction_index: u16,
+    message_offset: u16,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        ed25519_program::ID,
+        &ed25519_program_args(&[Ed25519SignatureOffsets::new(
+            message,
+            instruction_index,
+            message_offset,
+        )]),
+        vec![],
+    )
+}
+
+/// Verifies a Pyth Lazer message by calling `verify_message` of this program.
+///
+/// - `message_data` is the signed message, which must be contained in the data of the
+///   current instruction at `message_offset`.
+/// - `ed25519_instruction_index` is the index of the `ed25519_program` instruction
+///   within the transaction (see [`ed25519_instruction`]). This instruction must precede
+///   the current instruction.
+/// - `signature_index` is the index of the signature within the inputs to the `ed25519_program`.
+///
+/// The update fee is transferred from `accounts.payer` to the treasury.
+#[cfg(feature = "cpi")]
+pub fn verify_message_cpi<'info>(
+    program: AccountInfo<'info>,
+    accounts: crate::cpi::accounts::VerifyMessage<'info>,
+    message_data: &[u8],
+    ed25519_instruction_index: u16,
+    signature_index: u8,
+    message_offset: u16,
+) -> Result<VerifiedMessage> {
+    // `ed25519_program` verifies the message at `message_offset` in the current instruction,
+    // so `message_data` has to be the same message.
+    let instruction =
+        sysvar::instructions::get_instruction_relative(0, &accounts.instructions_sysvar)?;
+    let start = usize::from(message_offset);
+    let end = start
+        .checked_add(message_data.len())
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    if instruction.data.get(start..end) != Some(message_data) {
+        msg!("message data is not contained in the current instruction");
+        return Err(ProgramError::InvalidInstructionData.into());
+    }
+
+    let verified = crate::cpi::verify_message(
+        CpiContext::new(program, accounts),
+        message_data.to_vec(),
+        ed25519_instruction_index,
+        signature_index,
+        message_offset,
+    )?;
+    Ok(verified.get())
+}
@@ -1,15 +1,20 @@
+mod consumer;
 mod signature;
 
 use {
-    crate::signature::VerifiedMessage,
     anchor_lang::{
         prelude::*, solana_program::pubkey::PUBKEY_BYTES, system_program, Discriminator,
     },
     std::{io::Cursor, mem::size_of},
 };
 
+#[cfg(feature = "cpi")]
+pub use crate::consumer::verify_message_cpi;
 pub use {
-    crate::signature::{ed25519_program_args, Ed25519SignatureOffsets},
+    crate::{
+        consumer::{ed25519_instruction, ANCHOR_MESSAGE_OFFSET},
+        signature::{ed25519_program_args, Ed25519SignatureOffsets, VerifiedMessage},
+    },
     pyth_lazer_protocol as protocol,
 };
 
@@ -273,6 +278,7 @@ pub struct VerifyMessage<'info> {
     )]
     pub storage: Account<'info, Storage>,
     /// CHECK: this account doesn't need additional constraints.
+    #[account(mut)]
     pub treasury: AccountInfo<'info>,
     pub system_program: Program<'info, System>,
     /// CHECK: account ID is checked in Solana SDK during calls
@@ -0,0 +1,545 @@
+//! A client that keeps subscriptions alive across several router endpoints.
+//!
+//! [`FailoverClient`] connects to the healthiest of its endpoints and sends all active
+//! subscriptions on every new connection. A connection is dropped when it fails, is closed
+//! or stays silent for longer than [`FailoverConfig::idle_timeout`], after which the client
+//! switches to another endpoint.
+//!
+//! The router doesn't number its updates, but updates of a fixed rate channel are
+//! published at multiples of the channel period, so the timestamp divided by the period
+//! serves as a sequence number. The client uses it to drop updates it has already
+//! delivered (e.g. when a new endpoint is slightly behind the previous one) and to report
+//! the updates that were missed while switching endpoints as a [`Gap`]. Gaps of real time
+//! subscriptions can't be detected.
+
+use {
+    crate::{LazerConnection, LazerMessage},
+    anyhow::{bail, Context, Result},
+    pyth_lazer_protocol::{
+        message::{EvmMessage, SolanaMessage},
+        payload::{PayloadData, EVM_FORMAT_MAGIC, SOLANA_FORMAT_MAGIC_BE},
+        router::{Channel, JsonUpdate, TimestampUs},
+        subscription::{Response, SubscribeRequest, SubscriptionId},
+    },
+    pyth_lazer_sdk::{binary_update::BinaryUpdate, verifier::decode_json_binary},
+    std::{
+        collections::{HashMap, VecDeque},
+        time::Duration,
+    },
+    url::Url,
+};
+
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+pub const DEFAULT_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone)]
+pub struct FailoverConfig {
+    /// Stream endpoints of the routers, in order of preference.
+    pub endpoints: Vec<Url>,
+    pub access_token: String,
+    /// A connection that receives no message for this long is considered unhealthy.
+    pub idle_timeout: Duration,
+    pub connect_timeout: Duration,
+    /// Delay before reconnecting once every endpoint has failed in a row.
+    pub reconnect_delay: Duration,
+}
+
+impl FailoverConfig {
+    pub fn new(endpoints: Vec<Url>, access_token: String) -> Self {
+        Self {
+            endpoints,
+            access_token,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            reconnect_delay: DEFAULT_RECONNECT_DELAY,
+        }
+    }
+}
+
+/// Updates of a subscription that were not received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gap {
+    pub subscription_id: SubscriptionId,
+    /// Timestamp of the last update received before the gap.
+    pub last_timestamp_us: TimestampUs,
+    /// Timestamp of the first update received after the gap.
+    pub timestamp_us: TimestampUs,
+    /// Number of updates between the two.
+    pub missed_updates: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum FailoverEvent {
+    /// Connected to `endpoint`, and sent the active subscriptions to it.
+    Connected { endpoint: Url },
+    /// The connection to `endpoint` failed or was dropped.
+    Disconnected { endpoint: Url, reason: String },
+    /// A message received from the router. Stream updates that were already delivered are
+    /// not repeated.
+    Message(LazerMessage),
+    /// Updates were missed before the next stream update of the subscription.
+    Gap(Gap),
+}
+
+/// Detects missing and repeated updates of a fixed rate subscription.
+#[derive(Debug, Clone)]
+struct GapTracker {
+    period_us: u64,
+    last_timestamp_us: Option<TimestampUs>,
+}
+
+enum Sequence {
+    Next,
+    Repeated,
+    Gap(u64),
+}
+
+impl GapTracker {
+    fn new(channel: Channel) -> Option<Self> {
+        match channel {
+            Channel::FixedRate(rate) => Some(Self {
+                period_us: rate.value_us(),
+                last_timestamp_us: None,
+            }),
+            Channel::RealTime => None,
+        }
+    }
+
+    fn track(&mut self, timestamp_us: TimestampUs) -> Sequence {
+        let Some(last) = self.last_timestamp_us else {
+            self.last_timestamp_us = Some(timestamp_us);
+            return Sequence::Next;
+        };
+        if timestamp_us <= last {
+            return Sequence::Repeated;
+        }
+        self.last_timestamp_us = Some(timestamp_us);
+        // Round to the nearest period, in case timestamps are not exactly aligned.
+        let periods = (timestamp_us.0 - last.0 + self.period_us / 2) / self.period_us;
+        match periods.saturating_sub(1) {
+            0 => Sequence::Next,
+            missed => Sequence::Gap(missed),
+        }
+    }
+}
+
+struct Subscription {
+    request: SubscribeRequest,
+    tracker: Option<GapTracker>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct EndpointHealth {
+    /// Failures since the last message received from the endpoint.
+    consecutive_failures: u32,
+}
+
+pub struct FailoverClient {
+    config: FailoverConfig,
+    health: Vec<EndpointHealth>,
+    subscriptions: HashMap<SubscriptionId, Subscription>,
+    connection: Option<(usize, LazerConnection)>,
+    /// Index of the endpoint that was connected last.
+    last_endpoint: Option<usize>,
+    /// Failed connection attempts since the last healthy connection.
+    failed_attempts: usize,
+    events: VecDeque<FailoverEvent>,
+}
+
+impl FailoverClient {
+    pub fn new(config: FailoverConfig) -> Result<Self> {
+        if config.endpoints.is_empty() {
+            bail!("no endpoints configured");
+        }
+        Ok(Self {
+            health: vec![EndpointHealth::default(); config.endpoints.len()],
+            config,
+            subscriptions: HashMap::new(),
+            connection: None,
+            last_endpoint: None,
+            failed_attempts: 0,
+            events: VecDeque::new(),
+        })
+    }
+
+    /// Endpoint of the current connection, if any.
+    pub fn endpoint(&self) -> Option<&Url> {
+        self.connection
+            .as_ref()
+            .map(|(index, _)| &self.config.endpoints[*index])
+    }
+
+    /// Adds a subscription. It's sent to the current connection, if any, and to every
+    /// connection made later.
+    pub async fn subscribe(&mut self, request: SubscribeRequest) -> Result<()> {
+        let subscription = Subscription {
+            tracker: GapTracker::new(request.params.channel),
+            request: request.clone(),
+        };
+        self.subscriptions
+            .insert(request.subscription_id, subscription);
+        if let Some((_, connection)) = &mut self.connection {
+            if let Err(err) = connection.subscribe(request).await {
+                self.disconnect(format!("{err:#}"));
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn unsubscribe(&mut self, subscription_id: SubscriptionId) -> Result<()> {
+        if self.subscriptions.remove(&subscription_id).is_none() {
+            bail!("unknown subscription: {}", subscription_id.0);
+        }
+        if let Some((_, connection)) = &mut self.connection {
+            if let Err(err) = connection.unsubscribe(subscription_id).await {
+                self.disconnect(format!("{err:#}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Waits for the next event. Connection failures are reported as events and handled by
+    /// switching endpoints, so this keeps returning events for as long as it's called.
+    pub async fn next_event(&mut self) -> FailoverEvent {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return event;
+            }
+            let Some((index, connection)) = &mut self.connection else {
+                self.connect().await;
+                continue;
+            };
+            let index = *index;
+            let message =
+                tokio::time::timeout(self.config.idle_timeout, connection.next_message()).await;
+            match message {
+                Ok(Ok(Some(message))) => {
+                    self.health[index].consecutive_failures = 0;
+                    self.failed_attempts = 0;
+                    self.handle_message(message);
+                }
+                Ok(Ok(None)) => self.disconnect("connection closed by the router".to_string()),
+                Ok(Err(err)) => self.disconnect(format!("{err:#}")),
+                Err(_) => self.disconnect(format!(
+                    "no message received in {:?}",
+                    self.config.idle_timeout
+                )),
+            }
+        }
+    }
+
+    pub async fn close(mut self) -> Result<()> {
+        if let Some((_, connection)) = self.connection.take() {
+            connection.close().await?;
+        }
+        Ok(())
+    }
+
+    /// Picks the endpoint with the fewest consecutive failures, preferring the endpoints
+    /// after the last one so that failing endpoints are rotated.
+    fn select_endpoint(&self) -> usize {
+        let count = self.config.endpoints.len();
+        let first = self.last_endpoint.map_or(0, |index| index + 1);
+        (0..count)
+            .map(|offset| (first + offset) % count)
+            .min_by_key(|&index| self.health[index].consecutive_failures)
+            .expect("endpoints are not empty")
+    }
+
+    async fn connect(&mut self) {
+        if self.failed_attempts >= self.config.endpoints.len() {
+            tokio::time::sleep(self.config.reconnect_delay).await;
+        }
+        let index = self.select_endpoint();
+        self.last_endpoint = Some(index);
+        let endpoint = self.config.endpoints[index].clone();
+        match self.try_connect(&endpoint).await {
+            Ok(connection) => {
+                self.connection = Some((index, connection));
+                self.events.push_back(FailoverEvent::Connected { endpoint });
+            }
+            Err(err) => {
+                self.health[index].consecutive_failures += 1;
+                self.failed_attempts += 1;
+                self.events.push_back(FailoverEvent::Disconnected {
+                    endpoint,
+                    reason: format!("{err:#}"),
+                });
+            }
+        }
+    }
+
+    async fn try_connect(&self, endpoint: &Url) -> Result<LazerConnection> {
+        let mut connection = tokio::time::timeout(
+            self.config.connect_timeout,
+            LazerConnection::connect(endpoint, &self.config.access_token),
+        )
+        .await
+        .context("connection timed out")??;
+        for subscription in self.subscriptions.values() {
+            connection.subscribe(subscription.request.clone()).await?;
+        }
+        Ok(connection)
+    }
+
+    fn disconnect(&mut self, reason: String) {
+        if let Some((index, _)) = self.connection.take() {
+            self.health[index].consecutive_failures += 1;
+            self.failed_attempts += 1;
+            self.events.push_back(FailoverEvent::Disconnected {
+                endpoint: self.config.endpoints[index].clone(),
+                reason,
+            });
+        }
+    }
+
+    fn handle_message(&mut self, message: LazerMessage) {
+        let Some((subscription_id, timestamp_us)) = stream_update_timestamp(&message) else {
+            self.events.push_back(FailoverEvent::Message(message));
+            return;
+        };
+        let Some(tracker) = self
+            .subscriptions
+            .get_mut(&subscription_id)
+            .and_then(|subscription| subscription.tracker.as_mut())
+        else {
+            self.events.push_back(FailoverEvent::Message(message));
+            return;
+        };
+        let last_timestamp_us = tracker.last_timestamp_us;
+        match tracker.track(timestamp_us) {
+            Sequence::Next => {}
+            Sequence::Repeated => return,
+            Sequence::Gap(missed_updates) => {
+                self.events.push_back(FailoverEvent::Gap(Gap {
+                    subscription_id,
+                    last_timestamp_us: last_timestamp_us.expect("gaps follow an update"),
+                    timestamp_us,
+                    missed_updates,
+                }));
+            }
+        }
+        self.events.push_back(FailoverEvent::Message(message));
+    }
+}
+
+/// Subscription and timestamp of a stream update. The timestamp is taken from the parsed
+/// payload or, if the subscription didn't request it, from the payload of a signed message
+/// (without verifying the signature).
+fn stream_update_timestamp(message: &LazerMessage) -> Option<(SubscriptionId, TimestampUs)> {
+    match message {
+        LazerMessage::Response(Response::StreamUpdated(update)) => Some((
+            update.subscription_id,
+            json_update_timestamp(&update.payload).ok()?,
+        )),
+        LazerMessage::Binary(update) => Some((
+            update.subscription_id,
+            binary_update_timestamp(update).ok()?,
+        )),
+        LazerMessage::Response(_) => None,
+    }
+}
+
+fn json_update_timestamp(update: &JsonUpdate) -> Result<TimestampUs> {
+    if let Some(parsed) = &update.parsed {
+        return Ok(parsed.timestamp_us);
+    }
+    let data = update
+        .solana
+        .as_ref()
+        .or(update.evm.as_ref())
+        .context("no payload in update")?;
+    let data = decode_json_binary(data)?;
+    let magic = u32::from_be_bytes(data.get(..4).context("message too short")?.try_into()?);
+    let payload = if magic == SOLANA_FORMAT_MAGIC_BE {
+        PayloadData::deserialize_slice_le(&SolanaMessage::deserialize_slice(&data)?.payload)?
+    } else if magic == EVM_FORMAT_MAGIC {
+        PayloadData::deserialize_slice_be(&EvmMessage::deserialize_slice(&data)?.payload)?
+    } else {
+        bail!("unknown magic: {magic}");
+    };
+    Ok(payload.timestamp_us)
+}
+
+fn binary_update_timestamp(update: &BinaryUpdate) -> Result<TimestampUs> {
+    let payload = match (&update.parsed, &update.solana, &update.evm) {
+        (Some(parsed), _, _) => return Ok(parsed.timestamp_us),
+        (None, Some(message), _) => PayloadData::deserialize_slice_le(&message.payload)?,
+        (None, None, Some(message)) => PayloadData::deserialize_slice_be(&message.payload)?,
+        (None, None, None) => bail!("no payload in update"),
+    };
+    Ok(payload.timestamp_us)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::protocol::{
+            router::{
+                Chain, DeliveryFormat, FixedRate, JsonBinaryEncoding, ParsedPayload, PriceFeedId,
+                PriceFeedProperty, SubscriptionParams, SubscriptionParamsRepr,
+            },
+            subscription::{Request, StreamUpdatedResponse, SubscribedResponse},
+        },
+        futures_util::{SinkExt, StreamExt},
+        tokio::net::TcpListener,
+        tokio_tungstenite::{accept_async, tungstenite::Message},
+    };
+
+    const PERIOD_US: u64 = 200_000;
+
+    fn subscribe_request() -> SubscribeRequest {
+        SubscribeRequest {
+            subscription_id: SubscriptionId(1),
+            params: SubscriptionParams::new(SubscriptionParamsRepr {
+                price_feed_ids: vec![PriceFeedId(1)],
+                properties: vec![PriceFeedProperty::Price],
+                chains: vec![Chain::Solana],
+                delivery_format: DeliveryFormat::Json,
+                json_binary_encoding: JsonBinaryEncoding::Hex,
+                parsed: true,
+                channel: Channel::FixedRate(FixedRate::from_ms(200).unwrap()),
+                compact_format_versions: Vec::new(),
+            })
+            .unwrap(),
+        }
+    }
+
+    /// Accepts one connection, answers the subscription request and sends updates with
+    /// the timestamps `periods * PERIOD_US`. The connection is closed afterwards.
+    async fn serve(listener: TcpListener, periods: &[u64]) {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        let Some(Ok(Message::Text(text))) = ws.next().await else {
+            panic!("expected a subscription request");
+        };
+        let Request::Subscribe(request) = serde_json::from_str(&text).unwrap() else {
+            panic!("expected a subscription request");
+        };
+        let subscription_id = request.subscription_id;
+        let mut responses = vec![Response::Subscribed(SubscribedResponse {
+            subscription_id,
+            compact_format_version: None,
+        })];
+        responses.extend(periods.iter().map(|period| {
+            Response::StreamUpdated(StreamUpdatedResponse {
+                subscription_id,
+                payload: JsonUpdate {
+                    parsed: Some(ParsedPayload {
+                        timestamp_us: TimestampUs(period * PERIOD_US),
+                        price_feeds: Vec::new(),
+                    }),
+                    evm: None,
+                    solana: None,
+                },
+            })
+        }));
+        for response in responses {
+            ws.send(Message::Text(serde_json::to_string(&response).unwrap()))
+                .await
+                .unwrap();
+        }
+        ws.close(None).await.unwrap();
+    }
+
+    async fn endpoint(periods: &'static [u64]) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("ws://{}/v1/stream", listener.local_addr().unwrap());
+        tokio::spawn(serve(listener, periods));
+        endpoint.parse().unwrap()
+    }
+
+    fn update_period(event: &FailoverEvent) -> Option<u64> {
+        let FailoverEvent::Message(message) = event else {
+            return None;
+        };
+        let (_, timestamp_us) = stream_update_timestamp(message)?;
+        Some(timestamp_us.0 / PERIOD_US)
+    }
+
+    #[tokio::test]
+    async fn test_failover() {
+        let first = endpoint(&[1, 2]).await;
+        let second = endpoint(&[2, 5, 6]).await;
+        let mut client = FailoverClient::new(FailoverConfig::new(
+            vec![first.clone(), second.clone()],
+            "token".into(),
+        ))
+        .unwrap();
+        client.subscribe(subscribe_request()).await.unwrap();
+
+        let mut events = Vec::new();
+        loop {
+            let event = client.next_event().await;
+            let done = update_period(&event) == Some(6);
+            events.push(event);
+            if done {
+                break;
+            }
+        }
+
+        let mut events = events.into_iter();
+        let mut next = || events.next().unwrap();
+        assert!(matches!(next(), FailoverEvent::Connected { endpoint } if endpoint == first));
+        assert!(matches!(
+            next(),
+            FailoverEvent::Message(LazerMessage::Response(Response::Subscribed(_)))
+        ));
+        assert_eq!(update_period(&next()), Some(1));
+        assert_eq!(update_period(&next()), Some(2));
+        assert!(
+            matches!(next(), FailoverEvent::Disconnected { endpoint, .. } if endpoint == first)
+        );
+        // The first endpoint has failed, so the client switches to the second one
+        assert!(matches!(next(), FailoverEvent::Connected { endpoint } if endpoint == second));
+        assert!(matches!(
+            next(),
+            FailoverEvent::Message(LazerMessage::Response(Response::Subscribed(_)))
+        ));
+        // The repeated update 2 is dropped, and updates 3 and 4 are reported as missing
+        let FailoverEvent::Gap(gap) = next() else {
+            panic!("expected a gap");
+        };
+        assert_eq!(
+            gap,
+            Gap {
+                subscription_id: SubscriptionId(1),
+                last_timestamp_us: TimestampUs(2 * PERIOD_US),
+                timestamp_us: TimestampUs(5 * PERIOD_US),
+                missed_updates: 2,
+            }
+        );
+        assert_eq!(update_period(&next()), Some(5));
+        assert_eq!(update_period(&next()), Some(6));
+    }
+
+    #[test]
+    fn test_gap_tracker() {
+        let channel = Channel::FixedRate(FixedRate::from_ms(200).unwrap());
+        let mut tracker = GapTracker::new(channel).unwrap();
+        assert!(matches!(
+            tracker.track(TimestampUs(200_000)),
+            Sequence::Next
+        ));
+        // Timestamps that are slightly off are rounded to the closest period
+        assert!(matches!(
+            tracker.track(TimestampUs(400_010)),
+            Sequence::Next
+        ));
+        assert!(matches!(
+            tracker.track(TimestampUs(400_010)),
+            Sequence::Repeated
+        ));
+        assert!(matches!(
+            tracker.track(TimestampUs(200_000)),
+            Sequence::Repeated
+        ));
+        assert!(matches!(
+            tracker.track(TimestampUs(1_000_000)),
+            Sequence::Gap(2)
+        ));
+        assert!(GapTracker::new(Channel::RealTime).is_none());
+    }
+}
@@ -0,0 +1,209 @@
+//! A WebSocket client of the Pyth Lazer router.
+//!
+//! [`LazerConnection`] connects to the stream endpoint of a router, sends subscription
+//! requests and receives the responses and stream updates of the router. Use
+//! [`pyth_lazer_sdk::verifier::Verifier`] to verify the signed messages of the updates.
+//!
+//! [`failover::FailoverClient`] keeps subscriptions alive across several routers and
+//! reports missed updates.
+
+use {
+    anyhow::{Context, Result},
+    futures_util::{SinkExt, StreamExt},
+    pyth_lazer_protocol::subscription::{
+        Request, Response, SubscribeRequest, SubscriptionId, UnsubscribeRequest,
+    },
+    pyth_lazer_sdk::binary_update::BinaryUpdate,
+    tokio::net::TcpStream,
+    tokio_tungstenite::{
+        connect_async,
+        tungstenite::{client::IntoClientRequest, http::header::AUTHORIZATION, Message},
+        MaybeTlsStream, WebSocketStream,
+    },
+    url::Url,
+};
+
+pub mod failover;
+
+pub use {pyth_lazer_protocol as protocol, pyth_lazer_sdk as sdk};
+
+/// A message received from the router.
+#[derive(Debug, Clone)]
+pub enum LazerMessage {
+    /// A JSON message, which is either a response to a request or a stream update of a
+    /// subscription with JSON delivery format.
+    Response(Response),
+    /// A stream update of a subscription with binary delivery format.
+    Binary(BinaryUpdate),
+}
+
+/// A WebSocket connection to the stream endpoint of a Pyth Lazer router.
+pub struct LazerConnection {
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+impl LazerConnection {
+    /// Connects to `endpoint` (e.g. `wss://<router>/v1/stream`), authenticating with
+    /// `access_token`.
+    pub async fn connect(endpoint: &Url, access_token: &str) -> Result<Self> {
+        let mut request = endpoint.as_str().into_client_request()?;
+        request.headers_mut().insert(
+            AUTHORIZATION,
+            format!("Bearer {access_token}")
+                .parse()
+                .context("invalid access token")?,
+        );
+        let (ws, _) = connect_async(request)
+            .await
+            .with_context(|| format!("failed to connect to {endpoint}"))?;
+        Ok(Self { ws })
+    }
+
+    pub async fn send(&mut self, request: &Request) -> Result<()> {
+        self.ws
+            .send(Message::Text(serde_json::to_string(request)?))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn subscribe(&mut self, request: SubscribeRequest) -> Result<()> {
+        self.send(&Request::Subscribe(request)).await
+    }
+
+    pub async fn unsubscribe(&mut self, subscription_id: SubscriptionId) -> Result<()> {
+        self.send(&Request::Unsubscribe(UnsubscribeRequest {
+            subscription_id,
+        }))
+        .await
+    }
+
+    /// Waits for the next message from the router. Returns `None` once the connection
+    /// is closed.
+    pub async fn next_message(&mut self) -> Result<Option<LazerMessage>> {
+        while let Some(message) = self.ws.next().await {
+            match message? {
+                Message::Text(text) => {
+                    let response =
+                        serde_json::from_str(&text).context("failed to parse response")?;
+                    return Ok(Some(LazerMessage::Response(response)));
+                }
+                Message::Binary(data) => {
+                    let update = BinaryUpdate::deserialize_slice(&data)
+                        .context("failed to parse binary update")?;
+                    return Ok(Some(LazerMessage::Binary(update)));
+                }
+                Message::Close(_) => return Ok(None),
+                // Pings are answered by tungstenite.
+                Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => {}
+            }
+        }
+        Ok(None)
+    }
+
+    pub async fn close(mut self) -> Result<()> {
+        self.ws.close(None).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::protocol::{
+            router::{
+                Chain, Channel, DeliveryFormat, JsonBinaryEncoding, JsonUpdate, PriceFeedId,
+                PriceFeedProperty, SubscriptionParams, SubscriptionParamsRepr,
+            },
+            subscription::{StreamUpdatedResponse, SubscribedResponse},
+        },
+        tokio::net::TcpListener,
+        tokio_tungstenite::{
+            accept_hdr_async,
+            tungstenite::handshake::server::{
+                ErrorResponse, Request as HttpRequest, Response as HttpResponse,
+            },
+        },
+    };
+
+    /// Accepts one connection with the access token "token", answers the subscription
+    /// request and sends one stream update.
+    async fn serve(listener: TcpListener) {
+        let (stream, _) = listener.accept().await.unwrap();
+        let check_token = |request: &HttpRequest, response: HttpResponse| {
+            assert_eq!(request.headers()[AUTHORIZATION], "Bearer token");
+            Ok::<_, ErrorResponse>(response)
+        };
+        let mut ws = accept_hdr_async(stream, check_token).await.unwrap();
+
+        let Some(Ok(Message::Text(text))) = ws.next().await else {
+            panic!("expected a subscription request");
+        };
+        let Request::Subscribe(request) = serde_json::from_str(&text).unwrap() else {
+            panic!("expected a subscription request");
+        };
+        let responses = [
+            Response::Subscribed(SubscribedResponse {
+                subscription_id: request.subscription_id,
+                compact_format_version: None,
+            }),
+            Response::StreamUpdated(StreamUpdatedResponse {
+                subscription_id: request.subscription_id,
+                payload: JsonUpdate {
+                    parsed: None,
+                    evm: None,
+                    solana: None,
+                },
+            }),
+        ];
+        for response in responses {
+            ws.send(Message::Text(serde_json::to_string(&response).unwrap()))
+                .await
+                .unwrap();
+        }
+        ws.close(None).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_subscribe() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("ws://{}/v1/stream", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve(listener));
+
+        let mut connection = LazerConnection::connect(&endpoint.parse().unwrap(), "token")
+            .await
+            .unwrap();
+        connection
+            .subscribe(SubscribeRequest {
+                subscription_id: SubscriptionId(1),
+                params: SubscriptionParams::new(SubscriptionParamsRepr {
+                    price_feed_ids: vec![PriceFeedId(1)],
+                    properties: vec![PriceFeedProperty::Price],
+                    chains: vec![Chain::Solana],
+                    delivery_format: DeliveryFormat::Json,
+                    json_binary_encoding: JsonBinaryEncoding::Hex,
+                    parsed: true,
+                    channel: Channel::RealTime,
+                    compact_format_versions: Vec::new(),
+                })
+                .unwrap(),
+            })
+            .await
+            .unwrap();
+
+        let Some(LazerMessage::Response(Response::Subscribed(response))) =
+            connection.next_message().await.unwrap()
+        else {
+            panic!("expected a subscribed response");
+        };
+        assert_eq!(response.subscription_id, SubscriptionId(1));
+        let Some(LazerMessage::Response(Response::StreamUpdated(update))) =
+            connection.next_message().await.unwrap()
+        else {
+            panic!("expected a stream update");
+        };
+        assert_eq!(update.subscription_id, SubscriptionId(1));
+        assert!(connection.next_message().await.unwrap().is_none());
+        server.await.unwrap();
+    }
+}
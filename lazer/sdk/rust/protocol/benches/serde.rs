@@ -0,0 +1,89 @@
+//! Compares the JSON and the compact encoding of parsed payloads.
+//!
+//! Run with `cargo bench -p pyth-lazer-protocol`. For every number of feeds, this prints
+//! the encoded size and the average time to encode and decode one payload.
+
+use {
+    pyth_lazer_protocol::{
+        compact::CompactFormatVersion,
+        router::{ParsedFeedPayload, ParsedPayload, Price, PriceFeedId, TimestampUs},
+    },
+    std::{
+        hint::black_box,
+        time::{Duration, Instant},
+    },
+};
+
+const FEED_COUNTS: [u32; 4] = [1, 10, 100, 500];
+const MEASUREMENT_TIME: Duration = Duration::from_millis(500);
+
+fn payload(num_feeds: u32) -> ParsedPayload {
+    ParsedPayload {
+        timestamp_us: TimestampUs(1_728_909_000_000_000),
+        price_feeds: (1..=num_feeds)
+            .map(|feed_id| {
+                let price = 6_500_000_000_000 + i64::from(feed_id) * 1_234_567;
+                ParsedFeedPayload {
+                    price_feed_id: PriceFeedId(feed_id),
+                    price: Some(Price::new(price).unwrap()),
+                    best_bid_price: Some(Price::new(price - 50_000).unwrap()),
+                    best_ask_price: Some(Price::new(price + 50_000).unwrap()),
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Average duration of `f`, measured over `MEASUREMENT_TIME`.
+fn measure<T>(mut f: impl FnMut() -> T) -> Duration {
+    let start = Instant::now();
+    let mut iterations = 0u32;
+    while start.elapsed() < MEASUREMENT_TIME {
+        black_box(f());
+        iterations += 1;
+    }
+    start.elapsed() / iterations
+}
+
+fn main() {
+    println!(
+        "{:>6} | {:>10} {:>10} | {:>12} {:>12} | {:>12} {:>12}",
+        "feeds", "json B", "compact B", "json enc", "compact enc", "json dec", "compact dec"
+    );
+    for num_feeds in FEED_COUNTS {
+        let payload = payload(num_feeds);
+        let json = serde_json::to_vec(&payload).unwrap();
+        let mut compact = Vec::new();
+        payload
+            .serialize_compact(CompactFormatVersion::V1, &mut compact)
+            .unwrap();
+        assert_eq!(
+            ParsedPayload::deserialize_compact_slice(&compact).unwrap(),
+            payload
+        );
+
+        let json_encode = measure(|| serde_json::to_vec(black_box(&payload)).unwrap());
+        let compact_encode = measure(|| {
+            let mut data = Vec::with_capacity(compact.len());
+            black_box(&payload)
+                .serialize_compact(CompactFormatVersion::V1, &mut data)
+                .unwrap();
+            data
+        });
+        let json_decode =
+            measure(|| serde_json::from_slice::<ParsedPayload>(black_box(&json)).unwrap());
+        let compact_decode =
+            measure(|| ParsedPayload::deserialize_compact_slice(black_box(&compact)).unwrap());
+
+        println!(
+            "{:>6} | {:>10} {:>10} | {:>12?} {:>12?} | {:>12?} {:>12?}",
+            num_feeds,
+            json.len(),
+            compact.len(),
+            json_encode,
+            compact_encode,
+            json_decode,
+            compact_decode
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Compact binary encoding of parsed payloads.
+//!
+//! Binary stream updates carry the parsed payload as JSON by default
+//! (see [`PARSED_FORMAT_MAGIC`](crate::payload::PARSED_FORMAT_MAGIC)). Subscribers of many
+//! feeds can instead request the compact encoding by listing the versions they support in
+//! [`SubscriptionParamsRepr::compact_format_versions`](crate::router::SubscriptionParamsRepr).
+//! The router picks the highest version it supports and returns it in
+//! [`SubscribedResponse::compact_format_version`](crate::subscription::SubscribedResponse).
+//! If the router returns no version, the payload stays JSON.
+//!
+//! Version 1 layout (big endian):
+//! - magic (`u32`), version (`u8`), timestamp (`u64`), number of feeds (`u16`);
+//! - for every feed: feed id (`u32`), a bit mask of the present properties (`u8`, bit `i`
+//!   corresponds to [`PriceFeedProperty`] with value `i`), then the value (`i64`) of every
+//!   present property in order of the bits.
+
+use {
+    crate::router::{
+        ParsedFeedPayload, ParsedPayload, Price, PriceFeedId, PriceFeedProperty, TimestampUs,
+    },
+    anyhow::bail,
+    byteorder::{ReadBytesExt, WriteBytesExt, BE},
+    serde::{Deserialize, Serialize},
+    std::{
+        io::{Cursor, Read, Write},
+        num::NonZeroI64,
+    },
+};
+
+pub const COMPACT_FORMAT_MAGIC: u32 = 2745441015;
+
+/// Version of the compact encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct CompactFormatVersion(pub u8);
+
+impl CompactFormatVersion {
+    pub const V1: Self = Self(1);
+
+    /// Versions that this crate can encode and decode, in ascending order.
+    pub const SUPPORTED: &'static [Self] = &[Self::V1];
+
+    /// The highest version that is both requested and supported.
+    pub fn negotiate(requested: &[Self]) -> Option<Self> {
+        requested
+            .iter()
+            .copied()
+            .filter(|version| Self::SUPPORTED.contains(version))
+            .max()
+    }
+}
+
+const PROPERTIES: [PriceFeedProperty; 3] = [
+    PriceFeedProperty::Price,
+    PriceFeedProperty::BestBidPrice,
+    PriceFeedProperty::BestAskPrice,
+];
+
+fn property_value(feed: &ParsedFeedPayload, property: PriceFeedProperty) -> Option<Price> {
+    match property {
+        PriceFeedProperty::Price => feed.price,
+        PriceFeedProperty::BestBidPrice => feed.best_bid_price,
+        PriceFeedProperty::BestAskPrice => feed.best_ask_price,
+    }
+}
+
+fn property_value_mut(
+    feed: &mut ParsedFeedPayload,
+    property: PriceFeedProperty,
+) -> &mut Option<Price> {
+    match property {
+        PriceFeedProperty::Price => &mut feed.price,
+        PriceFeedProperty::BestBidPrice => &mut feed.best_bid_price,
+        PriceFeedProperty::BestAskPrice => &mut feed.best_ask_price,
+    }
+}
+
+impl ParsedPayload {
+    pub fn serialize_compact(
+        &self,
+        version: CompactFormatVersion,
+        mut writer: impl Write,
+    ) -> anyhow::Result<()> {
+        if version != CompactFormatVersion::V1 {
+            bail!("unsupported compact format version: {}", version.0);
+        }
+        writer.write_u32::<BE>(COMPACT_FORMAT_MAGIC)?;
+        writer.write_u8(version.0)?;
+        writer.write_u64::<BE>(self.timestamp_us.0)?;
+        writer.write_u16::<BE>(self.price_feeds.len().try_into()?)?;
+        for feed in &self.price_feeds {
+            writer.write_u32::<BE>(feed.price_feed_id.0)?;
+            let mask = PROPERTIES
+                .iter()
+                .filter(|&&property| property_value(feed, property).is_some())
+                .fold(0u8, |mask, &property| mask | 1 << property as u8);
+            writer.write_u8(mask)?;
+            for property in PROPERTIES {
+                if let Some(price) = property_value(feed, property) {
+                    writer.write_i64::<BE>(price.0.get())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn deserialize_compact_slice(data: &[u8]) -> anyhow::Result<Self> {
+        Self::deserialize_compact(Cursor::new(data))
+    }
+
+    pub fn deserialize_compact(mut reader: impl Read) -> anyhow::Result<Self> {
+        let magic = reader.read_u32::<BE>()?;
+        if magic != COMPACT_FORMAT_MAGIC {
+            bail!("magic mismatch");
+        }
+        let version = CompactFormatVersion(reader.read_u8()?);
+        if version != CompactFormatVersion::V1 {
+            bail!("unsupported compact format version: {}", version.0);
+        }
+        let timestamp_us = TimestampUs(reader.read_u64::<BE>()?);
+        let num_feeds = reader.read_u16::<BE>()?;
+        let mut price_feeds = Vec::with_capacity(num_feeds.into());
+        for _ in 0..num_feeds {
+            let mut feed = ParsedFeedPayload {
+                price_feed_id: PriceFeedId(reader.read_u32::<BE>()?),
+                price: None,
+                best_bid_price: None,
+                best_ask_price: None,
+            };
+            let mask = reader.read_u8()?;
+            if mask >> PROPERTIES.len() != 0 {
+                bail!("unknown property");
+            }
+            for property in PROPERTIES {
+                if mask & 1 << property as u8 != 0 {
+                    let Some(value) = NonZeroI64::new(reader.read_i64::<BE>()?) else {
+                        bail!("zero price");
+                    };
+                    *property_value_mut(&mut feed, property) = Some(Price(value));
+                }
+            }
+            price_feeds.push(feed);
+        }
+        Ok(Self {
+            timestamp_us,
+            price_feeds,
+        })
+    }
+}
+
+#[test]
+fn compact_serde() {
+    let payload = ParsedPayload {
+        timestamp_us: TimestampUs(1_728_909_000_000_000),
+        price_feeds: vec![
+            ParsedFeedPayload {
+                price_feed_id: PriceFeedId(1),
+                price: Some(Price::new(6_500_000_000_000).unwrap()),
+                best_bid_price: Some(Price::new(6_499_900_000_000).unwrap()),
+                best_ask_price: None,
+            },
+            ParsedFeedPayload {
+                price_feed_id: PriceFeedId(2),
+                price: None,
+                best_bid_price: None,
+                best_ask_price: Some(Price::new(-1).unwrap()),
+            },
+        ],
+    };
+    let mut data = Vec::new();
+    payload
+        .serialize_compact(CompactFormatVersion::V1, &mut data)
+        .unwrap();
+    assert_eq!(data.len(), 4 + 1 + 8 + 2 + (4 + 1 + 2 * 8) + (4 + 1 + 8));
+    assert_eq!(
+        ParsedPayload::deserialize_compact_slice(&data).unwrap(),
+        payload
+    );
+
+    // Truncated data
+    assert!(ParsedPayload::deserialize_compact_slice(&data[..data.len() - 1]).is_err());
+    // Unsupported version
+    data[4] = 2;
+    assert!(ParsedPayload::deserialize_compact_slice(&data).is_err());
+    assert!(payload
+        .serialize_compact(CompactFormatVersion(2), Vec::new())
+        .is_err());
+}
+
+#[test]
+fn compact_format_negotiation() {
+    let v2 = CompactFormatVersion(2);
+    assert_eq!(
+        CompactFormatVersion::negotiate(&[CompactFormatVersion::V1, v2]),
+        Some(CompactFormatVersion::V1)
+    );
+    assert_eq!(CompactFormatVersion::negotiate(&[v2]), None);
+    assert_eq!(CompactFormatVersion::negotiate(&[]), None);
+    assert_eq!(
+        serde_json::from_str::<Vec<CompactFormatVersion>>("[1,2]").unwrap(),
+        [CompactFormatVersion::V1, v2]
+    );
+}
@@ -1,7 +1,7 @@
 //! WebSocket JSON protocol types for API the router provides to consumers and publishers.
 
 use {
-    crate::payload::AggregatedPriceFeedData,
+    crate::{compact::CompactFormatVersion, payload::AggregatedPriceFeedData},
     anyhow::{bail, Context},
     itertools::Itertools,
     rust_decimal::{prelude::FromPrimitive, Decimal},
@@ -209,6 +209,16 @@ impl Channel {
             },
         }
     }
+
+    pub fn from_id(id: ChannelId) -> Option<Self> {
+        if id == channel_ids::REAL_TIME {
+            return Some(Channel::RealTime);
+        }
+        FixedRate::ALL
+            .into_iter()
+            .map(Channel::FixedRate)
+            .find(|channel| channel.id() == id)
+    }
 }
 
 #[test]
@@ -218,6 +228,18 @@ fn id_supports_all_fixed_rates() {
     }
 }
 
+#[test]
+fn from_id_inverts_id() {
+    let channels = FixedRate::ALL
+        .into_iter()
+        .map(Channel::FixedRate)
+        .chain([Channel::RealTime]);
+    for channel in channels {
+        assert_eq!(Channel::from_id(channel.id()), Some(channel));
+    }
+    assert_eq!(Channel::from_id(ChannelId(0)), None);
+}
+
 fn parse_channel(value: &str) -> Option<Channel> {
     if value == "real_time" {
         Some(Channel::RealTime)
@@ -300,6 +322,10 @@ pub struct SubscriptionParamsRepr {
     #[serde(default = "default_parsed")]
     pub parsed: bool,
     pub channel: Channel,
+    /// Versions of the compact encoding of the parsed payload that the client supports
+    /// (only if `delivery_format == Binary`). If empty, the parsed payload is sent as JSON.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compact_format_versions: Vec<CompactFormatVersion>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
@@ -333,10 +359,35 @@ impl SubscriptionParams {
         if !value.properties.iter().all_unique() {
             return Err("duplicate properties specified");
         }
+        if !value.compact_format_versions.is_empty() {
+            if value.delivery_format != DeliveryFormat::Binary {
+                return Err("compact format requires binary delivery format");
+            }
+            if !value.parsed {
+                return Err("compact format requires parsed payload");
+            }
+        }
         Ok(Self(value))
     }
 }
 
+#[test]
+fn compact_format_requires_binary_delivery() {
+    let params = |delivery_format, parsed| SubscriptionParamsRepr {
+        price_feed_ids: vec![PriceFeedId(1)],
+        properties: vec![PriceFeedProperty::Price],
+        chains: vec![],
+        delivery_format,
+        json_binary_encoding: JsonBinaryEncoding::Base64,
+        parsed,
+        channel: Channel::RealTime,
+        compact_format_versions: vec![CompactFormatVersion::V1],
+    };
+    assert!(SubscriptionParams::new(params(DeliveryFormat::Binary, true)).is_ok());
+    assert!(SubscriptionParams::new(params(DeliveryFormat::Json, true)).is_err());
+    assert!(SubscriptionParams::new(params(DeliveryFormat::Binary, false)).is_err());
+}
+
 impl Deref for SubscriptionParams {
     type Target = SubscriptionParamsRepr;
 
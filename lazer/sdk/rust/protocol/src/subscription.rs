@@ -2,7 +2,10 @@
 //! used across publishers, agents and routers.
 
 use {
-    crate::router::{JsonUpdate, SubscriptionParams},
+    crate::{
+        compact::CompactFormatVersion,
+        router::{JsonUpdate, SubscriptionParams},
+    },
     derive_more::From,
     serde::{Deserialize, Serialize},
 };
@@ -50,6 +53,10 @@ pub enum Response {
 #[serde(rename_all = "camelCase")]
 pub struct SubscribedResponse {
     pub subscription_id: SubscriptionId,
+    /// Version of the compact encoding that the parsed payloads of this subscription use,
+    /// chosen from the requested versions. If not set, parsed payloads are sent as JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compact_format_version: Option<CompactFormatVersion>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,145 @@
+//! Updates sent by the router as binary WebSocket messages
+//! (if `delivery_format == Binary`).
+
+use {
+    crate::protocol::{
+        compact::COMPACT_FORMAT_MAGIC,
+        message::{EvmMessage, SolanaMessage},
+        payload::{
+            BINARY_UPDATE_FORMAT_MAGIC, EVM_FORMAT_MAGIC, PARSED_FORMAT_MAGIC,
+            SOLANA_FORMAT_MAGIC_BE,
+        },
+        router::ParsedPayload,
+        subscription::SubscriptionId,
+    },
+    anyhow::{bail, Context},
+    byteorder::{ReadBytesExt, BE},
+    std::io::{Cursor, Read},
+};
+
+/// A binary stream update, containing the signed messages and the parsed payload
+/// requested in the subscription. The parsed payload is decoded from JSON or from the
+/// compact encoding, whichever the router used.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BinaryUpdate {
+    pub subscription_id: SubscriptionId,
+    pub evm: Option<EvmMessage>,
+    pub solana: Option<SolanaMessage>,
+    pub parsed: Option<ParsedPayload>,
+}
+
+impl BinaryUpdate {
+    pub fn deserialize_slice(data: &[u8]) -> anyhow::Result<Self> {
+        let mut reader = Cursor::new(data);
+        let magic = reader.read_u32::<BE>()?;
+        if magic != BINARY_UPDATE_FORMAT_MAGIC {
+            bail!("binary update format magic mismatch");
+        }
+        let mut update = Self {
+            subscription_id: SubscriptionId(reader.read_u64::<BE>()?),
+            evm: None,
+            solana: None,
+            parsed: None,
+        };
+        while usize::try_from(reader.position())? < data.len() {
+            let len: usize = reader.read_u16::<BE>()?.into();
+            let mut message = vec![0u8; len];
+            reader.read_exact(&mut message)?;
+            let magic = message.get(..4).context("message too short")?;
+            let magic = u32::from_be_bytes(magic.try_into()?);
+            if magic == EVM_FORMAT_MAGIC {
+                update.evm = Some(EvmMessage::deserialize_slice(&message)?);
+            } else if magic == SOLANA_FORMAT_MAGIC_BE {
+                update.solana = Some(SolanaMessage::deserialize_slice(&message)?);
+            } else if magic == PARSED_FORMAT_MAGIC {
+                update.parsed = Some(serde_json::from_slice(&message[4..])?);
+            } else if magic == COMPACT_FORMAT_MAGIC {
+                update.parsed = Some(ParsedPayload::deserialize_compact_slice(&message)?);
+            } else {
+                bail!("unknown magic: {magic}");
+            }
+        }
+        Ok(update)
+    }
+}
+
+#[test]
+fn binary_update_deserialize() {
+    use crate::protocol::router::{ParsedFeedPayload, PriceFeedId, TimestampUs};
+
+    let solana = SolanaMessage {
+        payload: vec![1, 2, 3],
+        signature: [4; 64],
+        public_key: [5; 32],
+    };
+    let parsed = ParsedPayload {
+        timestamp_us: TimestampUs(6),
+        price_feeds: vec![ParsedFeedPayload {
+            price_feed_id: PriceFeedId(7),
+            price: None,
+            best_bid_price: None,
+            best_ask_price: None,
+        }],
+    };
+
+    let mut data = BINARY_UPDATE_FORMAT_MAGIC.to_be_bytes().to_vec();
+    data.extend_from_slice(&8u64.to_be_bytes());
+    let mut solana_data = Vec::new();
+    solana.serialize(&mut solana_data).unwrap();
+    data.extend_from_slice(&u16::try_from(solana_data.len()).unwrap().to_be_bytes());
+    data.extend_from_slice(&solana_data);
+    let mut parsed_data = PARSED_FORMAT_MAGIC.to_be_bytes().to_vec();
+    parsed_data.extend_from_slice(&serde_json::to_vec(&parsed).unwrap());
+    data.extend_from_slice(&u16::try_from(parsed_data.len()).unwrap().to_be_bytes());
+    data.extend_from_slice(&parsed_data);
+
+    assert_eq!(
+        BinaryUpdate::deserialize_slice(&data).unwrap(),
+        BinaryUpdate {
+            subscription_id: SubscriptionId(8),
+            evm: None,
+            solana: Some(solana),
+            parsed: Some(parsed),
+        }
+    );
+
+    data.truncate(data.len() - 1);
+    assert!(BinaryUpdate::deserialize_slice(&data).is_err());
+}
+
+#[test]
+fn binary_update_deserialize_compact() {
+    use crate::protocol::{
+        compact::CompactFormatVersion,
+        router::{ParsedFeedPayload, Price, PriceFeedId, TimestampUs},
+    };
+
+    let parsed = ParsedPayload {
+        timestamp_us: TimestampUs(6),
+        price_feeds: vec![ParsedFeedPayload {
+            price_feed_id: PriceFeedId(7),
+            price: Some(Price::new(8).unwrap()),
+            best_bid_price: None,
+            best_ask_price: None,
+        }],
+    };
+
+    let mut data = BINARY_UPDATE_FORMAT_MAGIC.to_be_bytes().to_vec();
+    data.extend_from_slice(&9u64.to_be_bytes());
+    let mut parsed_data = Vec::new();
+    parsed
+        .serialize_compact(CompactFormatVersion::V1, &mut parsed_data)
+        .unwrap();
+    data.extend_from_slice(&u16::try_from(parsed_data.len()).unwrap().to_be_bytes());
+    data.extend_from_slice(&parsed_data);
+
+    assert_eq!(
+        BinaryUpdate::deserialize_slice(&data).unwrap(),
+        BinaryUpdate {
+            subscription_id: SubscriptionId(9),
+            evm: None,
+            solana: None,
+            parsed: Some(parsed),
+        }
+    );
+}
@@ -0,0 +1,12 @@
+//! Parsing and signature verification of Pyth Lazer updates.
+//!
+//! Updates received from Lazer carry the price data as a payload signed by the Lazer
+//! aggregator, wrapped in an envelope for the target chain (see
+//! [`protocol::message`]). [`verifier::Verifier`] checks the signature against a set of
+//! trusted signers and decodes the payload into a [`update::PriceUpdate`].
+
+pub mod binary_update;
+pub mod update;
+pub mod verifier;
+
+pub use pyth_lazer_protocol as protocol;
@@ -0,0 +1,107 @@
+//! Typed price data decoded from a signed payload.
+
+use {
+    crate::protocol::{
+        payload::{PayloadData, PayloadPropertyValue},
+        router::{Channel, Price, PriceFeedId, TimestampUs},
+    },
+    anyhow::Context,
+};
+
+/// Prices of the feeds of a subscription at one point in time.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PriceUpdate {
+    pub timestamp_us: TimestampUs,
+    pub channel: Channel,
+    pub feeds: Vec<FeedUpdate>,
+}
+
+/// Values of the properties of a price feed. Properties that weren't requested in the
+/// subscription or that currently have no value are `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FeedUpdate {
+    pub feed_id: PriceFeedId,
+    pub price: Option<Price>,
+    pub best_bid_price: Option<Price>,
+    pub best_ask_price: Option<Price>,
+}
+
+impl TryFrom<PayloadData> for PriceUpdate {
+    type Error = anyhow::Error;
+
+    fn try_from(payload: PayloadData) -> anyhow::Result<Self> {
+        let channel = Channel::from_id(payload.channel_id).context("unknown channel")?;
+        let feeds = payload
+            .feeds
+            .into_iter()
+            .map(|feed| {
+                let mut output = FeedUpdate {
+                    feed_id: feed.feed_id,
+                    price: None,
+                    best_bid_price: None,
+                    best_ask_price: None,
+                };
+                for property in feed.properties {
+                    match property {
+                        PayloadPropertyValue::Price(price) => output.price = price,
+                        PayloadPropertyValue::BestBidPrice(price) => output.best_bid_price = price,
+                        PayloadPropertyValue::BestAskPrice(price) => output.best_ask_price = price,
+                    }
+                }
+                output
+            })
+            .collect();
+        Ok(Self {
+            timestamp_us: payload.timestamp_us,
+            channel,
+            feeds,
+        })
+    }
+}
+
+impl PriceUpdate {
+    pub fn feed(&self, feed_id: PriceFeedId) -> Option<&FeedUpdate> {
+        self.feeds.iter().find(|feed| feed.feed_id == feed_id)
+    }
+}
+
+#[test]
+fn price_update_from_payload() {
+    use crate::protocol::{
+        payload::AggregatedPriceFeedData,
+        router::{ChannelId, PriceFeedProperty},
+    };
+
+    let payload = PayloadData::new(
+        TimestampUs(1_700_000_000_000_000),
+        Channel::RealTime.id(),
+        &[(
+            PriceFeedId(1),
+            AggregatedPriceFeedData {
+                price: Some(Price::new(100).unwrap()),
+                best_bid_price: Some(Price::new(99).unwrap()),
+                best_ask_price: Some(Price::new(101).unwrap()),
+            },
+        )],
+        &[PriceFeedProperty::Price, PriceFeedProperty::BestAskPrice],
+    );
+    let update = PriceUpdate::try_from(payload.clone()).unwrap();
+    assert_eq!(update.timestamp_us, TimestampUs(1_700_000_000_000_000));
+    assert_eq!(update.channel, Channel::RealTime);
+    assert_eq!(
+        update.feed(PriceFeedId(1)),
+        Some(&FeedUpdate {
+            feed_id: PriceFeedId(1),
+            price: Some(Price::new(100).unwrap()),
+            best_bid_price: None,
+            best_ask_price: Some(Price::new(101).unwrap()),
+        })
+    );
+    assert_eq!(update.feed(PriceFeedId(2)), None);
+
+    let unknown_channel = PayloadData {
+        channel_id: ChannelId(0),
+        ..payload
+    };
+    assert!(PriceUpdate::try_from(unknown_channel).is_err());
+}
@@ -0,0 +1,356 @@
+//! Verification of the signatures of Lazer messages.
+
+use {
+    crate::{
+        binary_update::BinaryUpdate,
+        protocol::{
+            message::{EvmMessage, SolanaMessage},
+            payload::{PayloadData, EVM_FORMAT_MAGIC, SOLANA_FORMAT_MAGIC_BE},
+            router::{JsonBinaryData, JsonBinaryEncoding, JsonUpdate},
+        },
+        update::PriceUpdate,
+    },
+    anyhow::Context,
+    base64::Engine,
+    sha3::{Digest, Keccak256},
+    thiserror::Error,
+};
+
+/// A key that Lazer messages are signed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Signer {
+    /// ed25519 public key, which signs the payloads of Solana messages
+    Solana([u8; 32]),
+    /// Ethereum address of the secp256k1 key which signs the payloads of EVM messages
+    Evm([u8; 20]),
+}
+
+#[derive(Debug, Error)]
+pub enum VerificationError {
+    #[error("invalid message: {0:#}")]
+    InvalidMessage(anyhow::Error),
+    #[error("the update contains no signed message")]
+    NoSignedMessage,
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("not a trusted signer: {0:?}")]
+    UntrustedSigner(Signer),
+}
+
+/// A price update with a valid signature of a trusted signer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VerifiedUpdate {
+    pub signer: Signer,
+    pub update: PriceUpdate,
+}
+
+/// Verifies Lazer messages against a set of trusted signers.
+#[derive(Debug, Clone, Default)]
+pub struct Verifier {
+    trusted_signers: Vec<Signer>,
+}
+
+impl Verifier {
+    pub fn new(trusted_signers: impl IntoIterator<Item = Signer>) -> Self {
+        Self {
+            trusted_signers: trusted_signers.into_iter().collect(),
+        }
+    }
+
+    pub fn trusted_signers(&self) -> &[Signer] {
+        &self.trusted_signers
+    }
+
+    fn check_trusted(&self, signer: Signer) -> Result<(), VerificationError> {
+        if !self.trusted_signers.contains(&signer) {
+            return Err(VerificationError::UntrustedSigner(signer));
+        }
+        Ok(())
+    }
+
+    /// Verifies a Solana message, whose payload is signed with ed25519 and encoded in
+    /// little endian.
+    pub fn verify_solana(
+        &self,
+        message: &SolanaMessage,
+    ) -> Result<VerifiedUpdate, VerificationError> {
+        let public_key = ed25519_dalek::PublicKey::from_bytes(&message.public_key)
+            .map_err(|_| VerificationError::InvalidSignature)?;
+        let signature = ed25519_dalek::Signature::from_bytes(&message.signature)
+            .map_err(|_| VerificationError::InvalidSignature)?;
+        public_key
+            .verify_strict(&message.payload, &signature)
+            .map_err(|_| VerificationError::InvalidSignature)?;
+
+        let signer = Signer::Solana(message.public_key);
+        self.check_trusted(signer)?;
+        Ok(VerifiedUpdate {
+            signer,
+            update: parse_payload(PayloadData::deserialize_slice_le(&message.payload))?,
+        })
+    }
+
+    /// Verifies an EVM message, whose payload is signed with secp256k1 over its keccak256
+    /// hash (like `ecrecover`) and encoded in big endian.
+    pub fn verify_evm(&self, message: &EvmMessage) -> Result<VerifiedUpdate, VerificationError> {
+        let hash = Keccak256::digest(&message.payload);
+        let signature = libsecp256k1::Signature::parse_standard(&message.signature)
+            .map_err(|_| VerificationError::InvalidSignature)?;
+        let recovery_id = libsecp256k1::RecoveryId::parse(message.recovery_id)
+            .map_err(|_| VerificationError::InvalidSignature)?;
+        let public_key = libsecp256k1::recover(
+            &libsecp256k1::Message::parse(&hash.into()),
+            &signature,
+            &recovery_id,
+        )
+        .map_err(|_| VerificationError::InvalidSignature)?;
+        let public_key_hash = Keccak256::digest(&public_key.serialize()[1..]);
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&public_key_hash[12..]);
+
+        let signer = Signer::Evm(address);
+        self.check_trusted(signer)?;
+        Ok(VerifiedUpdate {
+            signer,
+            update: parse_payload(PayloadData::deserialize_slice_be(&message.payload))?,
+        })
+    }
+
+    /// Verifies a serialized EVM or Solana message.
+    pub fn verify(&self, data: &[u8]) -> Result<VerifiedUpdate, VerificationError> {
+        let magic = data
+            .get(..4)
+            .context("message too short")
+            .map_err(VerificationError::InvalidMessage)?;
+        let magic = u32::from_be_bytes(magic.try_into().unwrap());
+        if magic == EVM_FORMAT_MAGIC {
+            let message =
+                EvmMessage::deserialize_slice(data).map_err(VerificationError::InvalidMessage)?;
+            self.verify_evm(&message)
+        } else if magic == SOLANA_FORMAT_MAGIC_BE {
+            let message = SolanaMessage::deserialize_slice(data)
+                .map_err(VerificationError::InvalidMessage)?;
+            self.verify_solana(&message)
+        } else {
+            Err(VerificationError::InvalidMessage(anyhow::anyhow!(
+                "unknown magic: {magic}"
+            )))
+        }
+    }
+
+    /// Verifies the signed message of a JSON stream update, preferring the Solana message if
+    /// the subscription requested both.
+    pub fn verify_json_update(
+        &self,
+        update: &JsonUpdate,
+    ) -> Result<VerifiedUpdate, VerificationError> {
+        let data = update
+            .solana
+            .as_ref()
+            .or(update.evm.as_ref())
+            .ok_or(VerificationError::NoSignedMessage)?;
+        self.verify(&decode_json_binary(data).map_err(VerificationError::InvalidMessage)?)
+    }
+
+    /// Verifies the signed message of a binary stream update, preferring the Solana message if
+    /// the subscription requested both.
+    pub fn verify_binary_update(
+        &self,
+        update: &BinaryUpdate,
+    ) -> Result<VerifiedUpdate, VerificationError> {
+        match (&update.solana, &update.evm) {
+            (Some(message), _) => self.verify_solana(message),
+            (None, Some(message)) => self.verify_evm(message),
+            (None, None) => Err(VerificationError::NoSignedMessage),
+        }
+    }
+}
+
+fn parse_payload(payload: anyhow::Result<PayloadData>) -> Result<PriceUpdate, VerificationError> {
+    payload
+        .and_then(PriceUpdate::try_from)
+        .map_err(VerificationError::InvalidMessage)
+}
+
+/// Decodes the binary data of a JSON stream update.
+pub fn decode_json_binary(data: &JsonBinaryData) -> anyhow::Result<Vec<u8>> {
+    Ok(match data.encoding {
+        JsonBinaryEncoding::Base64 => {
+            base64::engine::general_purpose::STANDARD.decode(&data.data)?
+        }
+        JsonBinaryEncoding::Hex => hex::decode(&data.data)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::protocol::{
+            payload::AggregatedPriceFeedData,
+            router::{Channel, Price, PriceFeedId, PriceFeedProperty, TimestampUs},
+        },
+        byteorder::{BE, LE},
+        ed25519_dalek::Signer as _,
+    };
+
+    fn payload_data() -> PayloadData {
+        PayloadData::new(
+            TimestampUs(1_700_000_000_000_000),
+            Channel::RealTime.id(),
+            &[(
+                PriceFeedId(1),
+                AggregatedPriceFeedData {
+                    price: Some(Price::new(6_500_000_000_000).unwrap()),
+                    best_bid_price: None,
+                    best_ask_price: None,
+                },
+            )],
+            &[PriceFeedProperty::Price],
+        )
+    }
+
+    fn solana_keypair() -> ed25519_dalek::Keypair {
+        let secret = ed25519_dalek::SecretKey::from_bytes(&[1; 32]).unwrap();
+        let public = ed25519_dalek::PublicKey::from(&secret);
+        ed25519_dalek::Keypair { secret, public }
+    }
+
+    fn solana_message() -> SolanaMessage {
+        let keypair = solana_keypair();
+        let mut payload = Vec::new();
+        payload_data().serialize::<LE>(&mut payload).unwrap();
+        SolanaMessage {
+            signature: keypair.sign(&payload).to_bytes(),
+            public_key: keypair.public.to_bytes(),
+            payload,
+        }
+    }
+
+    fn evm_secret_key() -> libsecp256k1::SecretKey {
+        libsecp256k1::SecretKey::parse(&[2; 32]).unwrap()
+    }
+
+    fn evm_address() -> [u8; 20] {
+        let public_key = libsecp256k1::PublicKey::from_secret_key(&evm_secret_key());
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&Keccak256::digest(&public_key.serialize()[1..])[12..]);
+        address
+    }
+
+    fn evm_message() -> EvmMessage {
+        let mut payload = Vec::new();
+        payload_data().serialize::<BE>(&mut payload).unwrap();
+        let hash = Keccak256::digest(&payload);
+        let (signature, recovery_id) = libsecp256k1::sign(
+            &libsecp256k1::Message::parse(&hash.into()),
+            &evm_secret_key(),
+        );
+        EvmMessage {
+            payload,
+            signature: signature.serialize(),
+            recovery_id: recovery_id.serialize(),
+        }
+    }
+
+    fn verifier() -> Verifier {
+        Verifier::new([
+            Signer::Solana(solana_keypair().public.to_bytes()),
+            Signer::Evm(evm_address()),
+        ])
+    }
+
+    #[test]
+    fn test_verify_solana() {
+        let message = solana_message();
+        let verified = verifier().verify_solana(&message).unwrap();
+        assert_eq!(
+            verified.signer,
+            Signer::Solana(solana_keypair().public.to_bytes())
+        );
+        assert_eq!(
+            verified.update,
+            PriceUpdate::try_from(payload_data()).unwrap()
+        );
+
+        let mut data = Vec::new();
+        message.serialize(&mut data).unwrap();
+        assert_eq!(verifier().verify(&data).unwrap(), verified);
+    }
+
+    #[test]
+    fn test_verify_evm() {
+        let message = evm_message();
+        let verified = verifier().verify_evm(&message).unwrap();
+        assert_eq!(verified.signer, Signer::Evm(evm_address()));
+        assert_eq!(
+            verified.update,
+            PriceUpdate::try_from(payload_data()).unwrap()
+        );
+
+        let mut data = Vec::new();
+        message.serialize(&mut data).unwrap();
+        assert_eq!(verifier().verify(&data).unwrap(), verified);
+    }
+
+    #[test]
+    fn test_reject_invalid_signature() {
+        let mut message = solana_message();
+        message.payload[10] ^= 1;
+        assert!(matches!(
+            verifier().verify_solana(&message),
+            Err(VerificationError::InvalidSignature)
+        ));
+
+        // A modified EVM payload recovers to a different address
+        let mut message = evm_message();
+        message.payload[10] ^= 1;
+        assert!(matches!(
+            verifier().verify_evm(&message),
+            Err(VerificationError::UntrustedSigner(_))
+        ));
+    }
+
+    #[test]
+    fn test_reject_untrusted_signer() {
+        let verifier = Verifier::new([Signer::Evm(evm_address())]);
+        assert!(matches!(
+            verifier.verify_solana(&solana_message()),
+            Err(VerificationError::UntrustedSigner(Signer::Solana(_)))
+        ));
+    }
+
+    #[test]
+    fn test_verify_json_update() {
+        let mut data = Vec::new();
+        evm_message().serialize(&mut data).unwrap();
+        let update = JsonUpdate {
+            parsed: None,
+            evm: Some(JsonBinaryData {
+                encoding: JsonBinaryEncoding::Hex,
+                data: hex::encode(&data),
+            }),
+            solana: None,
+        };
+        let verified = verifier().verify_json_update(&update).unwrap();
+        assert_eq!(verified.signer, Signer::Evm(evm_address()));
+
+        let update = JsonUpdate {
+            evm: Some(JsonBinaryData {
+                encoding: JsonBinaryEncoding::Base64,
+                data: base64::engine::general_purpose::STANDARD.encode(&data),
+            }),
+            ..update
+        };
+        assert_eq!(verifier().verify_json_update(&update).unwrap(), verified);
+
+        let update = JsonUpdate {
+            evm: None,
+            ..update
+        };
+        assert!(matches!(
+            verifier().verify_json_update(&update),
+            Err(VerificationError::NoSignedMessage)
+        ));
+    }
+}
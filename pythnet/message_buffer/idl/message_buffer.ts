@@ -130,6 +130,139 @@ export type MessageBuffer = {
         }
       ];
     },
+    {
+      name: "migrateWhitelist";
+      docs: [
+        "Grows a whitelist created before `pending_admin` was added to the",
+        "current size. Until it is migrated, such a whitelist can't be grown to",
+        "hold 32 allowed programs, and one that already holds 32 can't be",
+        "loaded by any other instruction, so the old layout is read by hand.",
+        "Does nothing if the whitelist already has the current size."
+      ];
+      accounts: [
+        {
+          name: "admin";
+          isMut: false;
+          isSigner: true;
+        },
+        {
+          name: "payer";
+          isMut: true;
+          isSigner: true;
+          docs: ["Pays for the additional rent of the larger whitelist"];
+        },
+        {
+          name: "whitelist";
+          isMut: true;
+          isSigner: false;
+          docs: [
+            "CHECK: may still have the old layout, so the discriminator and the",
+            "admin are checked by hand"
+          ];
+          pda: {
+            seeds: [
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "whitelist";
+              }
+            ];
+          };
+        },
+        {
+          name: "systemProgram";
+          isMut: false;
+          isSigner: false;
+        }
+      ];
+      args: [];
+    },
+    {
+      name: "proposeWhitelistAdmin";
+      docs: [
+        "Proposes a new admin for the whitelist. The transfer only takes effect",
+        "once the new admin signs `accept_whitelist_admin`, so the admin can be",
+        "handed over to a PDA (e.g. the remote executor PDA of a governance",
+        "emitter) without the risk of transferring it to a key nobody controls.",
+        "Proposing again replaces any previously pending admin."
+      ];
+      accounts: [
+        {
+          name: "admin";
+          isMut: false;
+          isSigner: true;
+        },
+        {
+          name: "whitelist";
+          isMut: true;
+          isSigner: false;
+          docs: [
+            "Whitelists created before `pending_admin` was added must be migrated",
+            "with `migrate_whitelist` first"
+          ];
+          pda: {
+            seeds: [
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "whitelist";
+              }
+            ];
+          };
+          relations: ["admin"];
+        }
+      ];
+      args: [
+        {
+          name: "newAdmin";
+          type: "publicKey";
+        }
+      ];
+    },
+    {
+      name: "acceptWhitelistAdmin";
+      docs: [
+        "Completes the transfer started by `propose_whitelist_admin`. Must be",
+        "signed by the pending admin."
+      ];
+      accounts: [
+        {
+          name: "pendingAdmin";
+          isMut: false;
+          isSigner: true;
+        },
+        {
+          name: "whitelist";
+          isMut: true;
+          isSigner: false;
+          pda: {
+            seeds: [
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "whitelist";
+              }
+            ];
+          };
+        }
+      ];
+      args: [];
+    },
     {
       name: "putAll";
       docs: [
@@ -153,6 +286,9 @@ export type MessageBuffer = {
         "ix is independent of any previous invocations. It will overwrite",
         "any existing contents.",
         "",
+        "If the buffer's `MessageStats` account is passed after the",
+        "`message_buffer`, the messages that were written are counted in it.",
+        "",
         "TODO:",
         '- handle updates ("paging/batches of messages")',
         ""
@@ -214,6 +350,43 @@ export type MessageBuffer = {
               }
             ];
           };
+        },
+        {
+          name: "messageStats";
+          isMut: true;
+          isSigner: false;
+          isOptional: true;
+          docs: [
+            "Optional since callers that predate `MessageStats` only pass the",
+            "accounts above"
+          ];
+          pda: {
+            seeds: [
+              {
+                kind: "account";
+                type: {
+                  defined: "Signer<'info>";
+                };
+                account: "WhitelistVerifier";
+                path: "whitelist_verifier.cpi_caller_auth";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "base_account_key";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "stats";
+              }
+            ];
+          };
         }
       ];
       args: [
@@ -230,56 +403,136 @@ export type MessageBuffer = {
       ];
     },
     {
-      name: "createBuffer";
+      name: "putAllAsWriter";
       docs: [
-        "Initializes the buffer account with the `target_size`",
+        "Put messages into a `MessageBuffer` owned by another whitelisted",
+        "program. The signing `cpi_caller_auth` must be one of the buffer's",
+        "writers set by `set_buffer_writers`, and the messages must be within",
+        "that writer's limits. Like `put_all`, this overwrites any existing",
+        "contents of the buffer.",
         "",
-        "*`allowed_program_auth` - The whitelisted pubkey representing an",
-        "allowed program. Used as one of the seeds",
+        "*`allowed_program_auth` - The whitelisted pubkey that owns the",
+        "`MessageBuffer`. Used as one of the seeds",
         "for deriving the `MessageBuffer` PDA.",
         "* `base_account_key`    - Pubkey of the original account the",
         "`MessageBuffer` is derived from",
         "(e.g. pyth price account)",
-        "*`target_size`          - Initial size to allocate for the",
-        "`MessageBuffer` PDA. `target_size`",
-        "must be >= HEADER_LEN && <= 10240"
+        "* `messages`            - Vec of vec of bytes, each representing a message",
+        "to be hashed and accumulated"
       ];
       accounts: [
         {
-          name: "whitelist";
+          name: "whitelistVerifier";
+          accounts: [
+            {
+              name: "whitelist";
+              isMut: false;
+              isSigner: false;
+              pda: {
+                seeds: [
+                  {
+                    kind: "const";
+                    type: "string";
+                    value: "message";
+                  },
+                  {
+                    kind: "const";
+                    type: "string";
+                    value: "whitelist";
+                  }
+                ];
+              };
+            },
+            {
+              name: "cpiCallerAuth";
+              isMut: false;
+              isSigner: true;
+              docs: ["PDA representing authorized cpi caller"];
+            }
+          ];
+        },
+        {
+          name: "bufferWriters";
           isMut: false;
           isSigner: false;
           pda: {
             seeds: [
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "allowed_program_auth";
+              },
               {
                 kind: "const";
                 type: "string";
                 value: "message";
               },
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "base_account_key";
+              },
               {
                 kind: "const";
                 type: "string";
-                value: "whitelist";
+                value: "writers";
               }
             ];
           };
-          relations: ["admin"];
-        },
-        {
-          name: "admin";
-          isMut: false;
-          isSigner: true;
         },
         {
-          name: "payer";
+          name: "messageBuffer";
           isMut: true;
-          isSigner: true;
-          docs: ["pays for account initialization"];
+          isSigner: false;
+          pda: {
+            seeds: [
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "allowed_program_auth";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "base_account_key";
+              }
+            ];
+          };
         },
         {
-          name: "systemProgram";
-          isMut: false;
+          name: "messageStats";
+          isMut: true;
           isSigner: false;
+          isOptional: true;
+          pda: {
+            seeds: [
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "allowed_program_auth";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "base_account_key";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "stats";
+              }
+            ];
+          };
         }
       ];
       args: [
@@ -292,11 +545,139 @@ export type MessageBuffer = {
           type: "publicKey";
         },
         {
-          name: "targetSize";
-          type: "u32";
-        }
-      ];
-    },
+          name: "messages";
+          type: {
+            vec: "bytes";
+          };
+        }
+      ];
+    },
+    {
+      name: "putBatch";
+      docs: [
+        "Put messages into several `MessageBuffer`s with a single CPI. Each",
+        "entry of `batch` is written the same way as `put_all` would write it,",
+        "so callers that update many accounts at once (e.g. the oracle in",
+        "an aggregation slot) don't need one CPI per account.",
+        "",
+        "* `batch`               - The messages to write for each",
+        "`base_account_key`. The `MessageBuffer` of",
+        "every entry must be passed in the remaining",
+        "accounts, in the same order.",
+        "",
+        "`MessageStats` accounts are not updated by this ix."
+      ];
+      accounts: [
+        {
+          name: "whitelistVerifier";
+          accounts: [
+            {
+              name: "whitelist";
+              isMut: false;
+              isSigner: false;
+              pda: {
+                seeds: [
+                  {
+                    kind: "const";
+                    type: "string";
+                    value: "message";
+                  },
+                  {
+                    kind: "const";
+                    type: "string";
+                    value: "whitelist";
+                  }
+                ];
+              };
+            },
+            {
+              name: "cpiCallerAuth";
+              isMut: false;
+              isSigner: true;
+              docs: ["PDA representing authorized cpi caller"];
+            }
+          ];
+        }
+      ];
+      args: [
+        {
+          name: "batch";
+          type: {
+            vec: {
+              defined: "BufferMessages";
+            };
+          };
+        }
+      ];
+    },
+    {
+      name: "createBuffer";
+      docs: [
+        "Initializes the buffer account with the `target_size`",
+        "",
+        "*`allowed_program_auth` - The whitelisted pubkey representing an",
+        "allowed program. Used as one of the seeds",
+        "for deriving the `MessageBuffer` PDA.",
+        "* `base_account_key`    - Pubkey of the original account the",
+        "`MessageBuffer` is derived from",
+        "(e.g. pyth price account)",
+        "*`target_size`          - Initial size to allocate for the",
+        "`MessageBuffer` PDA. `target_size`",
+        "must be >= HEADER_LEN && <= 10240"
+      ];
+      accounts: [
+        {
+          name: "whitelist";
+          isMut: false;
+          isSigner: false;
+          pda: {
+            seeds: [
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "whitelist";
+              }
+            ];
+          };
+          relations: ["admin"];
+        },
+        {
+          name: "admin";
+          isMut: false;
+          isSigner: true;
+        },
+        {
+          name: "payer";
+          isMut: true;
+          isSigner: true;
+          docs: ["pays for account initialization"];
+        },
+        {
+          name: "systemProgram";
+          isMut: false;
+          isSigner: false;
+        }
+      ];
+      args: [
+        {
+          name: "allowedProgramAuth";
+          type: "publicKey";
+        },
+        {
+          name: "baseAccountKey";
+          type: "publicKey";
+        },
+        {
+          name: "targetSize";
+          type: "u32";
+        }
+      ];
+    },
     {
       name: "resizeBuffer";
       docs: [
@@ -398,11 +779,91 @@ export type MessageBuffer = {
         }
       ];
     },
+    {
+      name: "migrateBuffer";
+      docs: [
+        "Migrates the header of the buffer account to the current layout",
+        "version. Buffers that were written by a previous version of the",
+        "program are rejected by `put_all` until they are migrated.",
+        "",
+        "If the new layout needs a larger header, the buffer must be resized",
+        "with `resize_buffer` first.",
+        "",
+        "*`allowed_program_auth` - The whitelisted pubkey representing an",
+        "allowed program. Used as one of the seeds",
+        "for deriving the `MessageBuffer` PDA.",
+        "* `base_account_key`    - Pubkey of the original account the",
+        "`MessageBuffer` is derived from",
+        "(e.g. pyth price account)"
+      ];
+      accounts: [
+        {
+          name: "whitelist";
+          isMut: false;
+          isSigner: false;
+          pda: {
+            seeds: [
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "whitelist";
+              }
+            ];
+          };
+          relations: ["admin"];
+        },
+        {
+          name: "admin";
+          isMut: false;
+          isSigner: true;
+        },
+        {
+          name: "messageBuffer";
+          isMut: true;
+          isSigner: false;
+          pda: {
+            seeds: [
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "allowed_program_auth";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "base_account_key";
+              }
+            ];
+          };
+        }
+      ];
+      args: [
+        {
+          name: "allowedProgramAuth";
+          type: "publicKey";
+        },
+        {
+          name: "baseAccountKey";
+          type: "publicKey";
+        }
+      ];
+    },
     {
       name: "deleteBuffer";
       docs: [
-        "Closes the buffer account and transfers the remaining lamports to the",
-        "`admin` account",
+        "Closes the buffer account, its `BufferWriters` account if it exists",
+        "and its `MessageStats` account if passed, and transfers the remaining",
+        "lamports to the `admin` account",
         "",
         "*`allowed_program_auth` - The whitelisted pubkey representing an",
         "allowed program. Used as one of the seeds",
@@ -466,6 +927,75 @@ export type MessageBuffer = {
               }
             ];
           };
+        },
+        {
+          name: "bufferWriters";
+          isMut: true;
+          isSigner: false;
+          docs: [
+            "The `BufferWriters` of the buffer. Always required so that a buffer",
+            "recreated at the same address doesn't inherit the writers of the",
+            "deleted one; it is closed along with the buffer if it exists.",
+            "CHECK: the address is checked by the seeds, and the account is only",
+            "loaded as `BufferWriters` if it is initialized"
+          ];
+          pda: {
+            seeds: [
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "allowed_program_auth";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "base_account_key";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "writers";
+              }
+            ];
+          };
+        },
+        {
+          name: "messageStats";
+          isMut: true;
+          isSigner: false;
+          isOptional: true;
+          docs: [
+            "The `MessageStats` of the buffer, if any, is closed along with it"
+          ];
+          pda: {
+            seeds: [
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "allowed_program_auth";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "base_account_key";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "stats";
+              }
+            ];
+          };
         }
       ];
       args: [
@@ -478,11 +1008,285 @@ export type MessageBuffer = {
           type: "publicKey";
         }
       ];
-    }
-  ];
-  accounts: [
+    },
     {
-      name: "messageBuffer";
+      name: "setBufferWriters";
+      docs: [
+        "Sets the additional writers that are allowed to put messages into",
+        "the buffer account, along with their per-writer limits. The",
+        "`BufferWriters` account is created on first use.",
+        "",
+        "*`allowed_program_auth` - The whitelisted pubkey that owns the",
+        "`MessageBuffer`. Used as one of the seeds",
+        "for deriving the `MessageBuffer` PDA.",
+        "* `base_account_key`    - Pubkey of the original account the",
+        "`MessageBuffer` is derived from",
+        "(e.g. pyth price account)",
+        "* `writers`             - Entire list of additional writers. Every",
+        "writer must also be in the whitelist."
+      ];
+      accounts: [
+        {
+          name: "whitelist";
+          isMut: false;
+          isSigner: false;
+          pda: {
+            seeds: [
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "whitelist";
+              }
+            ];
+          };
+          relations: ["admin"];
+        },
+        {
+          name: "admin";
+          isMut: false;
+          isSigner: true;
+        },
+        {
+          name: "payer";
+          isMut: true;
+          isSigner: true;
+          docs: ["Pays for the initialization of the `BufferWriters` account"];
+        },
+        {
+          name: "systemProgram";
+          isMut: false;
+          isSigner: false;
+        },
+        {
+          name: "messageBuffer";
+          isMut: false;
+          isSigner: false;
+          docs: ["The writers can only be set for an existing buffer"];
+          pda: {
+            seeds: [
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "allowed_program_auth";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "base_account_key";
+              }
+            ];
+          };
+        },
+        {
+          name: "bufferWriters";
+          isMut: true;
+          isSigner: false;
+          pda: {
+            seeds: [
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "allowed_program_auth";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "base_account_key";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "writers";
+              }
+            ];
+          };
+        }
+      ];
+      args: [
+        {
+          name: "allowedProgramAuth";
+          type: "publicKey";
+        },
+        {
+          name: "baseAccountKey";
+          type: "publicKey";
+        },
+        {
+          name: "writers";
+          type: {
+            vec: {
+              defined: "BufferWriter";
+            };
+          };
+        }
+      ];
+    },
+    {
+      name: "createMessageStats";
+      docs: [
+        "Creates the `MessageStats` account of the buffer. Once created, it",
+        "can be passed to `put_all` and `put_all_as_writer` (after the",
+        "`message_buffer` account) to count the messages written to the",
+        "buffer per message type.",
+        "",
+        "*`allowed_program_auth` - The whitelisted pubkey that owns the",
+        "`MessageBuffer`. Used as one of the seeds",
+        "for deriving the `MessageBuffer` PDA.",
+        "* `base_account_key`    - Pubkey of the original account the",
+        "`MessageBuffer` is derived from",
+        "(e.g. pyth price account)"
+      ];
+      accounts: [
+        {
+          name: "whitelist";
+          isMut: false;
+          isSigner: false;
+          pda: {
+            seeds: [
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "whitelist";
+              }
+            ];
+          };
+          relations: ["admin"];
+        },
+        {
+          name: "admin";
+          isMut: false;
+          isSigner: true;
+        },
+        {
+          name: "payer";
+          isMut: true;
+          isSigner: true;
+          docs: ["Pays for the initialization of the `MessageStats` account"];
+        },
+        {
+          name: "systemProgram";
+          isMut: false;
+          isSigner: false;
+        },
+        {
+          name: "messageBuffer";
+          isMut: false;
+          isSigner: false;
+          docs: ["Stats can only be created for an existing buffer"];
+          pda: {
+            seeds: [
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "allowed_program_auth";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "base_account_key";
+              }
+            ];
+          };
+        },
+        {
+          name: "messageStats";
+          isMut: true;
+          isSigner: false;
+          pda: {
+            seeds: [
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "allowed_program_auth";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "message";
+              },
+              {
+                kind: "arg";
+                type: "publicKey";
+                path: "base_account_key";
+              },
+              {
+                kind: "const";
+                type: "string";
+                value: "stats";
+              }
+            ];
+          };
+        }
+      ];
+      args: [
+        {
+          name: "allowedProgramAuth";
+          type: "publicKey";
+        },
+        {
+          name: "baseAccountKey";
+          type: "publicKey";
+        }
+      ];
+    }
+  ];
+  accounts: [
+    {
+      name: "bufferWriters";
+      docs: [
+        "Additional writers allowed to put messages into a single `MessageBuffer`.",
+        "",
+        "A `MessageBuffer` PDA is derived from the whitelisted `allowed_program_auth`",
+        "that owns it. This account lets the admin allow other whitelisted",
+        "authorities to write into the same buffer, each with its own limits.",
+        "",
+        "PDA seeds: `[allowed_program_auth, MESSAGE, base_account_key, WRITERS]`"
+      ];
+      type: {
+        kind: "struct";
+        fields: [
+          {
+            name: "bump";
+            type: "u8";
+          },
+          {
+            name: "writers";
+            type: {
+              vec: {
+                defined: "BufferWriter";
+              };
+            };
+          }
+        ];
+      };
+    },
+    {
+      name: "messageBuffer";
       docs: [
         "A MessageBuffer will have the following structure",
         "```ignore",
@@ -539,7 +1343,17 @@ export type MessageBuffer = {
       };
     },
     {
-      name: "whitelist";
+      name: "messageStats";
+      docs: [
+        "Write counters of a single `MessageBuffer`, kept per message type so",
+        "monitoring can tell when a publisher pipeline stops producing a type",
+        "of message without reading the raw buffer.",
+        "",
+        "The message type is the first byte of each message. Messages with a",
+        "type `>= MAX_MESSAGE_TYPES` and empty messages are not counted.",
+        "",
+        "PDA seeds: `[allowed_program_auth, MESSAGE, base_account_key, STATS]`"
+      ];
       type: {
         kind: "struct";
         fields: [
@@ -548,19 +1362,134 @@ export type MessageBuffer = {
             type: "u8";
           },
           {
-            name: "admin";
-            type: "publicKey";
-          },
-          {
-            name: "allowedPrograms";
+            name: "types";
             type: {
-              vec: "publicKey";
+              array: [
+                {
+                  defined: "MessageTypeStats";
+                },
+                8
+              ];
             };
           }
         ];
       };
-    }
-  ];
+    },
+    {
+      name: "whitelist";
+      type: {
+        kind: "struct";
+        fields: [
+          {
+            name: "bump";
+            type: "u8";
+          },
+          {
+            name: "admin";
+            type: "publicKey";
+          },
+          {
+            name: "allowedPrograms";
+            type: {
+              vec: "publicKey";
+            };
+          },
+          {
+            name: "pendingAdmin";
+            docs: [
+              "Admin proposed by `propose_whitelist_admin`. Only becomes the admin",
+              "once it signs `accept_whitelist_admin`."
+            ];
+            type: {
+              option: "publicKey";
+            };
+          }
+        ];
+      };
+    }
+  ];
+  types: [
+    {
+      name: "BufferWriter";
+      type: {
+        kind: "struct";
+        fields: [
+          {
+            name: "auth";
+            docs: [
+              "Pubkey representing the writer program. Must also be in the whitelist."
+            ];
+            type: "publicKey";
+          },
+          {
+            name: "maxMessages";
+            docs: [
+              "Maximum number of messages the writer can put in a single call"
+            ];
+            type: "u8";
+          },
+          {
+            name: "maxBytes";
+            docs: [
+              "Maximum total length in bytes of the messages the writer can put",
+              "in a single call"
+            ];
+            type: "u16";
+          }
+        ];
+      };
+    },
+    {
+      name: "MessageTypeStats";
+      type: {
+        kind: "struct";
+        fields: [
+          {
+            name: "messagesWritten";
+            docs: [
+              "Total number of messages of this type written to the buffer"
+            ];
+            type: "u64";
+          },
+          {
+            name: "bytesWritten";
+            docs: [
+              "Total length in bytes of the messages of this type written to the buffer"
+            ];
+            type: "u64";
+          },
+          {
+            name: "lastWriteSlot";
+            docs: [
+              "Slot of the last write that contained a message of this type"
+            ];
+            type: "u64";
+          }
+        ];
+      };
+    },
+    {
+      name: "BufferMessages";
+      docs: [
+        "Messages to put into the `MessageBuffer` derived from `base_account_key`"
+      ];
+      type: {
+        kind: "struct";
+        fields: [
+          {
+            name: "baseAccountKey";
+            type: "publicKey";
+          },
+          {
+            name: "messages";
+            type: {
+              vec: "bytes";
+            };
+          }
+        ];
+      };
+    }
+  ];
   errors: [
     {
       code: 6000;
@@ -596,6 +1525,31 @@ export type MessageBuffer = {
       code: 6006;
       name: "TargetSizeExceedsMaxLen";
       msg: "Target size exceeds MessageBuffer::MAX_LEN";
+    },
+    {
+      code: 6007;
+      name: "InvalidBufferWriter";
+      msg: "Invalid buffer writer";
+    },
+    {
+      code: 6008;
+      name: "MaximumBufferWritersExceeded";
+      msg: "Maximum number of buffer writers exceeded";
+    },
+    {
+      code: 6009;
+      name: "WriterLimitExceeded";
+      msg: "Messages exceed the limits of the buffer writer";
+    },
+    {
+      code: 6010;
+      name: "InvalidPendingAdmin";
+      msg: "Signer is not the pending whitelist admin";
+    },
+    {
+      code: 6011;
+      name: "BufferVersionMismatch";
+      msg: "Message Buffer version does not match the program version";
     }
   ];
 };
@@ -615,59 +1569,793 @@ export const IDL: MessageBuffer = {
           name: "admin",
           isMut: false,
           isSigner: true,
-          docs: [
-            "Admin that can update the whitelist and create/resize/delete buffers",
-          ],
+          docs: [
+            "Admin that can update the whitelist and create/resize/delete buffers",
+          ],
+        },
+        {
+          name: "payer",
+          isMut: true,
+          isSigner: true,
+        },
+        {
+          name: "whitelist",
+          isMut: true,
+          isSigner: false,
+          pda: {
+            seeds: [
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "whitelist",
+              },
+            ],
+          },
+        },
+        {
+          name: "systemProgram",
+          isMut: false,
+          isSigner: false,
+        },
+      ],
+      args: [],
+    },
+    {
+      name: "setAllowedPrograms",
+      docs: [
+        "Sets the programs that are allowed to invoke this program through CPI",
+        "",
+        "* `allowed_programs` - Entire list of programs that are allowed to",
+        "invoke this program through CPI",
+      ],
+      accounts: [
+        {
+          name: "admin",
+          isMut: false,
+          isSigner: true,
+        },
+        {
+          name: "whitelist",
+          isMut: true,
+          isSigner: false,
+          pda: {
+            seeds: [
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "whitelist",
+              },
+            ],
+          },
+          relations: ["admin"],
+        },
+      ],
+      args: [
+        {
+          name: "allowedPrograms",
+          type: {
+            vec: "publicKey",
+          },
+        },
+      ],
+    },
+    {
+      name: "updateWhitelistAdmin",
+      docs: ["Sets the new admin for the whitelist"],
+      accounts: [
+        {
+          name: "admin",
+          isMut: false,
+          isSigner: true,
+        },
+        {
+          name: "whitelist",
+          isMut: true,
+          isSigner: false,
+          pda: {
+            seeds: [
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "whitelist",
+              },
+            ],
+          },
+          relations: ["admin"],
+        },
+      ],
+      args: [
+        {
+          name: "newAdmin",
+          type: "publicKey",
+        },
+      ],
+    },
+    {
+      name: "migrateWhitelist",
+      docs: [
+        "Grows a whitelist created before `pending_admin` was added to the",
+        "current size. Until it is migrated, such a whitelist can't be grown to",
+        "hold 32 allowed programs, and one that already holds 32 can't be",
+        "loaded by any other instruction, so the old layout is read by hand.",
+        "Does nothing if the whitelist already has the current size.",
+      ],
+      accounts: [
+        {
+          name: "admin",
+          isMut: false,
+          isSigner: true,
+        },
+        {
+          name: "payer",
+          isMut: true,
+          isSigner: true,
+          docs: ["Pays for the additional rent of the larger whitelist"],
+        },
+        {
+          name: "whitelist",
+          isMut: true,
+          isSigner: false,
+          docs: [
+            "CHECK: may still have the old layout, so the discriminator and the",
+            "admin are checked by hand",
+          ],
+          pda: {
+            seeds: [
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "whitelist",
+              },
+            ],
+          },
+        },
+        {
+          name: "systemProgram",
+          isMut: false,
+          isSigner: false,
+        },
+      ],
+      args: [],
+    },
+    {
+      name: "proposeWhitelistAdmin",
+      docs: [
+        "Proposes a new admin for the whitelist. The transfer only takes effect",
+        "once the new admin signs `accept_whitelist_admin`, so the admin can be",
+        "handed over to a PDA (e.g. the remote executor PDA of a governance",
+        "emitter) without the risk of transferring it to a key nobody controls.",
+        "Proposing again replaces any previously pending admin.",
+      ],
+      accounts: [
+        {
+          name: "admin",
+          isMut: false,
+          isSigner: true,
+        },
+        {
+          name: "whitelist",
+          isMut: true,
+          isSigner: false,
+          docs: [
+            "Whitelists created before `pending_admin` was added must be migrated",
+            "with `migrate_whitelist` first",
+          ],
+          pda: {
+            seeds: [
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "whitelist",
+              },
+            ],
+          },
+          relations: ["admin"],
+        },
+      ],
+      args: [
+        {
+          name: "newAdmin",
+          type: "publicKey",
+        },
+      ],
+    },
+    {
+      name: "acceptWhitelistAdmin",
+      docs: [
+        "Completes the transfer started by `propose_whitelist_admin`. Must be",
+        "signed by the pending admin.",
+      ],
+      accounts: [
+        {
+          name: "pendingAdmin",
+          isMut: false,
+          isSigner: true,
+        },
+        {
+          name: "whitelist",
+          isMut: true,
+          isSigner: false,
+          pda: {
+            seeds: [
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "whitelist",
+              },
+            ],
+          },
+        },
+      ],
+      args: [],
+    },
+    {
+      name: "putAll",
+      docs: [
+        "Put messages into the Accumulator. All messages put for the same",
+        "`base_account_key` go into the same buffer PDA. The PDA's address is",
+        "`[allowed_program_auth, MESSAGE, base_account_key]`, where `allowed_program_auth`",
+        "is the whitelisted pubkey who authorized this call.",
+        "",
+        "* `base_account_key`    - Pubkey of the original account the",
+        "`MessageBuffer` is derived from",
+        "(e.g. pyth price account)",
+        "* `messages`            - Vec of vec of bytes, each representing a message",
+        "to be hashed and accumulated",
+        "",
+        "This ix will write as many of the messages up to the length",
+        "of the `accumulator_input.data`.",
+        "If `accumulator_input.data.len() < messages.map(|x| x.len()).sum()`",
+        "then the remaining messages will be ignored.",
+        "",
+        "The current implementation assumes that each invocation of this",
+        "ix is independent of any previous invocations. It will overwrite",
+        "any existing contents.",
+        "",
+        "If the buffer's `MessageStats` account is passed after the",
+        "`message_buffer`, the messages that were written are counted in it.",
+        "",
+        "TODO:",
+        '- handle updates ("paging/batches of messages")',
+        "",
+      ],
+      accounts: [
+        {
+          name: "whitelistVerifier",
+          accounts: [
+            {
+              name: "whitelist",
+              isMut: false,
+              isSigner: false,
+              pda: {
+                seeds: [
+                  {
+                    kind: "const",
+                    type: "string",
+                    value: "message",
+                  },
+                  {
+                    kind: "const",
+                    type: "string",
+                    value: "whitelist",
+                  },
+                ],
+              },
+            },
+            {
+              name: "cpiCallerAuth",
+              isMut: false,
+              isSigner: true,
+              docs: ["PDA representing authorized cpi caller"],
+            },
+          ],
+        },
+        {
+          name: "messageBuffer",
+          isMut: true,
+          isSigner: false,
+          pda: {
+            seeds: [
+              {
+                kind: "account",
+                type: {
+                  defined: "Signer<'info>",
+                },
+                account: "WhitelistVerifier",
+                path: "whitelist_verifier.cpi_caller_auth",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "base_account_key",
+              },
+            ],
+          },
+        },
+        {
+          name: "messageStats",
+          isMut: true,
+          isSigner: false,
+          isOptional: true,
+          docs: [
+            "Optional since callers that predate `MessageStats` only pass the",
+            "accounts above",
+          ],
+          pda: {
+            seeds: [
+              {
+                kind: "account",
+                type: {
+                  defined: "Signer<'info>",
+                },
+                account: "WhitelistVerifier",
+                path: "whitelist_verifier.cpi_caller_auth",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "base_account_key",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "stats",
+              },
+            ],
+          },
+        },
+      ],
+      args: [
+        {
+          name: "baseAccountKey",
+          type: "publicKey",
+        },
+        {
+          name: "messages",
+          type: {
+            vec: "bytes",
+          },
+        },
+      ],
+    },
+    {
+      name: "putAllAsWriter",
+      docs: [
+        "Put messages into a `MessageBuffer` owned by another whitelisted",
+        "program. The signing `cpi_caller_auth` must be one of the buffer's",
+        "writers set by `set_buffer_writers`, and the messages must be within",
+        "that writer's limits. Like `put_all`, this overwrites any existing",
+        "contents of the buffer.",
+        "",
+        "*`allowed_program_auth` - The whitelisted pubkey that owns the",
+        "`MessageBuffer`. Used as one of the seeds",
+        "for deriving the `MessageBuffer` PDA.",
+        "* `base_account_key`    - Pubkey of the original account the",
+        "`MessageBuffer` is derived from",
+        "(e.g. pyth price account)",
+        "* `messages`            - Vec of vec of bytes, each representing a message",
+        "to be hashed and accumulated",
+      ],
+      accounts: [
+        {
+          name: "whitelistVerifier",
+          accounts: [
+            {
+              name: "whitelist",
+              isMut: false,
+              isSigner: false,
+              pda: {
+                seeds: [
+                  {
+                    kind: "const",
+                    type: "string",
+                    value: "message",
+                  },
+                  {
+                    kind: "const",
+                    type: "string",
+                    value: "whitelist",
+                  },
+                ],
+              },
+            },
+            {
+              name: "cpiCallerAuth",
+              isMut: false,
+              isSigner: true,
+              docs: ["PDA representing authorized cpi caller"],
+            },
+          ],
+        },
+        {
+          name: "bufferWriters",
+          isMut: false,
+          isSigner: false,
+          pda: {
+            seeds: [
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "allowed_program_auth",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "base_account_key",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "writers",
+              },
+            ],
+          },
+        },
+        {
+          name: "messageBuffer",
+          isMut: true,
+          isSigner: false,
+          pda: {
+            seeds: [
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "allowed_program_auth",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "base_account_key",
+              },
+            ],
+          },
+        },
+        {
+          name: "messageStats",
+          isMut: true,
+          isSigner: false,
+          isOptional: true,
+          pda: {
+            seeds: [
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "allowed_program_auth",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "base_account_key",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "stats",
+              },
+            ],
+          },
+        },
+      ],
+      args: [
+        {
+          name: "allowedProgramAuth",
+          type: "publicKey",
+        },
+        {
+          name: "baseAccountKey",
+          type: "publicKey",
+        },
+        {
+          name: "messages",
+          type: {
+            vec: "bytes",
+          },
+        },
+      ],
+    },
+    {
+      name: "putBatch",
+      docs: [
+        "Put messages into several `MessageBuffer`s with a single CPI. Each",
+        "entry of `batch` is written the same way as `put_all` would write it,",
+        "so callers that update many accounts at once (e.g. the oracle in",
+        "an aggregation slot) don't need one CPI per account.",
+        "",
+        "* `batch`               - The messages to write for each",
+        "`base_account_key`. The `MessageBuffer` of",
+        "every entry must be passed in the remaining",
+        "accounts, in the same order.",
+        "",
+        "`MessageStats` accounts are not updated by this ix.",
+      ],
+      accounts: [
+        {
+          name: "whitelistVerifier",
+          accounts: [
+            {
+              name: "whitelist",
+              isMut: false,
+              isSigner: false,
+              pda: {
+                seeds: [
+                  {
+                    kind: "const",
+                    type: "string",
+                    value: "message",
+                  },
+                  {
+                    kind: "const",
+                    type: "string",
+                    value: "whitelist",
+                  },
+                ],
+              },
+            },
+            {
+              name: "cpiCallerAuth",
+              isMut: false,
+              isSigner: true,
+              docs: ["PDA representing authorized cpi caller"],
+            },
+          ],
+        },
+      ],
+      args: [
+        {
+          name: "batch",
+          type: {
+            vec: {
+              defined: "BufferMessages",
+            },
+          },
+        },
+      ],
+    },
+    {
+      name: "createBuffer",
+      docs: [
+        "Initializes the buffer account with the `target_size`",
+        "",
+        "*`allowed_program_auth` - The whitelisted pubkey representing an",
+        "allowed program. Used as one of the seeds",
+        "for deriving the `MessageBuffer` PDA.",
+        "* `base_account_key`    - Pubkey of the original account the",
+        "`MessageBuffer` is derived from",
+        "(e.g. pyth price account)",
+        "*`target_size`          - Initial size to allocate for the",
+        "`MessageBuffer` PDA. `target_size`",
+        "must be >= HEADER_LEN && <= 10240",
+      ],
+      accounts: [
+        {
+          name: "whitelist",
+          isMut: false,
+          isSigner: false,
+          pda: {
+            seeds: [
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "whitelist",
+              },
+            ],
+          },
+          relations: ["admin"],
+        },
+        {
+          name: "admin",
+          isMut: false,
+          isSigner: true,
+        },
+        {
+          name: "payer",
+          isMut: true,
+          isSigner: true,
+          docs: ["pays for account initialization"],
+        },
+        {
+          name: "systemProgram",
+          isMut: false,
+          isSigner: false,
+        },
+      ],
+      args: [
+        {
+          name: "allowedProgramAuth",
+          type: "publicKey",
+        },
+        {
+          name: "baseAccountKey",
+          type: "publicKey",
+        },
+        {
+          name: "targetSize",
+          type: "u32",
+        },
+      ],
+    },
+    {
+      name: "resizeBuffer",
+      docs: [
+        "Resizes the buffer account to the `target_size`",
+        "",
+        "*`allowed_program_auth` - The whitelisted pubkey representing an",
+        "allowed program. Used as one of the seeds",
+        "for deriving the `MessageBuffer` PDA.",
+        "* `base_account_key`    - Pubkey of the original account the",
+        "`MessageBuffer` is derived from",
+        "(e.g. pyth price account)",
+        "*`target_size`          -  Size to re-allocate for the",
+        "`MessageBuffer` PDA. If increasing the size,",
+        "max delta of current_size & target_size is 10240",
+      ],
+      accounts: [
+        {
+          name: "whitelist",
+          isMut: false,
+          isSigner: false,
+          pda: {
+            seeds: [
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "whitelist",
+              },
+            ],
+          },
+          relations: ["admin"],
+        },
+        {
+          name: "admin",
+          isMut: false,
+          isSigner: true,
         },
         {
           name: "payer",
           isMut: true,
           isSigner: true,
+          docs: [
+            "Pays for any additional rent needed to increase the buffer size",
+          ],
         },
         {
-          name: "whitelist",
+          name: "systemProgram",
+          isMut: false,
+          isSigner: false,
+        },
+        {
+          name: "messageBuffer",
           isMut: true,
           isSigner: false,
+          docs: [
+            "If decreasing, Anchor will automatically check",
+            "if target_size is < MessageBuffer::INIT_SPACE + 8",
+            "and if so,then load() will fail.",
+            "If increasing, Anchor also automatically checks if target_size delta",
+            "exceeds MAX_PERMITTED_DATA_INCREASE",
+          ],
           pda: {
             seeds: [
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "allowed_program_auth",
+              },
               {
                 kind: "const",
                 type: "string",
                 value: "message",
               },
               {
-                kind: "const",
-                type: "string",
-                value: "whitelist",
+                kind: "arg",
+                type: "publicKey",
+                path: "base_account_key",
               },
             ],
           },
         },
+      ],
+      args: [
         {
-          name: "systemProgram",
-          isMut: false,
-          isSigner: false,
+          name: "allowedProgramAuth",
+          type: "publicKey",
+        },
+        {
+          name: "baseAccountKey",
+          type: "publicKey",
+        },
+        {
+          name: "targetSize",
+          type: "u32",
         },
       ],
-      args: [],
     },
     {
-      name: "setAllowedPrograms",
+      name: "migrateBuffer",
       docs: [
-        "Sets the programs that are allowed to invoke this program through CPI",
+        "Migrates the header of the buffer account to the current layout",
+        "version. Buffers that were written by a previous version of the",
+        "program are rejected by `put_all` until they are migrated.",
         "",
-        "* `allowed_programs` - Entire list of programs that are allowed to",
-        "invoke this program through CPI",
+        "If the new layout needs a larger header, the buffer must be resized",
+        "with `resize_buffer` first.",
+        "",
+        "*`allowed_program_auth` - The whitelisted pubkey representing an",
+        "allowed program. Used as one of the seeds",
+        "for deriving the `MessageBuffer` PDA.",
+        "* `base_account_key`    - Pubkey of the original account the",
+        "`MessageBuffer` is derived from",
+        "(e.g. pyth price account)",
       ],
       accounts: [
-        {
-          name: "admin",
-          isMut: false,
-          isSigner: true,
-        },
         {
           name: "whitelist",
-          isMut: true,
+          isMut: false,
           isSigner: false,
           pda: {
             seeds: [
@@ -685,110 +2373,92 @@ export const IDL: MessageBuffer = {
           },
           relations: ["admin"],
         },
-      ],
-      args: [
-        {
-          name: "allowedPrograms",
-          type: {
-            vec: "publicKey",
-          },
-        },
-      ],
-    },
-    {
-      name: "updateWhitelistAdmin",
-      docs: ["Sets the new admin for the whitelist"],
-      accounts: [
         {
           name: "admin",
           isMut: false,
           isSigner: true,
         },
         {
-          name: "whitelist",
+          name: "messageBuffer",
           isMut: true,
           isSigner: false,
           pda: {
             seeds: [
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "allowed_program_auth",
+              },
               {
                 kind: "const",
                 type: "string",
                 value: "message",
               },
               {
-                kind: "const",
-                type: "string",
-                value: "whitelist",
+                kind: "arg",
+                type: "publicKey",
+                path: "base_account_key",
               },
             ],
           },
-          relations: ["admin"],
         },
       ],
       args: [
         {
-          name: "newAdmin",
+          name: "allowedProgramAuth",
+          type: "publicKey",
+        },
+        {
+          name: "baseAccountKey",
           type: "publicKey",
         },
       ],
     },
     {
-      name: "putAll",
+      name: "deleteBuffer",
       docs: [
-        "Put messages into the Accumulator. All messages put for the same",
-        "`base_account_key` go into the same buffer PDA. The PDA's address is",
-        "`[allowed_program_auth, MESSAGE, base_account_key]`, where `allowed_program_auth`",
-        "is the whitelisted pubkey who authorized this call.",
+        "Closes the buffer account, its `BufferWriters` account if it exists",
+        "and its `MessageStats` account if passed, and transfers the remaining",
+        "lamports to the `admin` account",
         "",
+        "*`allowed_program_auth` - The whitelisted pubkey representing an",
+        "allowed program. Used as one of the seeds",
+        "for deriving the `MessageBuffer` PDA.",
         "* `base_account_key`    - Pubkey of the original account the",
         "`MessageBuffer` is derived from",
         "(e.g. pyth price account)",
-        "* `messages`            - Vec of vec of bytes, each representing a message",
-        "to be hashed and accumulated",
-        "",
-        "This ix will write as many of the messages up to the length",
-        "of the `accumulator_input.data`.",
-        "If `accumulator_input.data.len() < messages.map(|x| x.len()).sum()`",
-        "then the remaining messages will be ignored.",
-        "",
-        "The current implementation assumes that each invocation of this",
-        "ix is independent of any previous invocations. It will overwrite",
-        "any existing contents.",
-        "",
-        "TODO:",
-        '- handle updates ("paging/batches of messages")',
-        "",
       ],
       accounts: [
         {
-          name: "whitelistVerifier",
-          accounts: [
-            {
-              name: "whitelist",
-              isMut: false,
-              isSigner: false,
-              pda: {
-                seeds: [
-                  {
-                    kind: "const",
-                    type: "string",
-                    value: "message",
-                  },
-                  {
-                    kind: "const",
-                    type: "string",
-                    value: "whitelist",
-                  },
-                ],
+          name: "whitelist",
+          isMut: false,
+          isSigner: false,
+          pda: {
+            seeds: [
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
               },
-            },
-            {
-              name: "cpiCallerAuth",
-              isMut: false,
-              isSigner: true,
-              docs: ["PDA representing authorized cpi caller"],
-            },
-          ],
+              {
+                kind: "const",
+                type: "string",
+                value: "whitelist",
+              },
+            ],
+          },
+          relations: ["admin"],
+        },
+        {
+          name: "admin",
+          isMut: false,
+          isSigner: true,
+        },
+        {
+          name: "payer",
+          isMut: true,
+          isSigner: true,
+          docs: ["Recipient of the lamports from closing the buffer account"],
         },
         {
           name: "messageBuffer",
@@ -797,12 +2467,73 @@ export const IDL: MessageBuffer = {
           pda: {
             seeds: [
               {
-                kind: "account",
-                type: {
-                  defined: "Signer<'info>",
-                },
-                account: "WhitelistVerifier",
-                path: "whitelist_verifier.cpi_caller_auth",
+                kind: "arg",
+                type: "publicKey",
+                path: "allowed_program_auth",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "base_account_key",
+              },
+            ],
+          },
+        },
+        {
+          name: "bufferWriters",
+          isMut: true,
+          isSigner: false,
+          docs: [
+            "The `BufferWriters` of the buffer. Always required so that a buffer",
+            "recreated at the same address doesn't inherit the writers of the",
+            "deleted one; it is closed along with the buffer if it exists.",
+            "CHECK: the address is checked by the seeds, and the account is only",
+            "loaded as `BufferWriters` if it is initialized",
+          ],
+          pda: {
+            seeds: [
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "allowed_program_auth",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "base_account_key",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "writers",
+              },
+            ],
+          },
+        },
+        {
+          name: "messageStats",
+          isMut: true,
+          isSigner: false,
+          isOptional: true,
+          docs: [
+            "The `MessageStats` of the buffer, if any, is closed along with it",
+          ],
+          pda: {
+            seeds: [
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "allowed_program_auth",
               },
               {
                 kind: "const",
@@ -814,37 +2545,41 @@ export const IDL: MessageBuffer = {
                 type: "publicKey",
                 path: "base_account_key",
               },
+              {
+                kind: "const",
+                type: "string",
+                value: "stats",
+              },
             ],
           },
         },
       ],
       args: [
         {
-          name: "baseAccountKey",
+          name: "allowedProgramAuth",
           type: "publicKey",
         },
         {
-          name: "messages",
-          type: {
-            vec: "bytes",
-          },
+          name: "baseAccountKey",
+          type: "publicKey",
         },
       ],
     },
     {
-      name: "createBuffer",
+      name: "setBufferWriters",
       docs: [
-        "Initializes the buffer account with the `target_size`",
+        "Sets the additional writers that are allowed to put messages into",
+        "the buffer account, along with their per-writer limits. The",
+        "`BufferWriters` account is created on first use.",
         "",
-        "*`allowed_program_auth` - The whitelisted pubkey representing an",
-        "allowed program. Used as one of the seeds",
+        "*`allowed_program_auth` - The whitelisted pubkey that owns the",
+        "`MessageBuffer`. Used as one of the seeds",
         "for deriving the `MessageBuffer` PDA.",
         "* `base_account_key`    - Pubkey of the original account the",
         "`MessageBuffer` is derived from",
         "(e.g. pyth price account)",
-        "*`target_size`          - Initial size to allocate for the",
-        "`MessageBuffer` PDA. `target_size`",
-        "must be >= HEADER_LEN && <= 10240",
+        "* `writers`             - Entire list of additional writers. Every",
+        "writer must also be in the whitelist.",
       ],
       accounts: [
         {
@@ -876,94 +2611,42 @@ export const IDL: MessageBuffer = {
           name: "payer",
           isMut: true,
           isSigner: true,
-          docs: ["pays for account initialization"],
+          docs: ["Pays for the initialization of the `BufferWriters` account"],
         },
         {
           name: "systemProgram",
           isMut: false,
           isSigner: false,
         },
-      ],
-      args: [
-        {
-          name: "allowedProgramAuth",
-          type: "publicKey",
-        },
-        {
-          name: "baseAccountKey",
-          type: "publicKey",
-        },
-        {
-          name: "targetSize",
-          type: "u32",
-        },
-      ],
-    },
-    {
-      name: "resizeBuffer",
-      docs: [
-        "Resizes the buffer account to the `target_size`",
-        "",
-        "*`allowed_program_auth` - The whitelisted pubkey representing an",
-        "allowed program. Used as one of the seeds",
-        "for deriving the `MessageBuffer` PDA.",
-        "* `base_account_key`    - Pubkey of the original account the",
-        "`MessageBuffer` is derived from",
-        "(e.g. pyth price account)",
-        "*`target_size`          -  Size to re-allocate for the",
-        "`MessageBuffer` PDA. If increasing the size,",
-        "max delta of current_size & target_size is 10240",
-      ],
-      accounts: [
         {
-          name: "whitelist",
+          name: "messageBuffer",
           isMut: false,
           isSigner: false,
+          docs: ["The writers can only be set for an existing buffer"],
           pda: {
             seeds: [
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "allowed_program_auth",
+              },
               {
                 kind: "const",
                 type: "string",
                 value: "message",
               },
               {
-                kind: "const",
-                type: "string",
-                value: "whitelist",
+                kind: "arg",
+                type: "publicKey",
+                path: "base_account_key",
               },
             ],
           },
-          relations: ["admin"],
-        },
-        {
-          name: "admin",
-          isMut: false,
-          isSigner: true,
-        },
-        {
-          name: "payer",
-          isMut: true,
-          isSigner: true,
-          docs: [
-            "Pays for any additional rent needed to increase the buffer size",
-          ],
-        },
-        {
-          name: "systemProgram",
-          isMut: false,
-          isSigner: false,
         },
         {
-          name: "messageBuffer",
+          name: "bufferWriters",
           isMut: true,
           isSigner: false,
-          docs: [
-            "If decreasing, Anchor will automatically check",
-            "if target_size is < MessageBuffer::INIT_SPACE + 8",
-            "and if so,then load() will fail.",
-            "If increasing, Anchor also automatically checks if target_size delta",
-            "exceeds MAX_PERMITTED_DATA_INCREASE",
-          ],
           pda: {
             seeds: [
               {
@@ -981,6 +2664,11 @@ export const IDL: MessageBuffer = {
                 type: "publicKey",
                 path: "base_account_key",
               },
+              {
+                kind: "const",
+                type: "string",
+                value: "writers",
+              },
             ],
           },
         },
@@ -995,19 +2683,25 @@ export const IDL: MessageBuffer = {
           type: "publicKey",
         },
         {
-          name: "targetSize",
-          type: "u32",
+          name: "writers",
+          type: {
+            vec: {
+              defined: "BufferWriter",
+            },
+          },
         },
       ],
     },
     {
-      name: "deleteBuffer",
+      name: "createMessageStats",
       docs: [
-        "Closes the buffer account and transfers the remaining lamports to the",
-        "`admin` account",
+        "Creates the `MessageStats` account of the buffer. Once created, it",
+        "can be passed to `put_all` and `put_all_as_writer` (after the",
+        "`message_buffer` account) to count the messages written to the",
+        "buffer per message type.",
         "",
-        "*`allowed_program_auth` - The whitelisted pubkey representing an",
-        "allowed program. Used as one of the seeds",
+        "*`allowed_program_auth` - The whitelisted pubkey that owns the",
+        "`MessageBuffer`. Used as one of the seeds",
         "for deriving the `MessageBuffer` PDA.",
         "* `base_account_key`    - Pubkey of the original account the",
         "`MessageBuffer` is derived from",
@@ -1043,10 +2737,40 @@ export const IDL: MessageBuffer = {
           name: "payer",
           isMut: true,
           isSigner: true,
-          docs: ["Recipient of the lamports from closing the buffer account"],
+          docs: ["Pays for the initialization of the `MessageStats` account"],
+        },
+        {
+          name: "systemProgram",
+          isMut: false,
+          isSigner: false,
         },
         {
           name: "messageBuffer",
+          isMut: false,
+          isSigner: false,
+          docs: ["Stats can only be created for an existing buffer"],
+          pda: {
+            seeds: [
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "allowed_program_auth",
+              },
+              {
+                kind: "const",
+                type: "string",
+                value: "message",
+              },
+              {
+                kind: "arg",
+                type: "publicKey",
+                path: "base_account_key",
+              },
+            ],
+          },
+        },
+        {
+          name: "messageStats",
           isMut: true,
           isSigner: false,
           pda: {
@@ -1066,6 +2790,11 @@ export const IDL: MessageBuffer = {
                 type: "publicKey",
                 path: "base_account_key",
               },
+              {
+                kind: "const",
+                type: "string",
+                value: "stats",
+              },
             ],
           },
         },
@@ -1083,6 +2812,35 @@ export const IDL: MessageBuffer = {
     },
   ],
   accounts: [
+    {
+      name: "bufferWriters",
+      docs: [
+        "Additional writers allowed to put messages into a single `MessageBuffer`.",
+        "",
+        "A `MessageBuffer` PDA is derived from the whitelisted `allowed_program_auth`",
+        "that owns it. This account lets the admin allow other whitelisted",
+        "authorities to write into the same buffer, each with its own limits.",
+        "",
+        "PDA seeds: `[allowed_program_auth, MESSAGE, base_account_key, WRITERS]`",
+      ],
+      type: {
+        kind: "struct",
+        fields: [
+          {
+            name: "bump",
+            type: "u8",
+          },
+          {
+            name: "writers",
+            type: {
+              vec: {
+                defined: "BufferWriter",
+              },
+            },
+          },
+        ],
+      },
+    },
     {
       name: "messageBuffer",
       docs: [
@@ -1140,6 +2898,39 @@ export const IDL: MessageBuffer = {
         ],
       },
     },
+    {
+      name: "messageStats",
+      docs: [
+        "Write counters of a single `MessageBuffer`, kept per message type so",
+        "monitoring can tell when a publisher pipeline stops producing a type",
+        "of message without reading the raw buffer.",
+        "",
+        "The message type is the first byte of each message. Messages with a",
+        "type `>= MAX_MESSAGE_TYPES` and empty messages are not counted.",
+        "",
+        "PDA seeds: `[allowed_program_auth, MESSAGE, base_account_key, STATS]`",
+      ],
+      type: {
+        kind: "struct",
+        fields: [
+          {
+            name: "bump",
+            type: "u8",
+          },
+          {
+            name: "types",
+            type: {
+              array: [
+                {
+                  defined: "MessageTypeStats",
+                },
+                8,
+              ],
+            },
+          },
+        ],
+      },
+    },
     {
       name: "whitelist",
       type: {
@@ -1159,6 +2950,98 @@ export const IDL: MessageBuffer = {
               vec: "publicKey",
             },
           },
+          {
+            name: "pendingAdmin",
+            docs: [
+              "Admin proposed by `propose_whitelist_admin`. Only becomes the admin",
+              "once it signs `accept_whitelist_admin`.",
+            ],
+            type: {
+              option: "publicKey",
+            },
+          },
+        ],
+      },
+    },
+  ],
+  types: [
+    {
+      name: "BufferWriter",
+      type: {
+        kind: "struct",
+        fields: [
+          {
+            name: "auth",
+            docs: [
+              "Pubkey representing the writer program. Must also be in the whitelist.",
+            ],
+            type: "publicKey",
+          },
+          {
+            name: "maxMessages",
+            docs: [
+              "Maximum number of messages the writer can put in a single call",
+            ],
+            type: "u8",
+          },
+          {
+            name: "maxBytes",
+            docs: [
+              "Maximum total length in bytes of the messages the writer can put",
+              "in a single call",
+            ],
+            type: "u16",
+          },
+        ],
+      },
+    },
+    {
+      name: "MessageTypeStats",
+      type: {
+        kind: "struct",
+        fields: [
+          {
+            name: "messagesWritten",
+            docs: [
+              "Total number of messages of this type written to the buffer",
+            ],
+            type: "u64",
+          },
+          {
+            name: "bytesWritten",
+            docs: [
+              "Total length in bytes of the messages of this type written to the buffer",
+            ],
+            type: "u64",
+          },
+          {
+            name: "lastWriteSlot",
+            docs: [
+              "Slot of the last write that contained a message of this type",
+            ],
+            type: "u64",
+          },
+        ],
+      },
+    },
+    {
+      name: "BufferMessages",
+      docs: [
+        "Messages to put into the `MessageBuffer` derived from `base_account_key`",
+      ],
+      type: {
+        kind: "struct",
+        fields: [
+          {
+            name: "baseAccountKey",
+            type: "publicKey",
+          },
+          {
+            name: "messages",
+            type: {
+              vec: "bytes",
+            },
+          },
         ],
       },
     },
@@ -1199,5 +3082,30 @@ export const IDL: MessageBuffer = {
       name: "TargetSizeExceedsMaxLen",
       msg: "Target size exceeds MessageBuffer::MAX_LEN",
     },
+    {
+      code: 6007,
+      name: "InvalidBufferWriter",
+      msg: "Invalid buffer writer",
+    },
+    {
+      code: 6008,
+      name: "MaximumBufferWritersExceeded",
+      msg: "Maximum number of buffer writers exceeded",
+    },
+    {
+      code: 6009,
+      name: "WriterLimitExceeded",
+      msg: "Messages exceed the limits of the buffer writer",
+    },
+    {
+      code: 6010,
+      name: "InvalidPendingAdmin",
+      msg: "Signer is not the pending whitelist admin",
+    },
+    {
+      code: 6011,
+      name: "BufferVersionMismatch",
+      msg: "Message Buffer version does not match the program version",
+    },
   ],
 };
@@ -4,7 +4,6 @@ use {
     },
     anchor_lang::{
         prelude::*,
-        solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE,
         system_program::{self, Allocate, Assign, Transfer},
     },
 };
@@ -26,17 +25,8 @@ pub fn create_buffer<'info>(
         .whitelist
         .is_allowed_program_auth(&allowed_program_auth)?;
 
-    require_gte!(
-        target_size,
-        MessageBuffer::HEADER_LEN as u32,
-        MessageBufferError::MessageBufferTooSmall
-    );
-
-    require_gte!(
-        MAX_PERMITTED_DATA_INCREASE,
-        target_size as usize,
-        MessageBufferError::TargetSizeDeltaExceeded
-    );
+    MessageBuffer::check_create_size(target_size)?;
+
     if is_uninitialized_account(buffer_account) {
         let (pda, bump) = Pubkey::find_program_address(
             &[
@@ -0,0 +1,53 @@
+use {
+    crate::{instructions::STATS, state::*, MESSAGE, WHITELIST},
+    anchor_lang::prelude::*,
+};
+
+pub fn create_message_stats<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateMessageStats<'info>>,
+    allowed_program_auth: Pubkey,
+    _base_account_key: Pubkey,
+) -> Result<()> {
+    ctx.accounts
+        .whitelist
+        .is_allowed_program_auth(&allowed_program_auth)?;
+
+    let message_stats = &mut ctx.accounts.message_stats;
+    message_stats.bump = *ctx.bumps.get("message_stats").unwrap();
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(allowed_program_auth: Pubkey, base_account_key: Pubkey)]
+pub struct CreateMessageStats<'info> {
+    #[account(
+        seeds = [MESSAGE.as_bytes(), WHITELIST.as_bytes()],
+        bump = whitelist.bump,
+        has_one = admin,
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+
+    pub admin: Signer<'info>,
+
+    /// Pays for the initialization of the `MessageStats` account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Stats can only be created for an existing buffer
+    #[account(
+        seeds = [allowed_program_auth.as_ref(), MESSAGE.as_bytes(), base_account_key.as_ref()],
+        bump = message_buffer.load()?.bump,
+    )]
+    pub message_buffer: AccountLoader<'info, MessageBuffer>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + MessageStats::INIT_SPACE,
+        seeds = [allowed_program_auth.as_ref(), MESSAGE.as_bytes(), base_account_key.as_ref(), STATS.as_bytes()],
+        bump,
+    )]
+    pub message_stats: Account<'info, MessageStats>,
+}
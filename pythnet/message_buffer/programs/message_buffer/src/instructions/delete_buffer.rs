@@ -1,13 +1,22 @@
 use {
-    crate::{state::*, MESSAGE, WHITELIST},
-    anchor_lang::prelude::*,
+    crate::{
+        instructions::{is_uninitialized_account, STATS, WRITERS},
+        state::*,
+        MESSAGE, WHITELIST,
+    },
+    anchor_lang::{prelude::*, AccountsClose},
 };
 
 pub fn delete_buffer<'info>(
-    _ctx: Context<'_, '_, '_, 'info, DeleteBuffer<'info>>,
+    ctx: Context<'_, '_, '_, 'info, DeleteBuffer<'info>>,
     _allowed_program_auth: Pubkey,
     _base_account_key: Pubkey,
 ) -> Result<()> {
+    let buffer_writers = ctx.accounts.buffer_writers.to_account_info();
+    if !is_uninitialized_account(&buffer_writers) {
+        Account::<BufferWriters>::try_from(&buffer_writers)?
+            .close(ctx.accounts.payer.to_account_info())?;
+    }
     Ok(())
 }
 
@@ -34,4 +43,25 @@ pub struct DeleteBuffer<'info> {
         bump = message_buffer.load()?.bump,
     )]
     pub message_buffer: AccountLoader<'info, MessageBuffer>,
+
+    /// The `BufferWriters` of the buffer. Always required so that a buffer
+    /// recreated at the same address doesn't inherit the writers of the
+    /// deleted one; it is closed along with the buffer if it exists.
+    /// CHECK: the address is checked by the seeds, and the account is only
+    /// loaded as `BufferWriters` if it is initialized
+    #[account(
+        mut,
+        seeds = [allowed_program_auth.as_ref(), MESSAGE.as_bytes(), base_account_key.as_ref(), WRITERS.as_bytes()],
+        bump,
+    )]
+    pub buffer_writers: UncheckedAccount<'info>,
+
+    /// The `MessageStats` of the buffer, if any, is closed along with it
+    #[account(
+        mut,
+        close = payer,
+        seeds = [allowed_program_auth.as_ref(), MESSAGE.as_bytes(), base_account_key.as_ref(), STATS.as_bytes()],
+        bump = message_stats.bump,
+    )]
+    pub message_stats: Option<Account<'info, MessageStats>>,
 }
@@ -0,0 +1,36 @@
+use {
+    crate::{state::*, MESSAGE, WHITELIST},
+    anchor_lang::prelude::*,
+};
+
+pub fn migrate_buffer<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateBuffer<'info>>,
+    allowed_program_auth: Pubkey,
+    _base_account_key: Pubkey,
+) -> Result<()> {
+    ctx.accounts
+        .whitelist
+        .is_allowed_program_auth(&allowed_program_auth)?;
+
+    ctx.accounts.message_buffer.load_mut()?.migrate()
+}
+
+#[derive(Accounts)]
+#[instruction(allowed_program_auth: Pubkey, base_account_key: Pubkey)]
+pub struct MigrateBuffer<'info> {
+    #[account(
+        seeds = [MESSAGE.as_bytes(), WHITELIST.as_bytes()],
+        bump = whitelist.bump,
+        has_one = admin,
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [allowed_program_auth.as_ref(), MESSAGE.as_bytes(), base_account_key.as_ref()],
+        bump = message_buffer.load()?.bump,
+    )]
+    pub message_buffer: AccountLoader<'info, MessageBuffer>,
+}
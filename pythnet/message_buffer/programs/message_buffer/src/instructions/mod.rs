@@ -1,10 +1,18 @@
 use anchor_lang::{prelude::*, system_program};
-pub use {create_buffer::*, delete_buffer::*, put_all::*, resize_buffer::*};
+pub use {
+    create_buffer::*, create_message_stats::*, delete_buffer::*, migrate_buffer::*, put_all::*,
+    put_all_as_writer::*, put_batch::*, resize_buffer::*, set_buffer_writers::*,
+};
 
 mod create_buffer;
+mod create_message_stats;
 mod delete_buffer;
+mod migrate_buffer;
 mod put_all;
+mod put_all_as_writer;
+mod put_batch;
 mod resize_buffer;
+mod set_buffer_writers;
 
 // String constants for deriving PDAs.
 //
@@ -13,6 +21,12 @@ mod resize_buffer;
 // is the whitelisted pubkey who authorized this call.
 pub const MESSAGE: &str = "message";
 pub const WHITELIST: &str = "whitelist";
+// The `BufferWriters` account of a message buffer has PDA seeds
+// [allowed_program_auth, MESSAGE, base_account_key, WRITERS].
+pub const WRITERS: &str = "writers";
+// The `MessageStats` account of a message buffer has PDA seeds
+// [allowed_program_auth, MESSAGE, base_account_key, STATS].
+pub const STATS: &str = "stats";
 
 pub fn is_uninitialized_account(ai: &AccountInfo) -> bool {
     ai.data_is_empty() && ai.owner == &system_program::ID
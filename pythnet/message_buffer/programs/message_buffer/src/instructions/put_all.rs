@@ -1,5 +1,5 @@
 use {
-    crate::{state::*, MESSAGE},
+    crate::{instructions::STATS, state::*, MessageBufferError, MESSAGE},
     anchor_lang::prelude::*,
 };
 
@@ -9,8 +9,21 @@ pub fn put_all<'info>(
     messages: Vec<Vec<u8>>,
 ) -> Result<()> {
     ctx.accounts.whitelist_verifier.is_allowed()?;
+    write_messages(
+        &ctx.accounts.message_buffer,
+        ctx.accounts.message_stats.as_deref_mut(),
+        &messages,
+    )
+}
 
-    let msg_buffer_ai = ctx.accounts.message_buffer.to_account_info();
+/// Overwrites the contents of the `MessageBuffer` with `messages` and
+/// records the messages that fit in `message_stats`, if provided
+pub(crate) fn write_messages(
+    message_buffer: &AccountLoader<MessageBuffer>,
+    message_stats: Option<&mut MessageStats>,
+    messages: &Vec<Vec<u8>>,
+) -> Result<()> {
+    let msg_buffer_ai = message_buffer.to_account_info();
     let account_data = &mut msg_buffer_ai.try_borrow_mut_data()?;
     let header_end_index = MessageBuffer::HEADER_LEN as usize;
 
@@ -18,11 +31,21 @@ pub fn put_all<'info>(
 
     let message_buffer: &mut MessageBuffer = bytemuck::from_bytes_mut(&mut header_bytes[8..]);
 
+    // buffers written by another version of the program must be migrated
+    // with `migrate_buffer` before they can be overwritten
+    require_eq!(
+        message_buffer.version,
+        MessageBuffer::CURRENT_VERSION,
+        MessageBufferError::BufferVersionMismatch
+    );
     message_buffer.refresh_header();
-    let (num_msgs, num_bytes) = message_buffer.put_all_in_buffer(body_bytes, &messages);
+    let (num_msgs, num_bytes) = message_buffer.put_all_in_buffer(body_bytes, messages);
     if num_msgs != messages.len() {
         msg!("unable to fit all messages in MessageBuffer account. Wrote {}/{} messages and {} bytes", num_msgs, messages.len(), num_bytes);
     }
+    if let Some(message_stats) = message_stats {
+        message_stats.record(&messages[..num_msgs], Clock::get()?.slot);
+    }
     Ok(())
 }
 
@@ -36,4 +59,12 @@ pub struct PutAll<'info> {
         bump = message_buffer.load()?.bump,
     )]
     pub message_buffer: AccountLoader<'info, MessageBuffer>,
+    /// Optional since callers that predate `MessageStats` only pass the
+    /// accounts above
+    #[account(
+        mut,
+        seeds = [whitelist_verifier.cpi_caller_auth.key().as_ref(), MESSAGE.as_bytes(), base_account_key.as_ref(), STATS.as_bytes()],
+        bump = message_stats.bump,
+    )]
+    pub message_stats: Option<Account<'info, MessageStats>>,
 }
@@ -0,0 +1,50 @@
+use {
+    crate::{
+        instructions::{write_messages, STATS, WRITERS},
+        state::*,
+        MESSAGE,
+    },
+    anchor_lang::prelude::*,
+};
+
+pub fn put_all_as_writer<'info>(
+    ctx: Context<'_, '_, '_, 'info, PutAllAsWriter<'info>>,
+    _allowed_program_auth: Pubkey,
+    _base_account_key: Pubkey,
+    messages: Vec<Vec<u8>>,
+) -> Result<()> {
+    ctx.accounts.whitelist_verifier.is_allowed()?;
+    ctx.accounts.buffer_writers.check_write(
+        &ctx.accounts.whitelist_verifier.cpi_caller_auth.key(),
+        &messages,
+    )?;
+    write_messages(
+        &ctx.accounts.message_buffer,
+        ctx.accounts.message_stats.as_deref_mut(),
+        &messages,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(allowed_program_auth: Pubkey, base_account_key: Pubkey)]
+pub struct PutAllAsWriter<'info> {
+    pub whitelist_verifier: WhitelistVerifier<'info>,
+    #[account(
+        seeds = [allowed_program_auth.as_ref(), MESSAGE.as_bytes(), base_account_key.as_ref(), WRITERS.as_bytes()],
+        bump = buffer_writers.bump,
+    )]
+    // Using a Box to move account from stack to heap
+    pub buffer_writers: Box<Account<'info, BufferWriters>>,
+    #[account(
+        mut,
+        seeds = [allowed_program_auth.as_ref(), MESSAGE.as_bytes(), base_account_key.as_ref()],
+        bump = message_buffer.load()?.bump,
+    )]
+    pub message_buffer: AccountLoader<'info, MessageBuffer>,
+    #[account(
+        mut,
+        seeds = [allowed_program_auth.as_ref(), MESSAGE.as_bytes(), base_account_key.as_ref(), STATS.as_bytes()],
+        bump = message_stats.bump,
+    )]
+    pub message_stats: Option<Account<'info, MessageStats>>,
+}
@@ -0,0 +1,110 @@
+use {
+    crate::{
+        instructions::{write_messages, STATS},
+        state::*,
+        MessageBufferError, MESSAGE,
+    },
+    anchor_lang::prelude::*,
+};
+
+/// Messages to put into the `MessageBuffer` derived from `base_account_key`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BufferMessages {
+    pub base_account_key: Pubkey,
+    pub messages: Vec<Vec<u8>>,
+}
+
+pub fn put_batch<'info>(
+    ctx: Context<'_, '_, '_, 'info, PutBatch<'info>>,
+    batch: Vec<BufferMessages>,
+) -> Result<()> {
+    ctx.accounts.whitelist_verifier.is_allowed()?;
+    let cpi_caller_auth = ctx.accounts.whitelist_verifier.cpi_caller_auth.key();
+
+    // the `MessageStats` accounts are optional, but if they are passed
+    // there must be one for every entry
+    require!(
+        ctx.remaining_accounts.len() == batch.len()
+            || ctx.remaining_accounts.len() == 2 * batch.len(),
+        MessageBufferError::MessageBufferNotProvided
+    );
+    let (message_buffers, message_stats) = ctx.remaining_accounts.split_at(batch.len());
+
+    for (i, (buffer_messages, message_buffer_ai)) in batch.iter().zip(message_buffers).enumerate() {
+        require!(message_buffer_ai.is_writable, ErrorCode::ConstraintMut);
+        let message_buffer = AccountLoader::<MessageBuffer>::try_from(message_buffer_ai)?;
+
+        // same check as the `seeds` constraint of `PutAll`
+        let bump = message_buffer.load()?.bump;
+        let expected_key = Pubkey::create_program_address(
+            &[
+                cpi_caller_auth.as_ref(),
+                MESSAGE.as_bytes(),
+                buffer_messages.base_account_key.as_ref(),
+                &[bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| ErrorCode::ConstraintSeeds)?;
+        require_keys_eq!(
+            message_buffer.key(),
+            expected_key,
+            ErrorCode::ConstraintSeeds
+        );
+
+        let mut message_stats = message_stats
+            .get(i)
+            .map(|message_stats_ai| {
+                load_message_stats(
+                    message_stats_ai,
+                    &cpi_caller_auth,
+                    &buffer_messages.base_account_key,
+                )
+            })
+            .transpose()?;
+        write_messages(
+            &message_buffer,
+            message_stats.as_deref_mut(),
+            &buffer_messages.messages,
+        )?;
+        if let Some(message_stats) = message_stats {
+            message_stats.exit(&crate::ID)?;
+        }
+    }
+    Ok(())
+}
+
+/// Loads the `MessageStats` of the buffer of `base_account_key`, with the
+/// same checks as the `message_stats` constraints of `PutAll`
+fn load_message_stats<'info>(
+    message_stats_ai: &AccountInfo<'info>,
+    cpi_caller_auth: &Pubkey,
+    base_account_key: &Pubkey,
+) -> Result<Account<'info, MessageStats>> {
+    require!(message_stats_ai.is_writable, ErrorCode::ConstraintMut);
+    let message_stats = Account::<MessageStats>::try_from(message_stats_ai)?;
+    let expected_key = Pubkey::create_program_address(
+        &[
+            cpi_caller_auth.as_ref(),
+            MESSAGE.as_bytes(),
+            base_account_key.as_ref(),
+            STATS.as_bytes(),
+            &[message_stats.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::ConstraintSeeds)?;
+    require_keys_eq!(
+        message_stats.key(),
+        expected_key,
+        ErrorCode::ConstraintSeeds
+    );
+    Ok(message_stats)
+}
+
+#[derive(Accounts)]
+pub struct PutBatch<'info> {
+    pub whitelist_verifier: WhitelistVerifier<'info>,
+    // remaining_accounts: one writable `MessageBuffer` per entry of the batch, in the same order,
+    // optionally followed by one writable `MessageStats` per entry, in the same order
+}
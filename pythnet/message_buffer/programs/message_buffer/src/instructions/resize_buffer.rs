@@ -1,5 +1,5 @@
 use {
-    crate::{state::*, MessageBufferError, MESSAGE, WHITELIST},
+    crate::{state::*, MESSAGE, WHITELIST},
     anchor_lang::prelude::*,
 };
 
@@ -13,22 +13,10 @@ pub fn resize_buffer<'info>(
         .whitelist
         .is_allowed_program_auth(&allowed_program_auth)?;
 
-    let message_buffer = &ctx.accounts.message_buffer.load()?;
-    let max_end_offset = message_buffer.end_offsets.iter().max().unwrap();
-    let minimum_size = max_end_offset + message_buffer.header_len;
-    require_gte!(
-        target_size as usize,
-        minimum_size as usize,
-        MessageBufferError::MessageBufferTooSmall
-    );
-
-    require_gte!(
-        MessageBuffer::MAX_LEN as usize,
-        target_size as usize,
-        MessageBufferError::TargetSizeExceedsMaxLen
-    );
-
-    Ok(())
+    ctx.accounts
+        .message_buffer
+        .load()?
+        .check_resize(target_size)
 }
 
 #[derive(Accounts)]
@@ -0,0 +1,58 @@
+use {
+    crate::{instructions::WRITERS, state::*, MESSAGE, WHITELIST},
+    anchor_lang::prelude::*,
+};
+
+pub fn set_buffer_writers<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetBufferWriters<'info>>,
+    allowed_program_auth: Pubkey,
+    _base_account_key: Pubkey,
+    writers: Vec<BufferWriter>,
+) -> Result<()> {
+    let whitelist = &ctx.accounts.whitelist;
+    whitelist.is_allowed_program_auth(&allowed_program_auth)?;
+    BufferWriters::validate_writers(&writers)?;
+    for writer in writers.iter() {
+        whitelist.is_allowed_program_auth(&writer.auth)?;
+    }
+
+    let buffer_writers = &mut ctx.accounts.buffer_writers;
+    buffer_writers.bump = *ctx.bumps.get("buffer_writers").unwrap();
+    buffer_writers.writers = writers;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(allowed_program_auth: Pubkey, base_account_key: Pubkey)]
+pub struct SetBufferWriters<'info> {
+    #[account(
+        seeds = [MESSAGE.as_bytes(), WHITELIST.as_bytes()],
+        bump = whitelist.bump,
+        has_one = admin,
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+
+    pub admin: Signer<'info>,
+
+    /// Pays for the initialization of the `BufferWriters` account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// The writers can only be set for an existing buffer
+    #[account(
+        seeds = [allowed_program_auth.as_ref(), MESSAGE.as_bytes(), base_account_key.as_ref()],
+        bump = message_buffer.load()?.bump,
+    )]
+    pub message_buffer: AccountLoader<'info, MessageBuffer>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + BufferWriters::INIT_SPACE,
+        seeds = [allowed_program_auth.as_ref(), MESSAGE.as_bytes(), base_account_key.as_ref(), WRITERS.as_bytes()],
+        bump,
+    )]
+    pub buffer_writers: Account<'info, BufferWriters>,
+}
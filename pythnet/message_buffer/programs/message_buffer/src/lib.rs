@@ -6,7 +6,7 @@ mod state;
 
 use {
     crate::{MESSAGE, WHITELIST},
-    anchor_lang::prelude::*,
+    anchor_lang::{prelude::*, system_program, Discriminator},
     instructions::*,
     state::*,
 };
@@ -46,6 +46,72 @@ pub mod message_buffer {
         let whitelist = &mut ctx.accounts.whitelist;
         whitelist.validate_new_admin(new_admin)?;
         whitelist.admin = new_admin;
+        whitelist.pending_admin = None;
+        Ok(())
+    }
+
+    /// Grows a whitelist created before `pending_admin` was added to the
+    /// current size. Until it is migrated, such a whitelist can't be grown to
+    /// hold 32 allowed programs, and one that already holds 32 can't be
+    /// loaded by any other instruction, so the old layout is read by hand.
+    /// Does nothing if the whitelist already has the current size.
+    pub fn migrate_whitelist(ctx: Context<MigrateWhitelist>) -> Result<()> {
+        let whitelist = ctx.accounts.whitelist.to_account_info();
+        {
+            // Layout: discriminator, bump, admin, ...
+            let data = whitelist.try_borrow_data()?;
+            require!(
+                data.len() >= 8 + 1 + 32 && data[..8] == Whitelist::DISCRIMINATOR,
+                ErrorCode::AccountDiscriminatorMismatch
+            );
+            let admin = Pubkey::try_from(&data[9..41]).unwrap();
+            require_keys_eq!(admin, ctx.accounts.admin.key(), ErrorCode::ConstraintHasOne);
+        }
+
+        let space = 8 + Whitelist::INIT_SPACE;
+        if whitelist.data_len() < space {
+            let rent = Rent::get()?
+                .minimum_balance(space)
+                .saturating_sub(whitelist.lamports());
+            if rent > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.payer.to_account_info(),
+                            to: whitelist.clone(),
+                        },
+                    ),
+                    rent,
+                )?;
+            }
+            // The new bytes are zeroed, so `pending_admin` is `None`
+            whitelist.realloc(space, true)?;
+        }
+        Ok(())
+    }
+
+    /// Proposes a new admin for the whitelist. The transfer only takes effect
+    /// once the new admin signs `accept_whitelist_admin`, so the admin can be
+    /// handed over to a PDA (e.g. the remote executor PDA of a governance
+    /// emitter) without the risk of transferring it to a key nobody controls.
+    /// Proposing again replaces any previously pending admin.
+    pub fn propose_whitelist_admin(
+        ctx: Context<ProposeWhitelistAdmin>,
+        new_admin: Pubkey,
+    ) -> Result<()> {
+        let whitelist = &mut ctx.accounts.whitelist;
+        whitelist.validate_new_admin(new_admin)?;
+        whitelist.pending_admin = Some(new_admin);
+        Ok(())
+    }
+
+    /// Completes the transfer started by `propose_whitelist_admin`. Must be
+    /// signed by the pending admin.
+    pub fn accept_whitelist_admin(ctx: Context<AcceptWhitelistAdmin>) -> Result<()> {
+        let whitelist = &mut ctx.accounts.whitelist;
+        whitelist.admin = ctx.accounts.pending_admin.key();
+        whitelist.pending_admin = None;
         Ok(())
     }
 
@@ -69,6 +135,9 @@ pub mod message_buffer {
     /// ix is independent of any previous invocations. It will overwrite
     /// any existing contents.
     ///
+    /// If the buffer's `MessageStats` account is passed after the
+    /// `message_buffer`, the messages that were written are counted in it.
+    ///
     /// TODO:
     ///     - handle updates ("paging/batches of messages")
     ///
@@ -80,6 +149,49 @@ pub mod message_buffer {
         instructions::put_all(ctx, base_account_key, messages)
     }
 
+    /// Put messages into a `MessageBuffer` owned by another whitelisted
+    /// program. The signing `cpi_caller_auth` must be one of the buffer's
+    /// writers set by `set_buffer_writers`, and the messages must be within
+    /// that writer's limits. Like `put_all`, this overwrites any existing
+    /// contents of the buffer.
+    ///
+    /// *`allowed_program_auth` - The whitelisted pubkey that owns the
+    ///                            `MessageBuffer`. Used as one of the seeds
+    ///                            for deriving the `MessageBuffer` PDA.
+    /// * `base_account_key`    - Pubkey of the original account the
+    ///                           `MessageBuffer` is derived from
+    ///                           (e.g. pyth price account)
+    /// * `messages`            - Vec of vec of bytes, each representing a message
+    ///                           to be hashed and accumulated
+    pub fn put_all_as_writer<'info>(
+        ctx: Context<'_, '_, '_, 'info, PutAllAsWriter<'info>>,
+        allowed_program_auth: Pubkey,
+        base_account_key: Pubkey,
+        messages: Vec<Vec<u8>>,
+    ) -> Result<()> {
+        instructions::put_all_as_writer(ctx, allowed_program_auth, base_account_key, messages)
+    }
+
+    /// Put messages into several `MessageBuffer`s with a single CPI. Each
+    /// entry of `batch` is written the same way as `put_all` would write it,
+    /// so callers that update many accounts at once (e.g. the oracle in
+    /// an aggregation slot) don't need one CPI per account.
+    ///
+    /// * `batch`               - The messages to write for each
+    ///                           `base_account_key`. The `MessageBuffer` of
+    ///                           every entry must be passed in the remaining
+    ///                           accounts, in the same order.
+    ///
+    /// The `MessageStats` accounts of the buffers are optional, like in
+    /// `put_all`. To update them, pass the `MessageStats` of every entry
+    /// after the `MessageBuffer`s, in the same order.
+    pub fn put_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, PutBatch<'info>>,
+        batch: Vec<BufferMessages>,
+    ) -> Result<()> {
+        instructions::put_batch(ctx, batch)
+    }
+
     /// Initializes the buffer account with the `target_size`
     ///
     /// *`allowed_program_auth` - The whitelisted pubkey representing an
@@ -120,8 +232,30 @@ pub mod message_buffer {
         instructions::resize_buffer(ctx, allowed_program_auth, base_account_key, target_size)
     }
 
-    /// Closes the buffer account and transfers the remaining lamports to the
-    /// `admin` account
+    /// Migrates the header of the buffer account to the current layout
+    /// version. Buffers that were written by a previous version of the
+    /// program are rejected by `put_all` until they are migrated.
+    ///
+    /// If the new layout needs a larger header, the buffer must be resized
+    /// with `resize_buffer` first.
+    ///
+    /// *`allowed_program_auth` - The whitelisted pubkey representing an
+    ///                            allowed program. Used as one of the seeds
+    ///                            for deriving the `MessageBuffer` PDA.
+    /// * `base_account_key`    - Pubkey of the original account the
+    ///                           `MessageBuffer` is derived from
+    ///                           (e.g. pyth price account)
+    pub fn migrate_buffer<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateBuffer<'info>>,
+        allowed_program_auth: Pubkey,
+        base_account_key: Pubkey,
+    ) -> Result<()> {
+        instructions::migrate_buffer(ctx, allowed_program_auth, base_account_key)
+    }
+
+    /// Closes the buffer account, its `BufferWriters` account if it exists
+    /// and its `MessageStats` account if passed, and transfers the remaining
+    /// lamports to the `admin` account
     ///
     /// *`allowed_program_auth` - The whitelisted pubkey representing an
     ///                            allowed program. Used as one of the seeds
@@ -136,6 +270,46 @@ pub mod message_buffer {
     ) -> Result<()> {
         instructions::delete_buffer(ctx, allowed_program_auth, base_account_key)
     }
+
+    /// Sets the additional writers that are allowed to put messages into
+    /// the buffer account, along with their per-writer limits. The
+    /// `BufferWriters` account is created on first use.
+    ///
+    /// *`allowed_program_auth` - The whitelisted pubkey that owns the
+    ///                            `MessageBuffer`. Used as one of the seeds
+    ///                            for deriving the `MessageBuffer` PDA.
+    /// * `base_account_key`    - Pubkey of the original account the
+    ///                           `MessageBuffer` is derived from
+    ///                           (e.g. pyth price account)
+    /// * `writers`             - Entire list of additional writers. Every
+    ///                           writer must also be in the whitelist.
+    pub fn set_buffer_writers<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetBufferWriters<'info>>,
+        allowed_program_auth: Pubkey,
+        base_account_key: Pubkey,
+        writers: Vec<BufferWriter>,
+    ) -> Result<()> {
+        instructions::set_buffer_writers(ctx, allowed_program_auth, base_account_key, writers)
+    }
+
+    /// Creates the `MessageStats` account of the buffer. Once created, it
+    /// can be passed to `put_all` and `put_all_as_writer` (after the
+    /// `message_buffer` account) to count the messages written to the
+    /// buffer per message type.
+    ///
+    /// *`allowed_program_auth` - The whitelisted pubkey that owns the
+    ///                            `MessageBuffer`. Used as one of the seeds
+    ///                            for deriving the `MessageBuffer` PDA.
+    /// * `base_account_key`    - Pubkey of the original account the
+    ///                           `MessageBuffer` is derived from
+    ///                           (e.g. pyth price account)
+    pub fn create_message_stats<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateMessageStats<'info>>,
+        allowed_program_auth: Pubkey,
+        base_account_key: Pubkey,
+    ) -> Result<()> {
+        instructions::create_message_stats(ctx, allowed_program_auth, base_account_key)
+    }
 }
 
 #[derive(Accounts)]
@@ -168,6 +342,51 @@ pub struct UpdateWhitelist<'info> {
     pub whitelist: Account<'info, Whitelist>,
 }
 
+#[derive(Accounts)]
+pub struct MigrateWhitelist<'info> {
+    pub admin: Signer<'info>,
+    /// Pays for the additional rent of the larger whitelist
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: may still have the old layout, so the discriminator and the
+    /// admin are checked by hand
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [MESSAGE.as_bytes(), WHITELIST.as_bytes()],
+        bump,
+    )]
+    pub whitelist: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeWhitelistAdmin<'info> {
+    pub admin: Signer<'info>,
+    /// Whitelists created before `pending_admin` was added must be migrated
+    /// with `migrate_whitelist` first
+    #[account(
+        mut,
+        seeds = [MESSAGE.as_bytes(), WHITELIST.as_bytes()],
+        bump = whitelist.bump,
+        has_one = admin,
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptWhitelistAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [MESSAGE.as_bytes(), WHITELIST.as_bytes()],
+        bump = whitelist.bump,
+        constraint = whitelist.pending_admin == Some(pending_admin.key())
+            @ MessageBufferError::InvalidPendingAdmin
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+}
+
 #[error_code]
 pub enum MessageBufferError {
     #[msg("CPI Caller not allowed")]
@@ -184,4 +403,14 @@ pub enum MessageBufferError {
     TargetSizeDeltaExceeded,
     #[msg("Target size exceeds MessageBuffer::MAX_LEN")]
     TargetSizeExceedsMaxLen,
+    #[msg("Invalid buffer writer")]
+    InvalidBufferWriter,
+    #[msg("Maximum number of buffer writers exceeded")]
+    MaximumBufferWritersExceeded,
+    #[msg("Messages exceed the limits of the buffer writer")]
+    WriterLimitExceeded,
+    #[msg("Signer is not the pending whitelist admin")]
+    InvalidPendingAdmin,
+    #[msg("Message Buffer version does not match the program version")]
+    BufferVersionMismatch,
 }
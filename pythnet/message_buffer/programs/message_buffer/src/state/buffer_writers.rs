@@ -0,0 +1,150 @@
+use {crate::MessageBufferError, anchor_lang::prelude::*};
+
+/// Additional writers allowed to put messages into a single `MessageBuffer`.
+///
+/// A `MessageBuffer` PDA is derived from the whitelisted `allowed_program_auth`
+/// that owns it. This account lets the admin allow other whitelisted
+/// authorities to write into the same buffer, each with its own limits.
+///
+/// PDA seeds: `[allowed_program_auth, MESSAGE, base_account_key, WRITERS]`
+#[account]
+#[derive(InitSpace)]
+pub struct BufferWriters {
+    pub bump: u8,
+    #[max_len(8)]
+    pub writers: Vec<BufferWriter>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct BufferWriter {
+    /// Pubkey representing the writer program. Must also be in the whitelist.
+    pub auth: Pubkey,
+    /// Maximum number of messages the writer can put in a single call
+    pub max_messages: u8,
+    /// Maximum total length in bytes of the messages the writer can put
+    /// in a single call
+    pub max_bytes: u16,
+}
+
+impl BufferWriters {
+    pub const MAX_WRITERS: usize = 8;
+
+    pub fn validate_writers(writers: &[BufferWriter]) -> Result<()> {
+        require_gte!(
+            Self::MAX_WRITERS,
+            writers.len(),
+            MessageBufferError::MaximumBufferWritersExceeded
+        );
+        for (i, writer) in writers.iter().enumerate() {
+            require_keys_neq!(
+                writer.auth,
+                Pubkey::default(),
+                MessageBufferError::InvalidBufferWriter
+            );
+            require!(
+                !writers[..i].iter().any(|w| w.auth == writer.auth),
+                MessageBufferError::InvalidBufferWriter
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that `auth` is an allowed writer and that `messages` are within
+    /// its limits.
+    pub fn check_write(&self, auth: &Pubkey, messages: &[Vec<u8>]) -> Result<()> {
+        let writer = self
+            .writers
+            .iter()
+            .find(|w| w.auth == *auth)
+            .ok_or(MessageBufferError::CallerNotAllowed)?;
+
+        require_gte!(
+            writer.max_messages as usize,
+            messages.len(),
+            MessageBufferError::WriterLimitExceeded
+        );
+        let num_bytes: usize = messages.iter().map(|m| m.len()).sum();
+        require_gte!(
+            writer.max_bytes as usize,
+            num_bytes,
+            MessageBufferError::WriterLimitExceeded
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn writer(auth: Pubkey, max_messages: u8, max_bytes: u16) -> BufferWriter {
+        BufferWriter {
+            auth,
+            max_messages,
+            max_bytes,
+        }
+    }
+
+    #[test]
+    fn test_validate_writers() {
+        let auth_1 = Pubkey::new_unique();
+        let auth_2 = Pubkey::new_unique();
+
+        assert!(BufferWriters::validate_writers(&[]).is_ok());
+        assert!(
+            BufferWriters::validate_writers(&[writer(auth_1, 1, 10), writer(auth_2, 2, 20)])
+                .is_ok()
+        );
+
+        assert_eq!(
+            BufferWriters::validate_writers(&[writer(Pubkey::default(), 1, 10)]).unwrap_err(),
+            MessageBufferError::InvalidBufferWriter.into()
+        );
+        assert_eq!(
+            BufferWriters::validate_writers(&[writer(auth_1, 1, 10), writer(auth_1, 2, 20)])
+                .unwrap_err(),
+            MessageBufferError::InvalidBufferWriter.into()
+        );
+
+        let too_many: Vec<_> = (0..=BufferWriters::MAX_WRITERS)
+            .map(|_| writer(Pubkey::new_unique(), 1, 10))
+            .collect();
+        assert_eq!(
+            BufferWriters::validate_writers(&too_many).unwrap_err(),
+            MessageBufferError::MaximumBufferWritersExceeded.into()
+        );
+    }
+
+    #[test]
+    fn test_check_write() {
+        let auth = Pubkey::new_unique();
+        let buffer_writers = BufferWriters {
+            bump: 0,
+            writers: vec![writer(auth, 2, 10)],
+        };
+
+        assert!(buffer_writers.check_write(&auth, &[]).is_ok());
+        assert!(buffer_writers
+            .check_write(&auth, &[vec![0u8; 4], vec![0u8; 6]])
+            .is_ok());
+
+        assert_eq!(
+            buffer_writers
+                .check_write(&Pubkey::new_unique(), &[vec![0u8; 1]])
+                .unwrap_err(),
+            MessageBufferError::CallerNotAllowed.into()
+        );
+        assert_eq!(
+            buffer_writers
+                .check_write(&auth, &[vec![0u8; 1], vec![0u8; 1], vec![0u8; 1]])
+                .unwrap_err(),
+            MessageBufferError::WriterLimitExceeded.into()
+        );
+        assert_eq!(
+            buffer_writers
+                .check_write(&auth, &[vec![0u8; 11]])
+                .unwrap_err(),
+            MessageBufferError::WriterLimitExceeded.into()
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use anchor_lang::prelude::*;
+use {
+    crate::MessageBufferError,
+    anchor_lang::{prelude::*, solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE},
+};
 
 /// A MessageBuffer will have the following structure
 /// ```ignore
@@ -50,6 +53,8 @@ impl MessageBuffer {
     // to the start of the `AccumulatorInput` data.
     pub const HEADER_LEN: u16 = 8 + MessageBuffer::INIT_SPACE as u16;
 
+    /// Layout version of the header. Bump it whenever the header layout
+    /// changes and handle the previous version in `migrate`.
     pub const CURRENT_VERSION: u8 = 1;
 
     // end_offsets are u16 so max size that account can be resized to
@@ -65,12 +70,75 @@ impl MessageBuffer {
         }
     }
 
+    /// Minimum length of the account data that still holds every message
+    /// in the buffer. The buffer can't be resized below this.
+    pub fn min_account_len(&self) -> u32 {
+        let max_end_offset = self.end_offsets.iter().max().unwrap();
+        // u32 since `header_len + max_end_offset` can exceed u16::MAX
+        self.header_len as u32 + *max_end_offset as u32
+    }
+
+    /// Checks that a new buffer account of `target_size` bytes can be
+    /// created in a single instruction
+    pub fn check_create_size(target_size: u32) -> Result<()> {
+        require_gte!(
+            target_size,
+            Self::HEADER_LEN as u32,
+            MessageBufferError::MessageBufferTooSmall
+        );
+        require_gte!(
+            MAX_PERMITTED_DATA_INCREASE,
+            target_size as usize,
+            MessageBufferError::TargetSizeDeltaExceeded
+        );
+        Ok(())
+    }
+
+    /// Checks that the buffer account can be resized to `target_size`
+    /// bytes without cutting off any of its messages
+    pub fn check_resize(&self, target_size: u32) -> Result<()> {
+        require_gte!(
+            target_size,
+            self.min_account_len(),
+            MessageBufferError::MessageBufferTooSmall
+        );
+        require_gte!(
+            Self::MAX_LEN,
+            target_size,
+            MessageBufferError::TargetSizeExceedsMaxLen
+        );
+        Ok(())
+    }
+
     pub fn refresh_header(&mut self) {
         self.header_len = Self::HEADER_LEN;
         self.version = Self::CURRENT_VERSION;
         self.end_offsets = [0u16; u8::MAX as usize];
     }
 
+    /// Upgrades the header of a buffer written by a previous version of the
+    /// program to the current layout. Buffers already at the current version
+    /// are left untouched.
+    ///
+    /// The messages of a migrated buffer are discarded since every `put_all`
+    /// overwrites the buffer anyway.
+    pub fn migrate(&mut self) -> Result<()> {
+        require_gte!(
+            Self::CURRENT_VERSION,
+            self.version,
+            MessageBufferError::BufferVersionMismatch
+        );
+        if self.version < Self::CURRENT_VERSION {
+            msg!(
+                "migrating MessageBuffer from version {} to {}",
+                self.version,
+                Self::CURRENT_VERSION
+            );
+            self.refresh_header();
+        }
+        Ok(())
+    }
+
     /// `put_all` writes all the messages to the `AccumulatorInput` account
     /// and updates the `end_offsets` array.
     ///
@@ -120,6 +188,7 @@ mod test {
         super::*,
         anchor_lang::solana_program::keccak::hashv,
         bytemuck::bytes_of_mut,
+        proptest::prelude::*,
         std::{
             io::Write,
             mem::{align_of, size_of},
@@ -446,4 +515,168 @@ mod test {
         assert_eq!(message_buffer.end_offsets[3], 0);
         assert_eq!(message_buffer.end_offsets[4], 0);
     }
+
+    #[test]
+    fn test_min_account_len() {
+        let message_buffer = &mut MessageBuffer::new(0);
+        assert_eq!(
+            message_buffer.min_account_len(),
+            MessageBuffer::HEADER_LEN as u32
+        );
+
+        message_buffer.end_offsets[0] = 10;
+        message_buffer.end_offsets[1] = 20;
+        assert_eq!(
+            message_buffer.min_account_len(),
+            MessageBuffer::HEADER_LEN as u32 + 20
+        );
+
+        message_buffer.end_offsets[2] = u16::MAX;
+        assert_eq!(message_buffer.min_account_len(), MessageBuffer::MAX_LEN);
+    }
+
+    #[test]
+    fn test_migrate() {
+        let message_buffer = &mut MessageBuffer::new(0);
+        message_buffer.end_offsets[0] = 10;
+
+        // already at the current version
+        message_buffer.migrate().unwrap();
+        assert_eq!(message_buffer.version, MessageBuffer::CURRENT_VERSION);
+        assert_eq!(message_buffer.end_offsets[0], 10);
+
+        // buffer written by a previous version of the program
+        message_buffer.version = MessageBuffer::CURRENT_VERSION - 1;
+        message_buffer.header_len = MessageBuffer::HEADER_LEN - 2;
+        message_buffer.migrate().unwrap();
+        assert_eq!(message_buffer.version, MessageBuffer::CURRENT_VERSION);
+        assert_eq!(message_buffer.header_len, MessageBuffer::HEADER_LEN);
+        assert_eq!(message_buffer.end_offsets, [0u16; 255]);
+
+        // buffer written by a newer version of the program can't be downgraded
+        message_buffer.version = MessageBuffer::CURRENT_VERSION + 1;
+        assert_eq!(
+            message_buffer.migrate().unwrap_err(),
+            MessageBufferError::BufferVersionMismatch.into()
+        );
+        assert_eq!(message_buffer.version, MessageBuffer::CURRENT_VERSION + 1);
+    }
+
+    /// Operations on a `MessageBuffer` account, applied to the account data
+    /// the same way the corresponding instructions do
+    #[derive(Clone, Debug)]
+    enum Op {
+        PutAll(Vec<Vec<u8>>),
+        Resize(u32),
+        /// `delete_buffer` followed by `create_buffer`
+        Recreate(u32),
+    }
+
+    /// Account sizes, biased towards `MAX_LEN` where the end offsets get
+    /// close to `u16::MAX`, and towards the sizes that `create_buffer` allows
+    fn size_strategy() -> impl Strategy<Value = u32> {
+        prop_oneof![
+            0..MessageBuffer::MAX_LEN + 1024,
+            MessageBuffer::MAX_LEN - 512..=MessageBuffer::MAX_LEN,
+            0..MAX_PERMITTED_DATA_INCREASE as u32 + 1024,
+        ]
+    }
+
+    fn op_strategy() -> impl Strategy<Value = Op> {
+        // Messages are never empty since they always start with their type.
+        // (A zero end offset terminates the list of messages.)
+        let messages = prop::collection::vec(prop::collection::vec(any::<u8>(), 1..2048), 0..64);
+        let many_small_messages =
+            prop::collection::vec(prop::collection::vec(any::<u8>(), 1..4), 0..300);
+        // enough to fill a buffer of `MAX_LEN` up to its last few hundred bytes
+        let filling_messages = prop::collection::vec(
+            (any::<u8>(), 256..512usize).prop_map(|(byte, len)| vec![byte; len]),
+            128..=255,
+        );
+        prop_oneof![
+            messages.prop_map(Op::PutAll),
+            many_small_messages.prop_map(Op::PutAll),
+            filling_messages.prop_map(Op::PutAll),
+            size_strategy().prop_map(Op::Resize),
+            size_strategy().prop_map(Op::Recreate),
+        ]
+    }
+
+    /// Applies `op` to `account_data` and updates `expected` with the messages
+    /// the buffer should contain afterwards
+    fn apply_op(account_data: &mut Vec<u8>, expected: &mut Vec<Vec<u8>>, op: &Op) {
+        let header_len = MessageBuffer::HEADER_LEN as usize;
+        match op {
+            Op::PutAll(messages) => {
+                let (header_bytes, body_bytes) = account_data.split_at_mut(header_len);
+                let message_buffer: &mut MessageBuffer =
+                    bytemuck::from_bytes_mut(&mut header_bytes[8..]);
+                message_buffer.refresh_header();
+                let (num_msgs, _) = message_buffer.put_all_in_buffer(body_bytes, messages);
+                *expected = messages[..num_msgs].to_vec();
+            }
+            Op::Resize(target_size) => {
+                let message_buffer: &MessageBuffer =
+                    bytemuck::from_bytes(&account_data[8..header_len]);
+                if message_buffer.check_resize(*target_size).is_ok() {
+                    // realloc::zero = false, so new bytes may hold anything
+                    account_data.resize(*target_size as usize, 0xff);
+                }
+            }
+            Op::Recreate(target_size) => {
+                if MessageBuffer::check_create_size(*target_size).is_ok() {
+                    *account_data =
+                        generate_message_buffer_bytes(*target_size as usize - header_len);
+                    expected.clear();
+                }
+            }
+        }
+    }
+
+    /// Checks that the message ranges in the header are in bounds, don't
+    /// overlap and hold exactly the `expected` messages
+    fn check_offsets(account_data: &[u8], expected: &[Vec<u8>]) {
+        let header_len = MessageBuffer::HEADER_LEN as usize;
+        assert!(account_data.len() <= MessageBuffer::MAX_LEN as usize);
+
+        let message_buffer: &MessageBuffer = bytemuck::from_bytes(&account_data[8..header_len]);
+        assert_eq!(message_buffer.header_len as usize, header_len);
+
+        let mut start = 0;
+        for (i, end_offset) in message_buffer.end_offsets.iter().enumerate() {
+            let end = *end_offset as usize;
+            match expected.get(i) {
+                Some(expected_msg) => {
+                    assert!(start < end);
+                    assert!(header_len + end <= account_data.len());
+                    assert_eq!(
+                        &account_data[header_len + start..header_len + end],
+                        expected_msg.as_slice()
+                    );
+                    start = end;
+                }
+                None => assert_eq!(end, 0),
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_offsets_integrity(
+            initial_size in size_strategy().prop_filter("invalid buffer size", |size| {
+                *size >= MessageBuffer::HEADER_LEN as u32 && *size <= MessageBuffer::MAX_LEN
+            }),
+            ops in prop::collection::vec(op_strategy(), 1..16),
+        ) {
+            let header_len = MessageBuffer::HEADER_LEN as usize;
+            let account_data =
+                &mut generate_message_buffer_bytes(initial_size as usize - header_len);
+            let expected = &mut vec![];
+
+            for op in ops.iter() {
+                apply_op(account_data, expected, op);
+                check_offsets(account_data, expected);
+            }
+        }
+    }
 }
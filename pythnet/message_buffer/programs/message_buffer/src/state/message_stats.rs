@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+/// Write counters of a single `MessageBuffer`, kept per message type so
+/// monitoring can tell when a publisher pipeline stops producing a type
+/// of message without reading the raw buffer.
+///
+/// The message type is the first byte of each message. Messages with a
+/// type `>= MAX_MESSAGE_TYPES` and empty messages are not counted.
+///
+/// PDA seeds: `[allowed_program_auth, MESSAGE, base_account_key, STATS]`
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct MessageStats {
+    pub bump: u8,
+    pub types: [MessageTypeStats; 8],
+}
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct MessageTypeStats {
+    /// Total number of messages of this type written to the buffer
+    pub messages_written: u64,
+    /// Total length in bytes of the messages of this type written to the buffer
+    pub bytes_written: u64,
+    /// Slot of the last write that contained a message of this type
+    pub last_write_slot: u64,
+}
+
+impl MessageStats {
+    pub const MAX_MESSAGE_TYPES: usize = 8;
+
+    /// Records `messages` as written to the buffer in `slot`
+    pub fn record(&mut self, messages: &[Vec<u8>], slot: u64) {
+        for message in messages {
+            if let Some(stats) = message
+                .first()
+                .and_then(|message_type| self.types.get_mut(*message_type as usize))
+            {
+                stats.messages_written = stats.messages_written.saturating_add(1);
+                stats.bytes_written = stats.bytes_written.saturating_add(message.len() as u64);
+                stats.last_write_slot = slot;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut message_stats = MessageStats {
+            bump: 0,
+            types: [MessageTypeStats::default(); MessageStats::MAX_MESSAGE_TYPES],
+        };
+
+        message_stats.record(&[vec![0, 1, 2], vec![1, 2], vec![0, 1]], 10);
+        message_stats.record(&[vec![1, 2, 3, 4], vec![], vec![8, 1]], 11);
+
+        assert_eq!(
+            message_stats.types[0],
+            MessageTypeStats {
+                messages_written: 2,
+                bytes_written: 5,
+                last_write_slot: 10,
+            }
+        );
+        assert_eq!(
+            message_stats.types[1],
+            MessageTypeStats {
+                messages_written: 2,
+                bytes_written: 6,
+                last_write_slot: 11,
+            }
+        );
+        assert!(message_stats.types[2..]
+            .iter()
+            .all(|stats| *stats == MessageTypeStats::default()));
+    }
+}
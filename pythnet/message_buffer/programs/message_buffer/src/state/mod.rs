@@ -1,4 +1,6 @@
-pub use {self::message_buffer::*, whitelist::*};
+pub use {self::message_buffer::*, buffer_writers::*, message_stats::*, whitelist::*};
 
+mod buffer_writers;
 mod message_buffer;
+mod message_stats;
 mod whitelist;
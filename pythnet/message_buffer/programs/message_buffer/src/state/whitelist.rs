@@ -15,6 +15,9 @@ pub struct Whitelist {
     // to determine initial account size
     #[max_len(32)]
     pub allowed_programs: Vec<Pubkey>,
+    /// Admin proposed by `propose_whitelist_admin`. Only becomes the admin
+    /// once it signs `accept_whitelist_admin`.
+    pub pending_admin: Option<Pubkey>,
 }
 
 impl Whitelist {
@@ -1,9 +1,13 @@
 use anchor_lang::solana_program::hash::hashv;
-pub use {add_price::*, cpi_max_test::*, update_price::*};
+pub use {
+    add_price::*, cpi_max_test::*, update_price::*, update_price_as_writer::*, update_prices::*,
+};
 
 mod add_price;
 mod cpi_max_test;
 mod update_price;
+mod update_price_as_writer;
+mod update_prices;
 
 /// Generate discriminator to be able to call anchor program's ix
 /// * `namespace` - "global" for instructions
@@ -21,3 +25,6 @@ pub fn sighash(namespace: &str, name: &str) -> [u8; 8] {
 
 pub const ACCUMULATOR_UPDATER_IX_NAME: &str = "put_all";
 pub const UPD_PRICE_WRITE: &str = "upd_price_write";
+/// Seed of a second authority that writes into the buffers owned by the
+/// `UPD_PRICE_WRITE` authority with `put_all_as_writer`
+pub const UPD_PRICE_WRITER: &str = "upd_price_writer";
@@ -0,0 +1,106 @@
+use {
+    crate::{
+        instructions::{sighash, UpdatePriceParams, UPD_PRICE_WRITER},
+        message::{
+            price::{CompactPriceMessage, FullPriceMessage},
+            AccumulatorSerializer,
+        },
+        state::PriceAccount,
+    },
+    anchor_lang::{prelude::*, system_program},
+    message_buffer::program::MessageBuffer as MessageBufferProgram,
+};
+
+#[derive(Accounts)]
+pub struct UpdatePriceAsWriter<'info> {
+    #[account(
+    mut,
+    seeds = [
+    b"pyth".as_ref(),
+    b"price".as_ref(),
+    &pyth_price_account.load()?.id.to_le_bytes()
+    ],
+    bump,
+    )]
+    pub pyth_price_account: AccountLoader<'info, PriceAccount>,
+    /// CHECK: whitelist
+    pub accumulator_whitelist: UncheckedAccount<'info>,
+    /// A second authority of this program, writing into the message buffer
+    /// owned by `allowed_program_auth`
+    #[account(
+        seeds = [UPD_PRICE_WRITER.as_bytes(), message_buffer_program.key().as_ref()],
+        owner = system_program::System::id(),
+        bump,
+    )]
+    pub writer_auth: SystemAccount<'info>,
+    pub message_buffer_program: Program<'info, MessageBufferProgram>,
+    // remaining_accounts: the `BufferWriters` and `MessageBuffer` of the
+    // price account
+}
+
+/// Updates the mock pyth price account and calls message_buffer
+/// put_all_as_writer ix
+pub fn update_price_as_writer<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdatePriceAsWriter<'info>>,
+    params: UpdatePriceParams,
+    allowed_program_auth: Pubkey,
+) -> Result<()> {
+    let mut inputs = vec![];
+
+    {
+        let pyth_price_acct = &mut ctx.accounts.pyth_price_account.load_mut()?;
+        pyth_price_acct.update(params)?;
+
+        inputs.push(FullPriceMessage::from(&**pyth_price_acct).accumulator_serialize()?);
+        inputs.push(CompactPriceMessage::from(&**pyth_price_acct).accumulator_serialize()?);
+    }
+
+    UpdatePriceAsWriter::emit_messages(ctx, allowed_program_auth, inputs)
+}
+
+impl<'info> UpdatePriceAsWriter<'info> {
+    /// Invoke message_buffer::put_all_as_writer ix cpi call
+    pub fn emit_messages(
+        ctx: Context<'_, '_, '_, 'info, UpdatePriceAsWriter<'info>>,
+        allowed_program_auth: Pubkey,
+        values: Vec<Vec<u8>>,
+    ) -> anchor_lang::Result<()> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(ctx.accounts.accumulator_whitelist.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.writer_auth.key(), true),
+        ];
+        // the `BufferWriters` is read-only
+        accounts.extend(ctx.remaining_accounts.iter().map(|a| {
+            if a.is_writable {
+                AccountMeta::new(a.key(), false)
+            } else {
+                AccountMeta::new_readonly(a.key(), false)
+            }
+        }));
+        let put_all_as_writer_ix = anchor_lang::solana_program::instruction::Instruction {
+            program_id: ctx.accounts.message_buffer_program.key(),
+            accounts,
+            data: (
+                sighash("global", "put_all_as_writer"),
+                allowed_program_auth,
+                ctx.accounts.pyth_price_account.key(),
+                values,
+            )
+                .try_to_vec()
+                .unwrap(),
+        };
+        let account_infos = &mut ctx.accounts.to_account_infos();
+        account_infos.extend_from_slice(ctx.remaining_accounts);
+        let bump = *ctx.bumps.get("writer_auth").unwrap();
+        anchor_lang::solana_program::program::invoke_signed(
+            &put_all_as_writer_ix,
+            account_infos,
+            &[&[
+                UPD_PRICE_WRITER.as_bytes(),
+                ctx.accounts.message_buffer_program.key().as_ref(),
+                &[bump],
+            ]],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use {
+    crate::{
+        instructions::{sighash, UpdatePriceParams, UPD_PRICE_WRITE},
+        message::{
+            price::{CompactPriceMessage, FullPriceMessage},
+            AccumulatorSerializer,
+        },
+        state::PriceAccount,
+    },
+    anchor_lang::{prelude::*, system_program},
+    message_buffer::{
+        instructions::BufferMessages, program::MessageBuffer as MessageBufferProgram,
+    },
+};
+
+#[derive(Accounts)]
+pub struct UpdatePrices<'info> {
+    /// CHECK: whitelist
+    pub accumulator_whitelist: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"upd_price_write".as_ref(), message_buffer_program.key().as_ref()],
+        owner = system_program::System::id(),
+        bump,
+    )]
+    pub auth: SystemAccount<'info>,
+    pub message_buffer_program: Program<'info, MessageBufferProgram>,
+    // remaining_accounts: the mock pyth price accounts followed by their
+    // message buffers, in the same order as `params`
+}
+
+/// Updates several mock pyth price accounts and calls message_buffer
+/// put_batch ix once for all of them
+pub fn update_prices<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdatePrices<'info>>,
+    params: Vec<UpdatePriceParams>,
+) -> Result<()> {
+    let (price_accounts, message_buffers) = ctx.remaining_accounts.split_at(params.len());
+    let mut batch = vec![];
+
+    for (price_account_ai, params) in price_accounts.iter().zip(params) {
+        let price_account = AccountLoader::<PriceAccount>::try_from(price_account_ai)?;
+        let pyth_price_acct = &mut price_account.load_mut()?;
+        pyth_price_acct.update(params)?;
+
+        batch.push(BufferMessages {
+            base_account_key: price_account_ai.key(),
+            messages: vec![
+                FullPriceMessage::from(&**pyth_price_acct).accumulator_serialize()?,
+                CompactPriceMessage::from(&**pyth_price_acct).accumulator_serialize()?,
+            ],
+        });
+    }
+
+    UpdatePrices::emit_messages(&ctx, message_buffers, batch)
+}
+
+impl<'info> UpdatePrices<'info> {
+    /// Invoke message_buffer::put_batch ix cpi call
+    pub fn emit_messages(
+        ctx: &Context<'_, '_, '_, 'info, UpdatePrices<'info>>,
+        message_buffers: &[AccountInfo<'info>],
+        batch: Vec<BufferMessages>,
+    ) -> anchor_lang::Result<()> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(ctx.accounts.accumulator_whitelist.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.auth.key(), true),
+        ];
+        accounts.extend(
+            message_buffers
+                .iter()
+                .map(|a| AccountMeta::new(a.key(), false)),
+        );
+        let put_batch_ix = anchor_lang::solana_program::instruction::Instruction {
+            program_id: ctx.accounts.message_buffer_program.key(),
+            accounts,
+            data: (sighash("global", "put_batch"), batch)
+                .try_to_vec()
+                .unwrap(),
+        };
+        let account_infos = &mut ctx.accounts.to_account_infos();
+        account_infos.extend_from_slice(message_buffers);
+        let bump = *ctx.bumps.get("auth").unwrap();
+        anchor_lang::solana_program::program::invoke_signed(
+            &put_batch_ix,
+            account_infos,
+            &[&[
+                UPD_PRICE_WRITE.as_bytes(),
+                ctx.accounts.message_buffer_program.key().as_ref(),
+                &[bump],
+            ]],
+        )?;
+        Ok(())
+    }
+}
@@ -29,6 +29,25 @@ pub mod mock_cpi_caller {
         instructions::update_price(ctx, params)
     }
 
+    /// Updates several `PriceAccount`s and writes all of their messages
+    /// with a single CPI
+    pub fn update_prices<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdatePrices<'info>>,
+        params: Vec<UpdatePriceParams>,
+    ) -> Result<()> {
+        instructions::update_prices(ctx, params)
+    }
+
+    /// Updates a `PriceAccount` and writes its messages into the buffer
+    /// owned by `allowed_program_auth`, as one of the buffer's writers
+    pub fn update_price_as_writer<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdatePriceAsWriter<'info>>,
+        params: UpdatePriceParams,
+        allowed_program_auth: Pubkey,
+    ) -> Result<()> {
+        instructions::update_price_as_writer(ctx, params, allowed_program_auth)
+    }
+
     /// num_messages is the number of 1kb messages to send to the CPI
     pub fn cpi_max_test<'info>(
         ctx: Context<'_, '_, '_, 'info, UpdatePrice<'info>>,
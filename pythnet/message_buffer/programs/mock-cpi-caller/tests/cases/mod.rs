@@ -9,9 +9,16 @@ pub use {
     },
 };
 
+mod test_compute_units;
 mod test_create_buffer;
 mod test_delete_buffer;
 mod test_initialize;
+mod test_message_stats;
+mod test_migrate_buffer;
 mod test_put_all;
+mod test_put_all_as_writer;
+mod test_put_batch;
 mod test_resize_buffer;
 mod test_set_allowed_programs;
+mod test_set_buffer_writers;
+mod test_whitelist_admin;
@@ -0,0 +1,181 @@
+//! Compute unit benchmarks for writing messages to the message buffer.
+//!
+//! The buffer write runs on the oracle hot path for every price update,
+//! so these fail when a change pushes consumption over the thresholds
+//! below. The thresholds cover the whole mock caller transaction (price
+//! update, message serialization and the CPI) and only apply to the BPF
+//! programs; set them to the measured values plus `MARGIN_PCT` percent.
+use super::*;
+
+/// Headroom over the measured compute units before a benchmark fails
+const MARGIN_PCT: u64 = 10;
+/// Compute units an instruction gets without a compute budget instruction.
+/// No budget may exceed it, since the oracle doesn't request more.
+const DEFAULT_INSTRUCTION_COMPUTE_UNITS: u64 = 200_000;
+
+/// One price update writing the full and compact price messages
+const UPDATE_PRICE_MAX_COMPUTE_UNITS: u64 = 40_000;
+/// `BATCH_SIZE` price updates written to their buffers with one `put_batch`
+const UPDATE_PRICES_MAX_COMPUTE_UNITS: u64 = 200_000;
+/// (close to) the largest amount of message data that fits in one CPI
+const MAX_BYTES_MAX_COMPUTE_UNITS: u64 = 150_000;
+/// The maximum number of messages a buffer can hold
+const MAX_MESSAGES_MAX_COMPUTE_UNITS: u64 = 200_000;
+
+const BATCH_SIZE: u64 = 8;
+
+/// Fails if `compute_units` exceeds `budget`, reporting the measured
+/// compute units so that the budget can be updated
+fn check_compute_units(name: &str, compute_units: u64, budget: u64) {
+    println!("{name}: {compute_units} CU");
+    assert!(budget <= DEFAULT_INSTRUCTION_COMPUTE_UNITS);
+    assert!(
+        compute_units <= budget,
+        "{name} used {compute_units} CU, over its budget of {budget} CU. If the increase is \
+         expected, set the budget to {} CU",
+        compute_units * (100 + MARGIN_PCT) / 100
+    );
+}
+
+async fn setup(ids: &[u64], target_size: u32) -> MessageBufferTestContext {
+    let mut context =
+        MessageBufferTestContext::initialize_with_default_test_allowed_programs(false)
+            .await
+            .unwrap();
+
+    let payer = context.payer.pubkey();
+    let whitelist = context.whitelist();
+    let cpi_caller_auth = MessageBufferTestContext::get_mock_cpi_auth();
+    for id in ids {
+        context.create_buffer(*id, target_size).await.unwrap();
+        context
+            .add_price((*id, 1, 1, 1, 1), payer, whitelist, cpi_caller_auth)
+            .await
+            .unwrap();
+    }
+    context
+}
+
+async fn update_price_compute_units(context: &mut MessageBufferTestContext, id: u64) -> u64 {
+    let cpi_caller_auth = MessageBufferTestContext::get_mock_cpi_auth();
+    let (msg_buffer_pda, _) = find_msg_buffer_pda(
+        cpi_caller_auth,
+        MessageBufferTestContext::get_mock_pyth_price_account(id),
+    );
+    let update_price_ix = update_price_ix(
+        (id, 2, 3, 4, 5),
+        context.whitelist(),
+        cpi_caller_auth,
+        msg_buffer_pda,
+    );
+    context
+        .process_ixs_with_compute_units(&[update_price_ix], vec![])
+        .await
+        .unwrap()
+}
+
+async fn cpi_max_test_compute_units(
+    context: &mut MessageBufferTestContext,
+    msg_sizes: &Vec<u16>,
+) -> u64 {
+    let cpi_caller_auth = MessageBufferTestContext::get_mock_cpi_auth();
+    let (msg_buffer_pda, _) = MessageBufferTestContext::default_msg_buffer();
+    let cpi_max_test_ix = cpi_max_test_ix(
+        MessageBufferTestContext::DEFAULT_ADD_PRICE_PARAMS,
+        msg_sizes,
+        context.whitelist(),
+        cpi_caller_auth,
+        msg_buffer_pda,
+    );
+    let compute_units = context
+        .process_ixs_with_compute_units(&[cpi_max_test_ix], vec![])
+        .await
+        .unwrap();
+
+    // make sure every message was actually written
+    let msg_buffer_account_data = context
+        .fetch_msg_buffer_account_data(&msg_buffer_pda)
+        .await
+        .unwrap();
+    let (_, _, _, end_offsets) = deserialize_msg_buffer_header(&msg_buffer_account_data);
+    assert_eq!(
+        end_offsets.iter().take_while(|end| **end != 0).count(),
+        msg_sizes.len()
+    );
+
+    compute_units
+}
+
+#[tokio::test]
+async fn bench_update_price() {
+    let id = MessageBufferTestContext::DEFAULT_TEST_PRICE_ID;
+    let mut context = setup(&[id], MessageBufferTestContext::DEFAULT_TARGET_SIZE).await;
+
+    // the first write after `add_price` and a steady state write
+    for _ in 0..2 {
+        let compute_units = update_price_compute_units(&mut context, id).await;
+        check_compute_units(
+            "update_price",
+            compute_units,
+            UPDATE_PRICE_MAX_COMPUTE_UNITS,
+        );
+    }
+}
+
+#[tokio::test]
+async fn bench_update_prices() {
+    let ids = (0..BATCH_SIZE).collect::<Vec<_>>();
+    let mut context = setup(&ids, MessageBufferTestContext::DEFAULT_TARGET_SIZE).await;
+
+    let params = ids.iter().map(|id| (*id, 2, 3, 4, 5)).collect::<Vec<_>>();
+    let cpi_caller_auth = MessageBufferTestContext::get_mock_cpi_auth();
+    let msg_buffers = ids
+        .iter()
+        .map(|id| {
+            find_msg_buffer_pda(
+                cpi_caller_auth,
+                MessageBufferTestContext::get_mock_pyth_price_account(*id),
+            )
+            .0
+        })
+        .collect::<Vec<_>>();
+    let update_prices_ix =
+        update_prices_ix(&params, context.whitelist(), cpi_caller_auth, &msg_buffers);
+
+    let compute_units = context
+        .process_ixs_with_compute_units(&[update_prices_ix], vec![])
+        .await
+        .unwrap();
+    check_compute_units(
+        &format!("update_prices ({BATCH_SIZE} prices)"),
+        compute_units,
+        UPDATE_PRICES_MAX_COMPUTE_UNITS,
+    );
+}
+
+#[tokio::test]
+async fn bench_put_all_max_bytes() {
+    let mut context = setup(&[MessageBufferTestContext::DEFAULT_TEST_PRICE_ID], 10_240).await;
+
+    // 9 * (7 + 1024) bytes of messages, just under the 10KB CPI data limit
+    let msg_sizes = vec![1024; 9];
+    let compute_units = cpi_max_test_compute_units(&mut context, &msg_sizes).await;
+    check_compute_units(
+        &format!("put_all ({} messages)", msg_sizes.len()),
+        compute_units,
+        MAX_BYTES_MAX_COMPUTE_UNITS,
+    );
+}
+
+#[tokio::test]
+async fn bench_put_all_max_messages() {
+    let mut context = setup(&[MessageBufferTestContext::DEFAULT_TEST_PRICE_ID], 10_240).await;
+
+    let msg_sizes = vec![16; u8::MAX as usize];
+    let compute_units = cpi_max_test_compute_units(&mut context, &msg_sizes).await;
+    check_compute_units(
+        &format!("put_all ({} messages)", msg_sizes.len()),
+        compute_units,
+        MAX_MESSAGES_MAX_COMPUTE_UNITS,
+    );
+}
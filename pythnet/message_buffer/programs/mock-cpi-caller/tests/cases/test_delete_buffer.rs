@@ -70,3 +70,63 @@ async fn delete_buffer_after_update_allowed_programs() {
     let payer_lamports_after = context.get_balance(payer).await;
     assert!(payer_lamports_before < payer_lamports_after);
 }
+
+#[tokio::test]
+async fn test_delete_buffer_with_buffer_writers() {
+    let mut context = MessageBufferTestContext::initialize_with_default_test_buffer(
+        false,
+        MessageBufferTestContext::DEFAULT_TARGET_SIZE,
+    )
+    .await
+    .unwrap();
+
+    let id = MessageBufferTestContext::DEFAULT_TEST_PRICE_ID;
+    let writers = vec![(MessageBufferTestContext::get_mock_cpi_auth(), 2, 512)];
+    context.set_buffer_writers(id, &writers).await.unwrap();
+    let (msg_buffer_pda, _) = MessageBufferTestContext::default_msg_buffer();
+
+    context.delete_buffer(id).await.unwrap();
+
+    assert!(context
+        .fetch_msg_buffer_account_data(&msg_buffer_pda)
+        .await
+        .is_none());
+    assert!(context.fetch_buffer_writers(id).await.is_none());
+
+    // a buffer recreated at the same address starts without writers
+    context
+        .create_buffer(id, MessageBufferTestContext::DEFAULT_TARGET_SIZE)
+        .await
+        .unwrap();
+    assert!(context.fetch_buffer_writers(id).await.is_none());
+}
+
+#[tokio::test]
+async fn delete_buffer_without_buffer_writers_should_fail() {
+    let mut context = MessageBufferTestContext::initialize_with_default_test_buffer(
+        false,
+        MessageBufferTestContext::DEFAULT_TARGET_SIZE,
+    )
+    .await
+    .unwrap();
+
+    let id = MessageBufferTestContext::DEFAULT_TEST_PRICE_ID;
+    let writers = vec![(MessageBufferTestContext::get_mock_cpi_auth(), 2, 512)];
+    context.set_buffer_writers(id, &writers).await.unwrap();
+    let (msg_buffer_pda, _) = MessageBufferTestContext::default_msg_buffer();
+
+    // the program id stands for an omitted optional account
+    let res = context
+        .delete_buffer_with_buffer_writers_account(id, ::message_buffer::id())
+        .await;
+    assert_eq!(
+        ProgramError::from(res.unwrap_err()),
+        ProgramError::Custom(anchor_lang::error::ErrorCode::ConstraintSeeds.into())
+    );
+
+    assert!(context
+        .fetch_msg_buffer_account_data(&msg_buffer_pda)
+        .await
+        .is_some());
+    assert!(context.fetch_buffer_writers(id).await.is_some());
+}
@@ -0,0 +1,135 @@
+use super::*;
+
+#[tokio::test]
+async fn test_create_message_stats() {
+    let mut context = MessageBufferTestContext::initialize_with_default_test_buffer(
+        false,
+        MessageBufferTestContext::DEFAULT_TARGET_SIZE,
+    )
+    .await
+    .unwrap();
+
+    let id = MessageBufferTestContext::DEFAULT_TEST_PRICE_ID;
+    assert!(context.fetch_message_stats(id).await.is_none());
+
+    let (_, message_stats_bump) = context.create_message_stats(id).await.unwrap();
+
+    let (bump, types) = context.fetch_message_stats(id).await.unwrap();
+    assert_eq!(bump, message_stats_bump);
+    assert_eq!(types, vec![(0, 0, 0); 8]);
+}
+
+#[tokio::test]
+async fn create_message_stats_without_buffer_should_fail() {
+    let mut context =
+        MessageBufferTestContext::initialize_with_default_test_allowed_programs(false)
+            .await
+            .unwrap();
+
+    let res = context
+        .create_message_stats(MessageBufferTestContext::DEFAULT_TEST_PRICE_ID)
+        .await;
+
+    assert!(res.is_err());
+
+    let err: ProgramError = res.unwrap_err().into();
+    // the uninitialized message buffer is still owned by the system program
+    assert_eq!(
+        err,
+        ProgramError::Custom(anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram.into())
+    );
+}
+
+#[tokio::test]
+async fn test_put_all_updates_message_stats() {
+    let mut context = MessageBufferTestContext::initialize_with_default_test_buffer(
+        false,
+        MessageBufferTestContext::DEFAULT_TARGET_SIZE,
+    )
+    .await
+    .unwrap();
+
+    let id = MessageBufferTestContext::DEFAULT_TEST_PRICE_ID;
+    context.create_message_stats(id).await.unwrap();
+
+    let payer = context.payer.pubkey();
+    let whitelist = context.whitelist();
+    let cpi_caller_auth = MessageBufferTestContext::get_mock_cpi_auth();
+
+    context.warp_to_slot(100);
+    context
+        .add_price_with_message_stats(
+            MessageBufferTestContext::DEFAULT_ADD_PRICE_PARAMS,
+            payer,
+            whitelist,
+            cpi_caller_auth,
+        )
+        .await
+        .unwrap();
+
+    let (_, types) = context.fetch_message_stats(id).await.unwrap();
+    // full price message: size_of(price::MessageHeader) + FullPriceMessage::SIZE
+    assert_eq!(types[0], (1, 7 + 40, 100));
+    // compact price message: size_of(price::MessageHeader) + CompactPriceMessage::SIZE
+    assert_eq!(types[1], (1, 7 + 24, 100));
+    assert_eq!(&types[2..], &[(0, 0, 0); 6]);
+}
+
+#[tokio::test]
+async fn test_put_all_without_message_stats() {
+    let mut context = MessageBufferTestContext::initialize_with_default_test_buffer(
+        false,
+        MessageBufferTestContext::DEFAULT_TARGET_SIZE,
+    )
+    .await
+    .unwrap();
+
+    let id = MessageBufferTestContext::DEFAULT_TEST_PRICE_ID;
+    context.create_message_stats(id).await.unwrap();
+
+    let payer = context.payer.pubkey();
+    let whitelist = context.whitelist();
+    let cpi_caller_auth = MessageBufferTestContext::get_mock_cpi_auth();
+
+    // the stats account is optional, callers that don't pass it still work
+    context
+        .add_price(
+            MessageBufferTestContext::DEFAULT_ADD_PRICE_PARAMS,
+            payer,
+            whitelist,
+            cpi_caller_auth,
+        )
+        .await
+        .unwrap();
+
+    let (_, types) = context.fetch_message_stats(id).await.unwrap();
+    assert_eq!(types, vec![(0, 0, 0); 8]);
+}
+
+#[tokio::test]
+async fn test_delete_buffer_with_message_stats() {
+    let mut context = MessageBufferTestContext::initialize_with_default_test_buffer(
+        false,
+        MessageBufferTestContext::DEFAULT_TARGET_SIZE,
+    )
+    .await
+    .unwrap();
+
+    let id = MessageBufferTestContext::DEFAULT_TEST_PRICE_ID;
+    context.create_message_stats(id).await.unwrap();
+    let (msg_buffer_pda, _) = MessageBufferTestContext::default_msg_buffer();
+
+    let payer = context.payer.pubkey();
+    let payer_lamports_before = context.get_balance(payer).await;
+
+    context.delete_buffer_with_message_stats(id).await.unwrap();
+
+    assert!(context
+        .fetch_msg_buffer_account_data(&msg_buffer_pda)
+        .await
+        .is_none());
+    assert!(context.fetch_message_stats(id).await.is_none());
+
+    let payer_lamports_after = context.get_balance(payer).await;
+    assert!(payer_lamports_before < payer_lamports_after);
+}
@@ -0,0 +1,118 @@
+use super::*;
+
+#[tokio::test]
+async fn test_migrate_buffer() {
+    let mut context = MessageBufferTestContext::initialize_with_default_test_buffer(
+        false,
+        MessageBufferTestContext::DEFAULT_TARGET_SIZE,
+    )
+    .await
+    .unwrap();
+
+    let payer = context.payer.pubkey();
+    let whitelist = context.whitelist();
+    let cpi_caller_auth = MessageBufferTestContext::get_mock_cpi_auth();
+    let (msg_buffer_pda, _) = MessageBufferTestContext::default_msg_buffer();
+    let id = MessageBufferTestContext::DEFAULT_TEST_PRICE_ID;
+
+    // buffers at the current version are left untouched
+    context
+        .add_price(
+            MessageBufferTestContext::DEFAULT_ADD_PRICE_PARAMS,
+            payer,
+            whitelist,
+            cpi_caller_auth,
+        )
+        .await
+        .unwrap();
+    let msg_buffer_account_data = context
+        .fetch_msg_buffer_account_data(&msg_buffer_pda)
+        .await
+        .unwrap();
+    context.migrate_buffer(id).await.unwrap();
+    assert_eq!(
+        context
+            .fetch_msg_buffer_account_data(&msg_buffer_pda)
+            .await
+            .unwrap(),
+        msg_buffer_account_data
+    );
+
+    // buffers written by a previous version can only be overwritten once migrated
+    context.set_msg_buffer_version(id, 0).await;
+    let res = context.update_prices(&[(id, 6, 7, 8, 9)]).await;
+    assert!(res.is_err());
+    let err: ProgramError = res.unwrap_err().into();
+    assert_eq!(
+        err,
+        ProgramError::Custom(MessageBufferError::BufferVersionMismatch.into())
+    );
+
+    context.migrate_buffer(id).await.unwrap();
+    let msg_buffer_account_data = context
+        .fetch_msg_buffer_account_data(&msg_buffer_pda)
+        .await
+        .unwrap();
+    let (_, version, header_len, end_offsets) =
+        deserialize_msg_buffer_header(&msg_buffer_account_data);
+    assert_eq!(version, 1);
+    assert_eq!(header_len, 522);
+    assert_eq!(end_offsets, [0u16; 255]);
+
+    context.update_prices(&[(id, 6, 7, 8, 9)]).await.unwrap();
+}
+
+#[tokio::test]
+async fn migrate_buffer_from_newer_version_should_fail() {
+    let mut context = MessageBufferTestContext::initialize_with_default_test_buffer(
+        false,
+        MessageBufferTestContext::DEFAULT_TARGET_SIZE,
+    )
+    .await
+    .unwrap();
+
+    let id = MessageBufferTestContext::DEFAULT_TEST_PRICE_ID;
+    context.set_msg_buffer_version(id, 2).await;
+
+    let res = context.migrate_buffer(id).await;
+
+    assert!(res.is_err());
+
+    let err: ProgramError = res.unwrap_err().into();
+    assert_eq!(
+        err,
+        ProgramError::Custom(MessageBufferError::BufferVersionMismatch.into())
+    );
+}
+
+#[tokio::test]
+async fn migrate_buffer_with_invalid_admin_should_fail() {
+    let mut context = MessageBufferTestContext::initialize_with_default_test_buffer(
+        false,
+        MessageBufferTestContext::DEFAULT_TARGET_SIZE,
+    )
+    .await
+    .unwrap();
+
+    let (msg_buffer_pda, _) = MessageBufferTestContext::default_msg_buffer();
+    let invalid_admin = Keypair::new();
+    let invalid_migrate_ix = migrate_msg_buffer_ix(
+        MessageBufferTestContext::get_mock_cpi_auth(),
+        MessageBufferTestContext::default_pyth_price_account(),
+        context.whitelist(),
+        invalid_admin.pubkey(),
+        msg_buffer_pda,
+    );
+
+    let res = context
+        .process_ixs(&[invalid_migrate_ix], vec![&invalid_admin])
+        .await;
+
+    assert!(res.is_err());
+
+    let err: ProgramError = res.unwrap_err().into();
+    assert_eq!(
+        err,
+        ProgramError::Custom(anchor_lang::error::ErrorCode::ConstraintHasOne.into())
+    )
+}
@@ -0,0 +1,123 @@
+use super::*;
+
+/// Creates the default buffer with a price account, and allows the writer
+/// authority of the mock cpi caller to write into the buffer with the
+/// given limits
+async fn setup_writer(max_messages: u8, max_bytes: u16) -> MessageBufferTestContext {
+    let mut context = MessageBufferTestContext::initialize_with_default_test_buffer(
+        false,
+        MessageBufferTestContext::DEFAULT_TARGET_SIZE,
+    )
+    .await
+    .unwrap();
+
+    let payer = context.payer.pubkey();
+    let whitelist = context.whitelist();
+    let cpi_caller_auth = MessageBufferTestContext::get_mock_cpi_auth();
+    context
+        .add_price(
+            MessageBufferTestContext::DEFAULT_ADD_PRICE_PARAMS,
+            payer,
+            whitelist,
+            cpi_caller_auth,
+        )
+        .await
+        .unwrap();
+
+    let writer_auth = MessageBufferTestContext::get_mock_cpi_writer_auth();
+    context
+        .set_allowed_programs(&vec![cpi_caller_auth, writer_auth])
+        .await
+        .unwrap();
+    context
+        .set_buffer_writers(
+            MessageBufferTestContext::DEFAULT_TEST_PRICE_ID,
+            &vec![(writer_auth, max_messages, max_bytes)],
+        )
+        .await
+        .unwrap();
+    context
+}
+
+#[tokio::test]
+async fn test_put_all_as_writer() {
+    let mut context = setup_writer(2, 512).await;
+
+    let (id, price, price_expo, ema, ema_expo) = (0, 6, 7, 8, 9);
+    context
+        .update_price_as_writer((id, price, price_expo, ema, ema_expo))
+        .await
+        .unwrap();
+
+    let (msg_buffer_pda, _) = MessageBufferTestContext::default_msg_buffer();
+    let msg_buffer_account_data = context
+        .fetch_msg_buffer_account_data(&msg_buffer_pda)
+        .await
+        .unwrap();
+    let (_, _, header_len, end_offsets) = deserialize_msg_buffer_header(&msg_buffer_account_data);
+    assert_eq!(&end_offsets[..2], &[7 + 40, 7 + 40 + 7 + 24]);
+
+    // the writer overwrote the messages of the owner of the buffer
+    let msgs = extract_msg_buffer_messages(header_len, end_offsets, &msg_buffer_account_data);
+    validate_price_msgs(id, price, price_expo, ema, ema_expo, &msgs).unwrap();
+}
+
+#[tokio::test]
+async fn put_all_as_writer_not_in_buffer_writers_should_fail() {
+    let mut context = setup_writer(2, 512).await;
+    context
+        .set_buffer_writers(MessageBufferTestContext::DEFAULT_TEST_PRICE_ID, &vec![])
+        .await
+        .unwrap();
+
+    let res = context
+        .update_price_as_writer(MessageBufferTestContext::DEFAULT_ADD_PRICE_PARAMS)
+        .await;
+
+    assert!(res.is_err());
+
+    let err: ProgramError = res.unwrap_err().into();
+    assert_eq!(
+        err,
+        ProgramError::Custom(MessageBufferError::CallerNotAllowed.into())
+    );
+}
+
+#[tokio::test]
+async fn put_all_as_writer_removed_from_whitelist_should_fail() {
+    let mut context = setup_writer(2, 512).await;
+    context
+        .set_allowed_programs(&MessageBufferTestContext::default_allowed_programs())
+        .await
+        .unwrap();
+
+    let res = context
+        .update_price_as_writer(MessageBufferTestContext::DEFAULT_ADD_PRICE_PARAMS)
+        .await;
+
+    assert!(res.is_err());
+
+    let err: ProgramError = res.unwrap_err().into();
+    assert_eq!(
+        err,
+        ProgramError::Custom(MessageBufferError::CallerNotAllowed.into())
+    );
+}
+
+#[tokio::test]
+async fn put_all_as_writer_over_limits_should_fail() {
+    // the mock cpi caller writes two messages
+    let mut context = setup_writer(1, 512).await;
+
+    let res = context
+        .update_price_as_writer(MessageBufferTestContext::DEFAULT_ADD_PRICE_PARAMS)
+        .await;
+
+    assert!(res.is_err());
+
+    let err: ProgramError = res.unwrap_err().into();
+    assert_eq!(
+        err,
+        ProgramError::Custom(MessageBufferError::WriterLimitExceeded.into())
+    );
+}
@@ -0,0 +1,175 @@
+use super::*;
+
+async fn setup_two_prices() -> (MessageBufferTestContext, Vec<AddPriceParams>) {
+    let mut context = MessageBufferTestContext::initialize_with_default_test_buffer(
+        false,
+        MessageBufferTestContext::DEFAULT_TARGET_SIZE,
+    )
+    .await
+    .unwrap();
+    context
+        .create_buffer(1, MessageBufferTestContext::DEFAULT_TARGET_SIZE)
+        .await
+        .unwrap();
+
+    let payer = context.payer.pubkey();
+    let whitelist = context.whitelist();
+    let cpi_caller_auth = MessageBufferTestContext::get_mock_cpi_auth();
+    for id in [0, 1] {
+        context
+            .add_price((id, 1, 1, 1, 1), payer, whitelist, cpi_caller_auth)
+            .await
+            .unwrap();
+    }
+
+    (context, vec![(0, 2, 3, 4, 5), (1, 6, 7, 8, 9)])
+}
+
+#[tokio::test]
+async fn test_put_batch() {
+    let (mut context, params) = setup_two_prices().await;
+
+    context.update_prices(&params).await.unwrap();
+
+    let cpi_caller_auth = MessageBufferTestContext::get_mock_cpi_auth();
+    for (id, price, price_expo, ema, ema_expo) in params {
+        let (msg_buffer_pda, _) = find_msg_buffer_pda(
+            cpi_caller_auth,
+            MessageBufferTestContext::get_mock_pyth_price_account(id),
+        );
+        let msg_buffer_account_data = context
+            .fetch_msg_buffer_account_data(&msg_buffer_pda)
+            .await
+            .unwrap();
+        let (_, _, header_len, end_offsets) =
+            deserialize_msg_buffer_header(&msg_buffer_account_data);
+
+        // each buffer only holds the messages of its own price account
+        assert_eq!(&end_offsets[..2], &[7 + 40, 7 + 40 + 7 + 24]);
+        assert_eq!(&end_offsets[2..], &[0u16; 253]);
+
+        let msgs = extract_msg_buffer_messages(header_len, end_offsets, &msg_buffer_account_data);
+        validate_price_msgs(id, price, price_expo, ema, ema_expo, &msgs).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_put_batch_updates_message_stats() {
+    let (mut context, params) = setup_two_prices().await;
+    for (id, ..) in &params {
+        context.create_message_stats(*id).await.unwrap();
+    }
+
+    context.warp_to_slot(100);
+    context
+        .update_prices_with_message_stats(&params)
+        .await
+        .unwrap();
+    context.warp_to_slot(200);
+    context
+        .update_prices_with_message_stats(&params[..1])
+        .await
+        .unwrap();
+
+    let (_, types) = context.fetch_message_stats(0).await.unwrap();
+    assert_eq!(types[0], (2, 2 * (7 + 40), 200));
+    assert_eq!(types[1], (2, 2 * (7 + 24), 200));
+    assert_eq!(&types[2..], &[(0, 0, 0); 6]);
+
+    let (_, types) = context.fetch_message_stats(1).await.unwrap();
+    assert_eq!(types[0], (1, 7 + 40, 100));
+    assert_eq!(types[1], (1, 7 + 24, 100));
+    assert_eq!(&types[2..], &[(0, 0, 0); 6]);
+}
+
+#[tokio::test]
+async fn put_batch_with_mismatched_message_stats_should_fail() {
+    let (mut context, params) = setup_two_prices().await;
+    for (id, ..) in &params {
+        context.create_message_stats(*id).await.unwrap();
+    }
+
+    // the stats accounts are passed in the wrong order
+    let cpi_caller_auth = MessageBufferTestContext::get_mock_cpi_auth();
+    let price_accounts = params
+        .iter()
+        .map(|(id, ..)| MessageBufferTestContext::get_mock_pyth_price_account(*id))
+        .collect::<Vec<_>>();
+    let mut accounts = price_accounts
+        .iter()
+        .map(|price_account| find_msg_buffer_pda(cpi_caller_auth, *price_account).0)
+        .collect::<Vec<_>>();
+    accounts.extend(
+        price_accounts
+            .iter()
+            .rev()
+            .map(|price_account| find_message_stats_pda(cpi_caller_auth, *price_account).0),
+    );
+    let update_prices_ix =
+        update_prices_ix(&params, context.whitelist(), cpi_caller_auth, &accounts);
+
+    let res = context.process_ixs(&[update_prices_ix], vec![]).await;
+
+    assert!(res.is_err());
+
+    let err: ProgramError = res.unwrap_err().into();
+    assert_eq!(
+        err,
+        ProgramError::Custom(anchor_lang::error::ErrorCode::ConstraintSeeds.into())
+    );
+}
+
+#[tokio::test]
+async fn put_batch_with_missing_buffer_should_fail() {
+    let (mut context, params) = setup_two_prices().await;
+
+    let cpi_caller_auth = MessageBufferTestContext::get_mock_cpi_auth();
+    let (msg_buffer_pda, _) = MessageBufferTestContext::default_msg_buffer();
+    let update_prices_ix = update_prices_ix(
+        &params,
+        context.whitelist(),
+        cpi_caller_auth,
+        &[msg_buffer_pda],
+    );
+
+    let res = context.process_ixs(&[update_prices_ix], vec![]).await;
+
+    assert!(res.is_err());
+
+    let err: ProgramError = res.unwrap_err().into();
+    assert_eq!(
+        err,
+        ProgramError::Custom(MessageBufferError::MessageBufferNotProvided.into())
+    );
+}
+
+#[tokio::test]
+async fn put_batch_with_mismatched_buffer_should_fail() {
+    let (mut context, params) = setup_two_prices().await;
+
+    // the buffers are passed in the wrong order so neither matches its entry
+    let cpi_caller_auth = MessageBufferTestContext::get_mock_cpi_auth();
+    let msg_buffers = params
+        .iter()
+        .rev()
+        .map(|(id, ..)| {
+            find_msg_buffer_pda(
+                cpi_caller_auth,
+                MessageBufferTestContext::get_mock_pyth_price_account(*id),
+            )
+            .0
+        })
+        .collect::<Vec<_>>();
+    let update_prices_ix =
+        update_prices_ix(&params, context.whitelist(), cpi_caller_auth, &msg_buffers);
+
+    let res = context.process_ixs(&[update_prices_ix], vec![]).await;
+
+    assert!(res.is_err());
+
+    let err: ProgramError = res.unwrap_err().into();
+    assert_eq!(
+        err,
+        ProgramError::Custom(anchor_lang::error::ErrorCode::ConstraintSeeds.into())
+    );
+}
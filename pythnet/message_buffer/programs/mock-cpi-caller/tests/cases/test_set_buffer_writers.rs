@@ -0,0 +1,118 @@
+use super::*;
+
+#[tokio::test]
+async fn test_set_buffer_writers() {
+    let mut context = MessageBufferTestContext::initialize_with_default_test_buffer(
+        false,
+        MessageBufferTestContext::DEFAULT_TARGET_SIZE,
+    )
+    .await
+    .unwrap();
+
+    let id = MessageBufferTestContext::DEFAULT_TEST_PRICE_ID;
+    assert!(context.fetch_buffer_writers(id).await.is_none());
+
+    let writers = vec![(MessageBufferTestContext::get_mock_cpi_auth(), 2, 512)];
+    context.set_buffer_writers(id, &writers).await.unwrap();
+
+    let pyth_price_acct = MessageBufferTestContext::default_pyth_price_account();
+    let (_, buffer_writers_bump) = find_buffer_writers_pda(
+        MessageBufferTestContext::get_mock_cpi_auth(),
+        pyth_price_acct,
+    );
+    let (bump, updated_writers) = context.fetch_buffer_writers(id).await.unwrap();
+    assert_eq!(bump, buffer_writers_bump);
+    assert_eq!(updated_writers, writers);
+
+    // updating the writers replaces the entire list
+    context.set_buffer_writers(id, &vec![]).await.unwrap();
+    let (_, updated_writers) = context.fetch_buffer_writers(id).await.unwrap();
+    assert_eq!(updated_writers, vec![]);
+}
+
+#[tokio::test]
+async fn set_buffer_writers_not_in_whitelist_should_fail() {
+    let mut context = MessageBufferTestContext::initialize_with_default_test_buffer(
+        false,
+        MessageBufferTestContext::DEFAULT_TARGET_SIZE,
+    )
+    .await
+    .unwrap();
+
+    let writers = vec![(Pubkey::new_unique(), 2, 512)];
+    let res = context
+        .set_buffer_writers(MessageBufferTestContext::DEFAULT_TEST_PRICE_ID, &writers)
+        .await;
+
+    assert!(res.is_err());
+
+    let err: ProgramError = res.unwrap_err().into();
+    assert_eq!(
+        err,
+        ProgramError::Custom(MessageBufferError::CallerNotAllowed.into())
+    );
+}
+
+#[tokio::test]
+async fn set_buffer_writers_with_duplicate_writers_should_fail() {
+    let mut context = MessageBufferTestContext::initialize_with_default_test_buffer(
+        false,
+        MessageBufferTestContext::DEFAULT_TARGET_SIZE,
+    )
+    .await
+    .unwrap();
+
+    let cpi_caller_auth = MessageBufferTestContext::get_mock_cpi_auth();
+    let writers = vec![(cpi_caller_auth, 2, 512), (cpi_caller_auth, 1, 256)];
+    let res = context
+        .set_buffer_writers(MessageBufferTestContext::DEFAULT_TEST_PRICE_ID, &writers)
+        .await;
+
+    assert!(res.is_err());
+
+    let err: ProgramError = res.unwrap_err().into();
+    assert_eq!(
+        err,
+        ProgramError::Custom(MessageBufferError::InvalidBufferWriter.into())
+    );
+}
+
+#[tokio::test]
+async fn set_buffer_writers_with_invalid_admin_should_fail() {
+    let mut context = MessageBufferTestContext::initialize_with_default_test_buffer(
+        false,
+        MessageBufferTestContext::DEFAULT_TARGET_SIZE,
+    )
+    .await
+    .unwrap();
+
+    let pyth_price_acct = MessageBufferTestContext::default_pyth_price_account();
+    let cpi_caller_auth = MessageBufferTestContext::get_mock_cpi_auth();
+    let (msg_buffer_pda, _) = MessageBufferTestContext::default_msg_buffer();
+    let (buffer_writers_pda, _) = find_buffer_writers_pda(cpi_caller_auth, pyth_price_acct);
+    let invalid_admin = Keypair::new();
+
+    let invalid_set_buffer_writers_ix = set_buffer_writers_ix(
+        cpi_caller_auth,
+        pyth_price_acct,
+        &vec![(cpi_caller_auth, 2, 512)],
+        context.whitelist(),
+        invalid_admin.pubkey(),
+        context.payer.pubkey(),
+        msg_buffer_pda,
+        buffer_writers_pda,
+    );
+
+    let res = context
+        .process_ixs(&[invalid_set_buffer_writers_ix], vec![&invalid_admin])
+        .await;
+
+    assert!(res.is_err());
+
+    let err: ProgramError = res.unwrap_err().into();
+    // violates the whitelist has_one = admin constraint
+    assert_eq!(
+        err,
+        ProgramError::Custom(anchor_lang::error::ErrorCode::ConstraintHasOne.into())
+    )
+}
@@ -0,0 +1,206 @@
+use super::*;
+
+#[tokio::test]
+async fn test_update_whitelist_admin() {
+    let context = &mut MessageBufferTestContext::initialize_context(false).await;
+    let admin = Keypair::new();
+    context.initialize(&admin).await.unwrap();
+
+    let new_admin = Pubkey::new_unique();
+    context.update_whitelist_admin(new_admin).await.unwrap();
+
+    let (_, admin_pubkey, _, _) = context.fetch_whitelist().await.unwrap();
+    assert_eq!(new_admin, admin_pubkey);
+    assert!(context.fetch_whitelist_pending_admin().await.is_none());
+}
+
+#[tokio::test]
+async fn test_propose_and_accept_whitelist_admin() {
+    let context =
+        &mut MessageBufferTestContext::initialize_with_default_test_allowed_programs(false)
+            .await
+            .unwrap();
+    let admin = context.admin();
+    let new_admin = Keypair::new();
+
+    context
+        .propose_whitelist_admin(new_admin.pubkey())
+        .await
+        .unwrap();
+
+    // the admin doesn't change until the transfer is accepted
+    let (_, admin_pubkey, _, allowed_programs) = context.fetch_whitelist().await.unwrap();
+    assert_eq!(admin.pubkey(), admin_pubkey);
+    assert_eq!(
+        Some(new_admin.pubkey()),
+        context.fetch_whitelist_pending_admin().await
+    );
+
+    context.accept_whitelist_admin(&new_admin).await.unwrap();
+
+    let (_, admin_pubkey, _, updated_allowed_programs) = context.fetch_whitelist().await.unwrap();
+    assert_eq!(new_admin.pubkey(), admin_pubkey);
+    assert_eq!(allowed_programs, updated_allowed_programs);
+    assert!(context.fetch_whitelist_pending_admin().await.is_none());
+
+    // the new admin can update the whitelist
+    context.set_allowed_programs(&vec![]).await.unwrap();
+    let (_, _, allowed_programs_len, _) = context.fetch_whitelist().await.unwrap();
+    assert_eq!(0, allowed_programs_len);
+}
+
+#[tokio::test]
+async fn accept_whitelist_admin_by_other_signer_should_fail() {
+    let context = &mut MessageBufferTestContext::initialize_context(false).await;
+    let admin = Keypair::new();
+    context.initialize(&admin).await.unwrap();
+
+    // nothing proposed yet
+    let res = context.accept_whitelist_admin(&Keypair::new()).await;
+    assert_eq!(
+        ProgramError::from(res.unwrap_err()),
+        ProgramError::Custom(MessageBufferError::InvalidPendingAdmin.into())
+    );
+
+    let new_admin = Keypair::new();
+    context
+        .propose_whitelist_admin(new_admin.pubkey())
+        .await
+        .unwrap();
+
+    let res = context.accept_whitelist_admin(&Keypair::new()).await;
+    assert_eq!(
+        ProgramError::from(res.unwrap_err()),
+        ProgramError::Custom(MessageBufferError::InvalidPendingAdmin.into())
+    );
+
+    let (_, admin_pubkey, _, _) = context.fetch_whitelist().await.unwrap();
+    assert_eq!(admin.pubkey(), admin_pubkey);
+}
+
+#[tokio::test]
+async fn test_update_whitelist_admin_clears_pending_admin() {
+    let context = &mut MessageBufferTestContext::initialize_context(false).await;
+    let admin = Keypair::new();
+    context.initialize(&admin).await.unwrap();
+
+    let pending_admin = Keypair::new();
+    context
+        .propose_whitelist_admin(pending_admin.pubkey())
+        .await
+        .unwrap();
+    context
+        .update_whitelist_admin(Pubkey::new_unique())
+        .await
+        .unwrap();
+    assert!(context.fetch_whitelist_pending_admin().await.is_none());
+
+    let res = context.accept_whitelist_admin(&pending_admin).await;
+    assert_eq!(
+        ProgramError::from(res.unwrap_err()),
+        ProgramError::Custom(MessageBufferError::InvalidPendingAdmin.into())
+    );
+}
+
+#[tokio::test]
+async fn test_migrate_full_legacy_whitelist() {
+    let context = &mut MessageBufferTestContext::initialize_context(false).await;
+    let admin = Keypair::new();
+    context.initialize(&admin).await.unwrap();
+
+    // a legacy whitelist holding the maximum number of allowed programs has
+    // no room left for `pending_admin`
+    let mut allowed_programs = MessageBufferTestContext::default_allowed_programs();
+    allowed_programs.extend((1..32).map(|_| Pubkey::new_unique()));
+    context
+        .set_allowed_programs(&allowed_programs)
+        .await
+        .unwrap();
+    context.set_legacy_whitelist_size().await;
+    assert_eq!(1069, context.fetch_whitelist_len().await);
+
+    let new_admin = Keypair::new();
+    let res = context.propose_whitelist_admin(new_admin.pubkey()).await;
+    assert_eq!(
+        ProgramError::from(res.unwrap_err()),
+        ProgramError::Custom(anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    );
+
+    let res = context.migrate_whitelist(&Keypair::new()).await;
+    assert_eq!(
+        ProgramError::from(res.unwrap_err()),
+        ProgramError::Custom(anchor_lang::error::ErrorCode::ConstraintHasOne.into())
+    );
+
+    context.migrate_whitelist(&admin).await.unwrap();
+    assert_eq!(
+        8 + 1 + 32 + 4 + 32 * 32 + 1 + 32,
+        context.fetch_whitelist_len().await
+    );
+    assert!(context.fetch_whitelist_pending_admin().await.is_none());
+    let (_, admin_pubkey, _, updated_allowed_programs) = context.fetch_whitelist().await.unwrap();
+    assert_eq!(admin.pubkey(), admin_pubkey);
+    assert_eq!(allowed_programs, updated_allowed_programs);
+
+    // the whitelist can be loaded again, including by `put_all`
+    let id = MessageBufferTestContext::DEFAULT_TEST_PRICE_ID;
+    context
+        .create_buffer(id, MessageBufferTestContext::DEFAULT_TARGET_SIZE)
+        .await
+        .unwrap();
+    context
+        .add_price(
+            MessageBufferTestContext::DEFAULT_ADD_PRICE_PARAMS,
+            context.payer.pubkey(),
+            context.whitelist(),
+            MessageBufferTestContext::get_mock_cpi_auth(),
+        )
+        .await
+        .unwrap();
+
+    context
+        .propose_whitelist_admin(new_admin.pubkey())
+        .await
+        .unwrap();
+    context.accept_whitelist_admin(&new_admin).await.unwrap();
+    let (_, admin_pubkey, _, _) = context.fetch_whitelist().await.unwrap();
+    assert_eq!(new_admin.pubkey(), admin_pubkey);
+
+    // migrating again does nothing
+    context.migrate_whitelist(&new_admin).await.unwrap();
+    assert_eq!(
+        8 + 1 + 32 + 4 + 32 * 32 + 1 + 32,
+        context.fetch_whitelist_len().await
+    );
+}
+
+#[tokio::test]
+async fn test_migrate_legacy_whitelist_to_max_allowed_programs() {
+    let context = &mut MessageBufferTestContext::initialize_context(false).await;
+    let admin = Keypair::new();
+    context.initialize(&admin).await.unwrap();
+
+    let mut allowed_programs: Vec<Pubkey> = (0..31).map(|_| Pubkey::new_unique()).collect();
+    context
+        .set_allowed_programs(&allowed_programs)
+        .await
+        .unwrap();
+    context.set_legacy_whitelist_size().await;
+
+    // the 32nd program doesn't fit next to `pending_admin` until migrated
+    allowed_programs.push(Pubkey::new_unique());
+    let res = context.set_allowed_programs(&allowed_programs).await;
+    assert_eq!(
+        ProgramError::from(res.unwrap_err()),
+        ProgramError::Custom(anchor_lang::error::ErrorCode::AccountDidNotSerialize.into())
+    );
+
+    context.migrate_whitelist(&admin).await.unwrap();
+    context
+        .set_allowed_programs(&allowed_programs)
+        .await
+        .unwrap();
+    let (_, _, _, updated_allowed_programs) = context.fetch_whitelist().await.unwrap();
+    assert_eq!(allowed_programs, updated_allowed_programs);
+    assert!(context.fetch_whitelist_pending_admin().await.is_none());
+}
@@ -8,10 +8,11 @@ use {
         Id,
     },
     byteorder::{BigEndian, LittleEndian, ReadBytesExt},
-    message_buffer::instructions::{MESSAGE, WHITELIST},
+    message_buffer::instructions::{MESSAGE, STATS, WHITELIST, WRITERS},
     solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext},
     solana_sdk::{
-        account::ReadableAccount,
+        account::{AccountSharedData, ReadableAccount},
+        compute_budget::ComputeBudgetInstruction,
         signature::{Keypair, Signer},
         transaction::{Transaction, TransactionError},
     },
@@ -31,6 +32,9 @@ pub struct MessageBufferTestContext {
 impl MessageBufferTestContext {
     pub const DEFAULT_TEST_PRICE_ID: u64 = 0u64;
     pub const DEFAULT_TARGET_SIZE: u32 = 1024;
+    pub const MAX_COMPUTE_UNITS: u32 = 1_400_000;
+    /// Size of the whitelist before `pending_admin` was added
+    pub const LEGACY_WHITELIST_LEN: usize = 8 + 1 + 32 + 4 + 32 * 32;
     pub const DEFAULT_ADD_PRICE_PARAMS: AddPriceParams = (
         MessageBufferTestContext::DEFAULT_TEST_PRICE_ID,
         2u64,
@@ -95,6 +99,10 @@ impl MessageBufferTestContext {
         Ok(context)
     }
 
+    pub fn warp_to_slot(&mut self, slot: u64) {
+        self.context.warp_to_slot(slot).unwrap();
+    }
+
     pub async fn get_balance(&mut self, pubkey: Pubkey) -> u64 {
         self.context.banks_client.get_balance(pubkey).await.unwrap()
     }
@@ -119,6 +127,19 @@ impl MessageBufferTestContext {
         mock_cpi_caller_auth
     }
 
+    /// The authority that the mock cpi caller uses to write into the
+    /// buffers of `get_mock_cpi_auth` with `put_all_as_writer`
+    pub fn get_mock_cpi_writer_auth() -> Pubkey {
+        let (mock_cpi_writer_auth, _) = Pubkey::find_program_address(
+            &[
+                b"upd_price_writer".as_ref(),
+                ::message_buffer::id().as_ref(),
+            ],
+            &::mock_cpi_caller::id(),
+        );
+        mock_cpi_writer_auth
+    }
+
     pub fn default_allowed_programs() -> Vec<Pubkey> {
         vec![MessageBufferTestContext::get_mock_cpi_auth()]
     }
@@ -174,6 +195,45 @@ impl MessageBufferTestContext {
         }
     }
 
+    /// Same as `process_ixs` but raises the compute unit limit of the
+    /// transaction to the maximum and returns the compute units consumed
+    pub async fn process_ixs_with_compute_units(
+        &mut self,
+        instructions: &[Instruction],
+        signers: Vec<&Keypair>,
+    ) -> anchor_lang::Result<u64> {
+        let recent_blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+
+        let mut ixs = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+            Self::MAX_COMPUTE_UNITS,
+        )];
+        ixs.extend_from_slice(instructions);
+        let mut transaction = Transaction::new_with_payer(&ixs, Some(&self.payer.pubkey()));
+        transaction.partial_sign(&[&self.payer], recent_blockhash);
+        transaction.partial_sign(&signers, recent_blockhash);
+
+        let res = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .unwrap();
+        match res.result {
+            Err(TransactionError::InstructionError(_, InstructionError::Custom(error_code))) => {
+                let e = Custom(error_code);
+                Err(e.into())
+            }
+            Err(e) => {
+                println!("process_ixs_with_compute_units Error: {:?}", e);
+                panic!(
+                    "Non Custom Ix Error in process_ixs_with_compute_units{:?}",
+                    e
+                );
+            }
+            Ok(_) => Ok(res.metadata.unwrap().compute_units_consumed),
+        }
+    }
+
     pub async fn initialize(&mut self, admin: &Keypair) -> Result<(Pubkey, u8)> {
         let (whitelist_pda, whitelist_bump) = Pubkey::find_program_address(
             &[MESSAGE.as_bytes(), WHITELIST.as_bytes()],
@@ -219,10 +279,83 @@ impl MessageBufferTestContext {
             vec![&self.admin.as_ref().unwrap().insecure_clone()],
         )
         .await
-        .unwrap();
+    }
+
+    pub async fn update_whitelist_admin(&mut self, new_admin: Pubkey) -> anchor_lang::Result<()> {
+        let admin = self.admin();
+        let update_whitelist_admin_ix =
+            update_whitelist_admin_ix(admin.pubkey(), self.whitelist(), new_admin);
+        self.process_ixs(&[update_whitelist_admin_ix], vec![&admin])
+            .await
+    }
+
+    pub async fn propose_whitelist_admin(&mut self, new_admin: Pubkey) -> anchor_lang::Result<()> {
+        let admin = self.admin();
+        let propose_whitelist_admin_ix =
+            propose_whitelist_admin_ix(admin.pubkey(), self.whitelist(), new_admin);
+        self.process_ixs(&[propose_whitelist_admin_ix], vec![&admin])
+            .await
+    }
+
+    pub async fn migrate_whitelist(&mut self, admin: &Keypair) -> anchor_lang::Result<()> {
+        let migrate_whitelist_ix =
+            migrate_whitelist_ix(admin.pubkey(), self.payer.pubkey(), self.whitelist());
+        self.process_ixs(&[migrate_whitelist_ix], vec![admin]).await
+    }
+
+    pub async fn accept_whitelist_admin(
+        &mut self,
+        pending_admin: &Keypair,
+    ) -> anchor_lang::Result<()> {
+        let accept_whitelist_admin_ix =
+            accept_whitelist_admin_ix(pending_admin.pubkey(), self.whitelist());
+        self.process_ixs(&[accept_whitelist_admin_ix], vec![pending_admin])
+            .await?;
+        self.admin = Some(pending_admin.insecure_clone());
         Ok(())
     }
 
+    pub async fn fetch_whitelist_pending_admin(&mut self) -> Option<Pubkey> {
+        let whitelist_account = self
+            .context
+            .banks_client
+            .get_account(self.whitelist())
+            .await
+            .unwrap()
+            .unwrap();
+        deserialize_whitelist_pending_admin(whitelist_account.data())
+    }
+
+    /// Truncates the whitelist to the size it had before `pending_admin` was
+    /// added, with the rent of that size, to simulate a whitelist created by
+    /// a previous version of the program
+    pub async fn set_legacy_whitelist_size(&mut self) {
+        let mut whitelist_account = self
+            .context
+            .banks_client
+            .get_account(self.whitelist())
+            .await
+            .unwrap()
+            .unwrap();
+        whitelist_account.data.truncate(Self::LEGACY_WHITELIST_LEN);
+        whitelist_account.lamports = Rent::default().minimum_balance(Self::LEGACY_WHITELIST_LEN);
+        self.context.set_account(
+            &self.whitelist(),
+            &AccountSharedData::from(whitelist_account),
+        );
+    }
+
+    pub async fn fetch_whitelist_len(&mut self) -> usize {
+        self.context
+            .banks_client
+            .get_account(self.whitelist())
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .len()
+    }
+
     pub async fn create_buffer(&mut self, id: u64, target_size: u32) -> Result<(Pubkey, u8)> {
         let pyth_price_account = Self::get_mock_pyth_price_account(id);
         let (msg_buffer_pda, msg_buffer_bump) =
@@ -257,6 +390,20 @@ impl MessageBufferTestContext {
 
     pub async fn delete_buffer(&mut self, id: u64) -> anchor_lang::Result<()> {
         let pyth_price_account = Self::get_mock_pyth_price_account(id);
+        let (buffer_writers_pda, _) =
+            find_buffer_writers_pda(Self::get_mock_cpi_auth(), pyth_price_account);
+        self.delete_buffer_with_buffer_writers_account(id, buffer_writers_pda)
+            .await
+    }
+
+    /// Deletes the buffer, passing `buffer_writers` as its `BufferWriters`
+    /// account
+    pub async fn delete_buffer_with_buffer_writers_account(
+        &mut self,
+        id: u64,
+        buffer_writers: Pubkey,
+    ) -> anchor_lang::Result<()> {
+        let pyth_price_account = Self::get_mock_pyth_price_account(id);
 
         let (msg_buffer_pda, _) =
             find_msg_buffer_pda(Self::get_mock_cpi_auth(), pyth_price_account);
@@ -269,12 +416,52 @@ impl MessageBufferTestContext {
             admin.pubkey(),
             self.payer.pubkey(),
             msg_buffer_pda,
+            buffer_writers,
         );
 
         self.process_ixs(&[delete_ix], vec![&admin]).await?;
         Ok(())
     }
 
+    pub async fn migrate_buffer(&mut self, id: u64) -> anchor_lang::Result<()> {
+        let pyth_price_account = Self::get_mock_pyth_price_account(id);
+        let (msg_buffer_pda, _) =
+            find_msg_buffer_pda(Self::get_mock_cpi_auth(), pyth_price_account);
+        let admin = self.admin();
+
+        let migrate_ix = migrate_msg_buffer_ix(
+            Self::get_mock_cpi_auth(),
+            pyth_price_account,
+            self.whitelist(),
+            admin.pubkey(),
+            msg_buffer_pda,
+        );
+
+        self.process_ixs(&[migrate_ix], vec![&admin]).await?;
+        Ok(())
+    }
+
+    /// Overwrites the header version of the buffer to simulate a buffer
+    /// written by another version of the program
+    pub async fn set_msg_buffer_version(&mut self, id: u64, version: u8) {
+        let (msg_buffer_pda, _) = find_msg_buffer_pda(
+            Self::get_mock_cpi_auth(),
+            Self::get_mock_pyth_price_account(id),
+        );
+        let mut msg_buffer_account = self
+            .context
+            .banks_client
+            .get_account(msg_buffer_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        msg_buffer_account.data[9] = version;
+        self.context.set_account(
+            &msg_buffer_pda,
+            &AccountSharedData::from(msg_buffer_account),
+        );
+    }
+
     pub async fn resize_msg_buffer(
         &mut self,
         id: u64,
@@ -305,6 +492,166 @@ impl MessageBufferTestContext {
         Ok(())
     }
 
+    pub async fn set_buffer_writers(
+        &mut self,
+        id: u64,
+        writers: &Vec<BufferWriter>,
+    ) -> anchor_lang::Result<()> {
+        let pyth_price_account = Self::get_mock_pyth_price_account(id);
+        let (msg_buffer_pda, _) =
+            find_msg_buffer_pda(Self::get_mock_cpi_auth(), pyth_price_account);
+        let (buffer_writers_pda, _) =
+            find_buffer_writers_pda(Self::get_mock_cpi_auth(), pyth_price_account);
+        let admin = self.admin();
+
+        let set_buffer_writers_ix = set_buffer_writers_ix(
+            Self::get_mock_cpi_auth(),
+            pyth_price_account,
+            writers,
+            self.whitelist(),
+            admin.pubkey(),
+            self.payer.pubkey(),
+            msg_buffer_pda,
+            buffer_writers_pda,
+        );
+
+        self.process_ixs(&[set_buffer_writers_ix], vec![&admin])
+            .await?;
+        Ok(())
+    }
+
+    pub async fn fetch_buffer_writers(&mut self, id: u64) -> Option<(u8, Vec<BufferWriter>)> {
+        let pyth_price_account = Self::get_mock_pyth_price_account(id);
+        let (buffer_writers_pda, _) =
+            find_buffer_writers_pda(Self::get_mock_cpi_auth(), pyth_price_account);
+        let buffer_writers_account = self
+            .context
+            .banks_client
+            .get_account(buffer_writers_pda)
+            .await
+            .unwrap();
+
+        buffer_writers_account.map(|a| deserialize_buffer_writers(a.data()))
+    }
+
+    pub async fn create_message_stats(&mut self, id: u64) -> anchor_lang::Result<(Pubkey, u8)> {
+        let pyth_price_account = Self::get_mock_pyth_price_account(id);
+        let (msg_buffer_pda, _) =
+            find_msg_buffer_pda(Self::get_mock_cpi_auth(), pyth_price_account);
+        let (message_stats_pda, message_stats_bump) =
+            find_message_stats_pda(Self::get_mock_cpi_auth(), pyth_price_account);
+        let admin = self.admin();
+
+        let create_message_stats_ix = create_message_stats_ix(
+            Self::get_mock_cpi_auth(),
+            pyth_price_account,
+            self.whitelist(),
+            admin.pubkey(),
+            self.payer.pubkey(),
+            msg_buffer_pda,
+            message_stats_pda,
+        );
+
+        self.process_ixs(&[create_message_stats_ix], vec![&admin])
+            .await?;
+        Ok((message_stats_pda, message_stats_bump))
+    }
+
+    pub async fn fetch_message_stats(&mut self, id: u64) -> Option<(u8, Vec<MessageTypeStats>)> {
+        let pyth_price_account = Self::get_mock_pyth_price_account(id);
+        let (message_stats_pda, _) =
+            find_message_stats_pda(Self::get_mock_cpi_auth(), pyth_price_account);
+        let message_stats_account = self
+            .context
+            .banks_client
+            .get_account(message_stats_pda)
+            .await
+            .unwrap();
+
+        message_stats_account.map(|a| deserialize_message_stats(a.data()))
+    }
+
+    /// Deletes the buffer along with its `MessageStats` account
+    pub async fn delete_buffer_with_message_stats(&mut self, id: u64) -> anchor_lang::Result<()> {
+        let pyth_price_account = Self::get_mock_pyth_price_account(id);
+        let (msg_buffer_pda, _) =
+            find_msg_buffer_pda(Self::get_mock_cpi_auth(), pyth_price_account);
+        let (message_stats_pda, _) =
+            find_message_stats_pda(Self::get_mock_cpi_auth(), pyth_price_account);
+        let (buffer_writers_pda, _) =
+            find_buffer_writers_pda(Self::get_mock_cpi_auth(), pyth_price_account);
+        let admin = self.admin();
+
+        let mut delete_ix = delete_msg_buffer_ix(
+            Self::get_mock_cpi_auth(),
+            pyth_price_account,
+            self.whitelist(),
+            admin.pubkey(),
+            self.payer.pubkey(),
+            msg_buffer_pda,
+            buffer_writers_pda,
+        );
+        delete_ix
+            .accounts
+            .push(AccountMeta::new(message_stats_pda, false));
+
+        self.process_ixs(&[delete_ix], vec![&admin]).await?;
+        Ok(())
+    }
+
+    /// Same as `add_price` but also passes the buffer's `MessageStats`
+    /// account to `put_all`
+    pub async fn add_price_with_message_stats(
+        &mut self,
+        add_price_params: AddPriceParams,
+        payer: Pubkey,
+        whitelist: Pubkey,
+        cpi_auth: Pubkey,
+    ) -> Result<()> {
+        let (id, price, price_expo, ema, ema_expo) = add_price_params;
+        let pyth_price_account = Self::get_mock_pyth_price_account(id);
+        let (msg_buffer_pda, _) = find_msg_buffer_pda(cpi_auth, pyth_price_account);
+        let (message_stats_pda, _) = find_message_stats_pda(cpi_auth, pyth_price_account);
+
+        let mut add_price_ix = add_price_ix(
+            id,
+            price,
+            price_expo,
+            ema,
+            ema_expo,
+            pyth_price_account,
+            payer,
+            whitelist,
+            cpi_auth,
+            msg_buffer_pda,
+        );
+        add_price_ix
+            .accounts
+            .push(AccountMeta::new(message_stats_pda, false));
+
+        self.process_ixs(&[add_price_ix], vec![]).await?;
+        Ok(())
+    }
+
+    /// Updates the mock pyth price account of `update_price_params` and
+    /// writes its messages into the buffer of `get_mock_cpi_auth` as the
+    /// `get_mock_cpi_writer_auth` writer
+    pub async fn update_price_as_writer(
+        &mut self,
+        update_price_params: AddPriceParams,
+    ) -> anchor_lang::Result<()> {
+        let update_price_as_writer_ix = update_price_as_writer_ix(
+            update_price_params,
+            self.whitelist(),
+            Self::get_mock_cpi_auth(),
+            Self::get_mock_cpi_writer_auth(),
+        );
+
+        self.process_ixs(&[update_price_as_writer_ix], vec![])
+            .await?;
+        Ok(())
+    }
+
     pub async fn add_price(
         &mut self,
         add_price_params: AddPriceParams,
@@ -332,6 +679,45 @@ impl MessageBufferTestContext {
         self.process_ixs(&[add_price_ix], vec![]).await?;
         Ok(())
     }
+    /// Updates the mock pyth price accounts of `params` (which must have been
+    /// added already) and writes their messages with a single `put_batch`
+    pub async fn update_prices(&mut self, params: &[AddPriceParams]) -> Result<()> {
+        let cpi_auth = Self::get_mock_cpi_auth();
+        let msg_buffers = params
+            .iter()
+            .map(|(id, ..)| find_msg_buffer_pda(cpi_auth, Self::get_mock_pyth_price_account(*id)).0)
+            .collect::<Vec<_>>();
+        let update_prices_ix = update_prices_ix(params, self.whitelist(), cpi_auth, &msg_buffers);
+
+        self.process_ixs(&[update_prices_ix], vec![]).await?;
+        Ok(())
+    }
+
+    /// Same as `update_prices` but also passes the `MessageStats` accounts
+    /// of the buffers to `put_batch`
+    pub async fn update_prices_with_message_stats(
+        &mut self,
+        params: &[AddPriceParams],
+    ) -> Result<()> {
+        let cpi_auth = Self::get_mock_cpi_auth();
+        let price_accounts = params
+            .iter()
+            .map(|(id, ..)| Self::get_mock_pyth_price_account(*id))
+            .collect::<Vec<_>>();
+        let mut accounts = price_accounts
+            .iter()
+            .map(|price_account| find_msg_buffer_pda(cpi_auth, *price_account).0)
+            .collect::<Vec<_>>();
+        accounts.extend(
+            price_accounts
+                .iter()
+                .map(|price_account| find_message_stats_pda(cpi_auth, *price_account).0),
+        );
+        let update_prices_ix = update_prices_ix(params, self.whitelist(), cpi_auth, &accounts);
+
+        self.process_ixs(&[update_prices_ix], vec![]).await?;
+        Ok(())
+    }
 }
 
 pub type AddPriceParams = (u64, u64, u64, u64, u64);
@@ -368,6 +754,60 @@ fn set_allowed_programs_ix(
     )
 }
 
+fn update_whitelist_admin_ix(admin: Pubkey, whitelist: Pubkey, new_admin: Pubkey) -> Instruction {
+    let ix_discriminator = sighash("global", "update_whitelist_admin");
+
+    Instruction::new_with_borsh(
+        ::message_buffer::id(),
+        &(ix_discriminator, new_admin),
+        vec![
+            AccountMeta::new_readonly(admin, true),
+            AccountMeta::new(whitelist, false),
+        ],
+    )
+}
+
+fn migrate_whitelist_ix(admin: Pubkey, payer: Pubkey, whitelist: Pubkey) -> Instruction {
+    let ix_discriminator = sighash("global", "migrate_whitelist");
+
+    Instruction::new_with_borsh(
+        ::message_buffer::id(),
+        &(ix_discriminator),
+        vec![
+            AccountMeta::new_readonly(admin, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new(whitelist, false),
+            AccountMeta::new_readonly(System::id(), false),
+        ],
+    )
+}
+
+fn propose_whitelist_admin_ix(admin: Pubkey, whitelist: Pubkey, new_admin: Pubkey) -> Instruction {
+    let ix_discriminator = sighash("global", "propose_whitelist_admin");
+
+    Instruction::new_with_borsh(
+        ::message_buffer::id(),
+        &(ix_discriminator, new_admin),
+        vec![
+            AccountMeta::new_readonly(admin, true),
+            AccountMeta::new(whitelist, false),
+        ],
+    )
+}
+
+fn accept_whitelist_admin_ix(pending_admin: Pubkey, whitelist: Pubkey) -> Instruction {
+    let ix_discriminator = sighash("global", "accept_whitelist_admin");
+
+    Instruction::new_with_borsh(
+        ::message_buffer::id(),
+        &(ix_discriminator),
+        vec![
+            AccountMeta::new_readonly(pending_admin, true),
+            AccountMeta::new(whitelist, false),
+        ],
+    )
+}
+
 pub fn create_msg_buffer_ix(
     cpi_caller_auth: Pubkey,
     pyth_price_acct: Pubkey,
@@ -397,6 +837,26 @@ pub fn create_msg_buffer_ix(
     )
 }
 
+pub fn migrate_msg_buffer_ix(
+    cpi_caller_auth: Pubkey,
+    pyth_price_acct: Pubkey,
+    whitelist: Pubkey,
+    admin: Pubkey,
+    msg_buffer_pda: Pubkey,
+) -> Instruction {
+    let migrate_ix_disc = sighash("global", "migrate_buffer");
+
+    Instruction::new_with_borsh(
+        ::message_buffer::id(),
+        &(migrate_ix_disc, cpi_caller_auth, pyth_price_acct),
+        vec![
+            AccountMeta::new_readonly(whitelist, false),
+            AccountMeta::new_readonly(admin, true),
+            AccountMeta::new(msg_buffer_pda, false),
+        ],
+    )
+}
+
 pub fn resize_msg_buffer_ix(
     cpi_caller_auth: Pubkey,
     pyth_price_acct: Pubkey,
@@ -433,6 +893,7 @@ pub fn delete_msg_buffer_ix(
     admin: Pubkey,
     payer: Pubkey,
     msg_buffer_pda: Pubkey,
+    buffer_writers_pda: Pubkey,
 ) -> Instruction {
     let delete_ix_disc = sighash("global", "delete_buffer");
 
@@ -444,6 +905,67 @@ pub fn delete_msg_buffer_ix(
             AccountMeta::new_readonly(admin, true),
             AccountMeta::new(payer, true),
             AccountMeta::new(msg_buffer_pda, false),
+            AccountMeta::new(buffer_writers_pda, false),
+        ],
+    )
+}
+
+pub fn create_message_stats_ix(
+    cpi_caller_auth: Pubkey,
+    pyth_price_acct: Pubkey,
+    whitelist: Pubkey,
+    admin: Pubkey,
+    payer: Pubkey,
+    msg_buffer_pda: Pubkey,
+    message_stats_pda: Pubkey,
+) -> Instruction {
+    let create_message_stats_ix_disc = sighash("global", "create_message_stats");
+
+    Instruction::new_with_borsh(
+        ::message_buffer::id(),
+        &(
+            create_message_stats_ix_disc,
+            cpi_caller_auth,
+            pyth_price_acct,
+        ),
+        vec![
+            AccountMeta::new_readonly(whitelist, false),
+            AccountMeta::new_readonly(admin, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(System::id(), false),
+            AccountMeta::new_readonly(msg_buffer_pda, false),
+            AccountMeta::new(message_stats_pda, false),
+        ],
+    )
+}
+
+pub fn set_buffer_writers_ix(
+    cpi_caller_auth: Pubkey,
+    pyth_price_acct: Pubkey,
+    writers: &Vec<BufferWriter>,
+    whitelist: Pubkey,
+    admin: Pubkey,
+    payer: Pubkey,
+    msg_buffer_pda: Pubkey,
+    buffer_writers_pda: Pubkey,
+) -> Instruction {
+    let set_buffer_writers_ix_disc = sighash("global", "set_buffer_writers");
+
+    Instruction::new_with_borsh(
+        ::message_buffer::id(),
+        &(
+            set_buffer_writers_ix_disc,
+            cpi_caller_auth,
+            pyth_price_acct,
+            writers,
+        ),
+        vec![
+            AccountMeta::new_readonly(whitelist, false),
+            AccountMeta::new_readonly(admin, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(System::id(), false),
+            AccountMeta::new_readonly(msg_buffer_pda, false),
+            AccountMeta::new(buffer_writers_pda, false),
         ],
     )
 }
@@ -476,6 +998,132 @@ fn add_price_ix(
     )
 }
 
+pub fn update_price_ix(
+    update_price_params: AddPriceParams,
+    whitelist: Pubkey,
+    cpi_auth: Pubkey,
+    msg_buffer_pda: Pubkey,
+) -> Instruction {
+    let update_price_disc = sighash("global", "update_price");
+    let (id, price, price_expo, ema, ema_expo) = update_price_params;
+    Instruction::new_with_borsh(
+        ::mock_cpi_caller::id(),
+        &(update_price_disc, price, price_expo, ema, ema_expo),
+        update_price_accounts(id, whitelist, cpi_auth, msg_buffer_pda),
+    )
+}
+
+pub fn update_price_as_writer_ix(
+    update_price_params: AddPriceParams,
+    whitelist: Pubkey,
+    cpi_auth: Pubkey,
+    writer_auth: Pubkey,
+) -> Instruction {
+    let update_price_as_writer_disc = sighash("global", "update_price_as_writer");
+    let (id, price, price_expo, ema, ema_expo) = update_price_params;
+    let pyth_price_account = MessageBufferTestContext::get_mock_pyth_price_account(id);
+    let (buffer_writers_pda, _) = find_buffer_writers_pda(cpi_auth, pyth_price_account);
+    let (msg_buffer_pda, _) = find_msg_buffer_pda(cpi_auth, pyth_price_account);
+    Instruction::new_with_borsh(
+        ::mock_cpi_caller::id(),
+        &(
+            update_price_as_writer_disc,
+            price,
+            price_expo,
+            ema,
+            ema_expo,
+            cpi_auth,
+        ),
+        vec![
+            AccountMeta::new(pyth_price_account, false),
+            AccountMeta::new_readonly(whitelist, false),
+            AccountMeta::new_readonly(writer_auth, false),
+            AccountMeta::new_readonly(::message_buffer::id(), false),
+            AccountMeta::new_readonly(buffer_writers_pda, false),
+            AccountMeta::new(msg_buffer_pda, false),
+        ],
+    )
+}
+
+/// `msg_sizes` are the sizes of the dummy messages to write, excluding
+/// their 7 byte header
+pub fn cpi_max_test_ix(
+    update_price_params: AddPriceParams,
+    msg_sizes: &Vec<u16>,
+    whitelist: Pubkey,
+    cpi_auth: Pubkey,
+    msg_buffer_pda: Pubkey,
+) -> Instruction {
+    let cpi_max_test_disc = sighash("global", "cpi_max_test");
+    let (id, price, price_expo, ema, ema_expo) = update_price_params;
+    Instruction::new_with_borsh(
+        ::mock_cpi_caller::id(),
+        &(
+            cpi_max_test_disc,
+            price,
+            price_expo,
+            ema,
+            ema_expo,
+            msg_sizes,
+        ),
+        update_price_accounts(id, whitelist, cpi_auth, msg_buffer_pda),
+    )
+}
+
+fn update_price_accounts(
+    id: u64,
+    whitelist: Pubkey,
+    cpi_auth: Pubkey,
+    msg_buffer_pda: Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(
+            MessageBufferTestContext::get_mock_pyth_price_account(id),
+            false,
+        ),
+        AccountMeta::new_readonly(whitelist, false),
+        AccountMeta::new_readonly(cpi_auth, false),
+        AccountMeta::new_readonly(::message_buffer::id(), false),
+        AccountMeta::new(msg_buffer_pda, false),
+    ]
+}
+
+pub fn update_prices_ix(
+    params: &[AddPriceParams],
+    whitelist: Pubkey,
+    cpi_auth: Pubkey,
+    msg_buffers: &[Pubkey],
+) -> Instruction {
+    let update_prices_disc = sighash("global", "update_prices");
+    let update_price_params = params
+        .iter()
+        .map(|(_, price, price_expo, ema, ema_expo)| (*price, *price_expo, *ema, *ema_expo))
+        .collect::<Vec<_>>();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(whitelist, false),
+        AccountMeta::new_readonly(cpi_auth, false),
+        AccountMeta::new_readonly(::message_buffer::id(), false),
+    ];
+    accounts.extend(params.iter().map(|(id, ..)| {
+        AccountMeta::new(
+            MessageBufferTestContext::get_mock_pyth_price_account(*id),
+            false,
+        )
+    }));
+    accounts.extend(
+        msg_buffers
+            .iter()
+            .map(|msg_buffer| AccountMeta::new(*msg_buffer, false)),
+    );
+
+    Instruction::new_with_borsh(
+        ::mock_cpi_caller::id(),
+        &(update_prices_disc, update_price_params),
+        accounts,
+    )
+}
+
 type Bump = u8;
 type Version = u8;
 type HeaderLen = u16;
@@ -610,6 +1258,75 @@ pub fn find_msg_buffer_pda(cpi_caller_auth: Pubkey, pyth_price_acct: Pubkey) ->
     )
 }
 
+pub fn find_buffer_writers_pda(cpi_caller_auth: Pubkey, pyth_price_acct: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            cpi_caller_auth.as_ref(),
+            MESSAGE.as_bytes(),
+            pyth_price_acct.as_ref(),
+            WRITERS.as_bytes(),
+        ],
+        &::message_buffer::id(),
+    )
+}
+
+// (auth, max_messages, max_bytes)
+pub type BufferWriter = (Pubkey, u8, u16);
+
+pub fn deserialize_buffer_writers(account_data: &[u8]) -> (u8, Vec<BufferWriter>) {
+    let mut cursor = Cursor::new(account_data);
+    let discriminator = &mut vec![0u8; 8];
+    cursor.read_exact(discriminator).unwrap();
+    assert_eq!(discriminator, &sighash("account", "BufferWriters"));
+
+    let buffer_writers_bump = cursor.read_u8().unwrap();
+    let writers_len = cursor.read_u32::<LittleEndian>().unwrap();
+
+    let mut writers = vec![];
+    for _ in 0..writers_len {
+        let auth_bytes = &mut vec![0u8; 32];
+        cursor.read_exact(auth_bytes).unwrap();
+        let auth = Pubkey::try_from_slice(auth_bytes).unwrap();
+        let max_messages = cursor.read_u8().unwrap();
+        let max_bytes = cursor.read_u16::<LittleEndian>().unwrap();
+        writers.push((auth, max_messages, max_bytes));
+    }
+    (buffer_writers_bump, writers)
+}
+
+pub fn find_message_stats_pda(cpi_caller_auth: Pubkey, pyth_price_acct: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            cpi_caller_auth.as_ref(),
+            MESSAGE.as_bytes(),
+            pyth_price_acct.as_ref(),
+            STATS.as_bytes(),
+        ],
+        &::message_buffer::id(),
+    )
+}
+
+// (messages_written, bytes_written, last_write_slot)
+pub type MessageTypeStats = (u64, u64, u64);
+
+pub fn deserialize_message_stats(account_data: &[u8]) -> (u8, Vec<MessageTypeStats>) {
+    let mut cursor = Cursor::new(account_data);
+    let discriminator = &mut vec![0u8; 8];
+    cursor.read_exact(discriminator).unwrap();
+    assert_eq!(discriminator, &sighash("account", "MessageStats"));
+
+    let message_stats_bump = cursor.read_u8().unwrap();
+
+    let mut types = vec![];
+    for _ in 0..8 {
+        let messages_written = cursor.read_u64::<LittleEndian>().unwrap();
+        let bytes_written = cursor.read_u64::<LittleEndian>().unwrap();
+        let last_write_slot = cursor.read_u64::<LittleEndian>().unwrap();
+        types.push((messages_written, bytes_written, last_write_slot));
+    }
+    (message_stats_bump, types)
+}
+
 pub fn deserialize_whitelist(account_data: &[u8]) -> Result<(u8, Pubkey, u32, Vec<Pubkey>)> {
     let mut cursor = Cursor::new(account_data);
     let discriminator = &mut vec![0u8; 8];
@@ -638,3 +1355,20 @@ pub fn deserialize_whitelist(account_data: &[u8]) -> Result<(u8, Pubkey, u32, Ve
         allowed_programs,
     ))
 }
+
+pub fn deserialize_whitelist_pending_admin(account_data: &[u8]) -> Option<Pubkey> {
+    let mut cursor = Cursor::new(account_data);
+    // discriminator, bump & admin
+    cursor.set_position(8 + 1 + 32);
+    let allowed_programs_len = cursor.read_u32::<LittleEndian>().unwrap();
+    cursor.set_position(cursor.position() + 32 * allowed_programs_len as u64);
+
+    match cursor.read_u8().unwrap() {
+        0 => None,
+        _ => {
+            let pending_admin_bytes = &mut vec![0u8; 32];
+            cursor.read_exact(pending_admin_bytes).unwrap();
+            Some(Pubkey::try_from_slice(pending_admin_bytes).unwrap())
+        }
+    }
+}
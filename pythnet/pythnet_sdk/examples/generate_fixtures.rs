@@ -0,0 +1,509 @@
+// Generate signed VAAs, accumulator updates and their expected decoded contents as JSON
+// fixtures, so the target chain test suites that can't use the Rust test utilities (Sway, Move,
+// Solidity, ...) share the same test vectors.
+//
+// Usage: cargo run --example generate_fixtures --features test-utils -- [output directory]
+//
+// The output is deterministic: VAAs are signed by a fixed set of the dummy guardians and the
+// signatures themselves are deterministic (RFC 6979), so regenerating the fixtures only produces
+// a diff if the generator or the wire format changed. Binary data is hex encoded without a `0x`
+// prefix, and integers wider than 32 bits are decimal strings so JavaScript based suites can read
+// them without losing precision.
+
+use {
+    byteorder::BigEndian,
+    pythnet_sdk::{
+        messages::{Message, PriceFeedMessage},
+        test_utils::{
+            create_accumulator_message, create_dummy_feed_id, create_dummy_price_feed_message,
+            create_vaa_from_payload_with_signers, dummy_guardians_addresses, DataSource,
+            DEFAULT_DATA_SOURCE, DEFAULT_GOVERNANCE_SOURCE, DEFAULT_NUM_SIGNATURES,
+            SECONDARY_DATA_SOURCE, WRONG_SOURCE,
+        },
+        wire::{
+            to_vec,
+            v1::{AccumulatorUpdateData, MerklePriceUpdate, Proof},
+            PrefixedVec,
+        },
+    },
+    serde_json::{json, Value},
+    serde_wormhole::RawMessage,
+    std::{env, fs, path::PathBuf},
+    wormhole_sdk::Vaa,
+};
+
+const GOVERNANCE_MAGIC: &[u8; 4] = b"PTGM";
+const GOVERNANCE_MODULE_TARGET: u8 = 1;
+const GOVERNANCE_ACTION_SET_DATA_SOURCES: u8 = 2;
+const GOVERNANCE_ACTION_SET_FEE: u8 = 3;
+const GOVERNANCE_ACTION_SET_VALID_PERIOD: u8 = 4;
+// Governance instructions with this target chain apply to all chains
+const ALL_CHAINS: u16 = 0;
+
+/// The first `n` dummy guardians, `DEFAULT_NUM_SIGNATURES` of them form a quorum.
+fn signers(n: usize) -> Vec<u8> {
+    (0..n as u8).collect()
+}
+
+fn data_source_json(source: &DataSource) -> Value {
+    json!({
+        "chain": u16::from(source.chain),
+        "emitter": hex::encode(source.address.0),
+    })
+}
+
+fn vaa_json(vaa: &Vaa<Box<RawMessage>>) -> Value {
+    json!({
+        "version": vaa.version,
+        "guardian_set_index": vaa.guardian_set_index,
+        "signer_indices": vaa.signatures.iter().map(|s| s.index).collect::<Vec<_>>(),
+        "timestamp": vaa.timestamp,
+        "nonce": vaa.nonce,
+        "emitter_chain": u16::from(vaa.emitter_chain),
+        "emitter_address": hex::encode(vaa.emitter_address.0),
+        "sequence": vaa.sequence.to_string(),
+        "consistency_level": vaa.consistency_level,
+        "payload": hex::encode(&vaa.payload[..]),
+    })
+}
+
+fn price_feed_json(message: &Message) -> Value {
+    let Message::PriceFeedMessage(msg) = message else {
+        panic!("Only price feed messages have expected outputs");
+    };
+    json!({
+        "feed_id": hex::encode(msg.feed_id),
+        "price": msg.price.to_string(),
+        "conf": msg.conf.to_string(),
+        "exponent": msg.exponent,
+        "publish_time": msg.publish_time.to_string(),
+        "prev_publish_time": msg.prev_publish_time.to_string(),
+        "ema_price": msg.ema_price.to_string(),
+        "ema_conf": msg.ema_conf.to_string(),
+    })
+}
+
+/// Re-encodes an accumulator update after `f` modified its VAA and Merkle updates.
+fn modify_accumulator_update(
+    update: &[u8],
+    f: impl FnOnce(&mut Vec<u8>, &mut Vec<MerklePriceUpdate>),
+) -> Vec<u8> {
+    let Proof::WormholeMerkle { vaa, mut updates } =
+        AccumulatorUpdateData::try_from_slice(update).unwrap().proof;
+    let mut vaa = Vec::from(vaa);
+    f(&mut vaa, &mut updates);
+    to_vec::<_, BigEndian>(&AccumulatorUpdateData::new(Proof::WormholeMerkle {
+        vaa: PrefixedVec::from(vaa),
+        updates,
+    }))
+    .unwrap()
+}
+
+/// Replaces the VAA of an accumulator update created by the test utilities, which is signed by
+/// a random set of guardians, with the same VAA signed by the guardians at `signers`.
+fn resign_accumulator_update(update: &[u8], signers: &[u8]) -> Vec<u8> {
+    modify_accumulator_update(update, |vaa_bytes, _| {
+        let vaa: Vaa<&RawMessage> = serde_wormhole::from_slice(vaa_bytes).unwrap();
+        let vaa = create_vaa_from_payload_with_signers(
+            vaa.payload,
+            vaa.emitter_address,
+            vaa.emitter_chain,
+            vaa.sequence,
+            signers,
+        );
+        *vaa_bytes = serde_wormhole::to_vec(&vaa).unwrap();
+    })
+}
+
+/// Applies `f` to the decoded VAA of an accumulator update. The VAA header is not covered by the
+/// signatures, so `f` may change it without invalidating them.
+fn modify_accumulator_vaa(update: &[u8], f: impl FnOnce(&mut Vaa<&RawMessage>)) -> Vec<u8> {
+    modify_accumulator_update(update, |vaa_bytes, _| {
+        let mut vaa: Vaa<&RawMessage> = serde_wormhole::from_slice(vaa_bytes).unwrap();
+        f(&mut vaa);
+        *vaa_bytes = serde_wormhole::to_vec(&vaa).unwrap();
+    })
+}
+
+fn config_fixture() -> Value {
+    json!({
+        "guardian_set_index": 0,
+        "guardians": dummy_guardians_addresses().iter().map(hex::encode).collect::<Vec<_>>(),
+        "quorum": DEFAULT_NUM_SIGNATURES,
+        "data_sources": [data_source_json(&DEFAULT_DATA_SOURCE)],
+        "governance_source": data_source_json(&DEFAULT_GOVERNANCE_SOURCE),
+    })
+}
+
+fn vaa_fixtures() -> Value {
+    let payload = b"pyth fixture payload";
+    let case = |name: &str, description: &str, num_signers: usize, error: Option<&str>| {
+        let vaa = create_vaa_from_payload_with_signers(
+            payload,
+            DEFAULT_DATA_SOURCE.address,
+            DEFAULT_DATA_SOURCE.chain,
+            1,
+            &signers(num_signers),
+        );
+        json!({
+            "name": name,
+            "description": description,
+            "vaa": hex::encode(serde_wormhole::to_vec(&vaa).unwrap()),
+            "valid": error.is_none(),
+            "error": error,
+            "expected": vaa_json(&vaa),
+        })
+    };
+
+    json!([
+        case(
+            "quorum",
+            "Signed by exactly a quorum of the guardian set",
+            DEFAULT_NUM_SIGNATURES,
+            None,
+        ),
+        case(
+            "all_guardians",
+            "Signed by every guardian of the guardian set",
+            dummy_guardians_addresses().len(),
+            None,
+        ),
+        case(
+            "below_quorum",
+            "Signed by one guardian less than a quorum",
+            DEFAULT_NUM_SIGNATURES - 1,
+            Some("insufficient_signatures"),
+        ),
+    ])
+}
+
+fn accumulator_update_fixtures() -> Value {
+    let realistic_feed = Message::PriceFeedMessage(PriceFeedMessage {
+        feed_id: create_dummy_feed_id(42),
+        price: 6_512_345_678_900,
+        conf: 1_234_567_890,
+        exponent: -8,
+        publish_time: 1_700_000_000,
+        prev_publish_time: 1_699_999_999,
+        ema_price: 6_500_000_000_000,
+        ema_conf: 1_500_000_000,
+    });
+    let many_feeds: Vec<_> = (1..=5)
+        .map(|i| create_dummy_price_feed_message(i * 10))
+        .collect();
+    let many_feeds: Vec<_> = many_feeds.iter().collect();
+    let quorum = signers(DEFAULT_NUM_SIGNATURES);
+
+    let update = |all_feeds: &[&Message], updates: &[&Message], signers: &[u8]| {
+        resign_accumulator_update(
+            &create_accumulator_message(all_feeds, updates, false, false, None),
+            signers,
+        )
+    };
+    let single_update = update(&[&realistic_feed], &[&realistic_feed], &quorum);
+    let many_updates = update(&many_feeds, &many_feeds, &quorum);
+
+    let case = |name: &str, description: &str, data: Vec<u8>, price_feeds: &[&Message]| {
+        json!({
+            "name": name,
+            "description": description,
+            "data": hex::encode(data),
+            "valid": true,
+            "error": null,
+            "price_feeds": price_feeds.iter().map(|m| price_feed_json(m)).collect::<Vec<_>>(),
+        })
+    };
+    let invalid_case = |name: &str, description: &str, data: Vec<u8>, error: &str| {
+        json!({
+            "name": name,
+            "description": description,
+            "data": hex::encode(data),
+            "valid": false,
+            "error": error,
+            "price_feeds": [],
+        })
+    };
+
+    let mut duplicate_signers = signers(DEFAULT_NUM_SIGNATURES - 1);
+    duplicate_signers.push(0);
+    let mut truncated = single_update.clone();
+    truncated.pop();
+    let mut trailing_bytes = single_update.clone();
+    trailing_bytes.push(0);
+
+    json!([
+        case(
+            "single_price_feed",
+            "One price feed update out of a tree of one message",
+            single_update.clone(),
+            &[&realistic_feed],
+        ),
+        case(
+            "multiple_price_feeds",
+            "Three price feed updates out of a tree of five messages",
+            update(
+                &many_feeds,
+                &[many_feeds[0], many_feeds[2], many_feeds[4]],
+                &quorum,
+            ),
+            &[many_feeds[0], many_feeds[2], many_feeds[4]],
+        ),
+        case(
+            "all_price_feeds",
+            "Updates for every message of a tree of five messages",
+            many_updates.clone(),
+            &many_feeds,
+        ),
+        case(
+            "all_guardians",
+            "The VAA is signed by every guardian of the guardian set",
+            update(
+                &[&realistic_feed],
+                &[&realistic_feed],
+                &signers(dummy_guardians_addresses().len()),
+            ),
+            &[&realistic_feed],
+        ),
+        case(
+            "no_price_feeds",
+            "A valid VAA without any price feed updates",
+            update(&many_feeds, &[], &quorum),
+            &[],
+        ),
+        invalid_case(
+            "invalid_wormhole_message",
+            "The VAA payload does not start with the accumulator magic",
+            resign_accumulator_update(
+                &create_accumulator_message(
+                    &[&realistic_feed],
+                    &[&realistic_feed],
+                    true,
+                    false,
+                    None,
+                ),
+                &quorum,
+            ),
+            "invalid_wormhole_message",
+        ),
+        invalid_case(
+            "unknown_message_type",
+            "The updated messages have an unknown message type",
+            resign_accumulator_update(
+                &create_accumulator_message(&many_feeds, &many_feeds, false, true, None),
+                &quorum,
+            ),
+            "invalid_message",
+        ),
+        invalid_case(
+            "invalid_data_source",
+            "The VAA is emitted by a source that is not a valid data source",
+            resign_accumulator_update(
+                &create_accumulator_message(
+                    &[&realistic_feed],
+                    &[&realistic_feed],
+                    false,
+                    false,
+                    Some(WRONG_SOURCE),
+                ),
+                &quorum,
+            ),
+            "invalid_data_source",
+        ),
+        invalid_case(
+            "below_quorum",
+            "The VAA is signed by one guardian less than a quorum",
+            update(
+                &[&realistic_feed],
+                &[&realistic_feed],
+                &signers(DEFAULT_NUM_SIGNATURES - 1),
+            ),
+            "insufficient_signatures",
+        ),
+        invalid_case(
+            "duplicate_signer",
+            "A quorum of signatures where one guardian signed twice",
+            update(&[&realistic_feed], &[&realistic_feed], &duplicate_signers),
+            "insufficient_signatures",
+        ),
+        invalid_case(
+            "invalid_signature",
+            "One of the quorum of signatures does not match its guardian",
+            modify_accumulator_vaa(&single_update, |vaa| vaa.signatures[0].signature[0] ^= 1),
+            "invalid_signature",
+        ),
+        invalid_case(
+            "unknown_guardian_set",
+            "The VAA refers to a guardian set that does not exist",
+            modify_accumulator_vaa(&single_update, |vaa| vaa.guardian_set_index = 1),
+            "invalid_guardian_set",
+        ),
+        invalid_case(
+            "tampered_message",
+            "The price of an updated message was changed after the Merkle tree was built",
+            modify_accumulator_update(&many_updates, |_, updates| {
+                let mut message = Vec::from(updates[0].message.clone());
+                // last byte of the price, after the message type and feed id
+                message[40] ^= 1;
+                updates[0].message = PrefixedVec::from(message);
+            }),
+            "invalid_merkle_proof",
+        ),
+        invalid_case(
+            "swapped_proofs",
+            "The proofs of two updated messages are swapped",
+            modify_accumulator_update(&many_updates, |_, updates| {
+                let proof = updates[0].proof.clone();
+                updates[0].proof = updates[1].proof.clone();
+                updates[1].proof = proof;
+            }),
+            "invalid_merkle_proof",
+        ),
+        invalid_case(
+            "truncated",
+            "The last byte of the update is missing",
+            truncated,
+            "invalid_update_data",
+        ),
+        invalid_case(
+            "trailing_bytes",
+            "The update is followed by an extra byte",
+            trailing_bytes,
+            "invalid_update_data",
+        ),
+    ])
+}
+
+fn governance_payload(action: u8, target_chain_id: u16, payload: &[u8]) -> Vec<u8> {
+    let mut result = GOVERNANCE_MAGIC.to_vec();
+    result.push(GOVERNANCE_MODULE_TARGET);
+    result.push(action);
+    result.extend_from_slice(&target_chain_id.to_be_bytes());
+    result.extend_from_slice(payload);
+    result
+}
+
+fn governance_fixtures() -> Value {
+    let case = |name: &str,
+                description: &str,
+                source: &DataSource,
+                sequence: u64,
+                payload: Vec<u8>,
+                instruction: Value,
+                error: Option<&str>| {
+        let vaa = create_vaa_from_payload_with_signers(
+            &payload,
+            source.address,
+            source.chain,
+            sequence,
+            &signers(DEFAULT_NUM_SIGNATURES),
+        );
+        json!({
+            "name": name,
+            "description": description,
+            "vaa": hex::encode(serde_wormhole::to_vec(&vaa).unwrap()),
+            "valid": error.is_none(),
+            "error": error,
+            "expected": vaa_json(&vaa),
+            "instruction": instruction,
+        })
+    };
+
+    let fee = |value: u64, expo: u64| {
+        let mut payload = value.to_be_bytes().to_vec();
+        payload.extend_from_slice(&expo.to_be_bytes());
+        payload
+    };
+    let mut data_sources = vec![2u8];
+    for source in [&DEFAULT_DATA_SOURCE, &SECONDARY_DATA_SOURCE] {
+        data_sources.extend_from_slice(&u16::from(source.chain).to_be_bytes());
+        data_sources.extend_from_slice(&source.address.0);
+    }
+    let instruction = |action: &str, target_chain_id: u16, fields: Value| {
+        json!({
+            "module": GOVERNANCE_MODULE_TARGET,
+            "action": action,
+            "target_chain_id": target_chain_id,
+            "fields": fields,
+        })
+    };
+
+    let set_fee = instruction("SetFee", ALL_CHAINS, json!({ "value": "5", "expo": "3" }));
+
+    json!([
+        case(
+            "set_fee",
+            "Sets the update fee to 5 * 10^3 for all chains",
+            &DEFAULT_GOVERNANCE_SOURCE,
+            1,
+            governance_payload(GOVERNANCE_ACTION_SET_FEE, ALL_CHAINS, &fee(5, 3)),
+            set_fee.clone(),
+            None,
+        ),
+        case(
+            "set_valid_period",
+            "Sets the valid time period to 120 seconds for all chains",
+            &DEFAULT_GOVERNANCE_SOURCE,
+            2,
+            governance_payload(
+                GOVERNANCE_ACTION_SET_VALID_PERIOD,
+                ALL_CHAINS,
+                &120u64.to_be_bytes(),
+            ),
+            instruction(
+                "SetValidPeriod",
+                ALL_CHAINS,
+                json!({ "valid_time_period_seconds": "120" }),
+            ),
+            None,
+        ),
+        case(
+            "set_data_sources",
+            "Replaces the data sources with the default and a secondary data source",
+            &DEFAULT_GOVERNANCE_SOURCE,
+            3,
+            governance_payload(
+                GOVERNANCE_ACTION_SET_DATA_SOURCES,
+                ALL_CHAINS,
+                &data_sources
+            ),
+            instruction(
+                "SetDataSources",
+                ALL_CHAINS,
+                json!({
+                    "data_sources": [
+                        data_source_json(&DEFAULT_DATA_SOURCE),
+                        data_source_json(&SECONDARY_DATA_SOURCE),
+                    ],
+                }),
+            ),
+            None,
+        ),
+        case(
+            "invalid_governance_source",
+            "A valid instruction emitted by a source that is not the governance source",
+            &WRONG_SOURCE,
+            4,
+            governance_payload(GOVERNANCE_ACTION_SET_FEE, ALL_CHAINS, &fee(5, 3)),
+            set_fee,
+            Some("invalid_governance_source"),
+        ),
+    ])
+}
+
+fn main() {
+    let output_dir = PathBuf::from(env::args().nth(1).unwrap_or_else(|| "fixtures".to_string()));
+    fs::create_dir_all(&output_dir).unwrap();
+
+    for (name, fixture) in [
+        ("config.json", config_fixture()),
+        ("vaas.json", vaa_fixtures()),
+        ("accumulator_updates.json", accumulator_update_fixtures()),
+        ("governance.json", governance_fixtures()),
+    ] {
+        let path = output_dir.join(name);
+        let mut json = serde_json::to_string_pretty(&fixture).unwrap();
+        json.push('\n');
+        fs::write(&path, json).unwrap();
+        println!("Wrote {}", path.display());
+    }
+}
@@ -10,6 +10,9 @@ pub enum Error {
 
     #[error("Deserialization error")]
     DeserializationError,
+
+    #[error("Invalid Message Buffer")]
+    InvalidMessageBuffer,
 }
 
 #[macro_export]
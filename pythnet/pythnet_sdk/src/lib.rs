@@ -1,6 +1,7 @@
 pub mod accumulators;
 pub mod error;
 pub mod hashers;
+pub mod message_buffer;
 pub mod messages;
 pub mod wire;
 pub mod wormhole;
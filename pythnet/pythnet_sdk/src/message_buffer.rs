@@ -0,0 +1,263 @@
+//! Zero-copy reader for `MessageBuffer` accounts owned by the Message Buffer program.
+//!
+//! A `MessageBuffer` account is laid out as follows (all integers little-endian):
+//!
+//! ```text
+//! [0..8)            anchor account discriminator
+//! [8]               bump
+//! [9]               version
+//! [10..12)          header_len: u16
+//! [12..522)         end_offsets: [u16; 255]
+//! [header_len..)    messages
+//! ```
+//!
+//! Message `i` spans `header_len + end_offsets[i - 1]..header_len + end_offsets[i]`. The first
+//! zero end offset terminates the list. Consumers only need the raw account data, so this module
+//! does not depend on anchor or the Message Buffer program crate.
+
+use crate::{error::Error, require, Pubkey};
+
+/// `sha256("account:MessageBuffer")[..8]`
+pub const MESSAGE_BUFFER_DISCRIMINATOR: [u8; 8] = [25, 244, 3, 5, 225, 165, 29, 250];
+
+/// `sha256("global:put_all")[..8]`
+pub const PUT_ALL_DISCRIMINATOR: [u8; 8] = [212, 225, 193, 91, 151, 238, 20, 93];
+
+/// Header layout version understood by this reader. Buffers with another version are rejected
+/// instead of being misread, so readers can be upgraded before the buffers are migrated.
+pub const CURRENT_VERSION: u8 = 1;
+
+/// Maximum number of messages a single buffer can hold.
+pub const MAX_MESSAGES: usize = 255;
+
+/// Length of the discriminator and the fixed-size header. `header_len` is never smaller.
+pub const HEADER_LEN: usize = 8 + 1 + 1 + 2 + 2 * MAX_MESSAGES;
+
+const END_OFFSETS_START: usize = 12;
+
+/// Read-only view over the data of a `MessageBuffer` account.
+///
+/// The header is validated once in [`MessageBufferReader::new`]; afterwards all accessors borrow
+/// from the underlying slice without copying.
+#[derive(Clone, Copy, Debug)]
+pub struct MessageBufferReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> MessageBufferReader<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, Error> {
+        require!(data.len() >= HEADER_LEN, Error::InvalidMessageBuffer);
+        require!(
+            data[..8] == MESSAGE_BUFFER_DISCRIMINATOR,
+            Error::InvalidMessageBuffer
+        );
+        require!(data[9] == CURRENT_VERSION, Error::InvalidMessageBuffer);
+
+        let reader = Self { data };
+        let header_len = reader.header_len() as usize;
+        require!(
+            header_len >= HEADER_LEN && header_len <= data.len(),
+            Error::InvalidMessageBuffer
+        );
+
+        let mut prev = 0;
+        for end in reader.end_offsets() {
+            if end == 0 {
+                break;
+            }
+            require!(
+                end >= prev && header_len + end as usize <= data.len(),
+                Error::InvalidMessageBuffer
+            );
+            prev = end;
+        }
+        Ok(reader)
+    }
+
+    pub fn bump(&self) -> u8 {
+        self.data[8]
+    }
+
+    pub fn version(&self) -> u8 {
+        self.data[9]
+    }
+
+    pub fn header_len(&self) -> u16 {
+        u16::from_le_bytes([self.data[10], self.data[11]])
+    }
+
+    /// Raw end offsets, including the trailing zero entries of unused slots.
+    pub fn end_offsets(&self) -> impl Iterator<Item = u16> + 'a {
+        self.data[END_OFFSETS_START..HEADER_LEN]
+            .chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    /// Number of messages currently in the buffer.
+    pub fn num_messages(&self) -> usize {
+        self.end_offsets().take_while(|end| *end != 0).count()
+    }
+
+    /// Iterates over the serialized messages in the buffer.
+    pub fn messages(&self) -> impl Iterator<Item = &'a [u8]> + 'a {
+        let data = self.data;
+        let header_len = self.header_len() as usize;
+        self.end_offsets()
+            .take_while(|end| *end != 0)
+            .scan(0usize, move |start, end| {
+                let end = end as usize;
+                let msg = &data[header_len + *start..header_len + end];
+                *start = end;
+                Some(msg)
+            })
+    }
+
+    /// Iterates over the messages whose first byte matches `message_type`, i.e. the variant index
+    /// of [`crate::messages::Message`] (`0` for `PriceFeedMessage`, `1` for `TwapMessage`, ...).
+    pub fn messages_of_type(&self, message_type: u8) -> impl Iterator<Item = &'a [u8]> + 'a {
+        self.messages()
+            .filter(move |msg| msg.first() == Some(&message_type))
+    }
+}
+
+/// Instruction data for a `put_all` CPI into the Message Buffer program.
+///
+/// The accounts of the instruction are, in order: the whitelist PDA, the signing
+/// `cpi_caller_auth` PDA of the calling program and the writable `MessageBuffer` PDA.
+pub fn put_all_instruction_data(base_account_key: &Pubkey, messages: &[&[u8]]) -> Vec<u8> {
+    let len = messages.iter().map(|msg| 4 + msg.len()).sum::<usize>();
+    let mut data = Vec::with_capacity(8 + 32 + 4 + len);
+    data.extend_from_slice(&PUT_ALL_DISCRIMINATOR);
+    data.extend_from_slice(base_account_key);
+    data.extend_from_slice(&(messages.len() as u32).to_le_bytes());
+    for msg in messages {
+        data.extend_from_slice(&(msg.len() as u32).to_le_bytes());
+        data.extend_from_slice(msg);
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk::hash::hash};
+
+    fn buffer(messages: &[&[u8]], extra_header: usize) -> Vec<u8> {
+        let header_len = HEADER_LEN + extra_header;
+        let mut data = vec![0u8; header_len];
+        data[..8].copy_from_slice(&MESSAGE_BUFFER_DISCRIMINATOR);
+        data[8] = 254;
+        data[9] = CURRENT_VERSION;
+        data[10..12].copy_from_slice(&(header_len as u16).to_le_bytes());
+
+        let mut end = 0u16;
+        for (i, msg) in messages.iter().enumerate() {
+            end += msg.len() as u16;
+            let pos = END_OFFSETS_START + 2 * i;
+            data[pos..pos + 2].copy_from_slice(&end.to_le_bytes());
+            data.extend_from_slice(msg);
+        }
+        data
+    }
+
+    #[test]
+    fn test_discriminators() {
+        assert_eq!(
+            MESSAGE_BUFFER_DISCRIMINATOR,
+            hash(b"account:MessageBuffer").to_bytes()[..8]
+        );
+        assert_eq!(
+            PUT_ALL_DISCRIMINATOR,
+            hash(b"global:put_all").to_bytes()[..8]
+        );
+    }
+
+    #[test]
+    fn test_read_messages() {
+        let messages: [&[u8]; 4] = [&[0, 1, 2], &[1, 3], &[0, 4, 5, 6], &[2]];
+        for extra_header in [0, 10] {
+            let data = buffer(&messages, extra_header);
+            let reader = MessageBufferReader::new(&data).unwrap();
+
+            assert_eq!(reader.bump(), 254);
+            assert_eq!(reader.version(), CURRENT_VERSION);
+            assert_eq!(reader.header_len() as usize, HEADER_LEN + extra_header);
+            assert_eq!(reader.num_messages(), 4);
+            assert_eq!(reader.messages().collect::<Vec<_>>(), messages);
+            assert_eq!(
+                reader.messages_of_type(0).collect::<Vec<_>>(),
+                [messages[0], messages[2]]
+            );
+            assert_eq!(
+                reader.messages_of_type(2).collect::<Vec<_>>(),
+                [messages[3]]
+            );
+            assert_eq!(reader.messages_of_type(3).count(), 0);
+        }
+
+        let data = buffer(&[], 0);
+        let reader = MessageBufferReader::new(&data).unwrap();
+        assert_eq!(reader.num_messages(), 0);
+        assert_eq!(reader.messages().count(), 0);
+    }
+
+    #[test]
+    fn test_unused_space_is_ignored() {
+        // buffers are usually allocated larger than their contents
+        let mut data = buffer(&[&[0, 1]], 0);
+        data.extend_from_slice(&[0xff; 32]);
+        let reader = MessageBufferReader::new(&data).unwrap();
+        assert_eq!(reader.messages().collect::<Vec<_>>(), [&[0u8, 1][..]]);
+    }
+
+    #[test]
+    fn test_invalid_buffers() {
+        let data = buffer(&[&[0, 1], &[1, 2, 3]], 0);
+        assert!(MessageBufferReader::new(&data).is_ok());
+        assert!(MessageBufferReader::new(&data[..HEADER_LEN - 1]).is_err());
+
+        // wrong discriminator
+        let mut invalid = data.clone();
+        invalid[0] ^= 1;
+        assert!(MessageBufferReader::new(&invalid).is_err());
+
+        // unknown header version
+        let mut invalid = data.clone();
+        invalid[9] = CURRENT_VERSION + 1;
+        assert!(MessageBufferReader::new(&invalid).is_err());
+
+        // header_len smaller than the header
+        let mut invalid = data.clone();
+        invalid[10..12].copy_from_slice(&(HEADER_LEN as u16 - 1).to_le_bytes());
+        assert!(MessageBufferReader::new(&invalid).is_err());
+
+        // header_len past the end of the account
+        let mut invalid = data.clone();
+        invalid[10..12].copy_from_slice(&(data.len() as u16 + 1).to_le_bytes());
+        assert!(MessageBufferReader::new(&invalid).is_err());
+
+        // decreasing end offsets
+        let mut invalid = data.clone();
+        invalid[END_OFFSETS_START + 2..END_OFFSETS_START + 4].copy_from_slice(&1u16.to_le_bytes());
+        assert!(MessageBufferReader::new(&invalid).is_err());
+
+        // message past the end of the account
+        assert!(MessageBufferReader::new(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_put_all_instruction_data() {
+        let base_account_key = [7u8; 32];
+        let messages: [&[u8]; 2] = [&[0, 1, 2], &[1]];
+        let data = put_all_instruction_data(&base_account_key, &messages);
+
+        let mut expected = PUT_ALL_DISCRIMINATOR.to_vec();
+        expected.extend(
+            borsh::BorshSerialize::try_to_vec(&(
+                base_account_key,
+                messages.iter().map(|m| m.to_vec()).collect::<Vec<_>>(),
+            ))
+            .unwrap(),
+        );
+        assert_eq!(data, expected);
+    }
+}
@@ -58,8 +58,8 @@ pub const DEFAULT_VALID_TIME_PERIOD: u64 = 180;
 
 const DEFAULT_SEQUENCE: u64 = 2;
 
-const NUM_GUARDIANS: u8 = 19; // Matches wormhole mainnet
-const DEFAULT_NUM_SIGNATURES: usize = 13; // Matches wormhole mainnet
+pub const NUM_GUARDIANS: u8 = 19; // Matches wormhole mainnet
+pub const DEFAULT_NUM_SIGNATURES: usize = 13; // Matches wormhole mainnet
 
 pub fn dummy_guardians() -> Vec<SecretKey> {
     let mut result: Vec<SecretKey> = vec![];
@@ -217,6 +217,29 @@ pub fn create_vaa_from_payload(
     emitter_address: Address,
     emitter_chain: Chain,
     sequence: u64,
+) -> Vaa<Box<RawMessage>> {
+    let guardian_indices: Vec<u8> = (0..NUM_GUARDIANS).collect();
+    let signers: Vec<u8> = guardian_indices
+        .choose_multiple(&mut thread_rng(), DEFAULT_NUM_SIGNATURES)
+        .cloned()
+        .collect();
+    create_vaa_from_payload_with_signers(
+        payload,
+        emitter_address,
+        emitter_chain,
+        sequence,
+        &signers,
+    )
+}
+
+/// Creates a VAA signed by the dummy guardians at the given indices. Signatures are deterministic,
+/// so the same arguments always produce the same VAA.
+pub fn create_vaa_from_payload_with_signers(
+    payload: &[u8],
+    emitter_address: Address,
+    emitter_chain: Chain,
+    sequence: u64,
+    signers: &[u8],
 ) -> Vaa<Box<RawMessage>> {
     let guardians = dummy_guardians();
 
@@ -230,34 +253,23 @@ pub fn create_vaa_from_payload(
 
     let digest = libsecp256k1Message::parse_slice(&body.digest().unwrap().secp256k_hash).unwrap();
 
-    let signatures: Vec<(Signature, RecoveryId)> = guardians
-        .iter()
-        .map(|x| libsecp256k1::sign(&digest, &x))
-        .collect();
-    let wormhole_signatures: Vec<wormhole_sdk::vaa::Signature> = signatures
+    let mut signers = signers.to_vec();
+    signers.sort();
+    let wormhole_signatures: Vec<wormhole_sdk::vaa::Signature> = signers
         .iter()
-        .enumerate()
-        .map(|(i, (x, y))| {
+        .map(|&index| {
+            let (x, y): (Signature, RecoveryId) =
+                libsecp256k1::sign(&digest, &guardians[index as usize]);
             let mut signature = [0u8; 65];
             signature[..64].copy_from_slice(&x.serialize());
             signature[64] = y.serialize();
-            wormhole_sdk::vaa::Signature {
-                index: i as u8,
-                signature,
-            }
+            wormhole_sdk::vaa::Signature { index, signature }
         })
         .collect();
 
-    let mut wormhole_signatures_subset: Vec<wormhole_sdk::vaa::Signature> = wormhole_signatures
-        .choose_multiple(&mut thread_rng(), DEFAULT_NUM_SIGNATURES)
-        .cloned()
-        .collect();
-
-    wormhole_signatures_subset.sort_by(|a, b| a.index.cmp(&b.index));
-
     let header = Header {
         version: 1,
-        signatures: wormhole_signatures_subset,
+        signatures: wormhole_signatures,
         ..Default::default()
     };
 
@@ -62,19 +62,38 @@ pub mod v1 {
         pub fn try_from_slice(bytes: &[u8]) -> Result<Self, Error> {
             let message = from_slice::<byteorder::BE, Self>(bytes)
                 .map_err(|_| Error::DeserializationError)?;
+            message.check_version()?;
+            Ok(message)
+        }
+
+        /// Like `try_from_slice`, but fails if there are bytes left after the update. Target
+        /// chains use this to reject updates with data appended to them.
+        pub fn try_from_slice_exact(bytes: &[u8]) -> Result<Self, Error> {
+            let mut deserializer = Deserializer::<byteorder::BE>::new(bytes);
+            let message =
+                Self::deserialize(&mut deserializer).map_err(|_| Error::DeserializationError)?;
+            require!(
+                deserializer.position() == bytes.len(),
+                Error::DeserializationError
+            );
+            message.check_version()?;
+            Ok(message)
+        }
+
+        fn check_version(&self) -> Result<(), Error> {
             require!(
-                &message.magic[..] == PYTHNET_ACCUMULATOR_UPDATE_MAGIC,
+                &self.magic[..] == PYTHNET_ACCUMULATOR_UPDATE_MAGIC,
                 Error::InvalidMagic
             );
-            require!(message.major_version == 1, Error::InvalidVersion);
+            require!(self.major_version == 1, Error::InvalidVersion);
             #[allow(clippy::absurd_extreme_comparisons)]
             {
                 require!(
-                    message.minor_version >= CURRENT_MINOR_VERSION,
+                    self.minor_version >= CURRENT_MINOR_VERSION,
                     Error::InvalidVersion
                 );
             }
-            Ok(message)
+            Ok(())
         }
     }
 
@@ -140,7 +159,7 @@ pub mod v1 {
 #[cfg(test)]
 mod tests {
     use crate::wire::{
-        array,
+        array, to_vec,
         v1::{AccumulatorUpdateData, Proof},
         Deserializer, PrefixedVec, Serializer,
     };
@@ -520,4 +539,25 @@ mod tests {
         buffer[4] = 0x03;
         AccumulatorUpdateData::try_from_slice(&buffer).unwrap_err();
     }
+
+    // Test that only try_from_slice accepts data after the end of the update
+    #[test]
+    fn test_accumulator_update_data_trailing_bytes() {
+        let update = AccumulatorUpdateData::new(Proof::WormholeMerkle {
+            vaa: PrefixedVec::from(vec![1, 2, 3]),
+            updates: vec![],
+        });
+        let mut buffer = to_vec::<_, byteorder::BE>(&update).unwrap();
+        assert_eq!(
+            AccumulatorUpdateData::try_from_slice_exact(&buffer).unwrap(),
+            update
+        );
+
+        buffer.push(0);
+        assert_eq!(
+            AccumulatorUpdateData::try_from_slice(&buffer).unwrap(),
+            update
+        );
+        AccumulatorUpdateData::try_from_slice_exact(&buffer).unwrap_err();
+    }
 }
@@ -100,6 +100,12 @@ where
             endian: std::marker::PhantomData,
         }
     }
+
+    /// The number of bytes of the buffer read so far. After deserializing a value, any bytes
+    /// past this position were left over.
+    pub fn position(&self) -> usize {
+        self.cursor.position() as usize
+    }
 }
 
 impl<'de, B> serde::de::Deserializer<'de> for &'_ mut Deserializer<'de, B>
@@ -0,0 +1,115 @@
+//! The CosmWasm contract, through the verification functions of `pyth-cosmwasm-sdk` that it
+//! runs before storing an update.
+
+use {
+    crate::{guardians::GuardianSet, Config, Decision, PriceFeed, UpdateCase, Verifier},
+    anyhow::Result,
+    cosmwasm_std::{
+        from_binary, testing::MockQuerier, to_binary, Addr, Binary, Empty, QuerierResult,
+        QuerierWrapper, SystemError, SystemResult, WasmQuery,
+    },
+    cw_wormhole::{msg::QueryMsg as WormholeQueryMsg, state::ParsedVAA},
+    pyth_cosmwasm_sdk::{parse_update, PythDataSource},
+    std::collections::HashSet,
+};
+
+const WORMHOLE_ADDR: &str = "wormhole";
+const BLOCK_TIME: u64 = 1_700_000_000;
+
+pub struct CosmwasmVerifier {
+    querier: MockQuerier,
+    data_sources: HashSet<PythDataSource>,
+}
+
+impl CosmwasmVerifier {
+    pub fn new(config: &Config) -> Result<Self> {
+        let guardians = GuardianSet::from_config(config)?;
+        let mut querier = MockQuerier::new(&[]);
+        querier.update_wasm(move |query| handle_wasm_query(&guardians, query));
+
+        let data_sources = config
+            .data_sources
+            .iter()
+            .map(|source| {
+                Ok(PythDataSource {
+                    emitter: Binary(hex::decode(&source.emitter)?),
+                    chain_id: source.chain,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(CosmwasmVerifier {
+            querier,
+            data_sources,
+        })
+    }
+}
+
+/// Answers the `VerifyVAA` queries of the verifier like the Wormhole contract would.
+fn handle_wasm_query(guardians: &GuardianSet, query: &WasmQuery) -> QuerierResult {
+    match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == WORMHOLE_ADDR => {
+            match from_binary::<WormholeQueryMsg>(msg) {
+                Ok(WormholeQueryMsg::VerifyVAA { vaa, .. }) => {
+                    let result = guardians
+                        .verify_vaa(&vaa)
+                        .map_err(|e| e.to_string())
+                        .and_then(|_| ParsedVAA::deserialize(&vaa).map_err(|e| e.to_string()))
+                        .and_then(|vaa| to_binary(&vaa).map_err(|e| e.to_string()));
+                    SystemResult::Ok(result.into())
+                }
+                _ => SystemResult::Err(SystemError::InvalidRequest {
+                    error: "Invalid message".into(),
+                    request: msg.clone(),
+                }),
+            }
+        }
+        _ => SystemResult::Err(SystemError::NoSuchContract {
+            addr: query_address(query),
+        }),
+    }
+}
+
+fn query_address(query: &WasmQuery) -> String {
+    match query {
+        WasmQuery::Smart { contract_addr, .. } | WasmQuery::Raw { contract_addr, .. } => {
+            contract_addr.clone()
+        }
+        _ => String::new(),
+    }
+}
+
+/// The fields of the corpus that a `PriceFeed` holds, it has no previous publish time.
+fn price_feed_fields(feed: &pyth_sdk_cw::PriceFeed) -> PriceFeed {
+    let price = feed.get_price_unchecked();
+    let ema_price = feed.get_ema_price_unchecked();
+    let mut fields = PriceFeed::new();
+    fields.insert("feed_id".into(), feed.id.to_hex().into());
+    fields.insert("price".into(), price.price.to_string().into());
+    fields.insert("conf".into(), price.conf.to_string().into());
+    fields.insert("exponent".into(), price.expo.into());
+    fields.insert("publish_time".into(), price.publish_time.to_string().into());
+    fields.insert("ema_price".into(), ema_price.price.to_string().into());
+    fields.insert("ema_conf".into(), ema_price.conf.to_string().into());
+    fields
+}
+
+impl Verifier for CosmwasmVerifier {
+    fn name(&self) -> &str {
+        "cosmwasm"
+    }
+
+    fn verify(&self, case: &UpdateCase) -> Option<Decision> {
+        let data = case.data().ok()?;
+        let result = parse_update(
+            &QuerierWrapper::<Empty>::new(&self.querier),
+            &Addr::unchecked(WORMHOLE_ADDR),
+            &self.data_sources,
+            BLOCK_TIME,
+            &Binary(data),
+        );
+        Some(match result {
+            Ok(feeds) => Decision::Accept(feeds.iter().map(price_feed_fields).collect()),
+            Err(e) => Decision::Reject(e.to_string()),
+        })
+    }
+}
@@ -0,0 +1,68 @@
+//! Decisions of a target whose verifier can't be called from Rust, read from a results file
+//! written by the test suite of that target.
+//!
+//! The file maps the name of every case the suite ran to its decision:
+//!
+//! ```json
+//! {
+//!   "verifier": "sui",
+//!   "results": {
+//!     "single_price_feed": { "accepted": true, "price_feeds": [{ "price": "6512345678900" }] },
+//!     "below_quorum": { "accepted": false, "error": "EInvalidSignatures" }
+//!   }
+//! }
+//! ```
+//!
+//! `price_feeds` holds the decoded fields of the returned price feeds in the encoding of the
+//! corpus and may leave out fields or be omitted altogether.
+
+use {
+    crate::{Decision, PriceFeed, UpdateCase, Verifier},
+    anyhow::{Context, Result},
+    serde::Deserialize,
+    std::{collections::HashMap, fs, path::Path},
+};
+
+#[derive(Clone, Debug, Deserialize)]
+struct CaseResult {
+    accepted: bool,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    price_feeds: Option<Vec<PriceFeed>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FixtureVerifier {
+    verifier: String,
+    results: HashMap<String, CaseResult>,
+}
+
+impl FixtureVerifier {
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = fs::read(path).with_context(|| format!("Could not read {}", path.display()))?;
+        serde_json::from_slice(&data).with_context(|| format!("Invalid results {}", path.display()))
+    }
+}
+
+impl Verifier for FixtureVerifier {
+    fn name(&self) -> &str {
+        &self.verifier
+    }
+
+    fn verify(&self, case: &UpdateCase) -> Option<Decision> {
+        let result = self.results.get(&case.name)?;
+        Some(if result.accepted {
+            // Results without decoded price feeds only report the decision, which then matches
+            // any expected price feeds
+            Decision::Accept(
+                result
+                    .price_feeds
+                    .clone()
+                    .unwrap_or_else(|| vec![PriceFeed::new(); case.price_feeds.len()]),
+            )
+        } else {
+            Decision::Reject(result.error.clone().unwrap_or_default())
+        })
+    }
+}
@@ -0,0 +1,5 @@
+//! Adapters that run the verifier of a target implementation on the corpus.
+
+pub mod cosmwasm;
+pub mod fixture;
+pub mod near;
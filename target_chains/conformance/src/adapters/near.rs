@@ -0,0 +1,145 @@
+//! The NEAR receiver contract, run in the mocked blockchain of `near-sdk`.
+//!
+//! An accumulator update goes through the same calls as on chain: `update_price_feeds` checks
+//! the update and the data source of its VAA, then calls Wormhole to verify the VAA, and
+//! `verify_wormhole_merkle_callback` checks the messages with the result of that call. The
+//! callback only stores the price feeds, so the returned ones come from
+//! `Pyth::parse_accumulator_update`, the function the callback parses the update with.
+
+use {
+    crate::{guardians::GuardianSet, Config, Decision, PriceFeed, UpdateCase, Verifier},
+    anyhow::Result,
+    near_sdk::{
+        json_types::U128, test_utils::VMContextBuilder, test_vm_config, testing_env, AccountId,
+        NearToken, PromiseError, PromiseResult, RuntimeFeesConfig,
+    },
+    pyth::{state::Source, Pyth},
+    pythnet_sdk::wire::v1::{AccumulatorUpdateData, Proof},
+    serde_json::json,
+    std::thread,
+};
+
+const BLOCK_TIME: u64 = 1_700_000_000;
+/// Covers the update fee and the storage of the price feeds of any update of the corpus
+const DEPOSIT: NearToken = NearToken::from_near(100);
+
+pub struct NearVerifier {
+    guardians: GuardianSet,
+    sources: Vec<Source>,
+    gov_source: Source,
+}
+
+impl NearVerifier {
+    pub fn new(config: &Config) -> Result<Self> {
+        let source = |chain: u16, emitter: &str| -> Result<Source> {
+            let emitter = hex::decode(emitter)?;
+            Ok(serde_json::from_value(
+                json!({ "emitter": emitter, "chain": chain }),
+            )?)
+        };
+        Ok(NearVerifier {
+            guardians: GuardianSet::from_config(config)?,
+            sources: config
+                .data_sources
+                .iter()
+                .map(|s| source(s.chain, &s.emitter))
+                .collect::<Result<_>>()?,
+            gov_source: source(
+                config.governance_source.chain,
+                &config.governance_source.emitter,
+            )?,
+        })
+    }
+
+    /// Runs an update through the contract, which is deployed from scratch for every update.
+    fn update(&self, data: &[u8]) -> Result<Vec<PriceFeed>, String> {
+        let contract: AccountId = "pyth.near".parse().unwrap();
+        let relayer: AccountId = "relayer.near".parse().unwrap();
+        let mut context = VMContextBuilder::new();
+        context
+            .current_account_id(contract.clone())
+            .predecessor_account_id(relayer.clone())
+            .block_timestamp(BLOCK_TIME * 1_000_000_000)
+            .attached_deposit(DEPOSIT);
+        testing_env!(context.build());
+
+        let mut pyth = Pyth::new(
+            "wormhole.near".parse().unwrap(),
+            self.sources.first().cloned().unwrap_or_default(),
+            self.gov_source.clone(),
+            U128(1),
+            60,
+        );
+        pyth.set_sources(self.sources.clone());
+
+        let hex_data = hex::encode(data);
+        pyth.update_price_feeds(hex_data.clone())
+            .map_err(|e| e.to_string())?;
+
+        // Wormhole panics on an invalid VAA, which fails the promise the callback gets.
+        let update_data = AccumulatorUpdateData::try_from_slice(data).map_err(|e| e.to_string())?;
+        let vaa_result = match update_data.proof {
+            Proof::WormholeMerkle { vaa, .. } => self.guardians.verify_vaa(vaa.as_ref()),
+        };
+        let (promise_result, callback_result) = match vaa_result {
+            Ok(()) => (PromiseResult::Successful(vec![]), Ok(0)),
+            Err(_) => (PromiseResult::Failed, Err(PromiseError::Failed)),
+        };
+        context.predecessor_account_id(contract);
+        testing_env!(
+            context.build(),
+            test_vm_config(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![promise_result],
+        );
+        pyth.verify_wormhole_merkle_callback(relayer, hex_data.clone(), callback_result)
+            .map_err(|e| e.to_string())?;
+
+        Ok(Pyth::parse_accumulator_update(&hex_data)
+            .map_err(|e| e.to_string())?
+            .iter()
+            .map(price_feed_fields)
+            .collect())
+    }
+}
+
+fn price_feed_fields(feed: &pyth::state::PriceFeed) -> PriceFeed {
+    let mut fields = PriceFeed::new();
+    fields.insert("feed_id".into(), hex::encode(feed.id.0).into());
+    fields.insert("price".into(), feed.price.price.0.to_string().into());
+    fields.insert("conf".into(), feed.price.conf.0.to_string().into());
+    fields.insert("exponent".into(), feed.price.expo.into());
+    fields.insert(
+        "publish_time".into(),
+        feed.price.publish_time.to_string().into(),
+    );
+    fields.insert(
+        "ema_price".into(),
+        feed.ema_price.price.0.to_string().into(),
+    );
+    fields.insert("ema_conf".into(), feed.ema_price.conf.0.to_string().into());
+    fields
+}
+
+impl Verifier for NearVerifier {
+    fn name(&self) -> &str {
+        "near"
+    }
+
+    fn verify(&self, case: &UpdateCase) -> Option<Decision> {
+        let data = case.data().ok()?;
+        // The mocked blockchain is per thread, so a new thread starts from empty storage. A
+        // panic fails the transaction on chain, so it counts as a rejection.
+        let result = thread::scope(|scope| {
+            scope
+                .spawn(|| self.update(&data))
+                .join()
+                .unwrap_or_else(|_| Err("contract panicked".to_string()))
+        });
+        Some(match result {
+            Ok(feeds) => Decision::Accept(feeds),
+            Err(e) => Decision::Reject(e),
+        })
+    }
+}
@@ -0,0 +1,83 @@
+//! Signature verification of VAAs against the guardian set of the corpus.
+//!
+//! The Rust verifiers leave the signature checks to the Wormhole contract of their chain. Their
+//! adapters stand in for that contract with [`GuardianSet::verify_vaa`], which applies the same
+//! rules as the Wormhole core contracts.
+
+use {
+    crate::Config,
+    anyhow::{bail, Context, Result},
+    libsecp256k1::{Message, RecoveryId, Signature},
+    pythnet_sdk::hashers::{keccak256::Keccak256, Hasher},
+    serde_wormhole::RawMessage,
+    wormhole_sdk::{vaa::Body, Vaa},
+};
+
+#[derive(Clone, Debug)]
+pub struct GuardianSet {
+    pub index: u32,
+    pub addresses: Vec<[u8; 20]>,
+}
+
+impl GuardianSet {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let addresses = config
+            .guardians
+            .iter()
+            .map(|address| {
+                let mut result = [0u8; 20];
+                hex::decode_to_slice(address, &mut result)
+                    .with_context(|| format!("Invalid guardian address {address}"))?;
+                Ok(result)
+            })
+            .collect::<Result<_>>()?;
+        Ok(GuardianSet {
+            index: config.guardian_set_index,
+            addresses,
+        })
+    }
+
+    /// The number of signatures a VAA needs, more than two thirds of the guardians.
+    pub fn quorum(&self) -> usize {
+        self.addresses.len() * 2 / 3 + 1
+    }
+
+    /// Checks that `data` is a VAA signed by a quorum of the guardian set.
+    pub fn verify_vaa(&self, data: &[u8]) -> Result<()> {
+        let vaa: Vaa<&RawMessage> = serde_wormhole::from_slice(data).context("Invalid VAA")?;
+        if vaa.guardian_set_index != self.index {
+            bail!("Unknown guardian set {}", vaa.guardian_set_index);
+        }
+        if vaa.signatures.len() < self.quorum() {
+            bail!("No quorum");
+        }
+
+        let signatures = vaa.signatures.clone();
+        let (_, body): (_, Body<&RawMessage>) = vaa.into();
+        let digest = Message::parse_slice(&body.digest()?.secp256k_hash)?;
+
+        let mut last_index = None;
+        for signature in signatures {
+            // Indices have to be strictly increasing, so no guardian is counted twice
+            if last_index.is_some_and(|last| signature.index <= last) {
+                bail!("Signatures are not in order");
+            }
+            last_index = Some(signature.index);
+
+            let address = self
+                .addresses
+                .get(signature.index as usize)
+                .context("Signature of an unknown guardian")?;
+            let public_key = libsecp256k1::recover(
+                &digest,
+                &Signature::parse_standard_slice(&signature.signature[..64])?,
+                &RecoveryId::parse(signature.signature[64])?,
+            )?;
+            let public_key = public_key.serialize();
+            if &Keccak256::hashv(&[&public_key[1..]])[12..] != address {
+                bail!("Invalid signature of guardian {}", signature.index);
+            }
+        }
+        Ok(())
+    }
+}
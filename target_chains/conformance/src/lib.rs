@@ -0,0 +1,219 @@
+//! Conformance tests of the target chain verifiers of Pyth price updates.
+//!
+//! The corpus in `corpus/` holds valid, malformed and adversarial accumulator updates together
+//! with the price feeds a verifier must return for them. It is generated by the
+//! `generate_fixtures` example of `pythnet-sdk`. Every [`Verifier`] decides whether to accept
+//! each update, and [`run`] compares the decisions with the corpus, so an implementation that
+//! accepts an update the others reject (or decodes it differently) shows up as a mismatch.
+//!
+//! Verifiers written in Rust are called natively through the adapters in [`adapters`]. Test
+//! suites of the other targets write their decisions to a JSON file that
+//! [`adapters::fixture::FixtureVerifier`] reads back.
+
+pub mod adapters;
+pub mod guardians;
+
+use {
+    anyhow::{Context, Result},
+    serde::Deserialize,
+    serde_json::{Map, Value},
+    std::{fmt, fs, path::Path},
+};
+
+/// The decoded fields of a price feed, as in the corpus. Integers wider than 32 bits are decimal
+/// strings.
+pub type PriceFeed = Map<String, Value>;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DataSource {
+    pub chain: u16,
+    /// Hex encoded emitter address
+    pub emitter: String,
+}
+
+/// The guardian set and data sources the updates of the corpus are verified against.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Config {
+    pub guardian_set_index: u32,
+    /// Hex encoded guardian addresses
+    pub guardians: Vec<String>,
+    pub quorum: usize,
+    pub data_sources: Vec<DataSource>,
+    pub governance_source: DataSource,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct UpdateCase {
+    pub name: String,
+    pub description: String,
+    /// Hex encoded accumulator update
+    pub data: String,
+    pub valid: bool,
+    /// Why the update must be rejected, for information only. Verifiers report errors in their
+    /// own terms, so only the decision itself is compared.
+    pub error: Option<String>,
+    pub price_feeds: Vec<PriceFeed>,
+}
+
+impl UpdateCase {
+    pub fn data(&self) -> Result<Vec<u8>> {
+        hex::decode(&self.data).with_context(|| format!("Invalid data in case {}", self.name))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Corpus {
+    pub config: Config,
+    pub updates: Vec<UpdateCase>,
+}
+
+impl Corpus {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let read = |name: &str| -> Result<Vec<u8>> {
+            let path = dir.join(name);
+            fs::read(&path).with_context(|| format!("Could not read {}", path.display()))
+        };
+        Ok(Corpus {
+            config: serde_json::from_slice(&read("config.json")?)?,
+            updates: serde_json::from_slice(&read("accumulator_updates.json")?)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Decision {
+    /// The update was accepted and contained these price feeds, in order
+    Accept(Vec<PriceFeed>),
+    /// The update was rejected with this error
+    Reject(String),
+}
+
+/// A target chain implementation of price update verification.
+pub trait Verifier {
+    fn name(&self) -> &str;
+
+    /// Verifies the update of `case`, or returns `None` if the verifier has no decision for it.
+    fn verify(&self, case: &UpdateCase) -> Option<Decision>;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Mismatch(String),
+    /// The verifier has no decision for the case
+    Missing,
+}
+
+#[derive(Clone, Debug)]
+pub struct CaseReport {
+    pub case: String,
+    /// The outcome of every verifier, in the order they were passed to [`run`]
+    pub outcomes: Vec<(String, Outcome)>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    pub cases: Vec<CaseReport>,
+}
+
+impl Report {
+    pub fn mismatches(&self) -> impl Iterator<Item = (&str, &str, &str)> {
+        self.cases.iter().flat_map(|report| {
+            report
+                .outcomes
+                .iter()
+                .filter_map(move |(verifier, outcome)| match outcome {
+                    Outcome::Mismatch(reason) => {
+                        Some((report.case.as_str(), verifier.as_str(), reason.as_str()))
+                    }
+                    _ => None,
+                })
+        })
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches().next().is_none()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for report in &self.cases {
+            writeln!(f, "{}", report.case)?;
+            for (verifier, outcome) in &report.outcomes {
+                match outcome {
+                    Outcome::Pass => writeln!(f, "  ok       {verifier}")?,
+                    Outcome::Missing => writeln!(f, "  missing  {verifier}")?,
+                    Outcome::Mismatch(reason) => writeln!(f, "  MISMATCH {verifier}: {reason}")?,
+                }
+            }
+        }
+        let mismatches = self.mismatches().count();
+        if mismatches == 0 {
+            writeln!(f, "All verifiers agree with the corpus")
+        } else {
+            writeln!(f, "{mismatches} decisions differ from the corpus")
+        }
+    }
+}
+
+/// Checks that the price feeds returned by a verifier match the expected ones. A verifier may
+/// leave out fields it does not decode, but every field it returns has to match.
+fn compare_price_feeds(expected: &[PriceFeed], actual: &[PriceFeed]) -> Outcome {
+    if expected.len() != actual.len() {
+        return Outcome::Mismatch(format!(
+            "returned {} price feeds instead of {}",
+            actual.len(),
+            expected.len()
+        ));
+    }
+    for (i, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+        for (field, value) in actual {
+            if expected.get(field) != Some(value) {
+                return Outcome::Mismatch(format!(
+                    "price feed {i} has {field} {value}, expected {}",
+                    expected.get(field).unwrap_or(&Value::Null)
+                ));
+            }
+        }
+    }
+    Outcome::Pass
+}
+
+fn compare(case: &UpdateCase, decision: Option<Decision>) -> Outcome {
+    match (case.valid, decision) {
+        (_, None) => Outcome::Missing,
+        (true, Some(Decision::Accept(price_feeds))) => {
+            compare_price_feeds(&case.price_feeds, &price_feeds)
+        }
+        (true, Some(Decision::Reject(error))) => {
+            Outcome::Mismatch(format!("rejected a valid update: {error}"))
+        }
+        (false, Some(Decision::Accept(_))) => Outcome::Mismatch(format!(
+            "accepted an invalid update, expected {}",
+            case.error.as_deref().unwrap_or("a rejection")
+        )),
+        (false, Some(Decision::Reject(_))) => Outcome::Pass,
+    }
+}
+
+/// Runs every verifier on every update of the corpus and compares the decisions with it.
+pub fn run(corpus: &Corpus, verifiers: &[Box<dyn Verifier>]) -> Report {
+    let cases = corpus
+        .updates
+        .iter()
+        .map(|case| CaseReport {
+            case: case.name.clone(),
+            outcomes: verifiers
+                .iter()
+                .map(|verifier| {
+                    (
+                        verifier.name().to_string(),
+                        compare(case, verifier.verify(case)),
+                    )
+                })
+                .collect(),
+        })
+        .collect();
+    Report { cases }
+}
@@ -0,0 +1,50 @@
+use {
+    anyhow::Result,
+    clap::Parser,
+    pyth_conformance::{
+        adapters::{cosmwasm::CosmwasmVerifier, fixture::FixtureVerifier, near::NearVerifier},
+        run, Corpus, Verifier,
+    },
+    std::{path::PathBuf, process::ExitCode},
+};
+
+/// Runs the target chain verifiers on the conformance corpus and reports every decision that
+/// differs from it.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Args {
+    /// Directory of the corpus
+    #[arg(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/corpus"))]
+    corpus: PathBuf,
+
+    /// Results file written by the test suite of a target that can't be called natively. Can
+    /// be given multiple times.
+    #[arg(long)]
+    results: Vec<PathBuf>,
+
+    /// Only check the results files, not the Rust verifiers
+    #[arg(long)]
+    skip_native: bool,
+}
+
+fn main() -> Result<ExitCode> {
+    let args = Args::parse();
+    let corpus = Corpus::load(&args.corpus)?;
+
+    let mut verifiers: Vec<Box<dyn Verifier>> = vec![];
+    if !args.skip_native {
+        verifiers.push(Box::new(CosmwasmVerifier::new(&corpus.config)?));
+        verifiers.push(Box::new(NearVerifier::new(&corpus.config)?));
+    }
+    for path in &args.results {
+        verifiers.push(Box::new(FixtureVerifier::load(path)?));
+    }
+
+    let report = run(&corpus, &verifiers);
+    print!("{report}");
+    Ok(if report.is_consistent() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
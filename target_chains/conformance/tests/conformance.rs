@@ -0,0 +1,75 @@
+use {
+    pyth_conformance::{
+        adapters::{cosmwasm::CosmwasmVerifier, fixture::FixtureVerifier, near::NearVerifier},
+        run, Corpus, Outcome, Verifier,
+    },
+    std::{fs, path::Path},
+    tempfile::NamedTempFile,
+};
+
+/// Decisions of the native verifiers known to differ from the corpus, as (case, verifier). The
+/// test fails if one of them starts to agree, so fixed deviations get removed from here.
+const KNOWN_MISMATCHES: &[(&str, &str)] = &[];
+
+fn corpus() -> Corpus {
+    Corpus::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus")).unwrap()
+}
+
+#[test]
+fn test_native_verifiers() {
+    let corpus = corpus();
+    let verifiers: Vec<Box<dyn Verifier>> = vec![
+        Box::new(CosmwasmVerifier::new(&corpus.config).unwrap()),
+        Box::new(NearVerifier::new(&corpus.config).unwrap()),
+    ];
+    let report = run(&corpus, &verifiers);
+    let mismatches: Vec<_> = report
+        .mismatches()
+        .map(|(case, verifier, _)| (case, verifier))
+        .collect();
+    assert_eq!(mismatches, KNOWN_MISMATCHES, "{report}");
+}
+
+#[test]
+fn test_fixture_verifier() {
+    let corpus = corpus();
+    let file = NamedTempFile::new().unwrap();
+    fs::write(
+        file.path(),
+        r#"{
+            "verifier": "test",
+            "results": {
+                "single_price_feed": {
+                    "accepted": true,
+                    "price_feeds": [{ "price": "6512345678900", "exponent": -8 }]
+                },
+                "multiple_price_feeds": { "accepted": true },
+                "all_price_feeds": { "accepted": true, "price_feeds": [] },
+                "invalid_data_source": { "accepted": false },
+                "below_quorum": { "accepted": true }
+            }
+        }"#,
+    )
+    .unwrap();
+    let verifiers: Vec<Box<dyn Verifier>> =
+        vec![Box::new(FixtureVerifier::load(file.path()).unwrap())];
+    let report = run(&corpus, &verifiers);
+
+    let outcome = |name: &str| {
+        report
+            .cases
+            .iter()
+            .find(|report| report.case == name)
+            .unwrap()
+            .outcomes[0]
+            .1
+            .clone()
+    };
+    assert_eq!(outcome("single_price_feed"), Outcome::Pass);
+    assert_eq!(outcome("multiple_price_feeds"), Outcome::Pass);
+    assert!(matches!(outcome("all_price_feeds"), Outcome::Mismatch(_)));
+    assert_eq!(outcome("invalid_data_source"), Outcome::Pass);
+    assert!(matches!(outcome("below_quorum"), Outcome::Mismatch(_)));
+    assert_eq!(outcome("truncated"), Outcome::Missing);
+    assert!(!report.is_consistent());
+}
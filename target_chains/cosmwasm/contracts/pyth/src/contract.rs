@@ -9,46 +9,42 @@ use {
         governance::{
             GovernanceAction::{
                 AuthorizeGovernanceDataSourceTransfer, RequestGovernanceDataSourceTransfer,
-                SetDataSources, SetFee, SetValidPeriod, UpgradeContract,
+                SetDataSources, SetFee, SetFeeInToken, SetFeeRecipient, SetIbcChannels,
+                SetPriceHistoryDepth, SetValidPeriod, SetWormholeAddress, UpgradeContract,
             },
             GovernanceInstruction, GovernanceModule,
         },
         msg::{InstantiateMsg, MigrateMsg},
         state::{
-            config, config_read, price_feed_bucket, price_feed_read_bucket, set_contract_version,
-            ConfigInfo, PythDataSource,
+            config, config_read, fee_recipient, fee_recipient_read, fee_tokens, fee_tokens_read,
+            ibc_channels, price_feed_bucket, price_feed_read_bucket, price_history_bucket,
+            price_history_depth, price_history_depth_read, price_history_read_bucket,
+            set_contract_version, ConfigInfo, FeeToken, PythDataSource, TokenFee,
         },
     },
-    byteorder::BigEndian,
     cosmwasm_std::{
-        coin, entry_point, to_binary, Addr, Binary, Coin, CosmosMsg, Deps, DepsMut, Env,
-        MessageInfo, OverflowError, OverflowOperation, QueryRequest, Response, StdResult, WasmMsg,
-        WasmQuery,
+        coin, entry_point, from_binary, has_coins, to_binary, Addr, Api, BankMsg, Binary, Coin,
+        CosmosMsg, Deps, DepsMut, Env, MessageInfo, OverflowError, OverflowOperation, Response,
+        StdResult, Storage, Uint128, WasmMsg,
     },
-    cw_wormhole::{msg::QueryMsg as WormholeQueryMsg, state::ParsedVAA},
+    cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg},
+    cw_wormhole::state::ParsedVAA,
     pyth_sdk::{Identifier, UnixTimestamp},
     pyth_sdk_cw::{
-        error::PythContractError, ExecuteMsg, Price, PriceFeed, PriceFeedResponse, PriceIdentifier,
-        QueryMsg,
-    },
-    pyth_wormhole_attester_sdk::{BatchPriceAttestation, PriceAttestation, PriceStatus},
-    pythnet_sdk::{
-        accumulators::merkle::MerkleRoot,
-        hashers::keccak256_160::Keccak160,
-        messages::Message,
-        wire::{
-            from_slice,
-            v1::{
-                AccumulatorUpdateData, Proof, WormholeMessage, WormholePayload,
-                PYTHNET_ACCUMULATOR_UPDATE_MAGIC,
-            },
-        },
+        error::PythContractError, Cw20HookMsg, Cw20ReceiveMsg, ExecuteMsg, PriceFeed,
+        PriceFeedHistoryResponse, PriceFeedResponse, PriceFeedUpdateResult, PriceFeedUpdateStatus,
+        QueryMsg, UpdatePriceFeedsResponse,
     },
+    pythnet_sdk::wire::v1::{AccumulatorUpdateData, Proof, PYTHNET_ACCUMULATOR_UPDATE_MAGIC},
     std::{collections::HashSet, convert::TryFrom, iter::FromIterator, time::Duration},
 };
 
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Maximum number of prices that can be kept in the history of each price feed. Every update
+/// rewrites the history of the feed, so this bounds the extra gas cost of an update.
+const MAX_PRICE_HISTORY_DEPTH: u16 = 64;
+
 /// Migration code that runs once when the contract is upgraded. On upgrade, the migrate
 /// function in the *new* code version is run, which allows the new code to update the on-chain
 /// state before any of its other functions are invoked.
@@ -98,14 +94,7 @@ pub fn instantiate(
 /// originates from the expected emitter.
 pub fn parse_and_verify_vaa(deps: Deps, block_time: u64, data: &Binary) -> StdResult<ParsedVAA> {
     let cfg = config_read(deps.storage).load()?;
-    let vaa: ParsedVAA = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: cfg.wormhole_contract.to_string(),
-        msg: to_binary(&WormholeQueryMsg::VerifyVAA {
-            vaa: data.clone(),
-            block_time,
-        })?,
-    }))?;
-    Ok(vaa)
+    pyth_cosmwasm_sdk::parse_and_verify_vaa(&deps.querier, &cfg.wormhole_contract, block_time, data)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -120,21 +109,41 @@ pub fn execute(
         ExecuteMsg::ExecuteGovernanceInstruction { data } => {
             execute_governance_instruction(deps, env, info, &data)
         }
+        ExecuteMsg::SweepFees {} => sweep_fees(deps.as_ref(), &env),
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
     }
 }
 
+/// Checks if `funds` pay the fee for `data` in one of the native tokens accepted by governance
+/// besides the fee in the config.
+fn is_token_fee_sufficient(deps: &Deps, funds: &[Coin], data: &[Binary]) -> StdResult<bool> {
+    let num_updates = get_num_updates(data)?;
+    for token_fee in fee_tokens_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default()
+    {
+        if let FeeToken::Native { denom } = token_fee.token {
+            let amount = token_fee.amount.checked_mul(num_updates.into())?;
+            if has_coins(funds, &Coin { denom, amount }) {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
 #[cfg(not(feature = "osmosis"))]
 fn is_fee_sufficient(deps: &Deps, info: MessageInfo, data: &[Binary]) -> StdResult<bool> {
-    use cosmwasm_std::has_coins;
-
     let state = config_read(deps.storage).load()?;
 
     // For any chain other than osmosis there is only one base denom
     // If base denom is present in coins and has enough amount this will return true
     // or if the base fee is set to 0
+    // or if the fee is paid in another token accepted by governance
     // else it will return false
     return Ok(state.fee.amount.u128() == 0
-        || has_coins(info.funds.as_ref(), &get_update_fee(deps, data)?));
+        || has_coins(info.funds.as_ref(), &get_update_fee(deps, data)?)
+        || is_token_fee_sufficient(deps, &info.funds, data)?);
 }
 
 // it only checks for fee denoms other than the base denom
@@ -163,6 +172,11 @@ fn is_allowed_tx_fees_denom(deps: &Deps, denom: &String) -> bool {
 fn is_fee_sufficient(deps: &Deps, info: MessageInfo, data: &[Binary]) -> StdResult<bool> {
     let state = config_read(deps.storage).load()?;
 
+    // tokens accepted by governance have their own fee
+    if is_token_fee_sufficient(deps, &info.funds, data)? {
+        return Ok(true);
+    }
+
     // how to change this in future
     // for given coins verify they are allowed in txfee module
     // convert each of them to the base token that is 'uosmo'
@@ -200,8 +214,11 @@ fn is_fee_sufficient(deps: &Deps, info: MessageInfo, data: &[Binary]) -> StdResu
 ///
 /// This method additionally requires the caller to pay a fee to the contract; the
 /// magnitude of the fee depends on both the data and the current contract configuration.
+///
+/// The response data is an [UpdatePriceFeedsResponse] describing how each price update was
+/// applied, so that callers can tell which feeds in the batch actually advanced.
 fn update_price_feeds(
-    mut deps: DepsMut,
+    deps: DepsMut,
     env: Env,
     info: MessageInfo,
     data: &[Binary],
@@ -210,28 +227,95 @@ fn update_price_feeds(
         Err(PythContractError::InsufficientFee)?;
     }
 
-    let (num_total_attestations, total_new_feeds) = apply_updates(&mut deps, &env, data)?;
+    process_price_updates(deps, env, data)
+}
 
-    let num_total_new_attestations = total_new_feeds.len();
+/// Handles CW20 tokens sent to the contract. The tokens pay the fee of the message in
+/// `msg.msg`, with the fee of the token set by governance.
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> StdResult<Response<MsgWrapper>> {
+    let token = FeeToken::Cw20 {
+        contract: info.sender.clone(),
+    };
+    let token_fee = fee_tokens_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default()
+        .into_iter()
+        .find(|token_fee| token_fee.token == token)
+        .ok_or(PythContractError::InvalidFeeDenom {
+            denom: info.sender.to_string(),
+        })?;
+
+    match from_binary(&msg.msg)? {
+        Cw20HookMsg::UpdatePriceFeeds { data } => {
+            let fee = token_fee
+                .amount
+                .checked_mul(get_num_updates(&data)?.into())?;
+            if msg.amount < fee {
+                Err(PythContractError::InsufficientFee)?;
+            }
+            process_price_updates(deps, env, &data)
+        }
+    }
+}
 
-    let response = Response::new();
+/// Applies the price updates in `data` after the fee for them has been paid.
+fn process_price_updates(
+    mut deps: DepsMut,
+    env: Env,
+    data: &[Binary],
+) -> StdResult<Response<MsgWrapper>> {
+    let updates = apply_updates(&mut deps, &env, data)?;
+
+    let num_total_attestations = updates.len();
+    let count = |status: PriceFeedUpdateStatus| updates.iter().filter(|u| u.1 == status).count();
+    let num_stale_skipped = count(PriceFeedUpdateStatus::StaleSkipped);
+    let num_unknown = count(PriceFeedUpdateStatus::Unknown);
+    // feeds seen for the first time are stored too, so they count as updated
+    let num_total_new_attestations = num_total_attestations - num_stale_skipped;
+
+    let results = updates
+        .iter()
+        .map(|(feed, status)| PriceFeedUpdateResult {
+            id: feed.id,
+            publish_time: feed.get_price_unchecked().publish_time,
+            status: *status,
+        })
+        .collect();
+
+    let response = Response::new()
+        .set_data(to_binary(&UpdatePriceFeedsResponse { results })?)
+        .add_attribute("action", "update_price_feeds")
+        .add_attribute("num_attestations", format!("{num_total_attestations}"))
+        .add_attribute("num_updated", format!("{num_total_new_attestations}"))
+        .add_attribute("num_stale_skipped", format!("{num_stale_skipped}"))
+        .add_attribute("num_unknown", format!("{num_unknown}"));
+
+    #[cfg(feature = "ibc")]
+    let response = response.add_messages(crate::ibc::forward_price_feeds(
+        deps.as_ref(),
+        &env,
+        &updates,
+    )?);
 
     #[cfg(feature = "injective")]
     {
+        let total_new_feeds = updates
+            .into_iter()
+            .filter(|(_, status)| *status != PriceFeedUpdateStatus::StaleSkipped)
+            .map(|(feed, _)| feed)
+            .collect();
         let inj_message = create_relay_pyth_prices_msg(env.contract.address, total_new_feeds);
-        Ok(response
-            .add_message(inj_message)
-            .add_attribute("action", "update_price_feeds")
-            .add_attribute("num_attestations", format!("{num_total_attestations}"))
-            .add_attribute("num_updated", format!("{num_total_new_attestations}")))
+        Ok(response.add_message(inj_message))
     }
 
     #[cfg(not(feature = "injective"))]
     {
-        Ok(response
-            .add_attribute("action", "update_price_feeds")
-            .add_attribute("num_attestations", format!("{num_total_attestations}"))
-            .add_attribute("num_updated", format!("{num_total_new_attestations}")))
+        Ok(response)
     }
 }
 
@@ -259,8 +343,7 @@ fn execute_governance_instruction(
         updated_config.governance_sequence_number = vaa.sequence;
     }
 
-    let data = &vaa.payload;
-    let instruction = GovernanceInstruction::deserialize(&data[..])
+    let instruction = GovernanceInstruction::deserialize(vaa.payload.as_slice())
         .map_err(|_| PythContractError::InvalidGovernancePayload)?;
 
     // Check that the instruction is intended for this chain.
@@ -295,16 +378,7 @@ fn execute_governance_instruction(
                 .add_attribute("new_data_sources", format!("{data_sources:?}"))
         }
         SetFee { val, expo } => {
-            let new_fee_amount: u128 = (val as u128)
-                .checked_mul(
-                    10_u128
-                        .checked_pow(
-                            u32::try_from(expo)
-                                .map_err(|_| PythContractError::InvalidGovernancePayload)?,
-                        )
-                        .ok_or(PythContractError::InvalidGovernancePayload)?,
-                )
-                .ok_or(PythContractError::InvalidGovernancePayload)?;
+            let new_fee_amount = fee_amount(val, expo)?;
 
             updated_config.fee = Coin::new(new_fee_amount, updated_config.fee.denom.clone());
 
@@ -312,6 +386,38 @@ fn execute_governance_instruction(
                 .add_attribute("action", "set_fee")
                 .add_attribute("new_fee", format!("{}", updated_config.fee))
         }
+        SetFeeInToken { val, expo, token } => {
+            let token = parse_fee_token(deps.api, &token)?;
+            let new_fee_amount = fee_amount(val, expo)?;
+
+            let mut token_fees = fee_tokens_read(deps.storage)
+                .may_load()?
+                .unwrap_or_default();
+            token_fees.retain(|token_fee| token_fee.token != token);
+            if new_fee_amount != 0 {
+                token_fees.push(TokenFee {
+                    token: token.clone(),
+                    amount: Uint128::new(new_fee_amount),
+                });
+            }
+            fee_tokens(deps.storage).save(&token_fees)?;
+
+            Response::new()
+                .add_attribute("action", "set_fee_in_token")
+                .add_attribute("token", format!("{token:?}"))
+                .add_attribute("new_fee", format!("{new_fee_amount}"))
+        }
+        SetFeeRecipient { address } => {
+            let recipient = deps
+                .api
+                .addr_humanize(&address.to_vec().into())
+                .map_err(|_| PythContractError::InvalidGovernancePayload)?;
+            fee_recipient(deps.storage).save(&recipient)?;
+
+            Response::new()
+                .add_attribute("action", "set_fee_recipient")
+                .add_attribute("new_fee_recipient", recipient)
+        }
         SetValidPeriod { valid_seconds } => {
             updated_config.valid_time_period = Duration::from_secs(valid_seconds);
 
@@ -319,6 +425,29 @@ fn execute_governance_instruction(
                 .add_attribute("action", "set_valid_period")
                 .add_attribute("new_valid_seconds", format!("{valid_seconds}"))
         }
+        SetWormholeAddress { address } => {
+            set_wormhole_address(deps.as_ref(), &env, &mut updated_config, &address, data)?
+        }
+        SetPriceHistoryDepth { depth } => {
+            if depth > MAX_PRICE_HISTORY_DEPTH {
+                Err(PythContractError::InvalidGovernancePayload)?
+            }
+            price_history_depth(deps.storage).save(&depth)?;
+
+            Response::new()
+                .add_attribute("action", "set_price_history_depth")
+                .add_attribute("new_price_history_depth", format!("{depth}"))
+        }
+        SetIbcChannels { channels } => {
+            let new_channels = HashSet::from_iter(channels.iter().cloned());
+            #[cfg(feature = "ibc")]
+            crate::ibc::remove_disallowed_subscriptions(deps.storage, &new_channels)?;
+            ibc_channels(deps.storage).save(&new_channels)?;
+
+            Response::new()
+                .add_attribute("action", "set_ibc_channels")
+                .add_attribute("new_ibc_channels", format!("{channels:?}"))
+        }
         RequestGovernanceDataSourceTransfer { .. } => {
             // RequestGovernanceDataSourceTransfer can only be part of the
             // AuthorizeGovernanceDataSourceTransfer message.
@@ -331,6 +460,87 @@ fn execute_governance_instruction(
     Ok(response)
 }
 
+/// Returns `val * (10 ** expo)`, the fee amount of the `SetFee` and `SetFeeInToken` actions.
+fn fee_amount(val: u64, expo: u64) -> StdResult<u128> {
+    Ok((val as u128)
+        .checked_mul(
+            10_u128
+                .checked_pow(
+                    u32::try_from(expo).map_err(|_| PythContractError::InvalidGovernancePayload)?,
+                )
+                .ok_or(PythContractError::InvalidGovernancePayload)?,
+        )
+        .ok_or(PythContractError::InvalidGovernancePayload)?)
+}
+
+/// Parses the token of a `SetFeeInToken` action, which is either the denom of a native token or
+/// `cw20:` followed by the address of a CW20 token contract.
+fn parse_fee_token(api: &dyn Api, token: &Binary) -> StdResult<FeeToken> {
+    let token = std::str::from_utf8(token.as_slice())
+        .map_err(|_| PythContractError::InvalidGovernancePayload)?;
+    match token.strip_prefix("cw20:") {
+        Some(contract) => Ok(FeeToken::Cw20 {
+            contract: api
+                .addr_validate(contract)
+                .map_err(|_| PythContractError::InvalidGovernancePayload)?,
+        }),
+        None if !token.is_empty() => Ok(FeeToken::Native {
+            denom: token.to_string(),
+        }),
+        None => Err(PythContractError::InvalidGovernancePayload)?,
+    }
+}
+
+/// Sends all native tokens held by the contract and its balances of the CW20 tokens accepted
+/// for the fee to the fee recipient. The contract holds no funds other than the fees, so this
+/// is safe to execute by anyone.
+///
+/// CW20 tokens are only swept while they are accepted for the fee, so governance should sweep
+/// the fees before it stops accepting a token.
+fn sweep_fees(deps: Deps, env: &Env) -> StdResult<Response<MsgWrapper>> {
+    let recipient = fee_recipient_read(deps.storage)
+        .may_load()?
+        .ok_or(PythContractError::FeeRecipientNotSet)?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "sweep_fees")
+        .add_attribute("recipient", recipient.as_str());
+
+    let balances = deps.querier.query_all_balances(&env.contract.address)?;
+    if !balances.is_empty() {
+        response = response.add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: balances,
+        });
+    }
+
+    for token_fee in fee_tokens_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default()
+    {
+        if let FeeToken::Cw20 { contract } = token_fee.token {
+            let balance: BalanceResponse = deps.querier.query_wasm_smart(
+                contract.to_string(),
+                &Cw20QueryMsg::Balance {
+                    address: env.contract.address.to_string(),
+                },
+            )?;
+            if !balance.balance.is_zero() {
+                response = response.add_message(WasmMsg::Execute {
+                    contract_addr: contract.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: recipient.to_string(),
+                        amount: balance.balance,
+                    })?,
+                    funds: vec![],
+                });
+            }
+        }
+    }
+
+    Ok(response)
+}
+
 /// Transfers governance to the data source provided in `parsed_claim_vaa`.
 /// This function updates the contract config in `next_config`; it is the caller's responsibility
 /// to save this configuration in the on-chain storage.
@@ -388,6 +598,56 @@ fn transfer_governance(
     }
 }
 
+/// Sets the wormhole contract to the one with the canonical address `address`. The new contract
+/// must be able to verify the governance VAA `data` containing this instruction, which guards
+/// against setting an address that would brick the contract.
+/// This function updates the contract config in `next_config`; it is the caller's responsibility
+/// to save this configuration in the on-chain storage.
+fn set_wormhole_address(
+    deps: Deps,
+    env: &Env,
+    next_config: &mut ConfigInfo,
+    address: &Binary,
+    data: &Binary,
+) -> StdResult<Response<MsgWrapper>> {
+    let new_wormhole_contract = deps
+        .api
+        .addr_humanize(&address.to_vec().into())
+        .map_err(|_| PythContractError::InvalidGovernancePayload)?;
+
+    let vaa = pyth_cosmwasm_sdk::parse_and_verify_vaa(
+        &deps.querier,
+        &new_wormhole_contract,
+        env.block.time.seconds(),
+        data,
+    )
+    .map_err(|_| PythContractError::InvalidWormholeAddressToSet)?;
+    verify_vaa_from_governance_source(next_config, &vaa)?;
+    if vaa.sequence != next_config.governance_sequence_number {
+        Err(PythContractError::InvalidWormholeAddressToSet)?
+    }
+
+    // Not necessary for security, but checks that the new contract parses the payload correctly.
+    let instruction = GovernanceInstruction::deserialize(vaa.payload.as_slice())
+        .map_err(|_| PythContractError::InvalidWormholeAddressToSet)?;
+    if instruction.action
+        != (SetWormholeAddress {
+            address: address.clone(),
+        })
+    {
+        Err(PythContractError::InvalidWormholeAddressToSet)?
+    }
+
+    next_config.wormhole_contract = new_wormhole_contract;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_wormhole_address")
+        .add_attribute(
+            "new_wormhole_address",
+            next_config.wormhole_contract.to_string(),
+        ))
+}
+
 /// Upgrades the contract at `address` to `new_code_id` (by sending a `Migrate` message). The
 /// migration will fail unless this contract is the admin of the contract being upgraded.
 /// (Typically, `address` is this contract's address, and the contract is its own admin.)
@@ -402,18 +662,6 @@ fn upgrade_contract(address: &Addr, new_code_id: u64) -> StdResult<Response<MsgW
         .add_attribute("new_code_id", format!("{new_code_id}")))
 }
 
-/// Check that `vaa` is from a valid data source (and hence is a legitimate price update message).
-fn verify_vaa_from_data_source(state: &ConfigInfo, vaa: &ParsedVAA) -> StdResult<()> {
-    let vaa_data_source = PythDataSource {
-        emitter: vaa.emitter_address.clone().into(),
-        chain_id: vaa.emitter_chain,
-    };
-    if !state.data_sources.contains(&vaa_data_source) {
-        Err(PythContractError::InvalidUpdateEmitter)?;
-    }
-    Ok(())
-}
-
 /// Check that `vaa` is from a valid governance source (and hence is a legitimate governance instruction).
 fn verify_vaa_from_governance_source(state: &ConfigInfo, vaa: &ParsedVAA) -> StdResult<()> {
     let vaa_data_source = PythDataSource {
@@ -427,146 +675,36 @@ fn verify_vaa_from_governance_source(state: &ConfigInfo, vaa: &ParsedVAA) -> Std
 }
 
 fn parse_update(deps: &Deps, env: &Env, data: &Binary) -> StdResult<Vec<PriceFeed>> {
-    let header = data.get(0..4);
-    let feeds = if header == Some(PYTHNET_ACCUMULATOR_UPDATE_MAGIC.as_slice()) {
-        parse_accumulator(deps, env, data)?
-    } else {
-        parse_batch_attestation(deps, env, data)?
-    };
-    Ok(feeds)
+    let state = config_read(deps.storage).load()?;
+    pyth_cosmwasm_sdk::parse_update(
+        &deps.querier,
+        &state.wormhole_contract,
+        &state.data_sources,
+        env.block.time.seconds(),
+        data,
+    )
 }
 
+/// Applies every price update in `data` and returns each update along with how it was applied.
 fn apply_updates(
     deps: &mut DepsMut,
     env: &Env,
     data: &[Binary],
-) -> StdResult<(usize, Vec<PriceFeed>)> {
-    let mut num_total_attestations: usize = 0;
-    let mut total_new_feeds: Vec<PriceFeed> = vec![];
+) -> StdResult<Vec<(PriceFeed, PriceFeedUpdateStatus)>> {
+    let mut updates = vec![];
 
     for datum in data {
         let feeds = parse_update(&deps.as_ref(), env, datum)?;
-        num_total_attestations += feeds.len();
         for feed in feeds {
-            if update_price_feed_if_new(deps, env, feed)? {
-                total_new_feeds.push(feed);
-            }
-        }
-    }
-    Ok((num_total_attestations, total_new_feeds))
-}
-
-fn parse_accumulator(deps: &Deps, env: &Env, data: &[u8]) -> StdResult<Vec<PriceFeed>> {
-    let update_data = AccumulatorUpdateData::try_from_slice(data)
-        .map_err(|_| PythContractError::InvalidAccumulatorPayload)?;
-    match update_data.proof {
-        Proof::WormholeMerkle { vaa, updates } => {
-            let parsed_vaa = parse_and_verify_vaa(
-                *deps,
-                env.block.time.seconds(),
-                &Binary::from(Vec::from(vaa)),
-            )?;
-            let state = config_read(deps.storage).load()?;
-            verify_vaa_from_data_source(&state, &parsed_vaa)?;
-
-            let msg = WormholeMessage::try_from_bytes(parsed_vaa.payload)
-                .map_err(|_| PythContractError::InvalidWormholeMessage)?;
-
-            let root: MerkleRoot<Keccak160> = MerkleRoot::new(match msg.payload {
-                WormholePayload::Merkle(merkle_root) => merkle_root.root,
-            });
-            let mut feeds = vec![];
-            for update in updates {
-                let message_vec = Vec::from(update.message);
-                if !root.check(update.proof, &message_vec) {
-                    Err(PythContractError::InvalidMerkleProof)?;
-                }
-
-                let msg = from_slice::<BigEndian, Message>(&message_vec)
-                    .map_err(|_| PythContractError::InvalidAccumulatorMessage)?;
-
-                match msg {
-                    Message::PriceFeedMessage(price_feed_message) => {
-                        let price_feed = PriceFeed::new(
-                            PriceIdentifier::new(price_feed_message.feed_id),
-                            Price {
-                                price: price_feed_message.price,
-                                conf: price_feed_message.conf,
-                                expo: price_feed_message.exponent,
-                                publish_time: price_feed_message.publish_time,
-                            },
-                            Price {
-                                price: price_feed_message.ema_price,
-                                conf: price_feed_message.ema_conf,
-                                expo: price_feed_message.exponent,
-                                publish_time: price_feed_message.publish_time,
-                            },
-                        );
-                        feeds.push(price_feed);
-                    }
-                    _ => return Err(PythContractError::InvalidAccumulatorMessageType)?,
-                }
-            }
-            Ok(feeds)
+            let status = update_price_feed_if_new(deps, env, feed)?;
+            updates.push((feed, status));
         }
     }
+    Ok(updates)
 }
 
-/// Update the on-chain storage for any new price updates provided in `batch_attestation`.
-fn parse_batch_attestation(deps: &Deps, env: &Env, data: &Binary) -> StdResult<Vec<PriceFeed>> {
-    let vaa = parse_and_verify_vaa(*deps, env.block.time.seconds(), data)?;
-    let state = config_read(deps.storage).load()?;
-    verify_vaa_from_data_source(&state, &vaa)?;
-    let data = &vaa.payload;
-    let batch_attestation = BatchPriceAttestation::deserialize(&data[..])
-        .map_err(|_| PythContractError::InvalidUpdatePayload)?;
-    let mut feeds = vec![];
-
-    // Update prices
-    for price_attestation in batch_attestation.price_attestations.iter() {
-        let price_feed = create_price_feed_from_price_attestation(price_attestation);
-        feeds.push(price_feed);
-    }
-
-    Ok(feeds)
-}
-
-fn create_price_feed_from_price_attestation(price_attestation: &PriceAttestation) -> PriceFeed {
-    match price_attestation.status {
-        PriceStatus::Trading => PriceFeed::new(
-            PriceIdentifier::new(price_attestation.price_id.to_bytes()),
-            Price {
-                price: price_attestation.price,
-                conf: price_attestation.conf,
-                expo: price_attestation.expo,
-                publish_time: price_attestation.publish_time,
-            },
-            Price {
-                price: price_attestation.ema_price,
-                conf: price_attestation.ema_conf,
-                expo: price_attestation.expo,
-                publish_time: price_attestation.publish_time,
-            },
-        ),
-        _ => PriceFeed::new(
-            PriceIdentifier::new(price_attestation.price_id.to_bytes()),
-            Price {
-                price: price_attestation.prev_price,
-                conf: price_attestation.prev_conf,
-                expo: price_attestation.expo,
-                publish_time: price_attestation.prev_publish_time,
-            },
-            Price {
-                price: price_attestation.ema_price,
-                conf: price_attestation.ema_conf,
-                expo: price_attestation.expo,
-                publish_time: price_attestation.prev_publish_time,
-            },
-        ),
-    }
-}
-
-/// Returns true if the price_feed is newer than the stored one.
+/// Stores `new_price_feed` if it is newer than the stored one. The returned status is
+/// `StaleSkipped` if the stored price feed was kept.
 ///
 /// This function returns error only if there be issues in ser/de when it reads from the bucket.
 /// Such an example would be upgrades which migration is not handled carefully so the binary stored
@@ -575,36 +713,54 @@ fn update_price_feed_if_new(
     deps: &mut DepsMut,
     _env: &Env,
     new_price_feed: PriceFeed,
-) -> StdResult<bool> {
-    let mut is_new_price = true;
-    price_feed_bucket(deps.storage).update(
-        new_price_feed.id.as_ref(),
-        |maybe_price_feed| -> StdResult<PriceFeed> {
-            match maybe_price_feed {
-                Some(price_feed) => {
-                    // This check ensures that a price won't be updated with the same or older
-                    // message. Publish_TIme is guaranteed increasing in
-                    // solana
-                    if price_feed.get_price_unchecked().publish_time
-                        < new_price_feed.get_price_unchecked().publish_time
-                    {
-                        Ok(new_price_feed)
-                    } else {
-                        is_new_price = false;
-                        Ok(price_feed)
-                    }
-                }
-                None => Ok(new_price_feed),
-            }
+) -> StdResult<PriceFeedUpdateStatus> {
+    let status = match price_feed_read_bucket(deps.storage).may_load(new_price_feed.id.as_ref())? {
+        // This check ensures that a price won't be updated with the same or older
+        // message. Publish_TIme is guaranteed increasing in
+        // solana
+        Some(price_feed)
+            if price_feed.get_price_unchecked().publish_time
+                >= new_price_feed.get_price_unchecked().publish_time =>
+        {
+            // The stored price feed is not written again, so skipping an update is cheaper
+            // than applying it.
+            return Ok(PriceFeedUpdateStatus::StaleSkipped);
+        }
+        Some(_) => PriceFeedUpdateStatus::Updated,
+        None => PriceFeedUpdateStatus::Unknown,
+    };
+    price_feed_bucket(deps.storage).save(new_price_feed.id.as_ref(), &new_price_feed)?;
+    record_price_history(deps.storage, &new_price_feed)?;
+    Ok(status)
+}
+
+/// Adds the price of `price_feed` to its history, dropping the oldest prices beyond the
+/// configured depth.
+fn record_price_history(storage: &mut dyn Storage, price_feed: &PriceFeed) -> StdResult<()> {
+    let depth = price_history_depth_read(storage).may_load()?.unwrap_or(0) as usize;
+    if depth == 0 {
+        return Ok(());
+    }
+
+    price_history_bucket(storage).update(
+        price_feed.id.as_ref(),
+        |maybe_history| -> StdResult<_> {
+            let mut history = maybe_history.unwrap_or_default();
+            history.push_front(price_feed.get_price_unchecked());
+            history.truncate(depth);
+            Ok(history)
         },
     )?;
-    Ok(is_new_price)
+    Ok(())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::PriceFeed { id } => to_binary(&query_price_feed(&deps, id.as_ref())?),
+        QueryMsg::PriceFeedHistory { id, limit } => {
+            to_binary(&query_price_feed_history(&deps, id.as_ref(), limit)?)
+        }
         #[cfg(feature = "osmosis")]
         QueryMsg::GetUpdateFeeForDenom { vaas, denom } => {
             to_binary(&get_update_fee_for_denom(&deps, &vaas, denom)?)
@@ -673,9 +829,35 @@ pub fn query_price_feed(deps: &Deps, feed_id: &[u8]) -> StdResult<PriceFeedRespo
     }
 }
 
-pub fn get_update_fee_amount(deps: &Deps, vaas: &[Binary]) -> StdResult<u128> {
-    let config = config_read(deps.storage).load()?;
+/// Returns up to `limit` of the most recent prices of the feed, newest first. If the history
+/// depth was reduced, only the prices within the new depth are returned.
+pub fn query_price_feed_history(
+    deps: &Deps,
+    feed_id: &[u8],
+    limit: Option<u32>,
+) -> StdResult<PriceFeedHistoryResponse> {
+    if price_feed_read_bucket(deps.storage)
+        .may_load(feed_id)?
+        .is_none()
+    {
+        Err(PythContractError::PriceFeedNotFound)?
+    }
+
+    let depth = price_history_depth_read(deps.storage)
+        .may_load()?
+        .unwrap_or(0) as usize;
+    let limit = limit.map_or(depth, |limit| depth.min(limit as usize));
+    let prices = price_history_read_bucket(deps.storage)
+        .may_load(feed_id)?
+        .unwrap_or_default()
+        .into_iter()
+        .take(limit)
+        .collect();
+    Ok(PriceFeedHistoryResponse { prices })
+}
 
+/// Returns the number of price updates in `vaas` that the fee is charged for.
+fn get_num_updates(vaas: &[Binary]) -> StdResult<u128> {
     let mut total_updates: u128 = 0;
     for datum in vaas {
         let header = datum.get(0..4);
@@ -691,6 +873,12 @@ pub fn get_update_fee_amount(deps: &Deps, vaas: &[Binary]) -> StdResult<u128> {
             total_updates += 1;
         }
     }
+    Ok(total_updates)
+}
+
+pub fn get_update_fee_amount(deps: &Deps, vaas: &[Binary]) -> StdResult<u128> {
+    let config = config_read(deps.storage).load()?;
+    let total_updates = get_num_updates(vaas)?;
 
     Ok(config
         .fee
@@ -743,20 +931,23 @@ mod test {
         super::*,
         crate::{
             governance::GovernanceModule::{Executor, Target},
-            state::get_contract_version,
+            state::{get_contract_version, ibc_channels_read},
         },
+        byteorder::BigEndian,
         cosmwasm_std::{
             coins, from_binary,
             testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
-            Addr, ContractResult, OwnedDeps, QuerierResult, StdError, SystemError, SystemResult,
-            Uint128,
+            Addr, Api, Attribute, ContractResult, OwnedDeps, QuerierResult, StdError, SystemError,
+            SystemResult, Uint128, WasmQuery,
         },
+        cw_wormhole::msg::QueryMsg as WormholeQueryMsg,
         pyth_sdk::UnixTimestamp,
-        pyth_sdk_cw::PriceIdentifier,
-        pyth_wormhole_attester_sdk::PriceAttestation,
+        pyth_sdk_cw::{Price, PriceIdentifier},
+        pyth_wormhole_attester_sdk::{BatchPriceAttestation, PriceAttestation, PriceStatus},
         pythnet_sdk::{
             accumulators::{merkle::MerkleTree, Accumulator},
-            messages::{PriceFeedMessage, TwapMessage},
+            hashers::keccak256_160::Keccak160,
+            messages::{Message, PriceFeedMessage, TwapMessage},
             test_utils::{
                 create_accumulator_message, create_accumulator_message_from_updates,
                 create_dummy_price_feed_message, create_vaa_from_payload, DEFAULT_CHAIN_ID,
@@ -772,6 +963,8 @@ mod test {
 
     /// Default valid time period for testing purposes.
     const WORMHOLE_ADDR: &str = "Wormhole";
+    /// Another wormhole contract understood by the mock querier.
+    const SECONDARY_WORMHOLE_ADDR: &str = "secondary_wormhole";
 
     fn default_config_info() -> ConfigInfo {
         ConfigInfo {
@@ -800,7 +993,9 @@ mod test {
 
     fn handle_wasm_query(wasm_query: &WasmQuery) -> QuerierResult {
         match wasm_query {
-            WasmQuery::Smart { contract_addr, msg } if *contract_addr == WORMHOLE_ADDR => {
+            WasmQuery::Smart { contract_addr, msg }
+                if *contract_addr == WORMHOLE_ADDR || *contract_addr == SECONDARY_WORMHOLE_ADDR =>
+            {
                 let query_msg = from_binary::<WormholeQueryMsg>(msg);
                 match query_msg {
                     Ok(WormholeQueryMsg::VerifyVAA { vaa, .. }) => {
@@ -909,6 +1104,7 @@ mod test {
     /// returns the update status (true means updated, false means ignored)
     fn do_update_price_feed(deps: &mut DepsMut, env: &Env, price_feed: PriceFeed) -> bool {
         update_price_feed_if_new(deps, env, price_feed).unwrap()
+            != PriceFeedUpdateStatus::StaleSkipped
     }
 
     fn apply_price_update(
@@ -920,7 +1116,14 @@ mod test {
         let (mut deps, env) = setup_test();
         config(&mut deps.storage).save(config_info).unwrap();
         let msg = create_batch_price_update_msg(emitter_address, emitter_chain, attestations);
-        apply_updates(&mut deps.as_mut(), &env, &[msg])
+        let updates = apply_updates(&mut deps.as_mut(), &env, &[msg])?;
+        let num_attestations = updates.len();
+        let new_feeds = updates
+            .into_iter()
+            .filter(|(_, status)| *status != PriceFeedUpdateStatus::StaleSkipped)
+            .map(|(feed, _)| feed)
+            .collect();
+        Ok((num_attestations, new_feeds))
     }
 
     #[test]
@@ -1233,6 +1436,72 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_update_price_feeds_results() {
+        let (mut deps, env) = setup_test();
+        config(&mut deps.storage)
+            .save(&default_config_info())
+            .unwrap();
+
+        let feed1 = create_dummy_price_feed_message(100);
+        let feed2 = create_dummy_price_feed_message(200);
+        let msg =
+            create_accumulator_message(&[&feed1, &feed2], &[&feed1, &feed2], false, false, None);
+        update_price_feeds(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("123", &[]),
+            &[msg.into()],
+        )
+        .unwrap();
+
+        // feed1 advances, feed2 is resubmitted at the same publish time and feed3 is new
+        let mut newer_feed1 = feed1.clone();
+        as_mut_price_feed(&mut newer_feed1).publish_time += 1;
+        let feed3 = create_dummy_price_feed_message(300);
+        let msg = create_accumulator_message(
+            &[&newer_feed1, &feed2, &feed3],
+            &[&newer_feed1, &feed2, &feed3],
+            false,
+            false,
+            None,
+        );
+        let response =
+            update_price_feeds(deps.as_mut(), env, mock_info("123", &[]), &[msg.into()]).unwrap();
+
+        let result = |msg: &Message, publish_time: i64, status| PriceFeedUpdateResult {
+            id: PriceIdentifier::new(msg.feed_id()),
+            publish_time,
+            status,
+        };
+        let data: UpdatePriceFeedsResponse = from_binary(response.data.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            data.results,
+            vec![
+                result(&feed1, 101, PriceFeedUpdateStatus::Updated),
+                result(&feed2, 200, PriceFeedUpdateStatus::StaleSkipped),
+                result(&feed3, 300, PriceFeedUpdateStatus::Unknown),
+            ]
+        );
+
+        let attribute = |key: &str| {
+            response
+                .attributes
+                .iter()
+                .find(|attr| attr.key == key)
+                .unwrap()
+                .value
+                .clone()
+        };
+        assert_eq!(attribute("num_attestations"), "3");
+        assert_eq!(attribute("num_updated"), "2");
+        assert_eq!(attribute("num_stale_skipped"), "1");
+        assert_eq!(attribute("num_unknown"), "1");
+        check_price_match(&deps, &newer_feed1);
+        check_price_match(&deps, &feed2);
+        check_price_match(&deps, &feed3);
+    }
+
     fn as_mut_price_feed(msg: &mut Message) -> &mut PriceFeedMessage {
         match msg {
             Message::PriceFeedMessage(ref mut price_feed) => price_feed,
@@ -1475,91 +1744,6 @@ mod test {
         );
     }
 
-    #[test]
-    fn test_create_price_feed_from_price_attestation_status_trading() {
-        let price_attestation = PriceAttestation {
-            price_id: pyth_wormhole_attester_sdk::Identifier::new([0u8; 32]),
-            price: 100,
-            conf: 100,
-            expo: 100,
-            ema_price: 100,
-            ema_conf: 100,
-            status: PriceStatus::Trading,
-            attestation_time: 100,
-            publish_time: 100,
-            prev_publish_time: 99,
-            prev_price: 99,
-            prev_conf: 99,
-            ..Default::default()
-        };
-
-        let price_feed = create_price_feed_from_price_attestation(&price_attestation);
-        let price = price_feed.get_price_unchecked();
-        let ema_price = price_feed.get_ema_price_unchecked();
-
-        // for price
-        assert_eq!(price.price, 100);
-        assert_eq!(price.conf, 100);
-        assert_eq!(price.expo, 100);
-        assert_eq!(price.publish_time, 100);
-
-        // for ema
-        assert_eq!(ema_price.price, 100);
-        assert_eq!(ema_price.conf, 100);
-        assert_eq!(ema_price.expo, 100);
-        assert_eq!(ema_price.publish_time, 100);
-    }
-
-    #[test]
-    fn test_create_price_feed_from_price_attestation_status_unknown() {
-        test_create_price_feed_from_price_attestation_not_trading(PriceStatus::Unknown)
-    }
-
-    #[test]
-    fn test_create_price_feed_from_price_attestation_status_halted() {
-        test_create_price_feed_from_price_attestation_not_trading(PriceStatus::Halted)
-    }
-
-    #[test]
-    fn test_create_price_feed_from_price_attestation_status_auction() {
-        test_create_price_feed_from_price_attestation_not_trading(PriceStatus::Auction)
-    }
-
-    fn test_create_price_feed_from_price_attestation_not_trading(status: PriceStatus) {
-        let price_attestation = PriceAttestation {
-            price_id: pyth_wormhole_attester_sdk::Identifier::new([0u8; 32]),
-            price: 100,
-            conf: 100,
-            expo: 100,
-            ema_price: 100,
-            ema_conf: 100,
-            status,
-            attestation_time: 100,
-            publish_time: 100,
-            prev_publish_time: 99,
-            prev_price: 99,
-            prev_conf: 99,
-            ..Default::default()
-        };
-
-        let price_feed = create_price_feed_from_price_attestation(&price_attestation);
-
-        let price = price_feed.get_price_unchecked();
-        let ema_price = price_feed.get_ema_price_unchecked();
-
-        // for price
-        assert_eq!(price.price, 99);
-        assert_eq!(price.conf, 99);
-        assert_eq!(price.expo, 100);
-        assert_eq!(price.publish_time, 99);
-
-        // for ema
-        assert_eq!(ema_price.price, 100);
-        assert_eq!(ema_price.conf, 100);
-        assert_eq!(ema_price.expo, 100);
-        assert_eq!(ema_price.publish_time, 99);
-    }
-
     #[test]
     fn test_parse_batch_attestation_status_not_trading() {
         let (mut deps, env) = setup_test();
@@ -1584,7 +1768,7 @@ mod test {
             .save(&default_config_info())
             .unwrap();
         let msg = create_batch_price_update_msg_from_attestations(vec![price_attestation]);
-        let feeds = parse_batch_attestation(&deps.as_ref(), &env, &msg).unwrap();
+        let feeds = parse_update(&deps.as_ref(), &env, &msg).unwrap();
         assert_eq!(feeds.len(), 1);
         let price = feeds[0].get_price_unchecked();
         let ema_price = feeds[0].get_ema_price_unchecked();
@@ -1626,7 +1810,7 @@ mod test {
             .save(&default_config_info())
             .unwrap();
         let msg = create_batch_price_update_msg_from_attestations(vec![price_attestation]);
-        let feeds = parse_batch_attestation(&deps.as_ref(), &env, &msg).unwrap();
+        let feeds = parse_update(&deps.as_ref(), &env, &msg).unwrap();
         assert_eq!(feeds.len(), 1);
         let price = feeds[0].get_price_unchecked();
         let ema_price = feeds[0].get_ema_price_unchecked();
@@ -2277,4 +2461,405 @@ mod test {
 
         assert!(apply_governance_vaa(&test_config, &test_vaa).is_err());
     }
+
+    fn set_wormhole_address_vaa(address: &str) -> Vaa<Box<RawMessage>> {
+        let address = MockApi::default().addr_canonicalize(address).unwrap();
+        governance_vaa(&GovernanceInstruction {
+            module: Target,
+            target_chain_id: DEFAULT_CHAIN_ID.into(),
+            action: SetWormholeAddress { address: address.0 },
+        })
+    }
+
+    #[test]
+    fn test_set_wormhole_address() {
+        let test_config = governance_test_config();
+
+        let test_vaa = set_wormhole_address_vaa(SECONDARY_WORMHOLE_ADDR);
+        let (_response, result_config) = apply_governance_vaa(&test_config, &test_vaa).unwrap();
+        assert_eq!(
+            result_config.wormhole_contract,
+            Addr::unchecked(SECONDARY_WORMHOLE_ADDR)
+        );
+        assert_eq!(result_config.governance_sequence_number, 7);
+    }
+
+    #[test]
+    fn test_set_wormhole_address_invalid_contract() {
+        let test_config = governance_test_config();
+
+        // the querier doesn't know this contract, so it can't verify the VAA
+        let test_vaa = set_wormhole_address_vaa("not_wormhole");
+        assert_eq!(
+            apply_governance_vaa(&test_config, &test_vaa),
+            Err(PythContractError::InvalidWormholeAddressToSet.into())
+        );
+    }
+
+    #[test]
+    fn test_set_ibc_channels() {
+        let test_config = governance_test_config();
+
+        let test_vaa = governance_vaa(&GovernanceInstruction {
+            module: Target,
+            target_chain_id: DEFAULT_CHAIN_ID.into(),
+            action: SetIbcChannels {
+                channels: vec!["channel-0".to_string(), "channel-12".to_string()],
+            },
+        });
+        let (mut deps, env) = setup_test();
+        config(&mut deps.storage).save(&test_config).unwrap();
+        ibc_channels(&mut deps.storage)
+            .save(&HashSet::from([
+                "channel-0".to_string(),
+                "channel-3".to_string(),
+            ]))
+            .unwrap();
+        #[cfg(feature = "ibc")]
+        for channel_id in ["channel-0", "channel-3"] {
+            crate::state::ibc_subscription_bucket(&mut deps.storage)
+                .save(
+                    channel_id.as_bytes(),
+                    &HashSet::from([PriceIdentifier::new([1; 32])]),
+                )
+                .unwrap();
+        }
+        execute_governance_instruction(
+            deps.as_mut(),
+            env,
+            mock_info("123", &[]),
+            &serde_wormhole::to_vec(&test_vaa).unwrap().into(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            ibc_channels_read(&deps.storage).load(),
+            Ok(HashSet::from([
+                "channel-0".to_string(),
+                "channel-12".to_string()
+            ]))
+        );
+        // the subscriptions of the removed channel are dropped
+        #[cfg(feature = "ibc")]
+        {
+            use crate::state::ibc_subscription_read_bucket;
+            assert!(ibc_subscription_read_bucket(&deps.storage)
+                .may_load(b"channel-0")
+                .unwrap()
+                .is_some());
+            assert_eq!(
+                ibc_subscription_read_bucket(&deps.storage).may_load(b"channel-3"),
+                Ok(None)
+            );
+        }
+    }
+
+    fn execute_governance_vaa(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        env: &Env,
+        vaa: &Vaa<Box<RawMessage>>,
+    ) -> StdResult<Response<MsgWrapper>> {
+        execute_governance_instruction(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("123", &[]),
+            &serde_wormhole::to_vec(vaa).unwrap().into(),
+        )
+    }
+
+    fn set_fee_in_token_vaa(val: u64, token: &str, sequence: u64) -> Vaa<Box<RawMessage>> {
+        create_vaa_from_payload(
+            &GovernanceInstruction {
+                module: Target,
+                target_chain_id: DEFAULT_CHAIN_ID.into(),
+                action: SetFeeInToken {
+                    val,
+                    expo: 1,
+                    token: Binary::from(token.as_bytes()),
+                },
+            }
+            .serialize()
+            .unwrap(),
+            DEFAULT_GOVERNANCE_SOURCE.address,
+            DEFAULT_GOVERNANCE_SOURCE.chain,
+            sequence,
+        )
+    }
+
+    #[test]
+    fn test_set_fee_in_token() {
+        let (mut deps, env) = setup_test();
+        config(&mut deps.storage)
+            .save(&governance_test_config())
+            .unwrap();
+
+        execute_governance_vaa(&mut deps, &env, &set_fee_in_token_vaa(5, "ubar", 5)).unwrap();
+        execute_governance_vaa(&mut deps, &env, &set_fee_in_token_vaa(3, "cw20:token", 6)).unwrap();
+        assert_eq!(
+            fee_tokens_read(&deps.storage).load(),
+            Ok(vec![
+                TokenFee {
+                    token: FeeToken::Native {
+                        denom: "ubar".to_string()
+                    },
+                    amount: Uint128::new(50),
+                },
+                TokenFee {
+                    token: FeeToken::Cw20 {
+                        contract: Addr::unchecked("token")
+                    },
+                    amount: Uint128::new(30),
+                },
+            ])
+        );
+
+        // a fee of 0 stops accepting the token
+        execute_governance_vaa(&mut deps, &env, &set_fee_in_token_vaa(0, "ubar", 7)).unwrap();
+        assert_eq!(
+            fee_tokens_read(&deps.storage).load().unwrap().len(),
+            1,
+            "ubar should no longer be accepted"
+        );
+
+        assert_eq!(
+            execute_governance_vaa(&mut deps, &env, &set_fee_in_token_vaa(1, "", 8)),
+            Err(PythContractError::InvalidGovernancePayload.into())
+        );
+    }
+
+    #[test]
+    fn test_is_fee_sufficient_in_token() {
+        let mut config_info = default_config_info();
+        config_info.fee = Coin::new(100, "foo");
+
+        let (mut deps, _env) = setup_test();
+        config(&mut deps.storage).save(&config_info).unwrap();
+        fee_tokens(&mut deps.storage)
+            .save(&vec![TokenFee {
+                token: FeeToken::Native {
+                    denom: "ubar".to_string(),
+                },
+                amount: Uint128::new(20),
+            }])
+            .unwrap();
+
+        let data = [
+            create_batch_price_update_msg_from_attestations(vec![PriceAttestation::default()]),
+            create_batch_price_update_msg_from_attestations(vec![PriceAttestation::default()]),
+        ];
+        let info = mock_info("123", &coins(40, "ubar"));
+        assert_eq!(is_fee_sufficient(&deps.as_ref(), info, &data), Ok(true));
+
+        // osmosis rejects the denom instead, since it is not a tx fee denom either
+        let info = mock_info("123", &coins(39, "ubar"));
+        assert_ne!(is_fee_sufficient(&deps.as_ref(), info, &data), Ok(true));
+    }
+
+    #[test]
+    fn test_receive_cw20() {
+        let (mut deps, env) = setup_test();
+        config(&mut deps.storage)
+            .save(&default_config_info())
+            .unwrap();
+        fee_tokens(&mut deps.storage)
+            .save(&vec![TokenFee {
+                token: FeeToken::Cw20 {
+                    contract: Addr::unchecked("token"),
+                },
+                amount: Uint128::new(20),
+            }])
+            .unwrap();
+
+        let receive = |amount: u128| Cw20ReceiveMsg {
+            sender: "updater".to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&Cw20HookMsg::UpdatePriceFeeds {
+                data: vec![create_batch_price_update_msg_from_attestations(vec![
+                    PriceAttestation::default(),
+                ])],
+            })
+            .unwrap(),
+        };
+
+        assert_eq!(
+            receive_cw20(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("token", &[]),
+                receive(19)
+            ),
+            Err(PythContractError::InsufficientFee.into())
+        );
+        assert_eq!(
+            receive_cw20(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("other_token", &[]),
+                receive(20)
+            ),
+            Err(PythContractError::InvalidFeeDenom {
+                denom: "other_token".to_string()
+            }
+            .into())
+        );
+
+        let response =
+            receive_cw20(deps.as_mut(), env, mock_info("token", &[]), receive(20)).unwrap();
+        assert!(response
+            .attributes
+            .contains(&Attribute::new("num_attestations", "1")));
+    }
+
+    #[test]
+    fn test_sweep_fees() {
+        let (mut deps, env) = setup_test();
+        assert_eq!(
+            sweep_fees(deps.as_ref(), &env),
+            Err(PythContractError::FeeRecipientNotSet.into())
+        );
+
+        config(&mut deps.storage)
+            .save(&governance_test_config())
+            .unwrap();
+        let address = MockApi::default().addr_canonicalize("treasury").unwrap();
+        let test_vaa = governance_vaa(&GovernanceInstruction {
+            module: Target,
+            target_chain_id: DEFAULT_CHAIN_ID.into(),
+            action: SetFeeRecipient { address: address.0 },
+        });
+        execute_governance_vaa(&mut deps, &env, &test_vaa).unwrap();
+        assert_eq!(
+            fee_recipient_read(&deps.storage).load(),
+            Ok(Addr::unchecked("treasury"))
+        );
+
+        fee_tokens(&mut deps.storage)
+            .save(&vec![
+                TokenFee {
+                    token: FeeToken::Cw20 {
+                        contract: Addr::unchecked("token"),
+                    },
+                    amount: Uint128::new(20),
+                },
+                TokenFee {
+                    token: FeeToken::Cw20 {
+                        contract: Addr::unchecked("empty_token"),
+                    },
+                    amount: Uint128::new(20),
+                },
+            ])
+            .unwrap();
+        deps.querier
+            .update_balance(env.contract.address.clone(), coins(100, "foo"));
+        deps.querier.update_wasm(|wasm_query| match wasm_query {
+            WasmQuery::Smart { contract_addr, .. } => {
+                let balance = if contract_addr == "token" { 60 } else { 0 };
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&BalanceResponse {
+                        balance: Uint128::new(balance),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => unreachable!(),
+        });
+
+        let response = sweep_fees(deps.as_ref(), &env).unwrap();
+        assert_eq!(
+            response
+                .messages
+                .into_iter()
+                .map(|m| m.msg)
+                .collect::<Vec<_>>(),
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "treasury".to_string(),
+                    amount: coins(100, "foo"),
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "token".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: "treasury".to_string(),
+                        amount: Uint128::new(60),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_price_history_depth() {
+        let test_config = governance_test_config();
+        let set_depth_vaa = |depth| {
+            governance_vaa(&GovernanceInstruction {
+                module: Target,
+                target_chain_id: DEFAULT_CHAIN_ID.into(),
+                action: SetPriceHistoryDepth { depth },
+            })
+        };
+
+        let (response, _) =
+            apply_governance_vaa(&test_config, &set_depth_vaa(MAX_PRICE_HISTORY_DEPTH)).unwrap();
+        assert!(response.attributes.contains(&Attribute::new(
+            "new_price_history_depth",
+            format!("{MAX_PRICE_HISTORY_DEPTH}")
+        )));
+
+        assert_eq!(
+            apply_governance_vaa(&test_config, &set_depth_vaa(MAX_PRICE_HISTORY_DEPTH + 1)),
+            Err(PythContractError::InvalidGovernancePayload.into())
+        );
+    }
+
+    #[test]
+    fn test_price_feed_history() {
+        let (mut deps, env) = setup_test();
+        let id = create_price_feed(1, 0).id;
+        let publish_times = |response: PriceFeedHistoryResponse| -> Vec<UnixTimestamp> {
+            response.prices.iter().map(|p| p.publish_time).collect()
+        };
+
+        // no history is kept until a depth is set
+        assert!(do_update_price_feed(
+            &mut deps.as_mut(),
+            &env,
+            create_price_feed(1, 1)
+        ));
+        assert_eq!(
+            query_price_feed_history(&deps.as_ref(), id.as_ref(), None),
+            Ok(PriceFeedHistoryResponse { prices: vec![] })
+        );
+
+        price_history_depth(&mut deps.storage).save(&3).unwrap();
+        for publish_time in 2..=5 {
+            assert!(do_update_price_feed(
+                &mut deps.as_mut(),
+                &env,
+                create_price_feed(1, publish_time)
+            ));
+        }
+        // stale updates are not recorded
+        assert!(!do_update_price_feed(
+            &mut deps.as_mut(),
+            &env,
+            create_price_feed(1, 4)
+        ));
+
+        let history = query_price_feed_history(&deps.as_ref(), id.as_ref(), None).unwrap();
+        assert_eq!(publish_times(history), vec![5, 4, 3]);
+        let history = query_price_feed_history(&deps.as_ref(), id.as_ref(), Some(2)).unwrap();
+        assert_eq!(publish_times(history), vec![5, 4]);
+
+        // reducing the depth hides the older prices right away
+        price_history_depth(&mut deps.storage).save(&1).unwrap();
+        let history = query_price_feed_history(&deps.as_ref(), id.as_ref(), Some(2)).unwrap();
+        assert_eq!(publish_times(history), vec![5]);
+
+        assert_eq!(
+            query_price_feed_history(&deps.as_ref(), b"123".as_ref(), None),
+            Err(PythContractError::PriceFeedNotFound.into())
+        );
+    }
 }
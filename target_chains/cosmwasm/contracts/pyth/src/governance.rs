@@ -66,6 +66,20 @@ pub enum GovernanceAction {
     /// on AuthorizeGovernanceDataSourceTransfer). `governance_data_source_index` is an incrementing
     /// sequence number that ensures old transfer messages cannot be replayed.
     RequestGovernanceDataSourceTransfer { governance_data_source_index: u32 }, // 5
+    /// Set the wormhole contract used to verify VAAs. `address` is the canonical address of the
+    /// new contract, which must be able to verify the VAA containing this action.
+    SetWormholeAddress { address: Binary }, // 6
+    /// Set the fee in `token` to val * (10 ** expo), or stop accepting the token if val is 0.
+    /// `token` is the denom of a native token, or `cw20:` followed by the address of a CW20
+    /// token contract.
+    SetFeeInToken { val: u64, expo: u64, token: Binary }, // 7
+    /// Set the IBC channels over which price updates can be forwarded.
+    SetIbcChannels { channels: Vec<String> }, // 8
+    /// Set the number of prices kept in the history of each price feed. 0 disables the history.
+    SetPriceHistoryDepth { depth: u16 }, // 9
+    /// Set the address that receives the fees swept from the contract. `address` is the
+    /// canonical address of the recipient.
+    SetFeeRecipient { address: Binary }, // 10
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -136,6 +150,48 @@ impl GovernanceInstruction {
                     governance_data_source_index,
                 })
             }
+            6 => {
+                let mut address: Vec<u8> = vec![];
+                bytes.read_to_end(&mut address)?;
+                Ok(GovernanceAction::SetWormholeAddress {
+                    address: Binary::from(address),
+                })
+            }
+            7 => {
+                let val = bytes.read_u64::<BigEndian>()?;
+                let expo = bytes.read_u64::<BigEndian>()?;
+                let token_len = bytes.read_u8()?;
+                let mut token = vec![0u8; token_len as usize];
+                bytes.read_exact(&mut token)?;
+                Ok(GovernanceAction::SetFeeInToken {
+                    val,
+                    expo,
+                    token: Binary::from(token),
+                })
+            }
+            8 => {
+                let num_channels = bytes.read_u8()?;
+                let mut channels: Vec<String> = vec![];
+                for _ in 0..num_channels {
+                    let len = bytes.read_u8()?;
+                    let mut channel = vec![0u8; len as usize];
+                    bytes.read_exact(&mut channel)?;
+                    channels.push(String::from_utf8(channel)?);
+                }
+
+                Ok(GovernanceAction::SetIbcChannels { channels })
+            }
+            9 => {
+                let depth = bytes.read_u16::<BigEndian>()?;
+                Ok(GovernanceAction::SetPriceHistoryDepth { depth })
+            }
+            10 => {
+                let mut address: Vec<u8> = vec![];
+                bytes.read_to_end(&mut address)?;
+                Ok(GovernanceAction::SetFeeRecipient {
+                    address: Binary::from(address),
+                })
+            }
             _ => Err(format!("Unknown governance action type: {action_type}",)),
         };
 
@@ -212,6 +268,39 @@ impl GovernanceInstruction {
                 buf.write_u16::<BigEndian>(self.target_chain_id)?;
                 buf.write_u32::<BigEndian>(*governance_data_source_index)?;
             }
+            GovernanceAction::SetWormholeAddress { address } => {
+                buf.write_u8(6)?;
+                buf.write_u16::<BigEndian>(self.target_chain_id)?;
+                buf.write_all(address.as_slice())?;
+            }
+            GovernanceAction::SetFeeInToken { val, expo, token } => {
+                buf.write_u8(7)?;
+                buf.write_u16::<BigEndian>(self.target_chain_id)?;
+
+                buf.write_u64::<BigEndian>(*val)?;
+                buf.write_u64::<BigEndian>(*expo)?;
+                buf.write_u8(u8::try_from(token.len())?)?;
+                buf.write_all(token.as_slice())?;
+            }
+            GovernanceAction::SetIbcChannels { channels } => {
+                buf.write_u8(8)?;
+                buf.write_u16::<BigEndian>(self.target_chain_id)?;
+                buf.write_u8(u8::try_from(channels.len())?)?;
+                for channel in channels {
+                    buf.write_u8(u8::try_from(channel.len())?)?;
+                    buf.write_all(channel.as_bytes())?;
+                }
+            }
+            GovernanceAction::SetPriceHistoryDepth { depth } => {
+                buf.write_u8(9)?;
+                buf.write_u16::<BigEndian>(self.target_chain_id)?;
+                buf.write_u16::<BigEndian>(*depth)?;
+            }
+            GovernanceAction::SetFeeRecipient { address } => {
+                buf.write_u8(10)?;
+                buf.write_u16::<BigEndian>(self.target_chain_id)?;
+                buf.write_all(address.as_slice())?;
+            }
         }
 
         Ok(buf)
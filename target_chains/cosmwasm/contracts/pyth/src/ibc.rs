@@ -0,0 +1,489 @@
+//! Forwarding of price updates over IBC.
+//!
+//! Counterparties open an unordered channel with the [IBC_APP_VERSION] version and subscribe
+//! to price feeds with [IbcPacketMsg] packets. Only channels allowed by governance
+//! (see [crate::governance::GovernanceAction::SetIbcChannels]) can subscribe and receive
+//! updates, and removing a channel from the allowed channels drops its subscriptions. After every
+//! verified update, the updated feeds subscribed to on a channel are sent to it as a single
+//! [PriceFeedsPacket].
+use {
+    crate::state::{
+        config_read, ibc_channels_read, ibc_subscription_bucket, ibc_subscription_read_bucket,
+    },
+    cosmwasm_std::{
+        entry_point, from_slice, to_binary, Binary, Deps, DepsMut, Env, IbcBasicResponse,
+        IbcChannel, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg,
+        IbcChannelOpenResponse, IbcMsg, IbcOrder, IbcPacketAckMsg, IbcPacketReceiveMsg,
+        IbcPacketTimeoutMsg, IbcReceiveResponse, IbcTimeout, StdResult, Storage,
+    },
+    pyth_sdk_cw::{
+        error::PythContractError,
+        ibc::{IbcAck, IbcPacketMsg, PriceFeedsPacket, IBC_APP_VERSION, MAX_IBC_SUBSCRIPTIONS},
+        PriceFeed, PriceFeedUpdateStatus,
+    },
+    std::collections::HashSet,
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_open(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelOpenMsg,
+) -> StdResult<IbcChannelOpenResponse> {
+    validate_channel(msg.channel(), msg.counterparty_version())?;
+    Ok(None)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_connect(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelConnectMsg,
+) -> StdResult<IbcBasicResponse> {
+    let channel = msg.channel();
+    validate_channel(channel, msg.counterparty_version())?;
+
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_channel_connect")
+        .add_attribute("channel_id", &channel.endpoint.channel_id))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_close(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelCloseMsg,
+) -> StdResult<IbcBasicResponse> {
+    let channel_id = &msg.channel().endpoint.channel_id;
+    ibc_subscription_bucket(deps.storage).remove(channel_id.as_bytes());
+
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_channel_close")
+        .add_attribute("channel_id", channel_id))
+}
+
+/// Handles subscription packets. Errors are returned in the acknowledgement instead of failing
+/// the transaction, so the counterparty learns about them.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_receive(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketReceiveMsg,
+) -> StdResult<IbcReceiveResponse> {
+    let channel_id = &msg.packet.dest.channel_id;
+    let ack = match receive_packet(deps.storage, channel_id, &msg.packet.data) {
+        Ok(()) => IbcAck::Result(Binary::default()),
+        Err(err) => IbcAck::Error(err.to_string()),
+    };
+
+    Ok(IbcReceiveResponse::new()
+        .set_ack(to_binary(&ack)?)
+        .add_attribute("action", "ibc_packet_receive")
+        .add_attribute("channel_id", channel_id))
+}
+
+/// Price packets are fire and forget; a failed delivery is superseded by the next update.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_ack(
+    _deps: DepsMut,
+    _env: Env,
+    _msg: IbcPacketAckMsg,
+) -> StdResult<IbcBasicResponse> {
+    Ok(IbcBasicResponse::new().add_attribute("action", "ibc_packet_ack"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_timeout(
+    _deps: DepsMut,
+    _env: Env,
+    _msg: IbcPacketTimeoutMsg,
+) -> StdResult<IbcBasicResponse> {
+    Ok(IbcBasicResponse::new().add_attribute("action", "ibc_packet_timeout"))
+}
+
+/// Returns the messages that send the updated feeds in `updates` to the allowed channels
+/// subscribed to them. Packets time out after the valid time period of the contract, since the
+/// prices they carry are not usable after that anyway.
+pub fn forward_price_feeds(
+    deps: Deps,
+    env: &Env,
+    updates: &[(PriceFeed, PriceFeedUpdateStatus)],
+) -> StdResult<Vec<IbcMsg>> {
+    let channels = ibc_channels_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+    if channels.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let valid_time_period = config_read(deps.storage).load()?.valid_time_period;
+    let timeout =
+        IbcTimeout::with_timestamp(env.block.time.plus_seconds(valid_time_period.as_secs()));
+
+    let mut channels: Vec<String> = channels.into_iter().collect();
+    // sort the channels so the order of the messages is deterministic
+    channels.sort();
+
+    let mut messages = vec![];
+    for channel_id in channels {
+        let subscription =
+            match ibc_subscription_read_bucket(deps.storage).may_load(channel_id.as_bytes())? {
+                Some(subscription) => subscription,
+                None => continue,
+            };
+
+        let price_feeds: Vec<PriceFeed> = updates
+            .iter()
+            .filter(|(feed, status)| {
+                *status != PriceFeedUpdateStatus::StaleSkipped && subscription.contains(&feed.id)
+            })
+            .map(|(feed, _)| *feed)
+            .collect();
+        if price_feeds.is_empty() {
+            continue;
+        }
+
+        messages.push(IbcMsg::SendPacket {
+            channel_id,
+            data: to_binary(&PriceFeedsPacket { price_feeds })?,
+            timeout: timeout.clone(),
+        });
+    }
+
+    Ok(messages)
+}
+
+/// Removes the subscriptions of the currently allowed channels that are not in `channels`, the
+/// new allowed channels. Governance calls this before replacing the allowed channels, so the
+/// subscriptions don't linger if a removed channel is allowed again later.
+pub fn remove_disallowed_subscriptions(
+    storage: &mut dyn Storage,
+    channels: &HashSet<String>,
+) -> StdResult<()> {
+    let current_channels = ibc_channels_read(storage).may_load()?.unwrap_or_default();
+    let mut bucket = ibc_subscription_bucket(storage);
+    for channel_id in current_channels.difference(channels) {
+        bucket.remove(channel_id.as_bytes());
+    }
+    Ok(())
+}
+
+fn validate_channel(channel: &IbcChannel, counterparty_version: Option<&str>) -> StdResult<()> {
+    if channel.order != IbcOrder::Unordered || channel.version != IBC_APP_VERSION {
+        Err(PythContractError::InvalidIbcChannel)?
+    }
+    if let Some(version) = counterparty_version {
+        if version != IBC_APP_VERSION {
+            Err(PythContractError::InvalidIbcChannel)?
+        }
+    }
+    Ok(())
+}
+
+fn receive_packet(storage: &mut dyn Storage, channel_id: &str, data: &Binary) -> StdResult<()> {
+    let allowed = ibc_channels_read(storage)
+        .may_load()?
+        .unwrap_or_default()
+        .contains(channel_id);
+    if !allowed {
+        Err(PythContractError::IbcChannelNotAllowed)?
+    }
+
+    let msg: IbcPacketMsg = from_slice(data)?;
+    let mut bucket = ibc_subscription_bucket(storage);
+    let mut subscription: HashSet<_> = bucket.may_load(channel_id.as_bytes())?.unwrap_or_default();
+    match msg {
+        IbcPacketMsg::Subscribe { ids } => {
+            subscription.extend(ids);
+            if subscription.len() > MAX_IBC_SUBSCRIPTIONS {
+                Err(PythContractError::TooManyIbcSubscriptions)?
+            }
+        }
+        IbcPacketMsg::Unsubscribe { ids } => {
+            for id in ids {
+                subscription.remove(&id);
+            }
+        }
+    }
+
+    if subscription.is_empty() {
+        bucket.remove(channel_id.as_bytes());
+        Ok(())
+    } else {
+        bucket.save(channel_id.as_bytes(), &subscription)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::state::{config, ibc_channels, ConfigInfo, PythDataSource},
+        cosmwasm_std::{
+            from_binary,
+            testing::{
+                mock_dependencies, mock_env, mock_ibc_channel_close_confirm,
+                mock_ibc_channel_open_init, mock_ibc_channel_open_try, mock_ibc_packet_recv,
+                MockApi, MockQuerier, MockStorage,
+            },
+            Addr, Coin, OwnedDeps, StdError,
+        },
+        pyth_sdk_cw::{Price, PriceIdentifier},
+        std::time::Duration,
+    };
+
+    const ALLOWED_CHANNEL: &str = "channel-0";
+    const OTHER_CHANNEL: &str = "channel-1";
+
+    fn setup_test() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        config(&mut deps.storage)
+            .save(&ConfigInfo {
+                wormhole_contract: Addr::unchecked(String::default()),
+                data_sources: HashSet::default(),
+                governance_source: PythDataSource {
+                    emitter: Binary(vec![]),
+                    chain_id: 0,
+                },
+                governance_source_index: 0,
+                governance_sequence_number: 0,
+                chain_id: 0,
+                valid_time_period: Duration::from_secs(60),
+                fee: Coin::new(0, ""),
+            })
+            .unwrap();
+        ibc_channels(&mut deps.storage)
+            .save(&HashSet::from([ALLOWED_CHANNEL.to_string()]))
+            .unwrap();
+        deps
+    }
+
+    fn price_feed(id: u8) -> PriceFeed {
+        PriceFeed::new(
+            PriceIdentifier::new([id; 32]),
+            Price::default(),
+            Price::default(),
+        )
+    }
+
+    fn receive(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        channel_id: &str,
+        msg: &IbcPacketMsg,
+    ) -> IbcAck {
+        let response = ibc_packet_receive(
+            deps.as_mut(),
+            mock_env(),
+            mock_ibc_packet_recv(channel_id, msg).unwrap(),
+        )
+        .unwrap();
+        from_binary(&response.acknowledgement).unwrap()
+    }
+
+    #[test]
+    fn test_channel_open() {
+        let mut deps = setup_test();
+
+        for msg in [
+            mock_ibc_channel_open_init(ALLOWED_CHANNEL, IbcOrder::Unordered, IBC_APP_VERSION),
+            mock_ibc_channel_open_try(ALLOWED_CHANNEL, IbcOrder::Unordered, IBC_APP_VERSION),
+        ] {
+            assert_eq!(ibc_channel_open(deps.as_mut(), mock_env(), msg), Ok(None));
+        }
+
+        for msg in [
+            mock_ibc_channel_open_init(ALLOWED_CHANNEL, IbcOrder::Ordered, IBC_APP_VERSION),
+            mock_ibc_channel_open_init(ALLOWED_CHANNEL, IbcOrder::Unordered, "ics20-1"),
+            mock_ibc_channel_open_try(ALLOWED_CHANNEL, IbcOrder::Unordered, "ics20-1"),
+        ] {
+            assert_eq!(
+                ibc_channel_open(deps.as_mut(), mock_env(), msg),
+                Err(PythContractError::InvalidIbcChannel.into())
+            );
+        }
+    }
+
+    #[test]
+    fn test_subscribe() {
+        let mut deps = setup_test();
+
+        let ack = receive(
+            &mut deps,
+            ALLOWED_CHANNEL,
+            &IbcPacketMsg::Subscribe {
+                ids: vec![price_feed(1).id, price_feed(2).id],
+            },
+        );
+        assert_eq!(ack, IbcAck::Result(Binary::default()));
+
+        let ack = receive(
+            &mut deps,
+            ALLOWED_CHANNEL,
+            &IbcPacketMsg::Unsubscribe {
+                ids: vec![price_feed(1).id],
+            },
+        );
+        assert_eq!(ack, IbcAck::Result(Binary::default()));
+        assert_eq!(
+            ibc_subscription_read_bucket(&deps.storage)
+                .load(ALLOWED_CHANNEL.as_bytes())
+                .unwrap(),
+            HashSet::from([price_feed(2).id])
+        );
+
+        ibc_channel_close(
+            deps.as_mut(),
+            mock_env(),
+            mock_ibc_channel_close_confirm(ALLOWED_CHANNEL, IbcOrder::Unordered, IBC_APP_VERSION),
+        )
+        .unwrap();
+        assert_eq!(
+            ibc_subscription_read_bucket(&deps.storage)
+                .may_load(ALLOWED_CHANNEL.as_bytes())
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_subscribe_too_many() {
+        let mut deps = setup_test();
+
+        let ids = (0..MAX_IBC_SUBSCRIPTIONS as u8)
+            .map(|id| price_feed(id).id)
+            .collect::<Vec<_>>();
+        let ack = receive(
+            &mut deps,
+            ALLOWED_CHANNEL,
+            &IbcPacketMsg::Subscribe { ids: ids.clone() },
+        );
+        assert_eq!(ack, IbcAck::Result(Binary::default()));
+
+        // subscribing again to the same feeds doesn't count towards the limit
+        let ack = receive(
+            &mut deps,
+            ALLOWED_CHANNEL,
+            &IbcPacketMsg::Subscribe { ids: ids.clone() },
+        );
+        assert_eq!(ack, IbcAck::Result(Binary::default()));
+
+        let ack = receive(
+            &mut deps,
+            ALLOWED_CHANNEL,
+            &IbcPacketMsg::Subscribe {
+                ids: vec![price_feed(u8::MAX).id],
+            },
+        );
+        assert_eq!(
+            ack,
+            IbcAck::Error(StdError::from(PythContractError::TooManyIbcSubscriptions).to_string())
+        );
+        assert_eq!(
+            ibc_subscription_read_bucket(&deps.storage)
+                .load(ALLOWED_CHANNEL.as_bytes())
+                .unwrap(),
+            ids.into_iter().collect::<HashSet<_>>()
+        );
+    }
+
+    #[test]
+    fn test_remove_disallowed_subscriptions() {
+        let mut deps = setup_test();
+        ibc_channels(&mut deps.storage)
+            .save(&HashSet::from([
+                ALLOWED_CHANNEL.to_string(),
+                OTHER_CHANNEL.to_string(),
+            ]))
+            .unwrap();
+        for channel_id in [ALLOWED_CHANNEL, OTHER_CHANNEL] {
+            receive(
+                &mut deps,
+                channel_id,
+                &IbcPacketMsg::Subscribe {
+                    ids: vec![price_feed(1).id],
+                },
+            );
+        }
+
+        remove_disallowed_subscriptions(
+            &mut deps.storage,
+            &HashSet::from([ALLOWED_CHANNEL.to_string()]),
+        )
+        .unwrap();
+        assert_eq!(
+            ibc_subscription_read_bucket(&deps.storage)
+                .may_load(ALLOWED_CHANNEL.as_bytes())
+                .unwrap(),
+            Some(HashSet::from([price_feed(1).id]))
+        );
+        assert_eq!(
+            ibc_subscription_read_bucket(&deps.storage)
+                .may_load(OTHER_CHANNEL.as_bytes())
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_subscribe_not_allowed_channel() {
+        let mut deps = setup_test();
+
+        let ack = receive(
+            &mut deps,
+            OTHER_CHANNEL,
+            &IbcPacketMsg::Subscribe {
+                ids: vec![price_feed(1).id],
+            },
+        );
+        assert_eq!(
+            ack,
+            IbcAck::Error(StdError::from(PythContractError::IbcChannelNotAllowed).to_string())
+        );
+        assert_eq!(
+            ibc_subscription_read_bucket(&deps.storage)
+                .may_load(OTHER_CHANNEL.as_bytes())
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_forward_price_feeds() {
+        let mut deps = setup_test();
+        let env = mock_env();
+
+        receive(
+            &mut deps,
+            ALLOWED_CHANNEL,
+            &IbcPacketMsg::Subscribe {
+                ids: vec![price_feed(1).id, price_feed(2).id],
+            },
+        );
+        // subscriptions of channels that are no longer allowed are ignored
+        ibc_subscription_bucket(&mut deps.storage)
+            .save(OTHER_CHANNEL.as_bytes(), &HashSet::from([price_feed(1).id]))
+            .unwrap();
+
+        let updates = [
+            (price_feed(1), PriceFeedUpdateStatus::Updated),
+            (price_feed(2), PriceFeedUpdateStatus::StaleSkipped),
+            (price_feed(3), PriceFeedUpdateStatus::Updated),
+        ];
+        let messages = forward_price_feeds(deps.as_ref(), &env, &updates).unwrap();
+        assert_eq!(
+            messages,
+            vec![IbcMsg::SendPacket {
+                channel_id: ALLOWED_CHANNEL.to_string(),
+                data: to_binary(&PriceFeedsPacket {
+                    price_feeds: vec![price_feed(1)],
+                })
+                .unwrap(),
+                timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(60)),
+            }]
+        );
+
+        // nothing is sent if none of the subscribed feeds was updated
+        let messages = forward_price_feeds(deps.as_ref(), &env, &updates[1..]).unwrap();
+        assert_eq!(messages, vec![]);
+    }
+}
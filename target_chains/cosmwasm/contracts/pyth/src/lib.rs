@@ -6,5 +6,7 @@ pub mod governance;
 pub mod msg;
 pub mod state;
 
+#[cfg(feature = "ibc")]
+pub mod ibc;
 #[cfg(feature = "injective")]
 mod injective;
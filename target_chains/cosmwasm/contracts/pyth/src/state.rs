@@ -1,25 +1,42 @@
+pub use pyth_cosmwasm_sdk::PythDataSource;
 use {
-    cosmwasm_std::{Addr, Binary, Coin, StdResult, Storage},
+    cosmwasm_std::{Addr, Coin, StdResult, Storage, Uint128},
     cosmwasm_storage::{
         bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
         Singleton,
     },
-    pyth_sdk_cw::PriceFeed,
+    pyth_sdk_cw::{Price, PriceFeed, PriceIdentifier},
     schemars::JsonSchema,
     serde::{Deserialize, Serialize},
-    std::{collections::HashSet, time::Duration},
+    std::{
+        collections::{HashSet, VecDeque},
+        time::Duration,
+    },
 };
 
 pub static CONFIG_KEY: &[u8] = b"config_v1";
 pub static PRICE_FEED_KEY: &[u8] = b"price_feed";
 pub static CONTRACT_VERSION_KEY: &[u8] = b"contract_version";
+pub static IBC_CHANNELS_KEY: &[u8] = b"ibc_channels";
+pub static IBC_SUBSCRIPTION_KEY: &[u8] = b"ibc_subscription";
+pub static PRICE_HISTORY_DEPTH_KEY: &[u8] = b"price_history_depth";
+pub static PRICE_HISTORY_KEY: &[u8] = b"price_history";
+pub static FEE_TOKENS_KEY: &[u8] = b"fee_tokens";
+pub static FEE_RECIPIENT_KEY: &[u8] = b"fee_recipient";
 
-/// A `PythDataSource` identifies a specific contract (given by its Wormhole `emitter`) on
-/// a specific blockchain (given by `chain_id`).
+/// A token the fee can be paid in besides the fee in the config.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, JsonSchema)]
-pub struct PythDataSource {
-    pub emitter: Binary,
-    pub chain_id: u16,
+#[serde(rename_all = "snake_case")]
+pub enum FeeToken {
+    Native { denom: String },
+    Cw20 { contract: Addr },
+}
+
+/// The fee per price update in `token`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct TokenFee {
+    pub token: FeeToken,
+    pub amount: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -70,3 +87,60 @@ pub fn set_contract_version(storage: &mut dyn Storage, contract_version: &String
 pub fn get_contract_version(storage: &mut dyn Storage) -> StdResult<String> {
     singleton_read(storage, CONTRACT_VERSION_KEY).load()
 }
+
+/// The number of prices kept in the history of each price feed, set by governance. No history
+/// is kept if it is unset or 0.
+pub fn price_history_depth(storage: &mut dyn Storage) -> Singleton<u16> {
+    singleton(storage, PRICE_HISTORY_DEPTH_KEY)
+}
+
+pub fn price_history_depth_read(storage: &dyn Storage) -> ReadonlySingleton<u16> {
+    singleton_read(storage, PRICE_HISTORY_DEPTH_KEY)
+}
+
+/// The most recent prices of each price feed, newest first, keyed by price feed id.
+pub fn price_history_bucket(storage: &mut dyn Storage) -> Bucket<VecDeque<Price>> {
+    bucket(storage, PRICE_HISTORY_KEY)
+}
+
+pub fn price_history_read_bucket(storage: &dyn Storage) -> ReadonlyBucket<VecDeque<Price>> {
+    bucket_read(storage, PRICE_HISTORY_KEY)
+}
+
+/// The IBC channels over which price updates can be forwarded, set by governance.
+pub fn ibc_channels(storage: &mut dyn Storage) -> Singleton<HashSet<String>> {
+    singleton(storage, IBC_CHANNELS_KEY)
+}
+
+pub fn ibc_channels_read(storage: &dyn Storage) -> ReadonlySingleton<HashSet<String>> {
+    singleton_read(storage, IBC_CHANNELS_KEY)
+}
+
+/// The price feeds subscribed to by the counterparty of each IBC channel, keyed by channel id.
+pub fn ibc_subscription_bucket(storage: &mut dyn Storage) -> Bucket<HashSet<PriceIdentifier>> {
+    bucket(storage, IBC_SUBSCRIPTION_KEY)
+}
+
+pub fn ibc_subscription_read_bucket(
+    storage: &dyn Storage,
+) -> ReadonlyBucket<HashSet<PriceIdentifier>> {
+    bucket_read(storage, IBC_SUBSCRIPTION_KEY)
+}
+
+/// The tokens the fee can be paid in besides the fee in the config, set by governance.
+pub fn fee_tokens(storage: &mut dyn Storage) -> Singleton<Vec<TokenFee>> {
+    singleton(storage, FEE_TOKENS_KEY)
+}
+
+pub fn fee_tokens_read(storage: &dyn Storage) -> ReadonlySingleton<Vec<TokenFee>> {
+    singleton_read(storage, FEE_TOKENS_KEY)
+}
+
+/// The address `SweepFees` sends the fees held by the contract to, set by governance.
+pub fn fee_recipient(storage: &mut dyn Storage) -> Singleton<Addr> {
+    singleton(storage, FEE_RECIPIENT_KEY)
+}
+
+pub fn fee_recipient_read(storage: &dyn Storage) -> ReadonlySingleton<Addr> {
+    singleton_read(storage, FEE_RECIPIENT_KEY)
+}
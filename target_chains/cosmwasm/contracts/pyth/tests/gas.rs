@@ -0,0 +1,365 @@
+//! Gas measurements of the compiled contract, checked against golden values.
+//!
+//! Unlike the unit tests, these run the optimized Wasm in the CosmWasm VM so the reported gas
+//! matches what a chain charges for executing the contract. Golden values are in CosmWasm gas;
+//! the test output also shows them in SDK gas. The wormhole contract is mocked natively, so the
+//! gas of VAA signature verification on the wormhole side is not included. Storage accesses are
+//! charged with the default KV store gas of the Cosmos SDK, which chains charge on top of the
+//! gas of the VM itself.
+//!
+//! The tests need the contract built by the optimizer with the same features as the test
+//! binary, e.g. for the default feature set:
+//!
+//! ```sh
+//! cd ../../deploy-scripts && pnpm run build-contract -- --cosmwasm
+//! cd ../contracts/pyth && CARGO_PROFILE_DEV_DEBUG_ASSERTIONS=false cargo test --test gas -- --ignored
+//! ```
+//!
+//! CI runs them for each feature set and uploads the measured values when they differ from the
+//! golden ones.
+//!
+//! Debug assertions have to be disabled because the wasmer version used by the VM fails the
+//! precondition checks of recent standard libraries. Gas usage depends on the compiler version,
+//! so regenerate the golden values when the toolchain of the build image changes.
+//!
+//! `PYTH_COSMWASM_WASM` overrides the path to the Wasm file. Run with `UPDATE_GAS_GOLDEN=1`
+//! to rewrite the golden values after an intended change in gas usage.
+use {
+    cosmwasm_std::{
+        from_binary, from_slice, testing::mock_info, to_binary, to_vec, Binary, Coin,
+        ContractResult, Order, QuerierResult, Record, SystemError, SystemResult, WasmQuery,
+    },
+    cosmwasm_vm::{
+        call_execute_raw, call_instantiate_raw, call_query_raw,
+        testing::{mock_env, MockApi, MockQuerier, MockStorage},
+        Backend, BackendResult, GasInfo, Instance, InstanceOptions, Size, Storage,
+    },
+    cw_wormhole::{msg::QueryMsg as WormholeQueryMsg, state::ParsedVAA},
+    pyth_cosmwasm::{msg::InstantiateMsg, state::PythDataSource},
+    pyth_sdk_cw::{ExecuteMsg, PriceIdentifier, QueryMsg},
+    pythnet_sdk::{
+        messages::Message,
+        test_utils::{
+            create_accumulator_message, create_dummy_price_feed_message, DEFAULT_CHAIN_ID,
+            DEFAULT_DATA_SOURCE, DEFAULT_GOVERNANCE_SOURCE, DEFAULT_VALID_TIME_PERIOD,
+        },
+    },
+    serde_json::Value,
+    std::{collections::BTreeMap, env, fs, path::PathBuf},
+};
+
+const WORMHOLE_ADDR: &str = "wormhole";
+/// All measured calls run on the same instance, so this is the limit for all of them together
+const GAS_LIMIT: u64 = 100_000_000_000_000_000;
+/// CosmWasm gas per SDK gas, wasmd's `DefaultGasMultiplier`
+const GAS_MULTIPLIER: u64 = 140_000_000;
+/// Relative difference to the golden value tolerated before a measurement fails
+const TOLERANCE: f64 = 0.01;
+/// Memory limit of the instance, the one of the mock instances of cosmwasm-vm
+const MEMORY_LIMIT: Size = Size::mebi(16);
+
+// The default `KVGasConfig` of the Cosmos SDK, in SDK gas
+const HAS_COST: u64 = 1_000;
+const DELETE_COST: u64 = 1_000;
+const READ_COST_FLAT: u64 = 1_000;
+const READ_COST_PER_BYTE: u64 = 3;
+const WRITE_COST_FLAT: u64 = 2_000;
+const WRITE_COST_PER_BYTE: u64 = 30;
+const ITER_NEXT_COST_FLAT: u64 = 30;
+
+fn feature_set() -> &'static str {
+    if cfg!(feature = "injective") {
+        "injective"
+    } else if cfg!(feature = "osmosis") {
+        "osmosis"
+    } else {
+        "cosmwasm"
+    }
+}
+
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn golden_path() -> PathBuf {
+    manifest_dir()
+        .join("tests")
+        .join("gas")
+        .join(format!("{}.json", feature_set()))
+}
+
+fn handle_wasm_query(wasm_query: &WasmQuery) -> QuerierResult {
+    match wasm_query {
+        WasmQuery::Smart { contract_addr, msg } if *contract_addr == WORMHOLE_ADDR => {
+            match from_binary::<WormholeQueryMsg>(msg) {
+                Ok(WormholeQueryMsg::VerifyVAA { vaa, .. }) => SystemResult::Ok(
+                    ContractResult::Ok(to_binary(&ParsedVAA::deserialize(&vaa).unwrap()).unwrap()),
+                ),
+                _ => SystemResult::Err(SystemError::InvalidRequest {
+                    error: "Invalid message".into(),
+                    request: msg.clone(),
+                }),
+            }
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".into(),
+        }),
+    }
+}
+
+fn sdk_gas(gas: u64) -> GasInfo {
+    GasInfo::with_externally_used(gas * GAS_MULTIPLIER)
+}
+
+/// Storage that charges the KV store gas of the Cosmos SDK instead of the nominal gas of
+/// `MockStorage`. Without it, the measurements barely depend on what the contract stores.
+#[derive(Default)]
+struct KvGasStorage(MockStorage);
+
+impl Storage for KvGasStorage {
+    fn get(&self, key: &[u8]) -> BackendResult<Option<Vec<u8>>> {
+        let (result, _) = self.0.get(key);
+        let gas = match &result {
+            Ok(Some(value)) => {
+                READ_COST_FLAT + READ_COST_PER_BYTE * (key.len() + value.len()) as u64
+            }
+            _ => HAS_COST,
+        };
+        (result, sdk_gas(gas))
+    }
+
+    fn scan(
+        &mut self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> BackendResult<u32> {
+        let (result, _) = self.0.scan(start, end, order);
+        (result, sdk_gas(ITER_NEXT_COST_FLAT))
+    }
+
+    fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>> {
+        let (result, _) = self.0.next(iterator_id);
+        let gas = match &result {
+            Ok(Some((key, value))) => {
+                ITER_NEXT_COST_FLAT + READ_COST_PER_BYTE * (key.len() + value.len()) as u64
+            }
+            _ => ITER_NEXT_COST_FLAT,
+        };
+        (result, sdk_gas(gas))
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) -> BackendResult<()> {
+        let (result, _) = self.0.set(key, value);
+        let gas = WRITE_COST_FLAT + WRITE_COST_PER_BYTE * (key.len() + value.len()) as u64;
+        (result, sdk_gas(gas))
+    }
+
+    fn remove(&mut self, key: &[u8]) -> BackendResult<()> {
+        let (result, _) = self.0.remove(key);
+        (result, sdk_gas(DELETE_COST))
+    }
+}
+
+/// Runs the measured calls on a single contract instance, recording the gas used by each.
+struct GasMeter {
+    instance: Instance<MockApi, KvGasStorage, MockQuerier>,
+    measurements: BTreeMap<String, u64>,
+}
+
+impl GasMeter {
+    fn new() -> Self {
+        let wasm_path = env::var("PYTH_COSMWASM_WASM")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| manifest_dir().join("../../artifacts/pyth_cosmwasm.wasm"));
+        let wasm = fs::read(&wasm_path)
+            .unwrap_or_else(|e| panic!("Could not read {}: {e}", wasm_path.display()));
+
+        let backend = Backend {
+            api: MockApi::default(),
+            storage: KvGasStorage::default(),
+            querier: MockQuerier::new(&[]),
+        };
+        let options = InstanceOptions {
+            gas_limit: GAS_LIMIT,
+            print_debug: false,
+        };
+        let mut instance =
+            Instance::from_code(&wasm, backend, options, Some(MEMORY_LIMIT)).unwrap();
+        instance
+            .with_querier(|querier| {
+                querier.update_wasm(handle_wasm_query);
+                Ok(())
+            })
+            .unwrap();
+
+        GasMeter {
+            instance,
+            measurements: BTreeMap::new(),
+        }
+    }
+
+    /// Runs a call of the contract and records its gas usage under `name`. The calls work on
+    /// the raw JSON, since the custom message type of the injective build is not public.
+    fn measure(
+        &mut self,
+        name: &str,
+        call: impl FnOnce(&mut Instance<MockApi, KvGasStorage, MockQuerier>) -> Vec<u8>,
+    ) -> Value {
+        let gas_before = self.instance.get_gas_left();
+        let result = call(&mut self.instance);
+        let gas_used = gas_before - self.instance.get_gas_left();
+        self.measurements.insert(name.to_string(), gas_used);
+
+        from_slice::<ContractResult<Value>>(&result)
+            .unwrap()
+            .into_result()
+            .unwrap_or_else(|e| panic!("{} failed: {}", name, e))
+    }
+
+    fn instantiate(&mut self) {
+        let msg = InstantiateMsg {
+            wormhole_contract: WORMHOLE_ADDR.to_string(),
+            data_sources: vec![PythDataSource {
+                emitter: Binary(DEFAULT_DATA_SOURCE.address.0.to_vec()),
+                chain_id: DEFAULT_DATA_SOURCE.chain.into(),
+            }],
+            governance_source: PythDataSource {
+                emitter: Binary(DEFAULT_GOVERNANCE_SOURCE.address.0.to_vec()),
+                chain_id: DEFAULT_GOVERNANCE_SOURCE.chain.into(),
+            },
+            governance_source_index: 0,
+            governance_sequence_number: 0,
+            chain_id: DEFAULT_CHAIN_ID.into(),
+            valid_time_period_secs: DEFAULT_VALID_TIME_PERIOD as u16,
+            fee: Coin::new(0, "uatom"),
+        };
+        self.measure("instantiate", |instance| {
+            call_instantiate_raw(
+                instance,
+                &to_vec(&mock_env()).unwrap(),
+                &to_vec(&mock_info("creator", &[])).unwrap(),
+                &to_vec(&msg).unwrap(),
+            )
+            .unwrap()
+        });
+    }
+
+    fn update_price_feeds(&mut self, name: &str, data: Vec<Binary>) {
+        let msg = ExecuteMsg::UpdatePriceFeeds { data };
+        self.measure(name, |instance| {
+            call_execute_raw(
+                instance,
+                &to_vec(&mock_env()).unwrap(),
+                &to_vec(&mock_info("updater", &[])).unwrap(),
+                &to_vec(&msg).unwrap(),
+            )
+            .unwrap()
+        });
+    }
+
+    fn query(&mut self, name: &str, msg: QueryMsg) {
+        self.measure(name, |instance| {
+            call_query_raw(
+                instance,
+                &to_vec(&mock_env()).unwrap(),
+                &to_vec(&msg).unwrap(),
+            )
+            .unwrap()
+        });
+    }
+
+    /// Compares the measurements with the golden values of the feature set, or rewrites them if
+    /// `UPDATE_GAS_GOLDEN` is set.
+    fn check_golden(self) {
+        let path = golden_path();
+        if env::var("UPDATE_GAS_GOLDEN").is_ok() {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            let mut json = serde_json::to_string_pretty(&self.measurements).unwrap();
+            json.push('\n');
+            fs::write(&path, json).unwrap();
+            return;
+        }
+
+        let golden: BTreeMap<String, u64> = from_slice(
+            &fs::read(&path).unwrap_or_else(|e| panic!("Could not read {}: {e}", path.display())),
+        )
+        .unwrap();
+        assert_eq!(
+            golden.keys().collect::<Vec<_>>(),
+            self.measurements.keys().collect::<Vec<_>>(),
+            "The measured calls differ from {}",
+            path.display()
+        );
+
+        let mut failures = vec![];
+        for (name, gas) in &self.measurements {
+            let expected = golden[name];
+            let diff = (*gas as f64 - expected as f64) / expected as f64;
+            println!(
+                "{name}: {gas} gas, {} SDK gas (golden {expected}, {:+.2}%)",
+                gas / GAS_MULTIPLIER,
+                diff * 100.0
+            );
+            if diff.abs() > TOLERANCE {
+                failures.push(name.as_str());
+            }
+        }
+        assert!(
+            failures.is_empty(),
+            "Gas usage of {failures:?} differs from {} by more than {}%",
+            path.display(),
+            TOLERANCE * 100.0
+        );
+    }
+}
+
+#[test]
+#[ignore = "requires the compiled contract, see the module documentation"]
+fn test_gas_golden() {
+    let mut meter = GasMeter::new();
+    meter.instantiate();
+
+    let feeds: Vec<_> = (1..=10)
+        .map(|i| create_dummy_price_feed_message(i * 100))
+        .collect();
+    let all_feeds: Vec<_> = feeds.iter().collect();
+    let update = |updates: &[&_]| -> Binary {
+        create_accumulator_message(&all_feeds, updates, false, false, None).into()
+    };
+
+    meter.update_price_feeds("update_price_feeds_first_1", vec![update(&all_feeds[..1])]);
+    meter.update_price_feeds("update_price_feeds_first_10", vec![update(&all_feeds)]);
+    // the same messages again are older than the stored prices and are skipped
+    meter.update_price_feeds("update_price_feeds_stale_1", vec![update(&all_feeds[..1])]);
+    meter.update_price_feeds("update_price_feeds_stale_10", vec![update(&all_feeds)]);
+
+    let newer_feeds: Vec<_> = (1..=10)
+        .map(|i| {
+            let mut feed = create_dummy_price_feed_message(i * 100);
+            if let Message::PriceFeedMessage(msg) = &mut feed {
+                msg.publish_time += 1;
+            }
+            feed
+        })
+        .collect();
+    let newer_feeds: Vec<_> = newer_feeds.iter().collect();
+    meter.update_price_feeds(
+        "update_price_feeds_newer_10",
+        vec![create_accumulator_message(&newer_feeds, &newer_feeds, false, false, None).into()],
+    );
+
+    let id = match feeds[0] {
+        Message::PriceFeedMessage(msg) => PriceIdentifier::new(msg.feed_id),
+        _ => unreachable!(),
+    };
+    meter.query("query_price_feed", QueryMsg::PriceFeed { id });
+    meter.query(
+        "query_get_update_fee_10",
+        QueryMsg::GetUpdateFee {
+            vaas: vec![update(&all_feeds)],
+        },
+    );
+
+    meter.check_golden();
+}
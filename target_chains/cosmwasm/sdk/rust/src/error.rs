@@ -69,6 +69,26 @@ pub enum PythContractError {
     /// Merkle proof is invalid
     #[error("InvalidMerkleProof")]
     InvalidMerkleProof,
+
+    /// The new wormhole contract can not verify the governance message that sets it
+    #[error("InvalidWormholeAddressToSet")]
+    InvalidWormholeAddressToSet,
+
+    /// The order or version of an IBC channel is not supported
+    #[error("InvalidIbcChannel")]
+    InvalidIbcChannel,
+
+    /// The IBC channel is not allowed by governance
+    #[error("IbcChannelNotAllowed")]
+    IbcChannelNotAllowed,
+
+    /// A channel can not subscribe to more than `MAX_IBC_SUBSCRIPTIONS` price feeds
+    #[error("TooManyIbcSubscriptions")]
+    TooManyIbcSubscriptions,
+
+    /// Fees can not be swept before governance sets a fee recipient
+    #[error("FeeRecipientNotSet")]
+    FeeRecipientNotSet,
 }
 
 impl From<PythContractError> for StdError {
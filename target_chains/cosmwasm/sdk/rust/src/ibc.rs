@@ -0,0 +1,45 @@
+//! Packets exchanged over IBC channels with the Pyth contract.
+//!
+//! Contracts on chains without a Wormhole light client can open a channel to the Pyth contract
+//! on a hub chain with the [`IBC_APP_VERSION`] version and subscribe to the price feeds they
+//! need. Every verified update of a subscribed feed on the hub chain is then forwarded as a
+//! [`PriceFeedsPacket`] over the channel.
+//!
+//! All packets and acknowledgements are JSON encoded.
+use {
+    crate::{PriceFeed, PriceIdentifier},
+    cosmwasm_schema::cw_serde,
+    cosmwasm_std::Binary,
+};
+
+/// Version of the IBC channels opened with the Pyth contract.
+pub const IBC_APP_VERSION: &str = "pyth-price-1";
+
+/// Maximum number of price feeds a channel can subscribe to, which bounds the size of the
+/// [`PriceFeedsPacket`]s sent over it.
+pub const MAX_IBC_SUBSCRIPTIONS: usize = 64;
+
+/// Packets sent by a counterparty to the Pyth contract. Subscriptions are only accepted on
+/// channels allowed by governance.
+#[derive(Eq)]
+#[cw_serde]
+pub enum IbcPacketMsg {
+    /// Forward the updates of the price feeds with the given ids over this channel
+    Subscribe { ids: Vec<PriceIdentifier> },
+    /// Stop forwarding the updates of the price feeds with the given ids over this channel
+    Unsubscribe { ids: Vec<PriceIdentifier> },
+}
+
+/// Packet sent by the Pyth contract to a counterparty. It holds the subscribed price feeds
+/// that were updated in a single `UpdatePriceFeeds` execution.
+#[cw_serde]
+pub struct PriceFeedsPacket {
+    pub price_feeds: Vec<PriceFeed>,
+}
+
+/// Acknowledgement of a packet, following the ICS-20 acknowledgement format.
+#[cw_serde]
+pub enum IbcAck {
+    Result(Binary),
+    Error(String),
+}
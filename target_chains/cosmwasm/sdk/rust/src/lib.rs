@@ -1,11 +1,12 @@
 pub mod error;
+pub mod ibc;
 pub mod testing;
 
 pub use pyth_sdk::{Price, PriceFeed, PriceIdentifier, UnixTimestamp};
 use {
     cosmwasm_schema::{cw_serde, QueryResponses},
     cosmwasm_std::{
-        to_binary, Addr, Binary, Coin, QuerierWrapper, QueryRequest, StdResult, WasmQuery,
+        to_binary, Addr, Binary, Coin, QuerierWrapper, QueryRequest, StdResult, Uint128, WasmQuery,
     },
     std::time::Duration,
 };
@@ -13,8 +14,36 @@ use {
 #[derive(Eq)]
 #[cw_serde]
 pub enum ExecuteMsg {
+    UpdatePriceFeeds {
+        data: Vec<Binary>,
+    },
+    ExecuteGovernanceInstruction {
+        data: Binary,
+    },
+    /// Send the fees held by the contract to the fee recipient set by governance. Anyone can
+    /// execute this.
+    SweepFees {},
+    /// Pay the fee in a CW20 token. This is sent by the token contract when the tokens are
+    /// sent to the Pyth contract with a [`Cw20HookMsg`].
+    Receive(Cw20ReceiveMsg),
+}
+
+/// The message a CW20 token contract sends to the Pyth contract on a CW20 `Send`, as defined by
+/// the CW20 specification. `msg` is the JSON-encoded [`Cw20HookMsg`] of the `Send`.
+#[derive(Eq)]
+#[cw_serde]
+pub struct Cw20ReceiveMsg {
+    pub sender: String,
+    pub amount: Uint128,
+    pub msg: Binary,
+}
+
+/// Messages that can be executed by sending CW20 tokens to the Pyth contract. The sent tokens
+/// pay the fee, so the token must be accepted by governance.
+#[derive(Eq)]
+#[cw_serde]
+pub enum Cw20HookMsg {
     UpdatePriceFeeds { data: Vec<Binary> },
-    ExecuteGovernanceInstruction { data: Binary },
 }
 
 #[cw_serde]
@@ -22,6 +51,11 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     #[returns(PriceFeedResponse)]
     PriceFeed { id: PriceIdentifier },
+    #[returns(PriceFeedHistoryResponse)]
+    PriceFeedHistory {
+        id: PriceIdentifier,
+        limit: Option<u32>,
+    },
     #[returns(Coin)]
     GetUpdateFee { vaas: Vec<Binary> },
     #[cfg(feature = "osmosis")]
@@ -36,6 +70,42 @@ pub struct PriceFeedResponse {
     pub price_feed: PriceFeed,
 }
 
+/// The most recent prices of a price feed, newest first. The current price is the first one.
+///
+/// The contract keeps up to a governance-configured number of prices per feed, and no history
+/// at all while that number is 0.
+#[cw_serde]
+pub struct PriceFeedHistoryResponse {
+    pub prices: Vec<Price>,
+}
+
+/// Data of the response to [`ExecuteMsg::UpdatePriceFeeds`]. It holds one result per price
+/// update in the submitted data, in the order the updates appear in it.
+#[cw_serde]
+pub struct UpdatePriceFeedsResponse {
+    pub results: Vec<PriceFeedUpdateResult>,
+}
+
+#[cw_serde]
+pub struct PriceFeedUpdateResult {
+    pub id: PriceIdentifier,
+    /// Publish time of the submitted update (not necessarily of the stored price feed)
+    pub publish_time: UnixTimestamp,
+    pub status: PriceFeedUpdateStatus,
+}
+
+/// Outcome of applying a single price update.
+#[derive(Copy, Eq)]
+#[cw_serde]
+pub enum PriceFeedUpdateStatus {
+    /// The stored price feed was replaced by the update.
+    Updated,
+    /// The update was not newer than the stored price feed and was ignored.
+    StaleSkipped,
+    /// The contract had no price feed with this id; the update was stored as its first price.
+    Unknown,
+}
+
 /// Queries the price on-chain
 pub fn query_price_feed(
     querier: &QuerierWrapper,
@@ -49,6 +119,20 @@ pub fn query_price_feed(
     Ok(price_feed_response)
 }
 
+/// Queries up to `limit` of the most recent prices of a price feed on-chain. All the kept
+/// prices are returned if `limit` is `None`.
+pub fn query_price_feed_history(
+    querier: &QuerierWrapper,
+    contract_addr: Addr,
+    id: PriceIdentifier,
+    limit: Option<u32>,
+) -> StdResult<PriceFeedHistoryResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: contract_addr.into_string(),
+        msg: to_binary(&QueryMsg::PriceFeedHistory { id, limit })?,
+    }))
+}
+
 /// Get the fee required in order to update the on-chain state with the provided
 /// `price_update_vaas`.
 pub fn get_update_fee(
@@ -1,10 +1,16 @@
 use {
-    crate::{error::PythContractError, PriceFeed, PriceFeedResponse, PriceIdentifier, QueryMsg},
+    crate::{
+        error::PythContractError, Price, PriceFeed, PriceFeedHistoryResponse, PriceFeedResponse,
+        PriceIdentifier, QueryMsg,
+    },
     cosmwasm_std::{
         from_binary, to_binary, Binary, Coin, ContractResult, QuerierResult, SystemError,
         SystemResult,
     },
-    std::{collections::HashMap, time::Duration},
+    std::{
+        collections::{HashMap, VecDeque},
+        time::Duration,
+    },
 };
 
 /// Mock version of Pyth for testing cosmwasm contracts.
@@ -14,6 +20,9 @@ pub struct MockPyth {
     pub valid_time_period: Duration,
     pub fee_per_vaa: Coin,
     pub feeds: HashMap<PriceIdentifier, PriceFeed>,
+    /// Number of prices kept per feed, newest first. The contract keeps none by default.
+    pub price_history_depth: u16,
+    pub price_history: HashMap<PriceIdentifier, VecDeque<Price>>,
 }
 
 impl MockPyth {
@@ -29,11 +38,27 @@ impl MockPyth {
             valid_time_period,
             fee_per_vaa,
             feeds: feeds_map,
+            price_history_depth: 0,
+            price_history: HashMap::new(),
+        }
+    }
+
+    /// Set the number of prices kept per feed, dropping the oldest prices beyond it.
+    pub fn set_price_history_depth(&mut self, depth: u16) {
+        self.price_history_depth = depth;
+        for history in self.price_history.values_mut() {
+            history.truncate(depth as usize);
         }
     }
 
-    /// Add a price feed that will be returned on queries.
+    /// Add a price feed that will be returned on queries. Its price is also added to the
+    /// history of the feed, like an update of the contract.
     pub fn add_feed(&mut self, feed: PriceFeed) {
+        if self.price_history_depth > 0 {
+            let history = self.price_history.entry(feed.id).or_default();
+            history.push_front(feed.get_price_unchecked());
+            history.truncate(self.price_history_depth as usize);
+        }
         self.feeds.insert(feed.id, feed);
     }
 
@@ -52,6 +77,28 @@ impl MockPyth {
                     PythContractError::PriceFeedNotFound,
                 ))),
             },
+            Ok(QueryMsg::PriceFeedHistory { id, limit }) => match self.feeds.get(&id) {
+                Some(_) => {
+                    let depth = self.price_history_depth as usize;
+                    let limit = limit.map_or(depth, |limit| depth.min(limit as usize));
+                    SystemResult::Ok(
+                        to_binary(&PriceFeedHistoryResponse {
+                            prices: self
+                                .price_history
+                                .get(&id)
+                                .into_iter()
+                                .flatten()
+                                .take(limit)
+                                .copied()
+                                .collect(),
+                        })
+                        .into(),
+                    )
+                }
+                None => SystemResult::Ok(ContractResult::from(Err(
+                    PythContractError::PriceFeedNotFound,
+                ))),
+            },
             Ok(QueryMsg::GetValidTimePeriod) => {
                 SystemResult::Ok(to_binary(&self.valid_time_period).into())
             }
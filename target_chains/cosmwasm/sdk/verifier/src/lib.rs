@@ -0,0 +1,458 @@
+//! Verification of Pyth price updates inside CosmWasm contracts.
+//!
+//! The functions of this crate check the same things as the Pyth contract before it stores an
+//! update: the Wormhole signatures of the VAA, the emitter of the VAA and, for accumulator
+//! updates, the Merkle proof of every price message. The Wormhole signatures are checked by
+//! querying the Wormhole contract of the chain, all other checks run in the calling contract.
+//!
+//! The crate has no entry points and keeps no state, so the caller provides the Wormhole
+//! contract and the data sources to accept.
+use {
+    byteorder::BigEndian,
+    cosmwasm_std::{
+        to_binary, Addr, Binary, CustomQuery, QuerierWrapper, QueryRequest, StdResult, WasmQuery,
+    },
+    cw_wormhole::{msg::QueryMsg as WormholeQueryMsg, state::ParsedVAA},
+    pyth_sdk_cw::{error::PythContractError, Price, PriceFeed, PriceIdentifier},
+    pyth_wormhole_attester_sdk::{BatchPriceAttestation, PriceAttestation, PriceStatus},
+    pythnet_sdk::{
+        accumulators::merkle::MerkleRoot,
+        hashers::keccak256_160::Keccak160,
+        messages::Message,
+        wire::{
+            from_slice,
+            v1::{
+                AccumulatorUpdateData, MerklePriceUpdate, Proof, WormholeMessage, WormholePayload,
+                PYTHNET_ACCUMULATOR_UPDATE_MAGIC,
+            },
+        },
+    },
+    schemars::JsonSchema,
+    serde::{Deserialize, Serialize},
+    std::collections::HashSet,
+};
+
+/// A `PythDataSource` identifies a specific contract (given by its Wormhole `emitter`) on
+/// a specific blockchain (given by `chain_id`).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, JsonSchema)]
+pub struct PythDataSource {
+    pub emitter: Binary,
+    pub chain_id: u16,
+}
+
+/// Verify that `data` represents an authentic Wormhole VAA, using the wormhole contract at
+/// `wormhole_contract`.
+///
+/// *Warning* this function does not verify the emitter of the wormhole message; it only checks
+/// that the wormhole signatures are valid. The caller is responsible for checking that the message
+/// originates from the expected emitter.
+pub fn parse_and_verify_vaa<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    wormhole_contract: &Addr,
+    block_time: u64,
+    data: &Binary,
+) -> StdResult<ParsedVAA> {
+    let vaa: ParsedVAA = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: wormhole_contract.to_string(),
+        msg: to_binary(&WormholeQueryMsg::VerifyVAA {
+            vaa: data.clone(),
+            block_time,
+        })?,
+    }))?;
+    Ok(vaa)
+}
+
+/// Check that `vaa` is from one of `data_sources` (and hence is a legitimate price update message).
+pub fn verify_vaa_from_data_source(
+    data_sources: &HashSet<PythDataSource>,
+    vaa: &ParsedVAA,
+) -> StdResult<()> {
+    let vaa_data_source = PythDataSource {
+        emitter: vaa.emitter_address.clone().into(),
+        chain_id: vaa.emitter_chain,
+    };
+    if !data_sources.contains(&vaa_data_source) {
+        Err(PythContractError::InvalidUpdateEmitter)?;
+    }
+    Ok(())
+}
+
+/// Parse and verify the price update `data`, which is either an accumulator update or a batch
+/// price attestation VAA, and return the price feeds it contains.
+///
+/// The returned price feeds are authentic but may be older than the ones the caller already has;
+/// checking the publish times is left to the caller.
+pub fn parse_update<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    wormhole_contract: &Addr,
+    data_sources: &HashSet<PythDataSource>,
+    block_time: u64,
+    data: &Binary,
+) -> StdResult<Vec<PriceFeed>> {
+    let header = data.get(0..4);
+    let feeds = if header == Some(PYTHNET_ACCUMULATOR_UPDATE_MAGIC.as_slice()) {
+        parse_accumulator(querier, wormhole_contract, data_sources, block_time, data)?
+    } else {
+        parse_batch_attestation(querier, wormhole_contract, data_sources, block_time, data)?
+    };
+    Ok(feeds)
+}
+
+/// Parse and verify an accumulator update, i.e. a VAA holding a Merkle root followed by price
+/// messages with their proofs. Updates with data after the last price message are rejected.
+pub fn parse_accumulator<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    wormhole_contract: &Addr,
+    data_sources: &HashSet<PythDataSource>,
+    block_time: u64,
+    data: &[u8],
+) -> StdResult<Vec<PriceFeed>> {
+    let update_data = AccumulatorUpdateData::try_from_slice_exact(data)
+        .map_err(|_| PythContractError::InvalidAccumulatorPayload)?;
+    match update_data.proof {
+        Proof::WormholeMerkle { vaa, updates } => {
+            let parsed_vaa = parse_and_verify_vaa(
+                querier,
+                wormhole_contract,
+                block_time,
+                &Binary::from(Vec::from(vaa)),
+            )?;
+            verify_vaa_from_data_source(data_sources, &parsed_vaa)?;
+            verify_merkle_updates(&parsed_vaa.payload, updates)
+        }
+    }
+}
+
+/// Check the proofs of `updates` against the Merkle root in `wormhole_message`, the payload of
+/// an accumulator VAA, and decode the price feeds of the updates.
+///
+/// *Warning* this function does not verify the VAA holding `wormhole_message`. The caller is
+/// responsible for checking its signatures and emitter first.
+pub fn verify_merkle_updates(
+    wormhole_message: &[u8],
+    updates: Vec<MerklePriceUpdate>,
+) -> StdResult<Vec<PriceFeed>> {
+    let msg = WormholeMessage::try_from_bytes(wormhole_message)
+        .map_err(|_| PythContractError::InvalidWormholeMessage)?;
+
+    let root: MerkleRoot<Keccak160> = MerkleRoot::new(match msg.payload {
+        WormholePayload::Merkle(merkle_root) => merkle_root.root,
+    });
+    let mut feeds = vec![];
+    for update in updates {
+        let message_vec = Vec::from(update.message);
+        if !root.check(update.proof, &message_vec) {
+            Err(PythContractError::InvalidMerkleProof)?;
+        }
+        feeds.push(parse_price_feed_message(&message_vec)?);
+    }
+    Ok(feeds)
+}
+
+/// Decode a price feed from a serialized accumulator message. Messages other than price feed
+/// messages are rejected.
+pub fn parse_price_feed_message(message: &[u8]) -> StdResult<PriceFeed> {
+    let msg = from_slice::<BigEndian, Message>(message)
+        .map_err(|_| PythContractError::InvalidAccumulatorMessage)?;
+
+    match msg {
+        Message::PriceFeedMessage(price_feed_message) => Ok(PriceFeed::new(
+            PriceIdentifier::new(price_feed_message.feed_id),
+            Price {
+                price: price_feed_message.price,
+                conf: price_feed_message.conf,
+                expo: price_feed_message.exponent,
+                publish_time: price_feed_message.publish_time,
+            },
+            Price {
+                price: price_feed_message.ema_price,
+                conf: price_feed_message.ema_conf,
+                expo: price_feed_message.exponent,
+                publish_time: price_feed_message.publish_time,
+            },
+        )),
+        _ => Err(PythContractError::InvalidAccumulatorMessageType)?,
+    }
+}
+
+/// Parse and verify a batch price attestation VAA.
+pub fn parse_batch_attestation<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    wormhole_contract: &Addr,
+    data_sources: &HashSet<PythDataSource>,
+    block_time: u64,
+    data: &Binary,
+) -> StdResult<Vec<PriceFeed>> {
+    let vaa = parse_and_verify_vaa(querier, wormhole_contract, block_time, data)?;
+    verify_vaa_from_data_source(data_sources, &vaa)?;
+    let batch_attestation = BatchPriceAttestation::deserialize(vaa.payload.as_slice())
+        .map_err(|_| PythContractError::InvalidUpdatePayload)?;
+
+    Ok(batch_attestation
+        .price_attestations
+        .iter()
+        .map(create_price_feed_from_price_attestation)
+        .collect())
+}
+
+/// Convert a price attestation to a price feed. Attestations of products that are not trading
+/// carry the last trading price in the `prev_*` fields, which are used instead.
+pub fn create_price_feed_from_price_attestation(price_attestation: &PriceAttestation) -> PriceFeed {
+    match price_attestation.status {
+        PriceStatus::Trading => PriceFeed::new(
+            PriceIdentifier::new(price_attestation.price_id.to_bytes()),
+            Price {
+                price: price_attestation.price,
+                conf: price_attestation.conf,
+                expo: price_attestation.expo,
+                publish_time: price_attestation.publish_time,
+            },
+            Price {
+                price: price_attestation.ema_price,
+                conf: price_attestation.ema_conf,
+                expo: price_attestation.expo,
+                publish_time: price_attestation.publish_time,
+            },
+        ),
+        _ => PriceFeed::new(
+            PriceIdentifier::new(price_attestation.price_id.to_bytes()),
+            Price {
+                price: price_attestation.prev_price,
+                conf: price_attestation.prev_conf,
+                expo: price_attestation.expo,
+                publish_time: price_attestation.prev_publish_time,
+            },
+            Price {
+                price: price_attestation.ema_price,
+                conf: price_attestation.ema_conf,
+                expo: price_attestation.expo,
+                publish_time: price_attestation.prev_publish_time,
+            },
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        cosmwasm_std::{
+            from_binary,
+            testing::{MockApi, MockQuerier, MockStorage},
+            ContractResult, Empty, OwnedDeps, QuerierResult, StdError, SystemError, SystemResult,
+        },
+        pythnet_sdk::test_utils::{
+            create_accumulator_message, create_dummy_price_feed_message, create_dummy_twap_message,
+            DEFAULT_DATA_SOURCE, WRONG_SOURCE,
+        },
+        std::marker::PhantomData,
+    };
+
+    const WORMHOLE_ADDR: &str = "Wormhole";
+
+    fn handle_wasm_query(wasm_query: &WasmQuery) -> QuerierResult {
+        match wasm_query {
+            WasmQuery::Smart { contract_addr, msg } if *contract_addr == WORMHOLE_ADDR => {
+                match from_binary::<WormholeQueryMsg>(msg) {
+                    Ok(WormholeQueryMsg::VerifyVAA { vaa, .. }) => {
+                        SystemResult::Ok(ContractResult::Ok(
+                            to_binary(&ParsedVAA::deserialize(&vaa).unwrap()).unwrap(),
+                        ))
+                    }
+                    _ => SystemResult::Err(SystemError::InvalidRequest {
+                        error: "Invalid message".into(),
+                        request: msg.clone(),
+                    }),
+                }
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "wasm".into(),
+            }),
+        }
+    }
+
+    fn mock_deps() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut querier = MockQuerier::default();
+        querier.update_wasm(handle_wasm_query);
+        OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier,
+            custom_query_type: PhantomData::<Empty>,
+        }
+    }
+
+    fn default_data_sources() -> HashSet<PythDataSource> {
+        HashSet::from([PythDataSource {
+            emitter: DEFAULT_DATA_SOURCE.address.0.to_vec().into(),
+            chain_id: DEFAULT_DATA_SOURCE.chain.into(),
+        }])
+    }
+
+    fn parse(
+        deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        data: Vec<u8>,
+    ) -> StdResult<Vec<PriceFeed>> {
+        parse_update(
+            &deps.as_ref().querier,
+            &Addr::unchecked(WORMHOLE_ADDR),
+            &default_data_sources(),
+            0,
+            &data.into(),
+        )
+    }
+
+    #[test]
+    fn test_parse_accumulator() {
+        let deps = mock_deps();
+        let feed1 = create_dummy_price_feed_message(100);
+        let feed2 = create_dummy_price_feed_message(200);
+        let data = create_accumulator_message(&[&feed1, &feed2], &[&feed2], false, false, None);
+
+        let feeds = parse(&deps, data).unwrap();
+        assert_eq!(feeds.len(), 1);
+        match feed2 {
+            Message::PriceFeedMessage(msg) => {
+                assert_eq!(feeds[0].id, PriceIdentifier::new(msg.feed_id));
+                assert_eq!(feeds[0].get_price_unchecked().price, msg.price);
+                assert_eq!(feeds[0].get_ema_price_unchecked().price, msg.ema_price);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_parse_accumulator_trailing_bytes() {
+        let deps = mock_deps();
+        let feed1 = create_dummy_price_feed_message(100);
+        let mut data = create_accumulator_message(&[&feed1], &[&feed1], false, false, None);
+        data.push(0);
+
+        assert_eq!(
+            parse(&deps, data).unwrap_err(),
+            StdError::from(PythContractError::InvalidAccumulatorPayload)
+        );
+    }
+
+    #[test]
+    fn test_parse_accumulator_invalid_wormhole_message() {
+        let deps = mock_deps();
+        let feed1 = create_dummy_price_feed_message(100);
+        let data = create_accumulator_message(&[&feed1], &[&feed1], true, false, None);
+
+        assert_eq!(
+            parse(&deps, data).unwrap_err(),
+            StdError::from(PythContractError::InvalidWormholeMessage)
+        );
+    }
+
+    #[test]
+    fn test_parse_accumulator_wrong_data_source() {
+        let deps = mock_deps();
+        let feed1 = create_dummy_price_feed_message(100);
+        let data =
+            create_accumulator_message(&[&feed1], &[&feed1], false, false, Some(WRONG_SOURCE));
+
+        assert_eq!(
+            parse(&deps, data).unwrap_err(),
+            StdError::from(PythContractError::InvalidUpdateEmitter)
+        );
+    }
+
+    #[test]
+    fn test_parse_accumulator_message_type() {
+        let deps = mock_deps();
+        let feed1 = create_dummy_twap_message();
+        let data = create_accumulator_message(&[&feed1], &[&feed1], false, false, None);
+
+        assert_eq!(
+            parse(&deps, data).unwrap_err(),
+            StdError::from(PythContractError::InvalidAccumulatorMessageType)
+        );
+    }
+
+    #[test]
+    fn test_create_price_feed_from_price_attestation_status_trading() {
+        let price_attestation = PriceAttestation {
+            price_id: pyth_wormhole_attester_sdk::Identifier::new([0u8; 32]),
+            price: 100,
+            conf: 100,
+            expo: 100,
+            ema_price: 100,
+            ema_conf: 100,
+            status: PriceStatus::Trading,
+            attestation_time: 100,
+            publish_time: 100,
+            prev_publish_time: 99,
+            prev_price: 99,
+            prev_conf: 99,
+            ..Default::default()
+        };
+
+        let price_feed = create_price_feed_from_price_attestation(&price_attestation);
+        let price = price_feed.get_price_unchecked();
+        let ema_price = price_feed.get_ema_price_unchecked();
+
+        // for price
+        assert_eq!(price.price, 100);
+        assert_eq!(price.conf, 100);
+        assert_eq!(price.expo, 100);
+        assert_eq!(price.publish_time, 100);
+
+        // for ema
+        assert_eq!(ema_price.price, 100);
+        assert_eq!(ema_price.conf, 100);
+        assert_eq!(ema_price.expo, 100);
+        assert_eq!(ema_price.publish_time, 100);
+    }
+
+    #[test]
+    fn test_create_price_feed_from_price_attestation_status_unknown() {
+        test_create_price_feed_from_price_attestation_not_trading(PriceStatus::Unknown)
+    }
+
+    #[test]
+    fn test_create_price_feed_from_price_attestation_status_halted() {
+        test_create_price_feed_from_price_attestation_not_trading(PriceStatus::Halted)
+    }
+
+    #[test]
+    fn test_create_price_feed_from_price_attestation_status_auction() {
+        test_create_price_feed_from_price_attestation_not_trading(PriceStatus::Auction)
+    }
+
+    fn test_create_price_feed_from_price_attestation_not_trading(status: PriceStatus) {
+        let price_attestation = PriceAttestation {
+            price_id: pyth_wormhole_attester_sdk::Identifier::new([0u8; 32]),
+            price: 100,
+            conf: 100,
+            expo: 100,
+            ema_price: 100,
+            ema_conf: 100,
+            status,
+            attestation_time: 100,
+            publish_time: 100,
+            prev_publish_time: 99,
+            prev_price: 99,
+            prev_conf: 99,
+            ..Default::default()
+        };
+
+        let price_feed = create_price_feed_from_price_attestation(&price_attestation);
+
+        let price = price_feed.get_price_unchecked();
+        let ema_price = price_feed.get_ema_price_unchecked();
+
+        // for price
+        assert_eq!(price.price, 99);
+        assert_eq!(price.conf, 99);
+        assert_eq!(price.expo, 100);
+        assert_eq!(price.publish_time, 99);
+
+        // for ema
+        assert_eq!(ema_price.price, 100);
+        assert_eq!(ema_price.conf, 100);
+        assert_eq!(ema_price.expo, 100);
+        assert_eq!(ema_price.publish_time, 99);
+    }
+}
@@ -3,7 +3,7 @@ use {
         borsh::{self, BorshDeserialize, BorshSerialize},
         env, is_promise_success, log, near_bindgen, AccountId, Gas, PanicOnDefault, Promise,
     },
-    pyth::state::{Price, PriceIdentifier},
+    pyth_near_sdk::{promise_results, Price, PriceIdentifier, Pyth},
 };
 
 /// Our contract simply processes prices, so for now the only state we
@@ -29,13 +29,11 @@ impl PythExample {
         identifiers: Vec<PriceIdentifier>,
         data: String,
     ) -> Promise {
-        pyth::ext::ext_pyth::ext(self.pyth.clone())
-            .with_static_gas(Gas(30_000_000_000_000))
-            .with_attached_deposit(env::attached_deposit())
-            .update_price_feeds(data)
+        Pyth::new(self.pyth.clone())
+            .update_price_feeds(data, env::attached_deposit())
             .then(
                 Self::ext(env::current_account_id())
-                    .with_static_gas(Gas(10_000_000_000))
+                    .with_static_gas(Gas::from_tgas(50))
                     .handle_update_callback(identifiers),
             )
     }
@@ -43,43 +41,36 @@ impl PythExample {
     /// Handle the case where prices successfully updated, we can start reads at this point.
     #[payable]
     #[private]
-    pub fn handle_update_callback(&mut self, mut identifiers: Vec<PriceIdentifier>) -> Promise {
+    pub fn handle_update_callback(&mut self, identifiers: Vec<PriceIdentifier>) -> Promise {
         if !is_promise_success() {
             panic!("Failed to Update Prices");
         }
 
-        // Fetch a few prices to use.
-        let price_1 = identifiers.pop().unwrap();
-        let price_2 = identifiers.pop().unwrap();
-        let price_1 = pyth::ext::ext_pyth::ext(self.pyth.clone()).get_price(price_1);
-        let price_2 = pyth::ext::ext_pyth::ext(self.pyth.clone()).get_price(price_2);
-
-        // Start parallel reads.
-        price_1.and(price_2).then(
-            Self::ext(env::current_account_id())
-                .with_static_gas(Gas(10_000_000_000))
-                .handle_results_callback(),
-        )
+        // Start parallel reads of prices no older than a minute.
+        let pyth = Pyth::new(self.pyth.clone());
+        identifiers
+            .into_iter()
+            .map(|identifier| pyth.get_price_no_older_than(identifier, 60))
+            .reduce(Promise::and)
+            .expect("No prices requested")
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(Gas::from_tgas(10))
+                    .handle_results_callback(),
+            )
     }
 
-    /// Handle results of reading multiple prices, the prices can be accessed using
-    /// NEAR's env::promise_* functions.
+    /// Handle results of reading multiple prices. As the number of reads is only known at
+    /// runtime, the results are read with `promise_results` rather than `#[callback_result]`.
     #[private]
-    #[handle_result]
-    pub fn handle_results_callback(
-        &self,
-        #[callback_result] price_1: Result<Price, near_sdk::PromiseError>,
-        #[callback_result] price_2: Result<Price, near_sdk::PromiseError>,
-    ) {
-        if !is_promise_success() {
-            return;
+    pub fn handle_results_callback(&self) {
+        for price in promise_results::<Option<Price>>() {
+            // Do something with the prices.
+            match price {
+                Ok(Some(price)) => log!("{:?}", price),
+                Ok(None) => log!("Price is stale or unknown"),
+                Err(_) => log!("Failed to read price"),
+            }
         }
-
-        let price_1 = price_1.unwrap();
-        let price_2 = price_2.unwrap();
-
-        // Do something with the prices.
-        log!("{:?}", price_1);
-        log!("{:?}", price_2);
     }
 }
@@ -66,6 +66,9 @@ pub enum Error {
 
     #[error("Invalid wormhole message.")]
     InvalidWormholeMessage,
+
+    #[error("Removing price feeds requires a retention period and fee recipient.")]
+    StorageCleanupDisabled,
 }
 
 /// Convert IO errors into Payload errors, the only I/O we do is parsing with `Cursor` so this is a
@@ -4,9 +4,9 @@
 use {
     crate::{
         error::Error,
-        state::{Price, PriceIdentifier, Source},
+        state::{BatchUpdateResult, Price, PriceIdentifier, Source},
     },
-    near_sdk::{ext_contract, json_types::U128},
+    near_sdk::{ext_contract, json_types::U128, AccountId},
     std::collections::HashMap,
 };
 
@@ -26,9 +26,23 @@ pub trait Pyth {
     // price feed endpoint, and should be fed in as base64.
     #[handle_result]
     fn update_price_feeds(&mut self, data: String) -> Result<(), Error>;
+    #[handle_result]
+    fn update_price_feeds_batch(
+        &mut self,
+        data: Vec<String>,
+        max_age: Option<u64>,
+    ) -> Result<Option<BatchUpdateResult>, Error>;
     fn get_update_fee_estimate(&self, vaa: String) -> U128;
     fn get_sources(&self) -> Vec<Source>;
     fn get_stale_threshold(&self) -> u64;
+    fn get_fee_recipient(&self) -> Option<AccountId>;
+    fn get_price_feed_retention(&self) -> Option<u64>;
+    fn get_storage_deposit(&self, price_identifier: PriceIdentifier) -> Option<U128>;
+    #[handle_result]
+    fn remove_expired_price_feeds(
+        &mut self,
+        price_ids: Vec<PriceIdentifier>,
+    ) -> Result<Vec<PriceIdentifier>, Error>;
 
     // See implementations for details, PriceIdentifier can be passed either as a 64 character
     // hex price ID which can be found on the Pyth homepage.
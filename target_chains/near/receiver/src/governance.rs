@@ -79,6 +79,14 @@ pub enum GovernanceAction {
     SetFee { base: u64, expo: u64 },
     SetValidPeriod { valid_seconds: u64 },
     RequestGovernanceDataSourceTransfer { governance_data_source_index: u32 },
+    SetWormholeAddress { address: AccountId },
+    // Actions 7 to 9 are only implemented by other chains and are rejected, the variants keep the
+    // discriminants of the following actions in line with the IDs used across chains.
+    SetFeeInToken,
+    SetIbcChannels,
+    SetPriceHistoryDepth,
+    SetFeeRecipient { address: AccountId },
+    SetPriceFeedRetention { retention_period: u64 },
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -168,6 +176,35 @@ impl GovernanceInstruction {
                         governance_data_source_index,
                     }
                 }
+
+                // NEAR account IDs are strings, so the payload is the UTF-8 encoded account ID
+                // rather than a fixed size address.
+                GovernanceActionId::SetWormholeAddress => {
+                    let (_input, bytes) = all_consuming(take(input.len()))(input)?;
+                    let address = std::str::from_utf8(bytes)
+                        .map_err(|_| InvalidPayload)?
+                        .parse::<AccountId>()
+                        .map_err(|_| InvalidPayload)?;
+                    GovernanceAction::SetWormholeAddress { address }
+                }
+
+                GovernanceActionId::SetFeeInToken
+                | GovernanceActionId::SetIbcChannels
+                | GovernanceActionId::SetPriceHistoryDepth => Err(InvalidGovernanceAction)?,
+
+                GovernanceActionId::SetFeeRecipient => {
+                    let (_input, bytes) = all_consuming(take(input.len()))(input)?;
+                    let address = std::str::from_utf8(bytes)
+                        .map_err(|_| InvalidPayload)?
+                        .parse::<AccountId>()
+                        .map_err(|_| InvalidPayload)?;
+                    GovernanceAction::SetFeeRecipient { address }
+                }
+
+                GovernanceActionId::SetPriceFeedRetention => {
+                    let (_input, retention_period) = all_consuming(be_u64)(input)?;
+                    GovernanceAction::SetPriceFeedRetention { retention_period }
+                }
             },
         })
     }
@@ -223,6 +260,28 @@ impl GovernanceInstruction {
                 buf.extend_from_slice(&u16::from(self.target).to_be_bytes());
                 buf.extend_from_slice(&governance_data_source_index.to_be_bytes());
             }
+
+            GovernanceAction::SetWormholeAddress { address } => {
+                buf.push(GovernanceActionId::SetWormholeAddress as u8);
+                buf.extend_from_slice(&u16::from(self.target).to_be_bytes());
+                buf.extend_from_slice(address.as_str().as_bytes());
+            }
+
+            GovernanceAction::SetFeeInToken
+            | GovernanceAction::SetIbcChannels
+            | GovernanceAction::SetPriceHistoryDepth => Err(InvalidGovernanceAction)?,
+
+            GovernanceAction::SetFeeRecipient { address } => {
+                buf.push(GovernanceActionId::SetFeeRecipient as u8);
+                buf.extend_from_slice(&u16::from(self.target).to_be_bytes());
+                buf.extend_from_slice(address.as_str().as_bytes());
+            }
+
+            GovernanceAction::SetPriceFeedRetention { retention_period } => {
+                buf.push(GovernanceActionId::SetPriceFeedRetention as u8);
+                buf.extend_from_slice(&u16::from(self.target).to_be_bytes());
+                buf.extend_from_slice(&retention_period.to_be_bytes());
+            }
         }
 
         Ok(buf)
@@ -300,9 +359,9 @@ impl Pyth {
         // Deserialize VAA, note that we already deserialized and verified the VAA in `process_vaa`
         // at this point so we only care about the `rest` component which contains bytes we can
         // deserialize into an Action.
-        let vaa = hex::decode(vaa).map_err(|_| InvalidPayload)?;
+        let bytes = hex::decode(vaa).map_err(|_| InvalidPayload)?;
         let vaa: wormhole_sdk::Vaa<&RawMessage> =
-            serde_wormhole::from_slice(&vaa).map_err(|_| InvalidPayload)?;
+            serde_wormhole::from_slice(&bytes).map_err(|_| InvalidPayload)?;
 
         // Deserialize and verify the action is destined for this chain.
         let instruction = GovernanceInstruction::deserialize(vaa.payload)?;
@@ -327,7 +386,14 @@ impl Pyth {
             SetDataSources { data_sources } => self.set_sources(data_sources),
             SetFee { base, expo } => self.set_update_fee(base, expo)?,
             SetValidPeriod { valid_seconds } => self.set_valid_period(valid_seconds),
-            RequestGovernanceDataSourceTransfer { .. } => Err(InvalidPayload)?,
+            SetFeeRecipient { address } => self.set_fee_recipient(address),
+            SetPriceFeedRetention { retention_period } => {
+                self.set_price_feed_retention(retention_period)
+            }
+            RequestGovernanceDataSourceTransfer { .. }
+            | SetFeeInToken
+            | SetIbcChannels
+            | SetPriceHistoryDepth => Err(InvalidPayload)?,
             UpgradeContract { codehash } => {
                 // Additionally restrict to only Near for upgrades. This is a safety measure to
                 // prevent accidental upgrades to the wrong contract.
@@ -369,6 +435,37 @@ impl Pyth {
                         ),
                 ));
             }
+
+            // The new Wormhole contract must be able to verify the VAA containing this action
+            // before it is accepted, which again implies an async call so the change is applied
+            // in the `set_wormhole_address` callback.
+            SetWormholeAddress { address } => {
+                ensure!(
+                    instruction.target == Chain::from(WormholeChain::Near),
+                    InvalidPayload
+                );
+
+                return Ok(PromiseOrValue::Promise(
+                    ext_wormhole::ext(address.clone())
+                        .with_static_gas(Gas::from_gas(10_000_000_000_000))
+                        .verify_vaa(hex::encode(&bytes))
+                        .then(
+                            Self::ext(env::current_account_id())
+                                .with_static_gas(Gas::from_gas(10_000_000_000_000))
+                                .with_attached_deposit(env::attached_deposit())
+                                .set_wormhole_address(
+                                    env::predecessor_account_id(),
+                                    address,
+                                    storage,
+                                ),
+                        )
+                        .then(
+                            Self::ext(env::current_account_id())
+                                .with_static_gas(Gas::from_gas(10_000_000_000_000))
+                                .refund_vaa(env::predecessor_account_id(), env::attached_deposit()),
+                        ),
+                ));
+            }
         }
 
         // Refund storage difference to `account_id` after storage execution.
@@ -457,6 +554,31 @@ impl Pyth {
         )
     }
 
+    #[private]
+    #[payable]
+    #[handle_result]
+    pub fn set_wormhole_address(
+        &mut self,
+        account_id: AccountId,
+        address: AccountId,
+        storage: u64,
+        #[callback_result] _result: Result<u32, near_sdk::PromiseError>,
+    ) -> Result<(), Error> {
+        // If the new Wormhole contract failed to verify the VAA we should bail.
+        ensure!(is_promise_success(), VaaVerificationFailed);
+
+        self.wormhole = address;
+
+        // Refund storage difference to `account_id` after storage execution.
+        Self::refund_storage_usage(
+            account_id,
+            storage,
+            env::storage_usage(),
+            env::attached_deposit(),
+            None,
+        )
+    }
+
     /// This method allows self-upgrading the contract to a new implementation.
     ///
     /// This function is open to call by anyone, but to perform an authorized upgrade a VAA
@@ -535,6 +657,15 @@ impl Pyth {
     pub fn set_upgrade_hash(&mut self, codehash: [u8; 32]) {
         self.codehash = codehash;
     }
+
+    pub fn set_fee_recipient(&mut self, address: AccountId) {
+        self.fee_recipient = Some(address);
+    }
+
+    /// A retention period of 0 disables the removal of price feeds.
+    pub fn set_price_feed_retention(&mut self, retention_period: u64) {
+        self.price_feed_retention = (retention_period != 0).then_some(retention_period);
+    }
 }
 
 #[cfg(test)]
@@ -633,6 +764,43 @@ mod tests {
         assert_eq!(contract.update_fee, NearToken::from_yoctonear(10000));
     }
 
+    #[test]
+    fn test_set_price_feed_retention() {
+        let mut context = get_context();
+        context.is_view(false);
+        testing_env!(context.build());
+
+        let mut contract = Pyth::new(
+            "pyth.near".parse::<near_sdk::AccountId>().unwrap(),
+            Source::default(),
+            Source::default(),
+            0.into(),
+            32,
+        );
+
+        contract.set_price_feed_retention(100);
+        assert_eq!(contract.price_feed_retention, Some(100));
+        contract.set_price_feed_retention(0);
+        assert_eq!(contract.price_feed_retention, None);
+    }
+
+    #[test]
+    fn test_governance_action_ids() {
+        // The IDs are shared with the other chains, so they must not move when actions are added.
+        assert_eq!(GovernanceActionId::SetWormholeAddress as u8, 6);
+        assert_eq!(GovernanceActionId::SetFeeRecipient as u8, 10);
+        assert_eq!(GovernanceActionId::SetPriceFeedRetention as u8, 11);
+
+        // Actions of other chains are rejected.
+        let mut payload = GOVERNANCE_MAGIC.to_vec();
+        payload.extend_from_slice(&[GovernanceModule::Target as u8, 7]);
+        payload.extend_from_slice(&u16::from(Chain::from(WormholeChain::Near)).to_be_bytes());
+        assert!(matches!(
+            GovernanceInstruction::deserialize(payload),
+            Err(InvalidGovernanceAction)
+        ));
+    }
+
     #[test]
     fn test_governance_serialize_matches_deserialize() {
         // We match on the GovernanceActionId so that when new variants are added the test is
@@ -740,6 +908,72 @@ mod tests {
             GovernanceActionId::RequestGovernanceDataSourceTransfer => {
                 unimplemented!()
             }
+
+            GovernanceActionId::SetWormholeAddress => {
+                let instruction = GovernanceInstruction {
+                    module: GovernanceModule::Target,
+                    target: Chain::from(WormholeChain::Near),
+                    action: GovernanceAction::SetWormholeAddress {
+                        address: "wormhole.near".parse().unwrap(),
+                    },
+                };
+
+                assert_eq!(
+                    instruction,
+                    GovernanceInstruction::deserialize(instruction.serialize().unwrap()).unwrap()
+                );
+            }
+
+            GovernanceActionId::SetFeeInToken
+            | GovernanceActionId::SetIbcChannels
+            | GovernanceActionId::SetPriceHistoryDepth => {
+                for action in [
+                    GovernanceAction::SetFeeInToken,
+                    GovernanceAction::SetIbcChannels,
+                    GovernanceAction::SetPriceHistoryDepth,
+                ] {
+                    let instruction = GovernanceInstruction {
+                        module: GovernanceModule::Target,
+                        target: Chain::from(WormholeChain::Near),
+                        action,
+                    };
+
+                    assert!(matches!(
+                        instruction.serialize(),
+                        Err(InvalidGovernanceAction)
+                    ));
+                }
+            }
+
+            GovernanceActionId::SetFeeRecipient => {
+                let instruction = GovernanceInstruction {
+                    module: GovernanceModule::Target,
+                    target: Chain::from(WormholeChain::Near),
+                    action: GovernanceAction::SetFeeRecipient {
+                        address: "treasury.near".parse().unwrap(),
+                    },
+                };
+
+                assert_eq!(
+                    instruction,
+                    GovernanceInstruction::deserialize(instruction.serialize().unwrap()).unwrap()
+                );
+            }
+
+            GovernanceActionId::SetPriceFeedRetention => {
+                let instruction = GovernanceInstruction {
+                    module: GovernanceModule::Target,
+                    target: Chain::from(WormholeChain::Near),
+                    action: GovernanceAction::SetPriceFeedRetention {
+                        retention_period: 86400,
+                    },
+                };
+
+                assert_eq!(
+                    instruction,
+                    GovernanceInstruction::deserialize(instruction.serialize().unwrap()).unwrap()
+                );
+            }
         }
     }
 }
@@ -5,11 +5,11 @@ use {
     ext::ext_wormhole,
     near_sdk::{
         borsh::{BorshDeserialize, BorshSerialize},
-        collections::{UnorderedMap, UnorderedSet},
+        collections::{LookupMap, UnorderedMap, UnorderedSet},
         env, is_promise_success,
         json_types::U128,
         log, near_bindgen, AccountId, BorshStorageKey, Duration, Gas, NearToken, PanicOnDefault,
-        Promise, StorageUsage,
+        Promise, PromiseError, PromiseResult, StorageUsage,
     },
     pyth_wormhole_attester_sdk::{BatchPriceAttestation, P2W_MAGIC},
     pythnet_sdk::{
@@ -25,7 +25,10 @@ use {
         },
     },
     serde_wormhole::RawMessage,
-    state::{Price, PriceFeed, PriceIdentifier, Source, Vaa},
+    state::{
+        BatchUpdateResult, Price, PriceFeed, PriceIdentifier, SkipReason, SkippedPriceFeed, Source,
+        Vaa,
+    },
     std::{
         collections::HashMap,
         io::{Cursor, Read},
@@ -44,6 +47,7 @@ pub mod tests;
 enum StorageKeys {
     Source,
     Prices,
+    StorageDeposits,
 }
 
 /// Alias to document time unit Pyth expects data to be in.
@@ -89,6 +93,17 @@ pub struct Pyth {
 
     /// Fee for updating price.
     update_fee: NearToken,
+
+    /// The storage stake locked by each price feed, charged to the caller that first stored it.
+    storage_deposits: LookupMap<PriceIdentifier, NearToken>,
+
+    /// The account receiving NEAR released by the contract, such as the storage stake of removed
+    /// price feeds.
+    fee_recipient: Option<AccountId>,
+
+    /// Price feeds not updated for longer than this can be removed to reclaim their storage
+    /// stake. Removal is disabled when unset.
+    price_feed_retention: Option<Seconds>,
 }
 
 #[cfg(not(feature = "library"))]
@@ -116,46 +131,51 @@ impl Pyth {
             wormhole,
             codehash: Default::default(),
             update_fee: NearToken::from_yoctonear(update_fee.into()),
+            storage_deposits: LookupMap::new(StorageKeys::StorageDeposits),
+            fee_recipient: None,
+            price_feed_retention: None,
         }
     }
 
     #[private]
     #[init(ignore_state)]
     pub fn migrate() -> Self {
-        // This currently deserializes and produces the same state, I.E migration is a no-op to the
-        // current state.
-        //
-        // In the case where we want to actually migrate to a new state, we can do this by defining
-        // the old State struct here and then deserializing into that, then migrating into the new
-        // state, example code for the future reader:
+        // The previous state did not track storage deposits, so deserialize into the old layout
+        // and start with no tracked deposits and the removal of price feeds disabled. Price feeds
+        // stored before the migration have no tracked deposit but can still be removed.
         //
-        // ```rust
-        // pub fn migrate() -> Self {
-        //     pub struct OldPyth {
-        //         sources:                        UnorderedSet<Source>,
-        //         gov_source:                     Source,
-        //         executed_governance_vaa:        u64,
-        //         executed_governance_change_vaa: u64,
-        //         prices:                         UnorderedMap<PriceIdentifier, PriceFeed>,
-        //         wormhole:                       AccountId,
-        //         codehash:                       [u8; 32],
-        //         stale_threshold:                Duration,
-        //         update_fee:                     u128,
-        //     }
-        //
-        //     // Construct new Pyth State from old, perform any migrations needed.
-        //     let old: OldPyth = env::state_read().expect("Failed to read state");
-        //
-        //     Self {
-        //        sources:    old.sources,
-        //        gov_source: old.gov_source,
-        //        ...
-        //     }
-        // }
-        // ```
-        let mut state: Self = env::state_read().expect("Failed to read state");
-        state.codehash = Default::default();
-        state
+        // When the state changes again, replace `OldPyth` with the layout being migrated from and
+        // update the revision the workspaces tests upgrade from in `workspace-test.sh`.
+        #[derive(BorshDeserialize)]
+        #[borsh(crate = "near_sdk::borsh")]
+        pub struct OldPyth {
+            sources: UnorderedSet<Source>,
+            gov_source: Source,
+            executed_governance_vaa: u64,
+            executed_governance_change_vaa: u64,
+            prices: UnorderedMap<PriceIdentifier, PriceFeed>,
+            wormhole: AccountId,
+            // Reset below so that the upgrade hash cannot be reused.
+            _codehash: [u8; 32],
+            stale_threshold: Duration,
+            update_fee: NearToken,
+        }
+
+        let old: OldPyth = env::state_read().expect("Failed to read state");
+        Self {
+            sources: old.sources,
+            gov_source: old.gov_source,
+            executed_governance_vaa: old.executed_governance_vaa,
+            executed_governance_change_vaa: old.executed_governance_change_vaa,
+            prices: old.prices,
+            wormhole: old.wormhole,
+            codehash: Default::default(),
+            stale_threshold: old.stale_threshold,
+            update_fee: old.update_fee,
+            storage_deposits: LookupMap::new(StorageKeys::StorageDeposits),
+            fee_recipient: None,
+            price_feed_retention: None,
+        }
     }
 
     /// Instruction for processing VAA's relayed via Wormhole.
@@ -174,8 +194,8 @@ impl Pyth {
 
         // Handle Accumulator style Price Updates.
         if &header == PYTHNET_ACCUMULATOR_UPDATE_MAGIC {
-            let update_data =
-                AccumulatorUpdateData::try_from_slice(&cursor.clone().into_inner()).unwrap();
+            let update_data = AccumulatorUpdateData::try_from_slice_exact(bytes)
+                .map_err(|_| Error::InvalidAccumulatorMessage)?;
 
             match update_data.proof {
                 Proof::WormholeMerkle { vaa, .. } => {
@@ -225,6 +245,74 @@ impl Pyth {
         Ok(())
     }
 
+    /// Instruction for processing several accumulator updates in a single call.
+    ///
+    /// Each VAA referenced by the updates is verified once, even when it is shared by several
+    /// updates as is the case for a single Hermes response, and the fee and storage are settled in
+    /// a single callback. Each price feed is stored only if it is newer than the stored price and
+    /// no further than `max_age` seconds from the block time, which defaults to the contract's
+    /// staleness threshold. The returned `BatchUpdateResult` lists the applied and skipped feeds.
+    #[payable]
+    #[handle_result]
+    pub fn update_price_feeds_batch(
+        &mut self,
+        data: Vec<String>,
+        max_age: Option<Seconds>,
+    ) -> Result<Promise, Error> {
+        ensure!(!data.is_empty(), Error::InvalidPayload);
+
+        // Verify every update is an accumulator update from a trusted source before calling out
+        // to Wormhole, collecting the distinct VAAs that need verification.
+        let mut vaas: Vec<String> = Vec::new();
+        for update in &data {
+            let bytes = hex::decode(update).map_err(|_| Error::InvalidHex)?;
+            ensure!(
+                bytes.starts_with(PYTHNET_ACCUMULATOR_UPDATE_MAGIC),
+                Error::InvalidAccumulatorMessage
+            );
+            let update_data = AccumulatorUpdateData::try_from_slice_exact(&bytes)
+                .map_err(|_| Error::InvalidAccumulatorMessage)?;
+
+            match update_data.proof {
+                Proof::WormholeMerkle { vaa, .. } => {
+                    self.verify_encoded_vaa_source(vaa.as_ref())?;
+                    let vaa_hex = hex::encode(vaa.as_ref());
+                    if !vaas.contains(&vaa_hex) {
+                        vaas.push(vaa_hex);
+                    }
+                }
+            }
+        }
+
+        // Verify all VAAs in parallel, the callback only runs once all of them have resolved.
+        let verify = vaas
+            .into_iter()
+            .map(|vaa| {
+                ext_wormhole::ext(self.wormhole.clone())
+                    .with_static_gas(Gas::from_gas(30_000_000_000_000))
+                    .verify_vaa(vaa)
+            })
+            .reduce(Promise::and)
+            .ok_or(Error::InvalidPayload)?;
+
+        Ok(verify
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(Gas::from_gas(30_000_000_000_000))
+                    .with_attached_deposit(env::attached_deposit())
+                    .verify_wormhole_merkle_batch_callback(
+                        env::predecessor_account_id(),
+                        data,
+                        max_age,
+                    ),
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(Gas::from_gas(10_000_000_000_000))
+                    .refund_batch_update(env::predecessor_account_id(), env::attached_deposit()),
+            ))
+    }
+
     #[payable]
     #[private]
     #[handle_result]
@@ -327,39 +415,9 @@ impl Pyth {
             .and_then(|s| u64::try_from(s).map_err(|_| Error::ArithmeticOverflow))?;
 
         let mut count_updates = 0;
-        let bytes = &*hex::decode(data.clone()).map_err(|_| Error::InvalidHex)?;
-        let cursor = &mut Cursor::new(bytes);
-        let update_data =
-            AccumulatorUpdateData::try_from_slice(&cursor.clone().into_inner()).unwrap();
-
-        match update_data.proof {
-            Proof::WormholeMerkle { vaa, updates } => {
-                let vaa: wormhole_sdk::Vaa<&RawMessage> =
-                    serde_wormhole::from_slice(vaa.as_ref()).unwrap();
-                let message = WormholeMessage::try_from_bytes(vaa.payload)
-                    .map_err(|_| Error::InvalidWormholeMessage)?;
-                let root: MerkleRoot<Keccak160> = MerkleRoot::new(match message.payload {
-                    WormholePayload::Merkle(merkle_root) => merkle_root.root,
-                });
-
-                for update in updates {
-                    let message_vec = Vec::from(update.message);
-                    if !root.check(update.proof, &message_vec) {
-                        return Err(Error::InvalidMerkleProof)?;
-                    }
-
-                    let msg = from_slice::<byteorder::BE, Message>(&message_vec)
-                        .map_err(|_| Error::InvalidAccumulatorMessage)?;
-
-                    match msg {
-                        Message::PriceFeedMessage(price_feed_message) => {
-                            if self.update_price_feed_if_new(PriceFeed::from(&price_feed_message)) {
-                                count_updates += 1;
-                            }
-                        }
-                        _ => return Err(Error::InvalidAccumulatorMessageType)?,
-                    }
-                }
+        for price_feed in Self::parse_accumulator_update(&data)? {
+            if self.update_price_feed_if_new(price_feed) {
+                count_updates += 1;
             }
         }
 
@@ -393,6 +451,170 @@ impl Pyth {
         )
     }
 
+    #[payable]
+    #[private]
+    #[handle_result]
+    pub fn verify_wormhole_merkle_batch_callback(
+        &mut self,
+        account_id: AccountId,
+        data: Vec<String>,
+        max_age: Option<Seconds>,
+    ) -> Result<BatchUpdateResult, Error> {
+        // Every VAA verification joined into this callback has to have succeeded.
+        ensure!(
+            (0..env::promise_results_count())
+                .all(|i| matches!(env::promise_result(i), PromiseResult::Successful(_))),
+            Error::VaaVerificationFailed
+        );
+
+        let storage = env::storage_usage();
+        let max_age = max_age.unwrap_or(self.stale_threshold);
+        let block_timestamp = env::block_timestamp() / 1_000_000_000;
+
+        let mut result = BatchUpdateResult::default();
+        let mut count_messages = 0u128;
+        for update in &data {
+            for price_feed in Self::parse_accumulator_update(update)? {
+                count_messages += 1;
+                let id = PriceIdentifier(price_feed.id.0);
+                let publish_time = u64::try_from(price_feed.price.publish_time).unwrap_or_default();
+
+                // The same check as `get_price_no_older_than`, so that stored prices are usable
+                // with the same age.
+                let reason = if u64::abs_diff(block_timestamp, publish_time) > max_age {
+                    Some(SkipReason::Stale)
+                } else if !self.update_price_feed_if_new(price_feed) {
+                    Some(SkipReason::NotNewer)
+                } else {
+                    None
+                };
+
+                match reason {
+                    Some(reason) => result.skipped.push(SkippedPriceFeed { id, reason }),
+                    None => result.applied.push(id),
+                }
+            }
+        }
+
+        log!(
+            r#"
+            {{
+                "standard": "pyth",
+                "version":  "1.0",
+                "event":    "AccumulatorBatchUpdates",
+                "data":     {{
+                    "count":   {},
+                    "skipped": {},
+                    "diffs":   {},
+                }}
+            }}
+            "#,
+            result.applied.len(),
+            result.skipped.len(),
+            env::storage_usage().saturating_sub(storage),
+        );
+
+        // The fee is charged for every message submitted, matching `get_update_fee_estimate`.
+        let fee = self
+            .update_fee
+            .checked_mul(count_messages)
+            .ok_or(Error::ArithmeticOverflow)?;
+
+        // Refund storage difference to `account_id` after storage execution.
+        Self::refund_storage_usage(
+            account_id,
+            storage,
+            env::storage_usage(),
+            env::attached_deposit(),
+            Some(fee),
+        )?;
+
+        Ok(result)
+    }
+
+    /// Refunds the caller if a batched update failed, and otherwise passes its result through so
+    /// that it becomes the result of `update_price_feeds_batch`.
+    #[private]
+    pub fn refund_batch_update(
+        &mut self,
+        account_id: AccountId,
+        amount: NearToken,
+        #[callback_result] result: Result<BatchUpdateResult, PromiseError>,
+    ) -> Option<BatchUpdateResult> {
+        match result {
+            Ok(result) => Some(result),
+            Err(_) => {
+                // No calculations needed as deposit size will have not changed. Can just refund
+                // the whole deposit amount.
+                Promise::new(account_id).transfer(amount);
+                None
+            }
+        }
+    }
+
+    /// Remove the given price feeds if they have not been updated for longer than the retention
+    /// period, sending the storage stake they release to the fee recipient. Anyone can call this,
+    /// price feeds that are missing or still within the retention period are left unchanged.
+    /// Returns the removed price feeds.
+    #[handle_result]
+    pub fn remove_expired_price_feeds(
+        &mut self,
+        price_ids: Vec<PriceIdentifier>,
+    ) -> Result<Vec<PriceIdentifier>, Error> {
+        let retention_period = self
+            .price_feed_retention
+            .ok_or(Error::StorageCleanupDisabled)?;
+        let recipient = self
+            .fee_recipient
+            .clone()
+            .ok_or(Error::StorageCleanupDisabled)?;
+
+        let storage = env::storage_usage();
+        let block_timestamp = env::block_timestamp() / 1_000_000_000;
+
+        let mut removed = Vec::new();
+        for price_id in price_ids {
+            let Some(feed) = self.prices.get(&price_id) else {
+                continue;
+            };
+
+            let publish_time = u64::try_from(feed.price.publish_time).unwrap_or_default();
+            if block_timestamp.saturating_sub(publish_time) > retention_period {
+                self.prices.remove(&price_id);
+                self.storage_deposits.remove(&price_id);
+                removed.push(price_id);
+            }
+        }
+
+        // The storage stake released is measured rather than taken from `storage_deposits`, which
+        // includes price feeds stored before deposits were tracked.
+        let reclaimed = env::storage_byte_cost()
+            .checked_mul(storage.saturating_sub(env::storage_usage()).into())
+            .ok_or(Error::ArithmeticOverflow)?;
+
+        log!(
+            r#"
+            {{
+                "standard": "pyth",
+                "version":  "1.0",
+                "event":    "RemoveExpiredPriceFeeds",
+                "data":     {{
+                    "count":     {},
+                    "reclaimed": {},
+                }}
+            }}
+            "#,
+            removed.len(),
+            reclaimed.as_yoctonear(),
+        );
+
+        if !reclaimed.is_zero() {
+            Promise::new(recipient).transfer(reclaimed);
+        }
+
+        Ok(removed)
+    }
+
     /// Return the deposit required to update a price feed. This is the upper limit for an update
     /// call and any remaining deposit not consumed for storage will be refunded.
     #[allow(unused_variables)]
@@ -445,6 +667,24 @@ impl Pyth {
         self.stale_threshold
     }
 
+    /// Get the account receiving NEAR released by the contract.
+    pub fn get_fee_recipient(&self) -> Option<AccountId> {
+        self.fee_recipient.clone()
+    }
+
+    /// Get the period after which price feeds that have not been updated can be removed.
+    pub fn get_price_feed_retention(&self) -> Option<u64> {
+        self.price_feed_retention
+    }
+
+    /// Get the storage stake locked by the price feed for the given price_identifier, if it was
+    /// stored after storage deposits started being tracked.
+    pub fn get_storage_deposit(&self, price_identifier: PriceIdentifier) -> Option<U128> {
+        self.storage_deposits
+            .get(&price_identifier)
+            .map(|deposit| deposit.as_yoctonear().into())
+    }
+
     /// Determine if a price feed for the given price_identifier exists
     pub fn price_feed_exists(&self, price_identifier: PriceIdentifier) -> bool {
         self.prices.get(&price_identifier).is_some()
@@ -618,14 +858,15 @@ impl Pyth {
     }
 }
 
-/// This second `impl Pyth` block contains only private methods that are called internally that
-/// have no transaction semantics associated with them. Note that these do not need `#[private]`
-/// annotations as they are already uncallable.
+/// This second `impl Pyth` block contains only methods that are called internally that have no
+/// transaction semantics associated with them. Note that these do not need `#[private]`
+/// annotations as they are not exported by the contract, even when public.
 #[cfg(not(feature = "library"))]
 impl Pyth {
     /// Verify a VAA source from a serialized VAA.
     fn verify_encoded_vaa_source(&self, vaa: &[u8]) -> Result<(), Error> {
-        let vaa: wormhole_sdk::Vaa<&RawMessage> = serde_wormhole::from_slice(vaa).unwrap();
+        let vaa: wormhole_sdk::Vaa<&RawMessage> =
+            serde_wormhole::from_slice(vaa).map_err(|_| Error::InvalidVaa)?;
 
         // Convert to local VAA type to catch API changes.
         let vaa: Vaa<&RawMessage> = Vaa::from(vaa);
@@ -640,6 +881,49 @@ impl Pyth {
         Ok(())
     }
 
+    /// Parse a hex encoded accumulator update into its price feeds, checking each message against
+    /// the merkle root of the update's VAA. The VAA itself must have been verified by Wormhole.
+    /// Updates with data after the last price message are rejected.
+    ///
+    /// Public so that the conformance tests can run it natively.
+    pub fn parse_accumulator_update(data: &str) -> Result<Vec<PriceFeed>, Error> {
+        let bytes = hex::decode(data).map_err(|_| Error::InvalidHex)?;
+        let update_data = AccumulatorUpdateData::try_from_slice_exact(&bytes)
+            .map_err(|_| Error::InvalidAccumulatorMessage)?;
+
+        match update_data.proof {
+            Proof::WormholeMerkle { vaa, updates } => {
+                let vaa: wormhole_sdk::Vaa<&RawMessage> =
+                    serde_wormhole::from_slice(vaa.as_ref()).map_err(|_| Error::InvalidVaa)?;
+                let message = WormholeMessage::try_from_bytes(vaa.payload)
+                    .map_err(|_| Error::InvalidWormholeMessage)?;
+                let root: MerkleRoot<Keccak160> = MerkleRoot::new(match message.payload {
+                    WormholePayload::Merkle(merkle_root) => merkle_root.root,
+                });
+
+                updates
+                    .into_iter()
+                    .map(|update| {
+                        let message_vec = Vec::from(update.message);
+                        ensure!(
+                            root.check(update.proof, &message_vec),
+                            Error::InvalidMerkleProof
+                        );
+
+                        match from_slice::<byteorder::BE, Message>(&message_vec)
+                            .map_err(|_| Error::InvalidAccumulatorMessage)?
+                        {
+                            Message::PriceFeedMessage(price_feed_message) => {
+                                Ok(PriceFeed::from(&price_feed_message))
+                            }
+                            _ => Err(Error::InvalidAccumulatorMessageType),
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
+
     /// Updates the Price Feed only if it is newer than the current one. This function never fails
     /// and will either update in-place or not update at all. The return value indicates whether
     /// the update was performed or not.
@@ -652,7 +936,15 @@ impl Pyth {
             }
 
             None => {
+                // Record the storage stake of the new price feed, including the entry recording
+                // it, which is inserted first so that its size is part of the measurement.
+                let storage = env::storage_usage();
+                self.storage_deposits
+                    .insert(&price_feed.id, &NearToken::from_yoctonear(0));
                 self.prices.insert(&price_feed.id, &price_feed);
+                let deposit = env::storage_byte_cost()
+                    .saturating_mul(env::storage_usage().saturating_sub(storage).into());
+                self.storage_deposits.insert(&price_feed.id, &deposit);
                 true
             }
         }
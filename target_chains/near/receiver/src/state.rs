@@ -145,6 +145,35 @@ impl From<&PriceFeedMessage> for PriceFeed {
     }
 }
 
+/// The reason a price feed contained in a batched update was not stored.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub enum SkipReason {
+    /// The stored price is at least as recent as the update.
+    NotNewer,
+    /// The update is further from the current block time than the allowed age.
+    Stale,
+}
+
+/// A price feed contained in a batched update that was not stored.
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SkippedPriceFeed {
+    pub id: PriceIdentifier,
+    pub reason: SkipReason,
+}
+
+/// The outcome of `update_price_feeds_batch`, listing every price feed in the submitted updates
+/// in the order they were processed.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BatchUpdateResult {
+    /// Price feeds that were stored.
+    pub applied: Vec<PriceIdentifier>,
+    /// Price feeds that were left unchanged.
+    pub skipped: Vec<SkippedPriceFeed>,
+}
+
 /// A wrapper around a 16bit chain identifier. We can't use Chain from the Wormhole SDK as it does
 /// not provide borsh serialization but we can re-wrap it here relying on the validation from
 /// `wormhole::Chain`.
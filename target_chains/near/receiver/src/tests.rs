@@ -2,8 +2,9 @@
 #[allow(clippy::module_inception)]
 mod tests {
     use {
-        crate::{state::Source, Pyth},
+        crate::{error::Error, state::Source, Pyth},
         near_sdk::{test_utils::VMContextBuilder, testing_env, VMContext},
+        pythnet_sdk::test_utils::{create_accumulator_message, create_dummy_price_feed_message},
     };
 
     fn create_contract() -> Pyth {
@@ -31,4 +32,22 @@ mod tests {
         assert_eq!(contract.sources.len(), 1);
         assert_eq!(contract.prices.len(), 0);
     }
+
+    #[test]
+    fn test_parse_accumulator_update_trailing_bytes() {
+        let feed = create_dummy_price_feed_message(100);
+        let mut data = create_accumulator_message(&[&feed], &[&feed], false, false, None);
+        assert_eq!(
+            Pyth::parse_accumulator_update(&hex::encode(&data))
+                .unwrap()
+                .len(),
+            1
+        );
+
+        data.push(0);
+        assert!(matches!(
+            Pyth::parse_accumulator_update(&hex::encode(&data)),
+            Err(Error::InvalidAccumulatorMessage)
+        ));
+    }
 }
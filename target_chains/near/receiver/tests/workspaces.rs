@@ -9,9 +9,9 @@ use {
         BatchPriceAttestation, Identifier, PriceAttestation, PriceStatus,
     },
     pythnet_sdk::test_utils::{
-        create_accumulator_message, create_dummy_price_feed_message, create_vaa_from_payload,
-        DEFAULT_DATA_SOURCE, DEFAULT_GOVERNANCE_SOURCE, DEFAULT_VALID_TIME_PERIOD,
-        SECONDARY_DATA_SOURCE, SECONDARY_GOVERNANCE_SOURCE,
+        create_accumulator_message, create_dummy_feed_id, create_dummy_price_feed_message,
+        create_vaa_from_payload, DEFAULT_DATA_SOURCE, DEFAULT_GOVERNANCE_SOURCE,
+        DEFAULT_VALID_TIME_PERIOD, SECONDARY_DATA_SOURCE, SECONDARY_GOVERNANCE_SOURCE,
     },
     serde_json::json,
     std::collections::HashMap,
@@ -22,14 +22,24 @@ async fn initialize_chain() -> (
     near_workspaces::Worker<near_workspaces::network::Sandbox>,
     near_workspaces::Contract,
     near_workspaces::Contract,
+) {
+    initialize_chain_with_code("pyth.wasm").await
+}
+
+async fn initialize_chain_with_code(
+    path: &str,
+) -> (
+    near_workspaces::Worker<near_workspaces::network::Sandbox>,
+    near_workspaces::Contract,
+    near_workspaces::Contract,
 ) {
     let worker = near_workspaces::sandbox().await.expect("Workspaces Failed");
 
     // Deploy Pyth
     let contract = worker
-        .dev_deploy(&std::fs::read("pyth.wasm").expect("Failed to find pyth.wasm"))
+        .dev_deploy(&std::fs::read(path).unwrap_or_else(|_| panic!("Failed to find {path}")))
         .await
-        .expect("Failed to deploy pyth.wasm");
+        .unwrap_or_else(|_| panic!("Failed to deploy {path}"));
 
     // Deploy Wormhole Stub, this is a dummy contract that always verifies VAA's correctly so we
     // can test the ext_wormhole API.
@@ -836,6 +846,112 @@ async fn test_governance_target_fails_if_not_near() {
         .is_empty());
 }
 
+// A test that moves the contract to a new Wormhole contract, which has to verify the VAA.
+#[tokio::test]
+async fn test_set_wormhole_address() {
+    let (worker, contract, _) = initialize_chain().await;
+
+    // Deploy a second Wormhole Stub to move to.
+    let new_wormhole = worker
+        .dev_deploy(
+            &std::fs::read("wormhole_stub.wasm").expect("Failed to find wormhole_stub.wasm"),
+        )
+        .await
+        .expect("Failed to deploy wormhole_stub.wasm");
+
+    let _ = new_wormhole
+        .call("new")
+        .args_json(json!({}))
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .transact_async()
+        .await
+        .expect("Failed to initialize Wormhole")
+        .await
+        .unwrap();
+
+    let vaa = create_vaa_from_payload(
+        &GovernanceInstruction {
+            target: Chain::from(WormholeChain::Near),
+            module: GovernanceModule::Target,
+            action: GovernanceAction::SetWormholeAddress {
+                address: new_wormhole.id().to_string().parse().unwrap(),
+            },
+        }
+        .serialize()
+        .unwrap(),
+        DEFAULT_GOVERNANCE_SOURCE.address,
+        DEFAULT_GOVERNANCE_SOURCE.chain,
+        1,
+    );
+    let vaa = hex::encode(serde_wormhole::to_vec(&vaa).unwrap());
+
+    assert!(contract
+        .call("execute_governance_instruction")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .deposit(NearToken::from_yoctonear(300_000_000_000_000_000_000_000))
+        .args_json(json!({
+            "vaa": vaa,
+        }))
+        .transact_async()
+        .await
+        .expect("Failed to submit VAA")
+        .await
+        .unwrap()
+        .failures()
+        .is_empty());
+
+    // Price updates are now verified by the new Wormhole contract.
+    let feed = create_dummy_price_feed_message(100);
+    let message = create_accumulator_message(&[&feed], &[&feed], false, false, None);
+
+    assert!(contract
+        .call("update_price_feeds")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .deposit(NearToken::from_yoctonear(300_000_000_000_000_000_000_000))
+        .args_json(json!({
+            "data": hex::encode(message),
+        }))
+        .transact_async()
+        .await
+        .expect("Failed to submit VAA")
+        .await
+        .unwrap()
+        .failures()
+        .is_empty());
+
+    // Moving to an account that cannot verify VAAs, such as the Pyth contract itself, fails.
+    let vaa = create_vaa_from_payload(
+        &GovernanceInstruction {
+            target: Chain::from(WormholeChain::Near),
+            module: GovernanceModule::Target,
+            action: GovernanceAction::SetWormholeAddress {
+                address: contract.id().to_string().parse().unwrap(),
+            },
+        }
+        .serialize()
+        .unwrap(),
+        DEFAULT_GOVERNANCE_SOURCE.address,
+        DEFAULT_GOVERNANCE_SOURCE.chain,
+        2,
+    );
+    let vaa = hex::encode(serde_wormhole::to_vec(&vaa).unwrap());
+
+    assert!(!contract
+        .call("execute_governance_instruction")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .deposit(NearToken::from_yoctonear(300_000_000_000_000_000_000_000))
+        .args_json(json!({
+            "vaa": vaa,
+        }))
+        .transact_async()
+        .await
+        .expect("Failed to submit VAA")
+        .await
+        .unwrap()
+        .failures()
+        .is_empty());
+}
+
 // A test to check accumulator style updates work as intended.
 #[tokio::test]
 async fn test_accumulator_updates() {
@@ -921,6 +1037,531 @@ async fn test_accumulator_updates() {
     );
 }
 
+// A test to check batched accumulator updates report applied and skipped feeds.
+#[tokio::test]
+async fn test_accumulator_batch_updates() {
+    let (_, contract, _) = initialize_chain().await;
+
+    // Two updates proven against the same VAA, which only needs to be verified once.
+    let feed_1 = create_dummy_price_feed_message(100);
+    let feed_2 = create_dummy_price_feed_message(200);
+    let data = vec![
+        hex::encode(create_accumulator_message(
+            &[&feed_1, &feed_2],
+            &[&feed_1],
+            false,
+            false,
+            None,
+        )),
+        hex::encode(create_accumulator_message(
+            &[&feed_1, &feed_2],
+            &[&feed_2],
+            false,
+            false,
+            None,
+        )),
+    ];
+
+    let identifier_1 = create_dummy_feed_id(100);
+    let identifier_2 = create_dummy_feed_id(200);
+
+    // The dummy feeds are published at timestamp 100 and 200, so allow any age to store them.
+    let result = contract
+        .call("update_price_feeds_batch")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .deposit(NearToken::from_yoctonear(300_000_000_000_000_000_000_000))
+        .args_json(json!({
+            "data":    data,
+            "max_age": u64::MAX,
+        }))
+        .transact()
+        .await
+        .expect("Failed to submit updates");
+    assert!(result.failures().is_empty());
+    assert_eq!(
+        result.json::<serde_json::Value>().unwrap(),
+        json!({
+            "applied": [PriceIdentifier(identifier_1), PriceIdentifier(identifier_2)],
+            "skipped": [],
+        })
+    );
+
+    assert_eq!(
+        Some(Price {
+            price: 200.into(),
+            conf: 200.into(),
+            expo: 200,
+            publish_time: 200,
+        }),
+        serde_json::from_slice::<Option<Price>>(
+            &contract
+                .view("get_price_unsafe")
+                .args_json(json!({ "price_identifier": PriceIdentifier(identifier_2) }))
+                .await
+                .unwrap()
+                .result
+        )
+        .unwrap(),
+    );
+
+    // Submitting the same updates again leaves the stored prices unchanged.
+    let result = contract
+        .call("update_price_feeds_batch")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .deposit(NearToken::from_yoctonear(300_000_000_000_000_000_000_000))
+        .args_json(json!({
+            "data":    data,
+            "max_age": u64::MAX,
+        }))
+        .transact()
+        .await
+        .expect("Failed to submit updates");
+    assert!(result.failures().is_empty());
+    assert_eq!(
+        result.json::<serde_json::Value>().unwrap(),
+        json!({
+            "applied": [],
+            "skipped": [
+                { "id": PriceIdentifier(identifier_1), "reason": "NotNewer" },
+                { "id": PriceIdentifier(identifier_2), "reason": "NotNewer" },
+            ],
+        })
+    );
+
+    // Without `max_age` the staleness threshold applies, which a price from timestamp 300 exceeds.
+    let feed_3 = create_dummy_price_feed_message(300);
+    let identifier_3 = create_dummy_feed_id(300);
+
+    let result = contract
+        .call("update_price_feeds_batch")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .deposit(NearToken::from_yoctonear(300_000_000_000_000_000_000_000))
+        .args_json(json!({
+            "data": vec![hex::encode(create_accumulator_message(
+                &[&feed_3],
+                &[&feed_3],
+                false,
+                false,
+                None,
+            ))],
+        }))
+        .transact()
+        .await
+        .expect("Failed to submit updates");
+    assert!(result.failures().is_empty());
+    assert_eq!(
+        result.json::<serde_json::Value>().unwrap(),
+        json!({
+            "applied": [],
+            "skipped": [{ "id": PriceIdentifier(identifier_3), "reason": "Stale" }],
+        })
+    );
+    assert!(!serde_json::from_slice::<bool>(
+        &contract
+            .view("price_feed_exists")
+            .args_json(json!({ "price_identifier": PriceIdentifier(identifier_3) }))
+            .await
+            .unwrap()
+            .result
+    )
+    .unwrap());
+
+    // An empty batch is rejected.
+    assert!(!contract
+        .call("update_price_feeds_batch")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .deposit(NearToken::from_yoctonear(300_000_000_000_000_000_000_000))
+        .args_json(json!({
+            "data": Vec::<String>::new(),
+        }))
+        .transact()
+        .await
+        .expect("Failed to submit updates")
+        .failures()
+        .is_empty());
+}
+
+// A test that removes a price feed past the retention period, reclaiming its storage stake.
+#[tokio::test]
+async fn test_remove_expired_price_feeds() {
+    let (worker, contract, _) = initialize_chain().await;
+    let recipient = worker.dev_create_account().await.unwrap();
+
+    // Store a price feed, published at timestamp 100 and so long expired.
+    let feed = create_dummy_price_feed_message(100);
+    let identifier = create_dummy_feed_id(100);
+    let message = create_accumulator_message(&[&feed], &[&feed], false, false, None);
+
+    assert!(contract
+        .call("update_price_feeds")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .deposit(NearToken::from_yoctonear(300_000_000_000_000_000_000_000))
+        .args_json(json!({
+            "data": hex::encode(message),
+        }))
+        .transact_async()
+        .await
+        .expect("Failed to submit VAA")
+        .await
+        .unwrap()
+        .failures()
+        .is_empty());
+
+    // The storage stake of the new price feed is tracked.
+    assert!(serde_json::from_slice::<Option<U128>>(
+        &contract
+            .view("get_storage_deposit")
+            .args_json(json!({ "price_identifier": PriceIdentifier(identifier) }))
+            .await
+            .unwrap()
+            .result
+    )
+    .unwrap()
+    .is_some_and(|deposit| deposit.0 > 0));
+
+    // Removal is disabled until governance sets a retention period and fee recipient.
+    assert!(!contract
+        .call("remove_expired_price_feeds")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .args_json(json!({ "price_ids": [PriceIdentifier(identifier)] }))
+        .transact()
+        .await
+        .unwrap()
+        .failures()
+        .is_empty());
+
+    for (sequence, action) in [
+        GovernanceAction::SetFeeRecipient {
+            address: recipient.id().to_string().parse().unwrap(),
+        },
+        GovernanceAction::SetPriceFeedRetention {
+            retention_period: 3600,
+        },
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let vaa = create_vaa_from_payload(
+            &GovernanceInstruction {
+                target: Chain::from(WormholeChain::Near),
+                module: GovernanceModule::Target,
+                action,
+            }
+            .serialize()
+            .unwrap(),
+            DEFAULT_GOVERNANCE_SOURCE.address,
+            DEFAULT_GOVERNANCE_SOURCE.chain,
+            sequence as u64 + 1,
+        );
+        let vaa = hex::encode(serde_wormhole::to_vec(&vaa).unwrap());
+
+        assert!(contract
+            .call("execute_governance_instruction")
+            .gas(Gas::from_gas(300_000_000_000_000))
+            .deposit(NearToken::from_yoctonear(300_000_000_000_000_000_000_000))
+            .args_json(json!({
+                "vaa": vaa,
+            }))
+            .transact_async()
+            .await
+            .expect("Failed to submit VAA")
+            .await
+            .unwrap()
+            .failures()
+            .is_empty());
+    }
+
+    assert_eq!(
+        serde_json::from_slice::<Option<u64>>(
+            &contract
+                .view("get_price_feed_retention")
+                .await
+                .unwrap()
+                .result
+        )
+        .unwrap(),
+        Some(3600),
+    );
+
+    // Anyone can remove the expired price feed, unknown price feeds are ignored.
+    let balance = recipient.view_account().await.unwrap().balance;
+    let result = contract
+        .call("remove_expired_price_feeds")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .args_json(json!({
+            "price_ids": [PriceIdentifier(identifier), PriceIdentifier([0xff; 32])],
+        }))
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.failures().is_empty());
+    assert_eq!(
+        result.json::<serde_json::Value>().unwrap(),
+        json!([PriceIdentifier(identifier)]),
+    );
+
+    assert!(!serde_json::from_slice::<bool>(
+        &contract
+            .view("price_feed_exists")
+            .args_json(json!({ "price_identifier": PriceIdentifier(identifier) }))
+            .await
+            .unwrap()
+            .result
+    )
+    .unwrap());
+
+    // The released storage stake went to the fee recipient.
+    assert!(recipient.view_account().await.unwrap().balance > balance);
+}
+
+// A test that upgrades a contract built before the last change of the state layout through
+// governance, checking the state survives the migration.
+#[tokio::test]
+async fn test_upgrade_migration() {
+    let (_, contract, _) = initialize_chain_with_code("pyth_migration_base.wasm").await;
+
+    // Store a price to check it survives the upgrade.
+    let feed = create_dummy_price_feed_message(100);
+    let identifier = create_dummy_feed_id(100);
+    let message = create_accumulator_message(&[&feed], &[&feed], false, false, None);
+
+    assert!(contract
+        .call("update_price_feeds")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .deposit(NearToken::from_yoctonear(300_000_000_000_000_000_000_000))
+        .args_json(json!({
+            "data": hex::encode(message),
+        }))
+        .transact_async()
+        .await
+        .expect("Failed to submit VAA")
+        .await
+        .unwrap()
+        .failures()
+        .is_empty());
+
+    // Upgrading without an authorized codehash fails.
+    let code = std::fs::read("pyth.wasm").expect("Failed to find pyth.wasm");
+    assert!(!contract
+        .call("update_contract")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .args(code.clone())
+        .transact()
+        .await
+        .unwrap()
+        .failures()
+        .is_empty());
+
+    // Authorize the upgrade with the hash of the new code.
+    near_sdk::testing_env!(near_sdk::test_utils::VMContextBuilder::new().build());
+    let codehash: [u8; 32] = near_sdk::env::sha256(&code).try_into().unwrap();
+
+    let vaa = create_vaa_from_payload(
+        &GovernanceInstruction {
+            target: Chain::from(WormholeChain::Near),
+            module: GovernanceModule::Target,
+            action: GovernanceAction::UpgradeContract { codehash },
+        }
+        .serialize()
+        .unwrap(),
+        DEFAULT_GOVERNANCE_SOURCE.address,
+        DEFAULT_GOVERNANCE_SOURCE.chain,
+        1,
+    );
+    let vaa = hex::encode(serde_wormhole::to_vec(&vaa).unwrap());
+
+    assert!(contract
+        .call("execute_governance_instruction")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .deposit(NearToken::from_yoctonear(300_000_000_000_000_000_000_000))
+        .args_json(json!({
+            "vaa": vaa,
+        }))
+        .transact_async()
+        .await
+        .expect("Failed to submit VAA")
+        .await
+        .unwrap()
+        .failures()
+        .is_empty());
+
+    assert!(contract
+        .call("update_contract")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .args(code.clone())
+        .transact()
+        .await
+        .unwrap()
+        .failures()
+        .is_empty());
+
+    // The state is unchanged by the migration.
+    assert_eq!(
+        serde_json::from_slice::<Vec<Source>>(&contract.view("get_sources").await.unwrap().result)
+            .unwrap(),
+        &[Source {
+            emitter: DEFAULT_DATA_SOURCE.address.0,
+            chain: Chain::from(WormholeChain::from(u16::from(DEFAULT_DATA_SOURCE.chain))),
+        }]
+    );
+    assert_eq!(
+        serde_json::from_slice::<u64>(&contract.view("get_stale_threshold").await.unwrap().result)
+            .unwrap(),
+        DEFAULT_VALID_TIME_PERIOD,
+    );
+    assert_eq!(
+        Some(Price {
+            price: 100.into(),
+            conf: 100.into(),
+            expo: 100,
+            publish_time: 100,
+        }),
+        serde_json::from_slice::<Option<Price>>(
+            &contract
+                .view("get_price_unsafe")
+                .args_json(json!({ "price_identifier": PriceIdentifier(identifier) }))
+                .await
+                .unwrap()
+                .result
+        )
+        .unwrap(),
+    );
+
+    // The state added by the migration starts empty.
+    assert_eq!(
+        serde_json::from_slice::<Option<String>>(
+            &contract.view("get_fee_recipient").await.unwrap().result
+        )
+        .unwrap(),
+        None,
+    );
+    assert_eq!(
+        serde_json::from_slice::<Option<u64>>(
+            &contract
+                .view("get_price_feed_retention")
+                .await
+                .unwrap()
+                .result
+        )
+        .unwrap(),
+        None,
+    );
+    assert_eq!(
+        serde_json::from_slice::<Option<U128>>(
+            &contract
+                .view("get_storage_deposit")
+                .args_json(json!({ "price_identifier": PriceIdentifier(identifier) }))
+                .await
+                .unwrap()
+                .result
+        )
+        .unwrap(),
+        None,
+    );
+
+    // The migration resets the codehash, so the authorization cannot be reused.
+    assert!(!contract
+        .call("update_contract")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .args(code)
+        .transact()
+        .await
+        .unwrap()
+        .failures()
+        .is_empty());
+}
+
+// A test measuring the gas used by price updates, checking that batching saves gas.
+#[tokio::test]
+async fn test_update_gas() {
+    let (_, contract, _) = initialize_chain().await;
+
+    let feeds: Vec<_> = (1..=5)
+        .map(|i| create_dummy_price_feed_message(i * 100))
+        .collect();
+    let all_feeds: Vec<_> = feeds.iter().collect();
+    let updates: Vec<_> = all_feeds
+        .iter()
+        .map(|feed| {
+            hex::encode(create_accumulator_message(
+                &all_feeds,
+                &[feed],
+                false,
+                false,
+                None,
+            ))
+        })
+        .collect();
+
+    // Submit the updates one by one.
+    let mut single_gas = 0;
+    for update in &updates {
+        let result = contract
+            .call("update_price_feeds")
+            .gas(Gas::from_gas(300_000_000_000_000))
+            .deposit(NearToken::from_yoctonear(300_000_000_000_000_000_000_000))
+            .args_json(json!({
+                "data": update,
+            }))
+            .transact()
+            .await
+            .expect("Failed to submit VAA");
+        assert!(result.failures().is_empty());
+        single_gas += result.total_gas_burnt.as_gas();
+    }
+
+    // Submit the same updates again as a batch, the prices are not newer this time, so submit a
+    // batch of unseen prices as well to measure the cost of storing them.
+    let result = contract
+        .call("update_price_feeds_batch")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .deposit(NearToken::from_yoctonear(300_000_000_000_000_000_000_000))
+        .args_json(json!({
+            "data":    updates,
+            "max_age": u64::MAX,
+        }))
+        .transact()
+        .await
+        .expect("Failed to submit updates");
+    assert!(result.failures().is_empty());
+    let stale_batch_gas = result.total_gas_burnt.as_gas();
+
+    let new_feeds: Vec<_> = (6..=10)
+        .map(|i| create_dummy_price_feed_message(i * 100))
+        .collect();
+    let all_new_feeds: Vec<_> = new_feeds.iter().collect();
+    let new_updates: Vec<_> = all_new_feeds
+        .iter()
+        .map(|feed| {
+            hex::encode(create_accumulator_message(
+                &all_new_feeds,
+                &[feed],
+                false,
+                false,
+                None,
+            ))
+        })
+        .collect();
+
+    let result = contract
+        .call("update_price_feeds_batch")
+        .gas(Gas::from_gas(300_000_000_000_000))
+        .deposit(NearToken::from_yoctonear(300_000_000_000_000_000_000_000))
+        .args_json(json!({
+            "data":    new_updates,
+            "max_age": u64::MAX,
+        }))
+        .transact()
+        .await
+        .expect("Failed to submit updates");
+    assert!(result.failures().is_empty());
+    let batch_gas = result.total_gas_burnt.as_gas();
+
+    // The batch verifies the shared VAA once and settles in a single callback.
+    assert!(batch_gas < single_gas);
+    assert!(stale_batch_gas <= batch_gas);
+}
+
 #[tokio::test]
 async fn test_sdk_compat() {
     let price = pyth_sdk::Price {
@@ -12,6 +12,19 @@ rustup target add wasm32-unknown-unknown
 cargo build --release --target wasm32-unknown-unknown
 cp target/wasm32-unknown-unknown/release/pyth.wasm .
 
+# Build the contract as it was before the last change of its state layout, so the tests can
+# upgrade from it. Update the revision when the layout changes again (see `Pyth::migrate`).
+MIGRATION_BASE_REV=46c4fe07830866dce9c6fcc19ee48aa73be3b34a
+MIGRATION_BASE_DIR=$(mktemp -d)
+trap 'rm -rf "$MIGRATION_BASE_DIR"' EXIT
+git -C "$(git rev-parse --show-toplevel)" archive "$MIGRATION_BASE_REV" target_chains/near wormhole_attester/sdk/rust pythnet/pythnet_sdk \
+    | tar -x -C "$MIGRATION_BASE_DIR"
+(
+    cd "$MIGRATION_BASE_DIR/target_chains/near/receiver"
+    cargo build --release --target wasm32-unknown-unknown
+)
+cp "$MIGRATION_BASE_DIR/target_chains/near/receiver/target/wasm32-unknown-unknown/release/pyth.wasm" pyth_migration_base.wasm
+
 (
     cd ../wormhole-stub
     cargo build --release --target wasm32-unknown-unknown
@@ -0,0 +1,326 @@
+//! Typed cross-contract calls to the Pyth contract on NEAR.
+//!
+//! The types in this crate serialize to the same JSON as the Pyth contract, so the results of the
+//! promises built by `Pyth` can be taken directly as `#[callback_result]` arguments of a callback.
+
+use {
+    near_sdk::{
+        borsh::{BorshDeserialize, BorshSerialize},
+        env, ext_contract,
+        json_types::{I64, U128, U64},
+        serde::{de::DeserializeOwned, Deserialize, Serialize},
+        serde_json, AccountId, Gas, NearToken, Promise, PromiseError, PromiseResult,
+    },
+    std::collections::HashMap,
+};
+
+pub mod testing;
+
+/// Alias to document time unit Pyth expects data to be in.
+pub type Seconds = u64;
+
+/// Gas attached to reads by default, see `Pyth::with_static_gas`.
+pub const DEFAULT_READ_GAS: Gas = Gas::from_tgas(10);
+
+/// Gas attached to `Pyth::update_price_feeds`, which verifies the update with Wormhole before
+/// storing it.
+pub const UPDATE_GAS: Gas = Gas::from_tgas(100);
+
+/// The identifier of a price feed, serialized as a 64 character hex string.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[borsh(crate = "near_sdk::borsh")]
+#[repr(transparent)]
+pub struct PriceIdentifier(pub [u8; 32]);
+
+impl<'de> near_sdk::serde::Deserialize<'de> for PriceIdentifier {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: near_sdk::serde::Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        if value.len() != 64 {
+            return Err(near_sdk::serde::de::Error::custom(format!(
+                "expected a 64 character hex string, got {}",
+                value.len()
+            )));
+        }
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(value, &mut bytes).map_err(near_sdk::serde::de::Error::custom)?;
+        Ok(PriceIdentifier(bytes))
+    }
+}
+
+impl near_sdk::serde::Serialize for PriceIdentifier {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: near_sdk::serde::Serializer,
+    {
+        serializer.serialize_str(&hex::encode(self.0))
+    }
+}
+
+/// A price with a degree of uncertainty, represented as a price +- a confidence interval.
+///
+/// The confidence interval roughly corresponds to the standard error of a normal distribution.
+/// Both the price and confidence are stored in a fixed-point numeric representation,
+/// `x * (10^expo)`, where `expo` is the exponent.
+///
+/// Please refer to the documentation at https://docs.pyth.network/documentation/pythnet-price-feeds/best-practices for how
+/// to use this price safely.
+#[derive(
+    BorshDeserialize, BorshSerialize, Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize,
+)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct Price {
+    pub price: I64,
+    /// Confidence interval around the price
+    pub conf: U64,
+    /// The exponent
+    pub expo: i32,
+    /// Unix timestamp of when this price was computed
+    pub publish_time: i64,
+}
+
+/// The interface of the Pyth contract. `Pyth` wraps the generated `ext_pyth` module with the
+/// gas and deposits these calls need.
+#[ext_contract(ext_pyth)]
+pub trait PythContract {
+    fn update_price_feeds(&mut self, data: String);
+    fn get_update_fee_estimate(&self, data: String) -> U128;
+    fn get_stale_threshold(&self) -> Seconds;
+    fn price_feed_exists(&self, price_identifier: PriceIdentifier) -> bool;
+    fn get_price(&self, price_identifier: PriceIdentifier) -> Option<Price>;
+    fn get_price_unsafe(&self, price_identifier: PriceIdentifier) -> Option<Price>;
+    fn get_price_no_older_than(&self, price_id: PriceIdentifier, age: Seconds) -> Option<Price>;
+    fn get_ema_price(&self, price_id: PriceIdentifier) -> Option<Price>;
+    fn get_ema_price_unsafe(&self, price_id: PriceIdentifier) -> Option<Price>;
+    fn get_ema_price_no_older_than(&self, price_id: PriceIdentifier, age: Seconds)
+        -> Option<Price>;
+    fn list_prices_no_older_than(
+        &self,
+        price_ids: Vec<PriceIdentifier>,
+        age: Seconds,
+    ) -> HashMap<PriceIdentifier, Option<Price>>;
+    fn list_ema_prices_no_older_than(
+        &self,
+        price_ids: Vec<PriceIdentifier>,
+        age: Seconds,
+    ) -> HashMap<PriceIdentifier, Option<Price>>;
+}
+
+/// A handle to a deployed Pyth contract for building promises against it.
+///
+/// Each method returns a `Promise` whose result deserializes into the return type documented on
+/// the method, either as a `#[callback_result]` argument or with `promise_results`.
+#[derive(Clone, Debug)]
+pub struct Pyth {
+    account_id: AccountId,
+    gas: Gas,
+}
+
+impl Pyth {
+    pub fn new(account_id: AccountId) -> Self {
+        Self {
+            account_id,
+            gas: DEFAULT_READ_GAS,
+        }
+    }
+
+    /// Set the gas attached to reads, `DEFAULT_READ_GAS` by default.
+    pub fn with_static_gas(mut self, gas: Gas) -> Self {
+        self.gas = gas;
+        self
+    }
+
+    pub fn account_id(&self) -> &AccountId {
+        &self.account_id
+    }
+
+    /// Submit a price update from Hermes. Any part of `deposit` not used for the update fee and
+    /// storage is refunded. Resolves to nothing.
+    pub fn update_price_feeds(&self, data: String, deposit: NearToken) -> Promise {
+        ext_pyth::ext(self.account_id.clone())
+            .with_static_gas(UPDATE_GAS)
+            .with_attached_deposit(deposit)
+            .update_price_feeds(data)
+    }
+
+    /// Resolves to the `U128` deposit in yoctoNEAR that `update_price_feeds` needs for `data`.
+    pub fn get_update_fee_estimate(&self, data: String) -> Promise {
+        self.ext().get_update_fee_estimate(data)
+    }
+
+    /// Resolves to `Option<Price>`, the price if it is no older than the stale threshold of the
+    /// Pyth contract.
+    pub fn get_price(&self, price_id: PriceIdentifier) -> Promise {
+        self.ext().get_price(price_id)
+    }
+
+    /// Resolves to `Option<Price>`, the price if it is no older than `age` seconds.
+    pub fn get_price_no_older_than(&self, price_id: PriceIdentifier, age: Seconds) -> Promise {
+        self.ext().get_price_no_older_than(price_id, age)
+    }
+
+    /// Resolves to `Option<Price>`, the EMA price if it is no older than `age` seconds.
+    pub fn get_ema_price_no_older_than(&self, price_id: PriceIdentifier, age: Seconds) -> Promise {
+        self.ext().get_ema_price_no_older_than(price_id, age)
+    }
+
+    /// Resolves to `HashMap<PriceIdentifier, Option<Price>>`, each price if it is no older than
+    /// `age` seconds.
+    pub fn list_prices_no_older_than(
+        &self,
+        price_ids: Vec<PriceIdentifier>,
+        age: Seconds,
+    ) -> Promise {
+        self.ext().list_prices_no_older_than(price_ids, age)
+    }
+
+    /// Resolves to `HashMap<PriceIdentifier, Option<Price>>`, each EMA price if it is no older
+    /// than `age` seconds.
+    pub fn list_ema_prices_no_older_than(
+        &self,
+        price_ids: Vec<PriceIdentifier>,
+        age: Seconds,
+    ) -> Promise {
+        self.ext().list_ema_prices_no_older_than(price_ids, age)
+    }
+
+    fn ext(&self) -> ext_pyth::PythContractExt {
+        ext_pyth::ext(self.account_id.clone()).with_static_gas(self.gas)
+    }
+}
+
+/// Deserialize the result of the promise at `index` among the promises the current callback was
+/// chained to. A failed promise and a result that does not deserialize are both
+/// `PromiseError::Failed`.
+pub fn promise_result<T: DeserializeOwned>(index: u64) -> Result<T, PromiseError> {
+    match env::promise_result(index) {
+        PromiseResult::Successful(data) => {
+            serde_json::from_slice(&data).map_err(|_| PromiseError::Failed)
+        }
+        _ => Err(PromiseError::Failed),
+    }
+}
+
+/// Deserialize the results of all promises the current callback was chained to, which is useful
+/// when the number of reads joined with `Promise::and` is only known at runtime.
+pub fn promise_results<T: DeserializeOwned>() -> Vec<Result<T, PromiseError>> {
+    (0..env::promise_results_count())
+        .map(promise_result)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::testing::{promise_failure, promise_success, MockPyth},
+        near_sdk::{test_utils::VMContextBuilder, testing_env, RuntimeFeesConfig},
+    };
+
+    fn price(publish_time: i64) -> Price {
+        Price {
+            price: 100.into(),
+            conf: 1.into(),
+            expo: -2,
+            publish_time,
+        }
+    }
+
+    fn set_block_time(seconds: u64, promise_results: Vec<PromiseResult>) {
+        testing_env!(
+            VMContextBuilder::new()
+                .block_timestamp(seconds * 1_000_000_000)
+                .build(),
+            near_sdk::test_vm_config(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            promise_results
+        );
+    }
+
+    #[test]
+    fn test_price_identifier_json() {
+        let id = PriceIdentifier([0xab; 32]);
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!("\"{}\"", "ab".repeat(32)));
+        assert_eq!(serde_json::from_str::<PriceIdentifier>(&json).unwrap(), id);
+        assert!(serde_json::from_str::<PriceIdentifier>("\"abab\"").is_err());
+    }
+
+    #[test]
+    fn test_price_json() {
+        // Matches the JSON returned by the Pyth contract, which encodes 64 bit integers as strings.
+        let json = r#"{"price":"100","conf":"1","expo":-2,"publish_time":10}"#;
+        assert_eq!(serde_json::from_str::<Price>(json).unwrap(), price(10));
+        assert_eq!(serde_json::to_string(&price(10)).unwrap(), json);
+    }
+
+    #[test]
+    fn test_mock_pyth_staleness() {
+        let id = PriceIdentifier([1; 32]);
+        let mut pyth = MockPyth::new(60);
+        pyth.add_price(id, price(1000), price(1000));
+
+        set_block_time(1030, vec![]);
+        assert_eq!(pyth.get_price(id), Some(price(1000)));
+        assert_eq!(pyth.get_price_no_older_than(id, 10), None);
+        assert_eq!(pyth.get_ema_price_no_older_than(id, 30), Some(price(1000)));
+
+        set_block_time(1100, vec![]);
+        assert_eq!(pyth.get_price(id), None);
+        assert_eq!(pyth.get_price_unsafe(id), Some(price(1000)));
+        assert_eq!(
+            pyth.get_price_no_older_than(PriceIdentifier([2; 32]), 60),
+            None
+        );
+    }
+
+    #[test]
+    fn test_promise_results() {
+        let id = PriceIdentifier([1; 32]);
+        let mut pyth = MockPyth::new(60);
+        pyth.add_price(id, price(1000), price(1000));
+
+        set_block_time(
+            1000,
+            vec![
+                promise_success(&pyth.get_price(id)),
+                promise_success(&pyth.get_price(PriceIdentifier([2; 32]))),
+                promise_failure(),
+                promise_success(&true),
+            ],
+        );
+
+        assert_eq!(
+            promise_results::<Option<Price>>(),
+            vec![
+                Ok(Some(price(1000))),
+                Ok(None),
+                Err(PromiseError::Failed),
+                Err(PromiseError::Failed),
+            ]
+        );
+        assert_eq!(promise_result::<bool>(3), Ok(true));
+    }
+
+    #[test]
+    fn test_mock_pyth_list_prices() {
+        let id = PriceIdentifier([1; 32]);
+        let missing = PriceIdentifier([2; 32]);
+        let mut pyth = MockPyth::new(60);
+        pyth.add_price(id, price(1000), price(990));
+
+        set_block_time(1005, vec![]);
+        let prices = pyth.list_ema_prices_no_older_than(vec![id, missing], 10);
+        assert_eq!(prices.len(), 2);
+        assert_eq!(prices[&id], None);
+        assert_eq!(prices[&missing], None);
+        assert_eq!(
+            pyth.list_prices_no_older_than(vec![id], 10)[&id],
+            Some(price(1000))
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! Utilities for unit testing contracts that read prices from Pyth.
+
+use {
+    crate::{Price, PriceIdentifier, Seconds},
+    near_sdk::{env, serde::Serialize, serde_json, PromiseResult},
+    std::collections::HashMap,
+};
+
+/// Mock version of Pyth for unit testing NEAR contracts.
+///
+/// The mock stores prices and answers the same reads as the Pyth contract, relative to the block
+/// time of the mocked blockchain. Its answers can be passed to a callback as promise results with
+/// `promise_success`, e.g. `testing_env!(context, config, fees, validators, promise_results)`.
+#[derive(Clone, Debug, Default)]
+pub struct MockPyth {
+    pub stale_threshold: Seconds,
+    pub prices: HashMap<PriceIdentifier, Price>,
+    pub ema_prices: HashMap<PriceIdentifier, Price>,
+}
+
+impl MockPyth {
+    pub fn new(stale_threshold: Seconds) -> Self {
+        Self {
+            stale_threshold,
+            ..Default::default()
+        }
+    }
+
+    /// Add a price feed that will be returned on reads.
+    pub fn add_price(&mut self, price_id: PriceIdentifier, price: Price, ema_price: Price) {
+        self.prices.insert(price_id, price);
+        self.ema_prices.insert(price_id, ema_price);
+    }
+
+    pub fn get_price(&self, price_id: PriceIdentifier) -> Option<Price> {
+        self.get_price_no_older_than(price_id, self.stale_threshold)
+    }
+
+    pub fn get_price_unsafe(&self, price_id: PriceIdentifier) -> Option<Price> {
+        self.get_price_no_older_than(price_id, Seconds::MAX)
+    }
+
+    pub fn get_price_no_older_than(
+        &self,
+        price_id: PriceIdentifier,
+        age: Seconds,
+    ) -> Option<Price> {
+        no_older_than(self.prices.get(&price_id), age)
+    }
+
+    pub fn get_ema_price_no_older_than(
+        &self,
+        price_id: PriceIdentifier,
+        age: Seconds,
+    ) -> Option<Price> {
+        no_older_than(self.ema_prices.get(&price_id), age)
+    }
+
+    pub fn list_prices_no_older_than(
+        &self,
+        price_ids: Vec<PriceIdentifier>,
+        age: Seconds,
+    ) -> HashMap<PriceIdentifier, Option<Price>> {
+        price_ids
+            .into_iter()
+            .map(|price_id| (price_id, self.get_price_no_older_than(price_id, age)))
+            .collect()
+    }
+
+    pub fn list_ema_prices_no_older_than(
+        &self,
+        price_ids: Vec<PriceIdentifier>,
+        age: Seconds,
+    ) -> HashMap<PriceIdentifier, Option<Price>> {
+        price_ids
+            .into_iter()
+            .map(|price_id| (price_id, self.get_ema_price_no_older_than(price_id, age)))
+            .collect()
+    }
+}
+
+/// The same check as the Pyth contract, which rejects prices too far from the block time in both
+/// directions.
+fn no_older_than(price: Option<&Price>, age: Seconds) -> Option<Price> {
+    let block_timestamp = env::block_timestamp() / 1_000_000_000;
+    price
+        .filter(|price| {
+            u64::try_from(price.publish_time).map_or(false, |publish_time| {
+                block_timestamp.abs_diff(publish_time) <= age
+            })
+        })
+        .copied()
+}
+
+/// The result of a successful promise returning `value`.
+pub fn promise_success<T: Serialize>(value: &T) -> PromiseResult {
+    PromiseResult::Successful(serde_json::to_vec(value).unwrap())
+}
+
+/// The result of a failed promise.
+pub fn promise_failure() -> PromiseResult {
+    PromiseResult::Failed
+}
@@ -584,4 +584,56 @@ mod tests {
 
         Ok(())
     }
+
+    /// P2W v3.1 batch payload taken from the mainnet VAA used as
+    /// `TEST_BATCH_UPDATE_DATA[1]` in the Fuel contract tests
+    /// (target_chains/fuel/contracts/src/constants.rs). Target chain
+    /// parsers are tested against payloads produced by this format, so
+    /// any change to the wire format must keep this fixture round-tripping.
+    const MAINNET_BATCH_FIXTURE: &str = concat!(
+        "50325748000300010001020005009d",
+        "b0e13ce3260d884b0417c6b4d152d45b2f13991a8592522fad0068a4bce3dfbdf0d57deca57b3da2fe63a493f4c25925fdfd8edf834b20f93e1f84dbd1504d4a0000000000011fbc000000000000005ffffffff6000000000001209e0000000000000068010000001100000016000000006509ac22000000006509ac22000000006509ac210000000000011fbc000000000000005f000000006509ac21",
+        "8ab03cff1844ab975dcdd1683020c0599fc5392b6f2e12d5dd615bcc2c2e6d08ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d0000000076e1a7a00000000000189196fffffff800000000774edb860000000000167db6010000001a00000020000000006509ac22000000006509ac22000000006509ac210000000076e18bf400000000001875ea000000006509ac21",
+        "127ab385f079cf02de5a6c0bc8414267acd086fd268730caf319e86b88d2342923d7315113f5b1d3ba7a83604c44b94d79f4fd69af77f804fc7f920a6dc657440000000002ac7a6d000000000000b6cafffffff80000000002b09148000000000000840b010000001200000015000000006509ac22000000006509ac22000000006509ac210000000002ac8bb30000000000009068000000006509ac21",
+        "c12e5d198c9c673e9ce03265e7d9be69cd6a0c674aabd3d2c41ff5764023e22878d185a741d07edb3412b09008b7c5cfb9bbbd7d568bf00ba737b456ba171501000000001a54d4420000000000045e4dfffffff8000000001a699208000000000004825201000000180000001d000000006509ac22000000006509ac22000000006509ac21000000001a54d44200000000000459ad000000006509ac21",
+        "6bfad3ab2ad6ed59591a5a77cc9b162f8e228e89ef56151b24e15426a2bb4d48eaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a0000000005f5e54c0000000000005463fffffff80000000005f5e09000000000000053af01000000160000001b000000006509ac22000000006509ac22000000006509ac210000000005f5e54c0000000000005463000000006509ac21",
+    );
+
+    #[test]
+    fn test_batch_mainnet_fixture() -> Result<(), ErrBox> {
+        let bytes = hex::decode(MAINNET_BATCH_FIXTURE)?;
+
+        let batch = BatchPriceAttestation::deserialize(bytes.as_slice())?;
+        assert_eq!(batch.price_attestations.len(), 5);
+
+        // USDC/USD
+        let expected = PriceAttestation {
+            product_id: Identifier::from_hex(
+                "6bfad3ab2ad6ed59591a5a77cc9b162f8e228e89ef56151b24e15426a2bb4d48",
+            )?,
+            price_id: Identifier::from_hex(
+                "eaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a",
+            )?,
+            price: 100001100,
+            conf: 21603,
+            expo: -8,
+            ema_price: 99999888,
+            ema_conf: 21423,
+            status: PriceStatus::Trading,
+            num_publishers: 22,
+            max_num_publishers: 27,
+            attestation_time: 1695132706,
+            publish_time: 1695132706,
+            prev_publish_time: 1695132705,
+            prev_price: 100001100,
+            prev_conf: 21603,
+            last_attested_publish_time: 1695132705,
+        };
+        assert_eq!(batch.price_attestations[4], expected);
+
+        // Re-serializing must reproduce the exact bytes sent on mainnet.
+        assert_eq!(batch.serialize()?, bytes);
+
+        Ok(())
+    }
 }